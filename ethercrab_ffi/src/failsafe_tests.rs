@@ -0,0 +1,62 @@
+use super::*;
+use super::pdo_tests::layout;
+
+fn slave(mode: u8, user_image: &[u8]) -> FailSafeSlave {
+    FailSafeSlave { mode, user_image: user_image.to_vec(), active: None }
+}
+
+#[test]
+fn test_fail_safe_image_modes() {
+    let current = [0x12, 0x34, 0x56];
+    assert_eq!(fail_safe_image(FAILSAFE_MODE_NONE, &[], &current), None);
+    assert_eq!(fail_safe_image(FAILSAFE_MODE_ZERO, &[], &current), Some(vec![0, 0, 0]));
+    assert_eq!(fail_safe_image(FAILSAFE_MODE_HOLD, &[], &current), Some(vec![0x12, 0x34, 0x56]));
+    // Short user images are zero-padded to the slave's outputs
+    assert_eq!(fail_safe_image(FAILSAFE_MODE_USER, &[0xFF], &current), Some(vec![0xFF, 0, 0]));
+}
+
+#[test]
+fn test_fail_safe_enter_writes_pdi_and_latches() {
+    // [Out0 (2) | Out2 (1) | In0 | In2], slave 1 has no process data
+    let layouts = [layout(0, 2, 3, 1), layout(2, 0, 4, 0), layout(2, 1, 4, 1)];
+    let mut fail_safe = FailSafe {
+        slaves: vec![slave(FAILSAFE_MODE_USER, &[0xAA, 0xBB]), slave(FAILSAFE_MODE_ZERO, &[]), slave(FAILSAFE_MODE_HOLD, &[])],
+        reason: FAILSAFE_REASON_NONE,
+    };
+    let mut pdi = [0x11, 0x22, 0x33, 0x44, 0x55];
+
+    assert_eq!(fail_safe.enter(FAILSAFE_REASON_WKC_LOSS, &layouts, &mut pdi), 2);
+    assert_eq!(pdi, [0xAA, 0xBB, 0x33, 0x44, 0x55]);
+    assert_eq!(fail_safe.reason, FAILSAFE_REASON_WKC_LOSS);
+    assert_eq!(fail_safe.active_image(0), Some(&[0xAA, 0xBB][..]));
+    assert_eq!(fail_safe.active_image(1), None);
+    assert_eq!(fail_safe.active_image(2), Some(&[0x33][..]));
+
+    // Host writes after the trigger do not change the latched images or the first reason
+    pdi[2] = 0x99;
+    assert_eq!(fail_safe.enter(FAILSAFE_REASON_DESTROY, &layouts, &mut pdi), 0);
+    assert_eq!(fail_safe.active_image(2), Some(&[0x33][..]));
+    assert_eq!(fail_safe.reason, FAILSAFE_REASON_WKC_LOSS);
+
+    fail_safe.reset();
+    assert_eq!(fail_safe.active_image(0), None);
+    assert_eq!(fail_safe.reason, FAILSAFE_REASON_NONE);
+}
+
+#[test]
+fn test_fail_safe_unconfigured_slaves_untouched() {
+    let layouts = [layout(0, 1, 2, 1), layout(1, 1, 3, 1)];
+    let mut fail_safe = FailSafe { slaves: vec![slave(FAILSAFE_MODE_NONE, &[])], reason: FAILSAFE_REASON_NONE };
+    let mut pdi = [0x01, 0x02, 0x03, 0x04];
+    assert_eq!(fail_safe.enter(FAILSAFE_REASON_HEARTBEAT, &layouts, &mut pdi), 0);
+    assert_eq!(pdi, [0x01, 0x02, 0x03, 0x04]);
+    assert_eq!(fail_safe.reason, FAILSAFE_REASON_NONE);
+}
+
+#[test]
+fn test_expected_lrw_wkc() {
+    // Coupler (0), digital input (1), digital output (2), mixed I/O (3)
+    let layouts = [layout(0, 0, 0, 0), layout(0, 0, 0, 2), layout(0, 1, 2, 0), layout(1, 4, 2, 4)];
+    assert_eq!(expected_lrw_wkc(&layouts), 6);
+    assert_eq!(expected_lrw_wkc(&[]), 0);
+}
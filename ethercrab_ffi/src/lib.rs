@@ -1,11 +1,11 @@
 use std::ffi::{CStr, c_char, c_int};
 use std::any::Any;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 use std::thread::JoinHandle;
 use ethercrab::{
-    MainDevice, MainDeviceConfig, PduStorage, Timeouts, std::ethercat_now,
+    Command, MainDevice, MainDeviceConfig, PduStorage, SubDeviceIdentity, Timeouts, std::ethercat_now,
     subdevice_group::{PreOp, SafeOp, Op},
 };
 use ethercrab::subdevice_group::SubDeviceGroup;
@@ -39,11 +39,18 @@ struct EcMasterState {
     expected_wkc: u16,
     mailbox_poll_interval_ms: Option<u32>,
     pdu_timeout_ms: u64,
+    mailbox_timeout_ms: u64,
+    eeprom_timeout_ms: u64,
+    process_image: ProcessImage,
+    fail_safe: Mutex<FailSafe>,
+    esc_errors: Mutex<EscErrorHistory>,
+    link_monitor: Mutex<Option<LinkMonitor>>,
 }
 
 #[derive(Clone, Copy)]
 struct InternalEmergencyInfo {
-    slave_index: u16,
+    /// Configured station address of the sender, resolved to a position on read.
+    configured_address: u16,
     error_code: u16,
     error_register: u8,
 }
@@ -55,6 +62,34 @@ static GLOBAL_DEVICE: Lazy<RwLock<Option<Arc<MainDevice<'static>>>>> = Lazy::new
 static TX_RX_RESOURCES: Lazy<Mutex<Option<TxRxResources>>> = Lazy::new(|| Mutex::new(None));
 static LAST_EMERGENCY: Lazy<Mutex<Option<InternalEmergencyInfo>>> = Lazy::new(|| Mutex::new(None));
 static NETWORK_HEALTHY: AtomicBool = AtomicBool::new(true);
+/// Calls still using the MainDevice after releasing STATE. `ethercrab_destroy` waits for
+/// them to finish before it frees the PDU storage.
+static DETACHED_USERS: AtomicUsize = AtomicUsize::new(0);
+
+/// The MainDevice of the running master, usable without holding STATE. Taken while the
+/// STATE read guard is held, so destroy has not cleared the state yet.
+struct DetachedMaster(Arc<MainDevice<'static>>);
+
+impl DetachedMaster {
+    fn new(state: &EcMasterState) -> Self {
+        DETACHED_USERS.fetch_add(1, Ordering::SeqCst);
+        Self(state.maindevice.clone())
+    }
+}
+
+impl std::ops::Deref for DetachedMaster {
+    type Target = MainDevice<'static>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Drop for DetachedMaster {
+    fn drop(&mut self) {
+        DETACHED_USERS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
 
 // --- Structured Error Infrastructure ---
 
@@ -72,11 +107,18 @@ pub enum FfiErrorCode {
     NetworkError = 12,
     InterfaceError = 13,
     StateTransitionFailed = 20,
+    InvalidState = 21,
     SdoError = 30,
     EepromError = 31,
     RegisterError = 32,
+    MailboxError = 33,
+    SoeError = 34,
     ResourceBusy = 40,
     PermissionDenied = 41,
+    Cancelled = 42,
+    HostWatchdogTimeout = 43,
+    LinkLost = 44,
+    DuplicateAlias = 45,
 }
 
 #[derive(Clone)]
@@ -150,6 +192,7 @@ fn json_escape(s: &str) -> String {
             '\n' => out.push_str("\\n"),
             '\r' => out.push_str("\\r"),
             '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
             c => out.push(c),
         }
     }
@@ -351,7 +394,7 @@ pub struct SlaveIdentity {
 // offset 11: padding (1 byte)
 pub struct FfiInitCommand {
     pub slave_index: u16,
-    pub command_type: u8, // 0=SDO, 1=Register
+    pub command_type: u8, // 0=SDO, 1=Register, 2=SoE (index=IDN, sub_index=drive number)
     pub index: u16,
     pub sub_index: u8,
     pub value: [u8; 4],
@@ -366,9 +409,11 @@ pub struct EmergencyInfo {
 
 // --- SDO Info Structures (ETG1000.6 §5.6.3.3.1) ---
 
-/// Object access flags bitfield (ETG1000.6 §5.6.2.5)
+/// Object access flags bitfield (ETG1000.6 §5.6.3.5, entry description).
+/// Bits 0-2: read in PreOp/SafeOp/Op, bits 3-5: write in PreOp/SafeOp/Op,
+/// bit 6: RxPDO mappable, bit 7: TxPDO mappable, bit 8: backup, bit 9: settings.
 #[repr(C)]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default)]
 pub struct ObjectAccess {
     flags: u16,
 }
@@ -379,27 +424,27 @@ impl ObjectAccess {
     }
     
     pub fn read_access(&self) -> bool {
-        (self.flags & 0x0001) != 0
+        (self.flags & 0x0007) != 0
     }
     
     pub fn write_access(&self) -> bool {
-        (self.flags & 0x0002) != 0
+        (self.flags & 0x0038) != 0
     }
     
     pub fn rx_pdo_mapping(&self) -> bool {
-        (self.flags & 0x0004) != 0
+        (self.flags & 0x0040) != 0
     }
     
     pub fn tx_pdo_mapping(&self) -> bool {
-        (self.flags & 0x0008) != 0
+        (self.flags & 0x0080) != 0
     }
     
     pub fn backup_param(&self) -> bool {
-        (self.flags & 0x0010) != 0
+        (self.flags & 0x0100) != 0
     }
     
     pub fn settings_param(&self) -> bool {
-        (self.flags & 0x0020) != 0
+        (self.flags & 0x0200) != 0
     }
 }
 
@@ -409,1420 +454,5818 @@ fn u32_from_bytes(bytes: [u8; 4]) -> u32 {
     u32::from_le_bytes(bytes)
 }
 
-#[allow(dead_code)]
-fn store_emergency(_state: &mut EcMasterState, slave_index: u16, error_code: u16, error_register: u8) {
+/// Record a CoE emergency payload: CoE header (2), error code (2), error register (1), data (5).
+fn store_emergency(configured_address: u16, payload: &[u8]) {
+    if payload.len() < 5 {
+        return;
+    }
     let mut guard = LAST_EMERGENCY.lock();
     *guard = Some(InternalEmergencyInfo {
-        slave_index,
-        error_code,
-        error_register,
+        configured_address,
+        error_code: u16::from_le_bytes([payload[2], payload[3]]),
+        error_register: payload[4],
     });
 }
 
-// --- FFI Exports ---
+/// Resolve a positional slave index to the slave's configured station address.
+fn slave_configured_address(state: &EcMasterState, idx: usize) -> Option<u16> {
+    let md = &state.maindevice;
+    match state.group.as_ref()? {
+        GroupState::PreOp(g) => g.iter(md).nth(idx).map(|s| s.configured_address()),
+        GroupState::SafeOp(g) => g.iter(md).nth(idx).map(|s| s.configured_address()),
+        GroupState::Op(g) => g.iter(md).nth(idx).map(|s| s.configured_address()),
+    }
+}
 
-#[no_mangle]
-pub extern "C" fn ethercrab_version(buffer: *mut u8, len: usize) -> c_int {
-    with_ffi_guard(0, || {
-        let version = env!("CARGO_PKG_VERSION");
-        let version_bytes = version.as_bytes();
-        if buffer.is_null() || len == 0 {
-            return version_bytes.len() as c_int;
-        }
-        let to_copy = version_bytes.len().min(len);
-        unsafe {
-            std::ptr::copy_nonoverlapping(version_bytes.as_ptr(), buffer, to_copy);
+/// Configured station addresses of all slaves in the group, by position.
+fn slave_configured_addresses(state: &EcMasterState) -> Vec<u16> {
+    let md = &state.maindevice;
+    match state.group.as_ref() {
+        Some(GroupState::PreOp(g)) => g.iter(md).map(|s| s.configured_address()).collect(),
+        Some(GroupState::SafeOp(g)) => g.iter(md).map(|s| s.configured_address()).collect(),
+        Some(GroupState::Op(g)) => g.iter(md).map(|s| s.configured_address()).collect(),
+        None => Vec::new(),
+    }
+}
+
+// --- Raw ESC Access (FPRD/FPWR by configured address) ---
+
+/// Read `len` bytes from an ESC register/memory area of a single slave.
+async fn esc_read(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    register: u16,
+    len: u16,
+) -> Result<Vec<u8>, ethercrab::error::Error> {
+    let data = Command::fprd(configured_address, register)
+        .receive_slice(md, len)
+        .await?;
+    Ok(data.to_vec())
+}
+
+/// Write `data` to an ESC register/memory area of a single slave.
+async fn esc_write(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    register: u16,
+    data: &[u8],
+) -> Result<(), ethercrab::error::Error> {
+    Command::fpwr(configured_address, register).send(md, data).await
+}
+
+// --- Mailbox Transport (ETG1000.4 §5.6, ETG1000.6 §5.2) ---
+
+const MAILBOX_HEADER_LEN: usize = 6;
+const MAILBOX_TYPE_ERR: u8 = 0x00;
+const MAILBOX_TYPE_COE: u8 = 0x03;
+const MAILBOX_TYPE_SOE: u8 = 0x05;
+const SM_STATUS_MAILBOX_FULL: u8 = 0x08;
+const SM_STATUS_TOGGLE: u8 = 0x02;
+/// SM1 activate (0x080E) bit 1 = repeat request; SM1 PDI control (0x080F) bit 1 = repeat ack.
+const SM_REPEAT_BIT: u8 = 0x02;
+const MAILBOX_POLL_DELAY: Duration = Duration::from_millis(1);
+/// Attempts made by the resilient mailbox layer (Feature 402) before giving up.
+const MAILBOX_RESILIENT_ATTEMPTS: usize = 3;
+
+/// Mailbox channel of every slave, keyed by configured station address.
+static MAILBOX_CHANNELS: Lazy<Mutex<std::collections::HashMap<u16, Arc<smol::lock::Mutex<MailboxChannel>>>>> =
+    Lazy::new(|| Mutex::new(std::collections::HashMap::new()));
+/// Bumped whenever ethercrab may have used slave mailboxes itself (group init and
+/// state transitions), which leaves the slaves' last seen counter unknown to us.
+static MAILBOX_EPOCH: AtomicU32 = AtomicU32::new(0);
+/// Counter of the last frame read from SM1 per configured station address.
+static MAILBOX_RX_COUNTERS: Lazy<Mutex<std::collections::HashMap<u16, u8>>> =
+    Lazy::new(|| Mutex::new(std::collections::HashMap::new()));
+
+#[derive(Debug)]
+enum MailboxError {
+    Io(ethercrab::error::Error),
+    Timeout,
+    NoMailbox,
+    TooLarge { len: usize, capacity: usize },
+    /// Mailbox error reply (type 0x00) with its detail code (ETG1000.4 Table 30).
+    ErrorReply(u16),
+    /// SM1 kept returning a frame whose counter matches the last one received.
+    Stale,
+    /// SoE response with the error flag set, carrying the SoE error code.
+    Soe(u16),
+    /// SDO Information error response carrying the SDO abort code.
+    SdoInfo(u32),
+    /// SDO transfer aborted by the slave, carrying the SDO abort code.
+    SdoAbort(u32),
+    Protocol(String),
+}
+
+impl std::fmt::Display for MailboxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MailboxError::Io(e) => write!(f, "{}", e),
+            MailboxError::Timeout => write!(f, "mailbox response timeout"),
+            MailboxError::NoMailbox => write!(f, "slave has no mailbox configured (SM0/SM1 length is 0)"),
+            MailboxError::TooLarge { len, capacity } => {
+                write!(f, "mailbox payload of {} bytes exceeds capacity of {} bytes", len, capacity)
+            }
+            MailboxError::ErrorReply(code) => write!(f, "mailbox error reply 0x{:04X}", code),
+            MailboxError::Stale => write!(f, "mailbox frame counter repeated {} times", MAILBOX_RESILIENT_ATTEMPTS),
+            MailboxError::Soe(code) => write!(f, "SoE error 0x{:04X}", code),
+            MailboxError::SdoInfo(code) => write!(f, "SDO Information abort 0x{:08X}", code),
+            MailboxError::SdoAbort(code) => write!(f, "SDO abort 0x{:08X}", code),
+            MailboxError::Protocol(msg) => write!(f, "{}", msg),
         }
-        to_copy as c_int
-    })
+    }
 }
 
-#[no_mangle]
-pub extern "C" fn ethercrab_get_last_error(buffer: *mut u8, len: usize) -> c_int {
-    with_ffi_guard(0, || {
-        if buffer.is_null() || len == 0 {
-            return 0;
+impl From<ethercrab::error::Error> for MailboxError {
+    fn from(e: ethercrab::error::Error) -> Self {
+        MailboxError::Io(e)
+    }
+}
+
+/// SM0 (master → slave) and SM1 (slave → master) mailbox areas, read from 0x0800/0x0808.
+#[derive(Clone, Copy, Debug)]
+struct MailboxConfig {
+    write_offset: u16,
+    write_len: u16,
+    read_offset: u16,
+    read_len: u16,
+}
+
+impl MailboxConfig {
+    fn write_capacity(&self) -> usize {
+        (self.write_len as usize).saturating_sub(MAILBOX_HEADER_LEN)
+    }
+}
+
+#[derive(Debug)]
+struct MailboxFrame {
+    mailbox_type: u8,
+    counter: u8,
+    payload: Vec<u8>,
+}
+
+/// Result of one sync manager status poll under the toggle-bit rules of the
+/// resilient mailbox layer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum MailboxPoll {
+    Empty,
+    NewMail,
+    /// Mailbox full but the toggle bit matches the last frame: likely a re-read.
+    Stale,
+}
+
+/// Classify an SM status byte. `last_toggle_bit` > 1 means no frame has been
+/// seen yet, so any full mailbox is accepted.
+fn classify_mailbox_status(status: u8, last_toggle_bit: u8) -> MailboxPoll {
+    if (status & SM_STATUS_MAILBOX_FULL) == 0 {
+        return MailboxPoll::Empty;
+    }
+    let current_toggle = (status & SM_STATUS_TOGGLE) >> 1;
+    if last_toggle_bit > 1 || current_toggle != last_toggle_bit {
+        MailboxPoll::NewMail
+    } else {
+        MailboxPoll::Stale
+    }
+}
+
+/// Mailbox state of one slave. Every protocol the master runs over SM0/SM1
+/// (CoE SDO, SDO Information, SoE, raw frames) takes the same channel, so
+/// request/response pairs cannot interleave and consecutive requests never
+/// repeat a counter, which the slave would drop as a retransmission.
+struct MailboxChannel {
+    /// Last counter sent (1..=7, 0 = unused).
+    counter: u8,
+    /// `MAILBOX_EPOCH` of the last completed exchange.
+    epoch: u32,
+}
+
+impl MailboxChannel {
+    fn new() -> Self {
+        Self { counter: 0, epoch: u32::MAX }
+    }
+
+    /// Next mailbox counter. Counters cycle 1..=7; 0 is reserved.
+    fn next_counter(&mut self) -> u8 {
+        self.counter = if self.counter >= 7 { 1 } else { self.counter + 1 };
+        self.counter
+    }
+
+    /// Record a counter chosen by a raw mailbox caller (0 = no repeat detection).
+    fn note_counter(&mut self, counter: u8) {
+        if counter != 0 {
+            self.counter = counter;
         }
-        let ring = ERROR_RING.lock();
-        let formatted = match ring.latest() {
-            Some(entry) => format_error_for_ffi(entry),
-            None => return 0,
-        };
-        let error_bytes = formatted.as_bytes();
-        let to_copy = error_bytes.len().min(len);
-        unsafe {
-            std::ptr::copy_nonoverlapping(error_bytes.as_ptr(), buffer, to_copy);
+    }
+
+    /// Whether an exchange has to be repeated with the next counter. ethercrab keeps its
+    /// own counter for the mailbox traffic it runs during group transitions, so the first
+    /// exchange after one may reuse the slave's last counter and be dropped as a repeat.
+    /// That exchange is retried once on timeout; later ones follow our own sequence.
+    fn retry<T>(&mut self, result: &Result<T, MailboxError>) -> bool {
+        let epoch = MAILBOX_EPOCH.load(Ordering::Acquire);
+        if self.epoch == epoch {
+            return false;
         }
-        to_copy as c_int
-    })
+        self.epoch = epoch;
+        matches!(result, Err(MailboxError::Timeout))
+    }
 }
 
-/// Returns 1 if the network is healthy, 0 if not.
-#[no_mangle]
-pub extern "C" fn ethercrab_get_network_healthy() -> c_int {
-    if NETWORK_HEALTHY.load(Ordering::Relaxed) { 1 } else { 0 }
+/// Lock the mailbox channel of a slave for one complete exchange.
+async fn mailbox_channel(configured_address: u16) -> smol::lock::MutexGuardArc<MailboxChannel> {
+    let channel = MAILBOX_CHANNELS
+        .lock()
+        .entry(configured_address)
+        .or_insert_with(|| Arc::new(smol::lock::Mutex::new(MailboxChannel::new())))
+        .clone();
+    channel.lock_arc().await
 }
 
-/// Returns the total number of errors recorded since process start.
-#[no_mangle]
-pub extern "C" fn ethercrab_get_error_count() -> u64 {
-    ERROR_RING.lock().total_count
+/// Called after ethercrab used the slave mailboxes on its own (see `MailboxChannel::retry`).
+fn mailbox_channels_invalidate() {
+    MAILBOX_EPOCH.fetch_add(1, Ordering::AcqRel);
 }
 
-/// Get a specific error from the ring buffer as JSON.
-/// index: 0 = oldest available, use ethercrab_get_error_count to find range.
-/// Pass index = -1 (as u32::MAX) for the latest error.
-/// Returns bytes written, or 0 if no error at that index.
-#[no_mangle]
-pub extern "C" fn ethercrab_get_error_detail(buffer: *mut u8, len: usize, index: i32) -> c_int {
-    with_ffi_guard(0, || {
-        if buffer.is_null() || len == 0 {
-            return 0;
+fn encode_mailbox_frame(mailbox_type: u8, counter: u8, payload: &[u8], frame_len: usize) -> Vec<u8> {
+    let mut frame = vec![0u8; frame_len.max(MAILBOX_HEADER_LEN + payload.len())];
+    frame[0..2].copy_from_slice(&(payload.len() as u16).to_le_bytes());
+    // Address (2 bytes) and channel/priority (1 byte) stay zero.
+    frame[5] = (mailbox_type & 0x0F) | ((counter & 0x07) << 4);
+    frame[MAILBOX_HEADER_LEN..MAILBOX_HEADER_LEN + payload.len()].copy_from_slice(payload);
+    frame
+}
+
+fn decode_mailbox_frame(raw: &[u8]) -> Result<MailboxFrame, MailboxError> {
+    if raw.len() < MAILBOX_HEADER_LEN {
+        return Err(MailboxError::Protocol(format!("mailbox frame too short ({} bytes)", raw.len())));
+    }
+    let len = u16::from_le_bytes([raw[0], raw[1]]) as usize;
+    if MAILBOX_HEADER_LEN + len > raw.len() {
+        return Err(MailboxError::Protocol(format!(
+            "mailbox header length {} exceeds SM1 size {}",
+            len,
+            raw.len() - MAILBOX_HEADER_LEN
+        )));
+    }
+    Ok(MailboxFrame {
+        mailbox_type: raw[5] & 0x0F,
+        counter: (raw[5] >> 4) & 0x07,
+        payload: raw[MAILBOX_HEADER_LEN..MAILBOX_HEADER_LEN + len].to_vec(),
+    })
+}
+
+async fn mailbox_config(md: &MainDevice<'_>, configured_address: u16) -> Result<MailboxConfig, MailboxError> {
+    let raw = esc_read(md, configured_address, 0x0800, 16).await?;
+    let cfg = MailboxConfig {
+        write_offset: u16::from_le_bytes([raw[0], raw[1]]),
+        write_len: u16::from_le_bytes([raw[2], raw[3]]),
+        read_offset: u16::from_le_bytes([raw[8], raw[9]]),
+        read_len: u16::from_le_bytes([raw[10], raw[11]]),
+    };
+    if cfg.write_len as usize <= MAILBOX_HEADER_LEN || cfg.read_len as usize <= MAILBOX_HEADER_LEN {
+        return Err(MailboxError::NoMailbox);
+    }
+    Ok(cfg)
+}
+
+/// Poll a sync manager status register until the mailbox-full bit matches `full`.
+async fn wait_sm_status(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    status_register: u16,
+    full: bool,
+    deadline: Instant,
+) -> Result<(), MailboxError> {
+    loop {
+        let status = esc_read(md, configured_address, status_register, 1).await?;
+        if ((status[0] & SM_STATUS_MAILBOX_FULL) != 0) == full {
+            return Ok(());
         }
-        let ring = ERROR_RING.lock();
-        let entry = if index < 0 {
-            ring.latest()
-        } else {
-            ring.get(index as usize)
-        };
-        let entry = match entry {
-            Some(e) => e,
-            None => return 0,
-        };
-        let json = format!(
-            "{{\"code\":{},\"message\":\"{}\",\"context\":{},\"timestamp_ms\":{}}}",
-            entry.code as u8,
-            json_escape(&entry.message),
-            if entry.context_json.is_empty() { "null".to_string() } else { entry.context_json.clone() },
-            entry.timestamp_ms,
-        );
-        let json_bytes = json.as_bytes();
-        let to_copy = json_bytes.len().min(len);
-        unsafe {
-            std::ptr::copy_nonoverlapping(json_bytes.as_ptr(), buffer, to_copy);
+        if Instant::now() >= deadline {
+            return Err(MailboxError::Timeout);
         }
-        to_copy as c_int
-    })
+        smol::Timer::after(MAILBOX_POLL_DELAY).await;
+    }
 }
 
-#[no_mangle]
-pub extern "C" fn is_raw_socket_available() -> c_int {
-    with_ffi_guard(0, || {
-        #[cfg(target_os = "linux")]
-        unsafe {
-            let fd = libc::socket(libc::AF_PACKET, libc::SOCK_RAW, 0);
-            if fd >= 0 { 
-                libc::close(fd); 
-                return 1; 
-            }
-            return 0;
+/// Write one frame into SM0 once the slave has consumed the previous one.
+async fn mailbox_send(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    cfg: &MailboxConfig,
+    mailbox_type: u8,
+    counter: u8,
+    payload: &[u8],
+    deadline: Instant,
+) -> Result<(), MailboxError> {
+    if payload.len() > cfg.write_capacity() {
+        return Err(MailboxError::TooLarge { len: payload.len(), capacity: cfg.write_capacity() });
+    }
+    wait_sm_status(md, configured_address, 0x0805, false, deadline).await?;
+    // The whole SM0 area must be written so the last byte triggers the buffer hand-over.
+    let frame = encode_mailbox_frame(mailbox_type, counter, payload, cfg.write_len as usize);
+    esc_write(md, configured_address, cfg.write_offset, &frame).await?;
+    Ok(())
+}
+
+/// Ask the slave to put the last SM1 frame back after a lost read (ETG1000.4 §5.6.2.2.2):
+/// toggle the repeat request bit, wait for the matching ack, then for SM1 to fill again.
+async fn mailbox_repeat_request(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    deadline: Instant,
+) -> Result<(), MailboxError> {
+    let activate = esc_read(md, configured_address, 0x080E, 1).await?[0] ^ SM_REPEAT_BIT;
+    esc_write(md, configured_address, 0x080E, &[activate]).await?;
+    loop {
+        let pdi_control = esc_read(md, configured_address, 0x080F, 1).await?[0];
+        if (pdi_control & SM_REPEAT_BIT) == (activate & SM_REPEAT_BIT) {
+            break;
+        }
+        if Instant::now() >= deadline {
+            return Err(MailboxError::Timeout);
         }
+        smol::Timer::after(MAILBOX_POLL_DELAY).await;
+    }
+    wait_sm_status(md, configured_address, 0x080D, true, deadline).await
+}
 
-        #[cfg(target_os = "macos")]
-        unsafe {
-            for i in 0..256 {
-                let dev = format!("/dev/bpf{}\0", i);
-                match libc::open(dev.as_ptr() as *const libc::c_char, libc::O_RDWR | libc::O_NONBLOCK) {
-                    -1 => continue,
-                    fd => {
-                        libc::close(fd);
-                        return 1;
-                    }
-                }
+/// Read the complete SM1 frame (reading the last byte frees the buffer). A failed
+/// read is recovered with a repeat request, up to `MAILBOX_RESILIENT_ATTEMPTS` times.
+async fn mailbox_read_sm1(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    cfg: &MailboxConfig,
+    deadline: Instant,
+) -> Result<MailboxFrame, MailboxError> {
+    let mut attempt = 1;
+    loop {
+        match esc_read(md, configured_address, cfg.read_offset, cfg.read_len).await {
+            Ok(raw) => {
+                let frame = decode_mailbox_frame(&raw)?;
+                MAILBOX_RX_COUNTERS.lock().insert(configured_address, frame.counter);
+                return Ok(frame);
             }
-            return 0;
-        }
-        
-        #[cfg(target_os = "windows")]
-        {
-            use pnet_datalink;
-            let interfaces = pnet_datalink::interfaces();
-            if interfaces.is_empty() { return 0; }
-            if let Some(interface) = interfaces.first() {
-                 if let Ok(cap_builder) = pcap::Capture::from_device(interface.name.as_str()) {
-                     if cap_builder.open().is_ok() { return 1; }
-                 }
+            Err(_) if attempt < MAILBOX_RESILIENT_ATTEMPTS => {
+                attempt += 1;
+                mailbox_repeat_request(md, configured_address, deadline).await?;
             }
-            return 0;
+            Err(e) => return Err(e.into()),
         }
+    }
+}
 
-        #[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
-        return 0;
-    })
+/// Whether a frame repeats the last one received from the slave. Slaves that support
+/// repeat detection number their frames 1..=7; counter 0 means it is not supported.
+fn is_repeated_frame(counter: u8, last_counter: Option<u8>) -> bool {
+    counter != 0 && last_counter == Some(counter)
 }
 
-#[no_mangle]
-pub extern "C" fn ethercrab_init(
-    interface: *const c_char,
-    _expected_slaves: *const SlaveIdentity,
-    _expected_count: usize,
-    init_commands: *const FfiInitCommand,
-    init_command_count: usize,
-    pdu_timeout_ms: u64,
-    state_transition_timeout_ms: u64,
-    mailbox_response_timeout_ms: u64,
-    eeprom_timeout_ms: u64,
-    pdu_retries: usize,
-) -> c_int {
-    with_ffi_guard(-1, || {
-        if interface.is_null() { return -1; }
-        
-        // Idempotency check: if already initialized, return 0
-        if STATE.read().is_some() {
-            return 0;
+/// Wait for the next SM1 frame, discarding frames that carry the same mailbox counter
+/// as the previous one (re-delivered after a repeat request). The frame is returned
+/// as-is, including mailbox error replies (type 0x00).
+async fn mailbox_receive_raw(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    cfg: &MailboxConfig,
+    deadline: Instant,
+) -> Result<MailboxFrame, MailboxError> {
+    let mut repeated = 0;
+    loop {
+        wait_sm_status(md, configured_address, 0x080D, true, deadline).await?;
+        let last_counter = MAILBOX_RX_COUNTERS.lock().get(&configured_address).copied();
+        let frame = mailbox_read_sm1(md, configured_address, cfg, deadline).await?;
+        if !is_repeated_frame(frame.counter, last_counter) {
+            return Ok(frame);
         }
+        repeated += 1;
+        if repeated >= MAILBOX_RESILIENT_ATTEMPTS {
+            return Err(MailboxError::Stale);
+        }
+        smol::Timer::after(MAILBOX_POLL_DELAY).await;
+    }
+}
 
-        let interface_str = unsafe {
-            match CStr::from_ptr(interface).to_str() {
-                Ok(s) => s.to_string(),
-                Err(e) => {
-                    set_error_ctx(FfiErrorCode::InvalidArgument, format!("Invalid interface string: {}", e), &[("op", "init")]);
-                    return -2;
-                }
-            }
-        };
+/// Wait for SM1 to fill and read the complete frame, mapping error replies to `ErrorReply`.
+async fn mailbox_receive(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    cfg: &MailboxConfig,
+    deadline: Instant,
+) -> Result<MailboxFrame, MailboxError> {
+    wait_sm_status(md, configured_address, 0x080D, true, deadline).await?;
+    let frame = mailbox_read_sm1(md, configured_address, cfg, deadline).await?;
+    if frame.mailbox_type == MAILBOX_TYPE_ERR {
+        let detail = frame.payload.get(2..4).map(|b| u16::from_le_bytes([b[0], b[1]])).unwrap_or(0);
+        return Err(MailboxError::ErrorReply(detail));
+    }
+    Ok(frame)
+}
 
-        let cmds = if !init_commands.is_null() && init_command_count > 0 {
-            unsafe { std::slice::from_raw_parts(init_commands, init_command_count).to_vec() }
-        } else {
-            Vec::new()
-        };
+/// Handle a frame that is not the response an exchange waits for. CoE emergencies
+/// are kept for `ethercrab_get_last_emergency`; anything else is dropped.
+fn mailbox_unsolicited(configured_address: u16, frame: &MailboxFrame) {
+    if frame.mailbox_type == MAILBOX_TYPE_COE && coe_service(&frame.payload) == Some(COE_SERVICE_EMERGENCY) {
+        store_emergency(configured_address, &frame.payload);
+    }
+}
 
-        // Build custom Timeouts from parameters
-        let timeouts = Timeouts {
-            pdu: Duration::from_millis(pdu_timeout_ms),
-            state_transition: Duration::from_millis(state_transition_timeout_ms),
-            mailbox_response: Duration::from_millis(mailbox_response_timeout_ms),
-            // Configurable eeprom timeout
-            eeprom: Duration::from_millis(eeprom_timeout_ms),
-            wait_loop_delay: Duration::from_millis(0),
-            mailbox_echo: Duration::from_millis(100),
-        };
+// --- SoE: Servo Profile over EtherCAT (IEC 61800-7-304, ETG1000.6 §5.8) ---
+
+const SOE_HEADER_LEN: usize = 4;
+const SOE_OPCODE_READ_REQ: u8 = 0x01;
+const SOE_OPCODE_READ_RES: u8 = 0x02;
+const SOE_OPCODE_WRITE_REQ: u8 = 0x03;
+const SOE_OPCODE_WRITE_RES: u8 = 0x04;
+
+/// IDN element selectors (SoE header byte 1).
+pub const SOE_ELEMENT_DATA_STATE: u8 = 0x01;
+pub const SOE_ELEMENT_NAME: u8 = 0x02;
+pub const SOE_ELEMENT_ATTRIBUTE: u8 = 0x04;
+pub const SOE_ELEMENT_UNIT: u8 = 0x08;
+pub const SOE_ELEMENT_MIN: u8 = 0x10;
+pub const SOE_ELEMENT_MAX: u8 = 0x20;
+pub const SOE_ELEMENT_VALUE: u8 = 0x40;
+pub const SOE_ELEMENT_DEFAULT: u8 = 0x80;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct SoeHeader {
+    opcode: u8,
+    incomplete: bool,
+    error: bool,
+    drive_no: u8,
+    elements: u8,
+    /// IDN, or the number of fragments left while `incomplete` is set.
+    idn: u16,
+}
 
-        // Run purely on this thread. smol::block_on spins a local executor.
-        let result = smol::block_on(async move {
-            // Reset health status
-            NETWORK_HEALTHY.store(true, Ordering::Relaxed);
+impl SoeHeader {
+    fn encode(&self) -> [u8; SOE_HEADER_LEN] {
+        let idn = self.idn.to_le_bytes();
+        [
+            (self.opcode & 0x07)
+                | ((self.incomplete as u8) << 3)
+                | ((self.error as u8) << 4)
+                | ((self.drive_no & 0x07) << 5),
+            self.elements,
+            idn[0],
+            idn[1],
+        ]
+    }
 
-            #[cfg(target_os = "windows")]
-            let maindevice = {
-                let guard = GLOBAL_DEVICE.read();
-                if let Some(md) = guard.as_ref() {
-                    // Reuse existing maindevice - TX_RX_RESOURCES already has the thread info
-                    md.clone()
-                } else {
-                    drop(guard); // Release read lock before acquiring write lock
+    fn decode(raw: &[u8]) -> Option<Self> {
+        if raw.len() < SOE_HEADER_LEN {
+            return None;
+        }
+        Some(Self {
+            opcode: raw[0] & 0x07,
+            incomplete: (raw[0] & 0x08) != 0,
+            error: (raw[0] & 0x10) != 0,
+            drive_no: (raw[0] >> 5) & 0x07,
+            elements: raw[1],
+            idn: u16::from_le_bytes([raw[2], raw[3]]),
+        })
+    }
+}
 
-                    // Double-check locking pattern
-                    let mut guard = GLOBAL_DEVICE.write();
-                    // Check if someone else initialized while we were waiting
-                    if let Some(md) = guard.as_ref() {
-                        md.clone()
-                    } else {
-                        // Create storage on heap and convert to raw pointer to manage lifetime manually
-                        let storage_box = Box::new(PduStorage::<MAX_FRAMES, MAX_PDU_DATA>::new());
-                        let ptr = Box::into_raw(storage_box);
-                        let storage_ptr_val = ptr as usize;
-                        let storage = unsafe { &mut *ptr };
+/// Operation data length in bytes encoded in an IDN attribute (bits 16-18).
+/// Returns `None` for variable-length (list/string) IDNs.
+fn soe_value_len_from_attribute(attribute: u32) -> Option<usize> {
+    match (attribute >> 16) & 0x07 {
+        0b001 => Some(2),
+        0b010 => Some(4),
+        0b011 => Some(8),
+        _ => None,
+    }
+}
 
-                        let (mut tx, mut rx, pdu_loop) = storage.try_split().map_err(|_| {
-                            set_error("Failed to split PDU storage - likely already in use");
-                            -3
-                        })?;
+fn soe_check_response(payload: &[u8], expected_opcode: u8) -> Result<SoeHeader, MailboxError> {
+    let header = SoeHeader::decode(payload)
+        .ok_or_else(|| MailboxError::Protocol("SoE response shorter than header".to_string()))?;
+    if header.error {
+        let code = payload
+            .get(SOE_HEADER_LEN..SOE_HEADER_LEN + 2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+            .unwrap_or(0);
+        return Err(MailboxError::Soe(code));
+    }
+    if header.opcode != expected_opcode {
+        return Err(MailboxError::Protocol(format!(
+            "unexpected SoE opcode {} (expected {})",
+            header.opcode, expected_opcode
+        )));
+    }
+    Ok(header)
+}
 
-                        let maindevice = Arc::new(MainDevice::new(
-                            pdu_loop,
-                            timeouts,
-                            MainDeviceConfig {
-                                dc_static_sync_iterations: 0,  // Disable DC to avoid timeouts
-                                retry_behaviour: ethercrab::RetryBehaviour::Count(pdu_retries),
-                                ..MainDeviceConfig::default()
-                            },
-                        ));
+/// Wait for the next SoE frame, passing other mailbox traffic (e.g. CoE emergencies)
+/// to `mailbox_unsolicited`.
+async fn soe_receive(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    cfg: &MailboxConfig,
+    deadline: Instant,
+) -> Result<Vec<u8>, MailboxError> {
+    loop {
+        let frame = mailbox_receive(md, configured_address, cfg, deadline).await?;
+        if frame.mailbox_type == MAILBOX_TYPE_SOE {
+            return Ok(frame.payload);
+        }
+        mailbox_unsolicited(configured_address, &frame);
+    }
+}
 
-                        // Spawn the network TX/RX task on a dedicated thread.
-                        let iface = interface_str.clone();
-                        let shutdown = Arc::new(AtomicBool::new(false));
-                        let shutdown_clone = shutdown.clone();
+/// Read one or more elements of an IDN, reassembling fragmented responses.
+async fn soe_read(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    drive_no: u8,
+    idn: u16,
+    elements: u8,
+    timeout: Duration,
+) -> Result<Vec<u8>, MailboxError> {
+    let mut channel = mailbox_channel(configured_address).await;
+    let cfg = mailbox_config(md, configured_address).await?;
+    loop {
+        let deadline = Instant::now() + timeout;
+        let result = soe_read_once(md, configured_address, &cfg, &mut channel, drive_no, idn, elements, deadline).await;
+        if !channel.retry(&result) {
+            return result;
+        }
+    }
+}
 
-                        // Validate interface before spawning thread
-                        validate_windows_interface(&iface)?;
+#[allow(clippy::too_many_arguments)]
+async fn soe_read_once(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    cfg: &MailboxConfig,
+    channel: &mut MailboxChannel,
+    drive_no: u8,
+    idn: u16,
+    elements: u8,
+    deadline: Instant,
+) -> Result<Vec<u8>, MailboxError> {
+    let request = SoeHeader {
+        opcode: SOE_OPCODE_READ_REQ,
+        incomplete: false,
+        error: false,
+        drive_no,
+        elements,
+        idn,
+    };
+    let counter = channel.next_counter();
+    mailbox_send(md, configured_address, cfg, MAILBOX_TYPE_SOE, counter, &request.encode(), deadline).await?;
+
+    let mut data = Vec::new();
+    loop {
+        let payload = soe_receive(md, configured_address, cfg, deadline).await?;
+        let header = soe_check_response(&payload, SOE_OPCODE_READ_RES)?;
+        data.extend_from_slice(&payload[SOE_HEADER_LEN..]);
+        if !header.incomplete {
+            return Ok(data);
+        }
+    }
+}
 
-                        let handle = std::thread::Builder::new()
-                            .name("ethercrab-tx-rx".into())
-                            .stack_size(8 * 1024 * 1024)
-                            .spawn(move || {
-                                let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                                    windows_tx_rx_loop!(iface.as_str(), tx, rx, shutdown_clone);
-                                }));
+/// Write one element of an IDN, fragmenting the data if it does not fit into SM0.
+async fn soe_write(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    drive_no: u8,
+    idn: u16,
+    elements: u8,
+    data: &[u8],
+    timeout: Duration,
+) -> Result<(), MailboxError> {
+    let mut channel = mailbox_channel(configured_address).await;
+    let cfg = mailbox_config(md, configured_address).await?;
+    loop {
+        let deadline = Instant::now() + timeout;
+        let result = soe_write_once(md, configured_address, &cfg, &mut channel, drive_no, idn, elements, data, deadline).await;
+        if !channel.retry(&result) {
+            return result;
+        }
+    }
+}
 
-                                if let Err(panic) = result {
-                                    set_error(format!("TX/RX thread panicked: {}", panic_message(&panic)));
-                                    NETWORK_HEALTHY.store(false, Ordering::Relaxed);
-                                }
-                            })
-                            .map_err(|e| {
-                                set_error(format!("Failed to spawn TX/RX thread: {}", e));
-                                -3
-                            })?;
+#[allow(clippy::too_many_arguments)]
+async fn soe_write_once(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    cfg: &MailboxConfig,
+    channel: &mut MailboxChannel,
+    drive_no: u8,
+    idn: u16,
+    elements: u8,
+    data: &[u8],
+    deadline: Instant,
+) -> Result<(), MailboxError> {
+    let chunk_len = cfg.write_capacity().saturating_sub(SOE_HEADER_LEN);
+    if chunk_len == 0 {
+        return Err(MailboxError::TooLarge { len: data.len(), capacity: 0 });
+    }
+    let chunks: Vec<&[u8]> = if data.is_empty() { vec![&[][..]] } else { data.chunks(chunk_len).collect() };
+    let total = chunks.len();
+
+    for (i, chunk) in chunks.into_iter().enumerate() {
+        let fragments_left = total - i - 1;
+        let header = SoeHeader {
+            opcode: SOE_OPCODE_WRITE_REQ,
+            incomplete: fragments_left > 0,
+            error: false,
+            drive_no,
+            elements,
+            // Intermediate fragments carry the remaining fragment count instead of the IDN
+            idn: if fragments_left > 0 { fragments_left as u16 } else { idn },
+        };
+        let mut payload = Vec::with_capacity(SOE_HEADER_LEN + chunk.len());
+        payload.extend_from_slice(&header.encode());
+        payload.extend_from_slice(chunk);
+        let counter = channel.next_counter();
+        mailbox_send(md, configured_address, cfg, MAILBOX_TYPE_SOE, counter, &payload, deadline).await?;
+    }
 
-                        // Store TX/RX resources globally for cleanup
-                        *TX_RX_RESOURCES.lock() = Some(TxRxResources {
-                            thread_handle: Some(handle),
-                            shutdown_signal: shutdown,
-                            storage_ptr: storage_ptr_val,
-                        });
+    let payload = soe_receive(md, configured_address, cfg, deadline).await?;
+    soe_check_response(&payload, SOE_OPCODE_WRITE_RES)?;
+    Ok(())
+}
 
-                        *guard = Some(maindevice.clone());
-                        
-                        // Give the TX/RX thread time to start up and open the interface
-                        std::thread::sleep(Duration::from_millis(100));
-                        
-                        maindevice
-                    }
-                }
-            };
+/// Write an IDN value from an init command, sizing it from the IDN attribute.
+async fn soe_write_init_value(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    drive_no: u8,
+    idn: u16,
+    value: [u8; 4],
+    timeout: Duration,
+) -> Result<(), MailboxError> {
+    let attribute = soe_read(md, configured_address, drive_no, idn, SOE_ELEMENT_ATTRIBUTE, timeout).await?;
+    let len = attribute
+        .get(0..4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .and_then(soe_value_len_from_attribute)
+        .unwrap_or(4);
+    if len > value.len() {
+        return Err(MailboxError::Protocol(format!(
+            "IDN 0x{:04X} holds {} bytes, init commands carry at most {}",
+            idn,
+            len,
+            value.len()
+        )));
+    }
+    soe_write(md, configured_address, drive_no, idn, SOE_ELEMENT_VALUE, &value[..len], timeout).await
+}
 
-            #[cfg(not(target_os = "windows"))]
-            let maindevice = {
-                let guard = GLOBAL_DEVICE.read();
-                if let Some(md) = guard.as_ref() {
-                    // Reuse existing maindevice - TX_RX_RESOURCES already has the thread info
-                    md.clone()
-                } else {
-                    drop(guard); // Release read lock before acquiring write lock
+// --- CoE SDO Transfer (ETG1000.6 §5.6.2) ---
+//
+// Every SDO the FFI issues goes through this client rather than ethercrab's
+// `sdo_read`/`sdo_write`, so it shares the slave's mailbox channel (lock and
+// counter) with SDO Information, SoE and raw mailbox traffic.
+
+const COE_SERVICE_EMERGENCY: u16 = 0x01;
+const COE_SERVICE_SDO_REQ: u16 = 0x02;
+const COE_SERVICE_SDO_RES: u16 = 0x03;
+/// CoE header (2) + SDO command (1) + index (2) + subindex (1) + data or complete size (4).
+const SDO_HEADER_LEN: usize = 10;
+const SDO_CMD_UPLOAD_REQ: u8 = 0x40;
+const SDO_CMD_UPLOAD_SEGMENT_REQ: u8 = 0x60;
+/// Normal download, complete size indicated.
+const SDO_CMD_DOWNLOAD_REQ: u8 = 0x21;
+/// Expedited download, size indicated; bits 2-3 hold the number of unused data bytes.
+const SDO_CMD_EXPEDITED_DOWNLOAD_REQ: u8 = 0x23;
+const SDO_CMD_ABORT: u8 = 0x80;
+const SDO_SCS_MASK: u8 = 0xE0;
+const SDO_SCS_UPLOAD_SEGMENT: u8 = 0x00;
+const SDO_SCS_UPLOAD: u8 = 0x40;
+const SDO_SCS_DOWNLOAD: u8 = 0x60;
+
+/// CoE service number (header bits 12-15) of a CoE mailbox payload.
+fn coe_service(payload: &[u8]) -> Option<u16> {
+    payload.get(0..2).map(|b| u16::from_le_bytes([b[0], b[1]]) >> 12)
+}
 
-                    // Double-check locking pattern
-                    let mut guard = GLOBAL_DEVICE.write();
-                    // Check if someone else initialized while we were waiting
-                    if let Some(md) = guard.as_ref() {
-                        md.clone()
-                    } else {
-                        // Create storage on heap and convert to raw pointer to manage lifetime manually
-                        let storage_box = Box::new(PduStorage::<MAX_FRAMES, MAX_PDU_DATA>::new());
-                        let ptr = Box::into_raw(storage_box);
-                        let storage_ptr_val = ptr as usize;
-                        let storage = unsafe { &mut *ptr };
+fn encode_sdo_request(command: u8, index: u16, sub_index: u8, data: &[u8]) -> Vec<u8> {
+    let mut request = Vec::with_capacity(SDO_HEADER_LEN + data.len());
+    request.extend_from_slice(&(COE_SERVICE_SDO_REQ << 12).to_le_bytes());
+    request.push(command);
+    request.extend_from_slice(&index.to_le_bytes());
+    request.push(sub_index);
+    request.extend_from_slice(data);
+    request.resize(request.len().max(SDO_HEADER_LEN), 0);
+    request
+}
 
-                        let (tx, rx, pdu_loop) = storage.try_split().map_err(|_| {
-                            set_error("Failed to split PDU storage - likely already in use");
-                            -3
-                        })?;
+/// Request frame for downloading `data`: expedited up to 4 bytes, normal otherwise.
+fn encode_sdo_download(index: u16, sub_index: u8, data: &[u8]) -> Vec<u8> {
+    if data.len() <= 4 {
+        let mut value = [0u8; 4];
+        value[..data.len()].copy_from_slice(data);
+        let command = SDO_CMD_EXPEDITED_DOWNLOAD_REQ | (((4 - data.len()) as u8) << 2);
+        return encode_sdo_request(command, index, sub_index, &value);
+    }
+    let mut body = Vec::with_capacity(4 + data.len());
+    body.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    body.extend_from_slice(data);
+    encode_sdo_request(SDO_CMD_DOWNLOAD_REQ, index, sub_index, &body)
+}
 
-                        let maindevice = Arc::new(MainDevice::new(
-                            pdu_loop,
-                            timeouts,
-                            MainDeviceConfig {
-                                dc_static_sync_iterations: 0,  // Disable DC to avoid timeouts
-                                retry_behaviour: ethercrab::RetryBehaviour::Count(pdu_retries),
-                                ..MainDeviceConfig::default()
-                            },
-                        ));
+/// SDO command byte of a response, mapping abort transfers to `SdoAbort`.
+fn sdo_response_command(payload: &[u8], expected_scs: u8) -> Result<u8, MailboxError> {
+    if payload.len() < SDO_HEADER_LEN {
+        return Err(MailboxError::Protocol(format!("SDO response too short ({} bytes)", payload.len())));
+    }
+    let command = payload[2];
+    if command == SDO_CMD_ABORT {
+        return Err(MailboxError::SdoAbort(u32::from_le_bytes([payload[6], payload[7], payload[8], payload[9]])));
+    }
+    if (command & SDO_SCS_MASK) != expected_scs {
+        return Err(MailboxError::Protocol(format!("unexpected SDO response command 0x{:02X}", command)));
+    }
+    Ok(command)
+}
 
-                        // Spawn the network TX/RX task on a dedicated thread.
-                        let iface = interface_str.clone();
-                        let shutdown = Arc::new(AtomicBool::new(false));
-                        let shutdown_clone = shutdown.clone();
+/// Check that an initiate response answers the object that was requested.
+fn sdo_check_object(payload: &[u8], index: u16, sub_index: u8) -> Result<(), MailboxError> {
+    let (res_index, res_sub_index) = (u16::from_le_bytes([payload[3], payload[4]]), payload[5]);
+    if (res_index, res_sub_index) != (index, sub_index) {
+        return Err(MailboxError::Protocol(format!(
+            "SDO response for 0x{:04X}:{} (expected 0x{:04X}:{})",
+            res_index, res_sub_index, index, sub_index
+        )));
+    }
+    Ok(())
+}
 
-                        let handle = std::thread::Builder::new()
-                            .name("ethercrab-tx-rx".into())
-                            .stack_size(8 * 1024 * 1024)
-                            .spawn(move || {
-                                let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                                    // Use tx_rx_task which returns a Result<Future, Error>
-                                    match ethercrab::std::tx_rx_task(&iface, tx, rx) {
-                                        Ok(task) => {
-                                            // Map task output to () so types match with shutdown_fut
-                                            let task = async move {
-                                                task.await.map(|_| ())
-                                            };
-                                            let shutdown_fut = async {
-                                                while !shutdown_clone.load(Ordering::SeqCst) {
-                                                    smol::Timer::after(Duration::from_millis(50)).await;
-                                                }
-                                                Ok::<(), ethercrab::error::Error>(())
-                                            };
-                                            
-                                            match smol::block_on(future::race(task, shutdown_fut)) {
-                                                Ok(_) => {},
-                                                Err(e) => set_error(format!("TX/RX loop failed: {}", e)),
-                                            }
-                                        },
-                                        Err(e) => {
-                                            set_error(format!("Failed to create tx_rx task: {}", e));
-                                        }
-                                    }
-                                }));
+/// Decode an upload initiate response into (complete size, data carried in this frame).
+fn decode_sdo_upload_response(payload: &[u8], index: u16, sub_index: u8) -> Result<(usize, Vec<u8>), MailboxError> {
+    let command = sdo_response_command(payload, SDO_SCS_UPLOAD)?;
+    sdo_check_object(payload, index, sub_index)?;
+    let expedited = (command & 0x02) != 0;
+    let size_indicated = (command & 0x01) != 0;
+    if expedited {
+        let len = if size_indicated { 4 - ((command >> 2) & 0x03) as usize } else { 4 };
+        return Ok((len, payload[6..6 + len].to_vec()));
+    }
+    let size = u32::from_le_bytes([payload[6], payload[7], payload[8], payload[9]]) as usize;
+    Ok((size, payload[SDO_HEADER_LEN..].iter().take(size).copied().collect()))
+}
 
-                                if let Err(panic) = result {
-                                    set_error(format!("TX/RX thread panicked: {}", panic_message(&panic)));
-                                    NETWORK_HEALTHY.store(false, Ordering::Relaxed);
-                                }
-                            })
-                            .map_err(|e| {
-                                set_error(format!("Failed to spawn TX/RX thread: {}", e));
-                                -3
-                            })?;
+/// Decode an upload segment response into (last segment, segment data).
+fn decode_sdo_segment_response(payload: &[u8], toggle: bool) -> Result<(bool, &[u8]), MailboxError> {
+    let command = sdo_response_command(payload, SDO_SCS_UPLOAD_SEGMENT)?;
+    if ((command & 0x10) != 0) != toggle {
+        return Err(MailboxError::Protocol("SDO segment toggle bit mismatch".to_string()));
+    }
+    // A minimum-length segment pads its data to 7 bytes; bits 1-3 give the unused count
+    let data = if payload.len() == SDO_HEADER_LEN {
+        &payload[3..SDO_HEADER_LEN - ((command >> 1) & 0x07) as usize]
+    } else {
+        &payload[3..]
+    };
+    Ok(((command & 0x01) != 0, data))
+}
 
-                        // Store TX/RX resources globally for cleanup
-                        *TX_RX_RESOURCES.lock() = Some(TxRxResources {
-                            thread_handle: Some(handle),
-                            shutdown_signal: shutdown,
-                            storage_ptr: storage_ptr_val,
-                        });
+/// Wait for the next CoE SDO response frame, passing other traffic to `mailbox_unsolicited`.
+async fn coe_sdo_receive(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    cfg: &MailboxConfig,
+    deadline: Instant,
+) -> Result<Vec<u8>, MailboxError> {
+    loop {
+        let frame = mailbox_receive(md, configured_address, cfg, deadline).await?;
+        if frame.mailbox_type == MAILBOX_TYPE_COE && coe_service(&frame.payload) == Some(COE_SERVICE_SDO_RES) {
+            return Ok(frame.payload);
+        }
+        mailbox_unsolicited(configured_address, &frame);
+    }
+}
 
-                        *guard = Some(maindevice.clone());
-                        
-                        // Give the TX/RX thread time to start up and open the interface
-                        std::thread::sleep(Duration::from_millis(100));
-                        
-                        maindevice
-                    }
-                }
-            };
+/// Upload (read) an object entry: expedited, normal or segmented, as the slave answers.
+async fn coe_sdo_upload(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    index: u16,
+    sub_index: u8,
+    timeout: Duration,
+) -> Result<Vec<u8>, MailboxError> {
+    let mut channel = mailbox_channel(configured_address).await;
+    let cfg = mailbox_config(md, configured_address).await?;
+    loop {
+        let deadline = Instant::now() + timeout;
+        let result = coe_sdo_upload_once(md, configured_address, &cfg, &mut channel, index, sub_index, deadline).await;
+        if !channel.retry(&result) {
+            return result;
+        }
+    }
+}
 
-            // Init Group
-            let group = maindevice.init_single_group::<MAX_SUBDEVICES, MAX_PDI>(ethercat_now)
-                .await.map_err(|e| {
-                    set_error_ctx(
-                        FfiErrorCode::NetworkError,
-                        format!("Failed to init single group: {:?}", e),
-                        &[("op", "init"), ("step", "init_single_group"), ("interface", &interface_str), ("error_detail", &format!("{:?}", e)), ("suggestion", "Check network interface name, cable connections, and that slaves are powered on")],
-                    );
-                    -5
-                })?;
+async fn coe_sdo_upload_once(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    cfg: &MailboxConfig,
+    channel: &mut MailboxChannel,
+    index: u16,
+    sub_index: u8,
+    deadline: Instant,
+) -> Result<Vec<u8>, MailboxError> {
+    let request = encode_sdo_request(SDO_CMD_UPLOAD_REQ, index, sub_index, &[]);
+    mailbox_send(md, configured_address, cfg, MAILBOX_TYPE_COE, channel.next_counter(), &request, deadline).await?;
+    let payload = coe_sdo_receive(md, configured_address, cfg, deadline).await?;
+    let (size, mut data) = decode_sdo_upload_response(&payload, index, sub_index)?;
+
+    let mut toggle = false;
+    while data.len() < size {
+        let request = encode_sdo_request(SDO_CMD_UPLOAD_SEGMENT_REQ | ((toggle as u8) << 4), 0, 0, &[]);
+        mailbox_send(md, configured_address, cfg, MAILBOX_TYPE_COE, channel.next_counter(), &request, deadline).await?;
+        let payload = coe_sdo_receive(md, configured_address, cfg, deadline).await?;
+        let (last, chunk) = decode_sdo_segment_response(&payload, toggle)?;
+        data.extend_from_slice(chunk);
+        if last {
+            break;
+        }
+        toggle = !toggle;
+    }
+    data.truncate(size);
+    Ok(data)
+}
 
+/// Download (write) an object entry. Values must fit into one SM0 frame.
+async fn coe_sdo_download(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    index: u16,
+    sub_index: u8,
+    data: &[u8],
+    timeout: Duration,
+) -> Result<(), MailboxError> {
+    let mut channel = mailbox_channel(configured_address).await;
+    let cfg = mailbox_config(md, configured_address).await?;
+    let request = encode_sdo_download(index, sub_index, data);
+    loop {
+        let deadline = Instant::now() + timeout;
+        let result = async {
+            mailbox_send(md, configured_address, &cfg, MAILBOX_TYPE_COE, channel.next_counter(), &request, deadline).await?;
+            let payload = coe_sdo_receive(md, configured_address, &cfg, deadline).await?;
+            sdo_response_command(&payload, SDO_SCS_DOWNLOAD)?;
+            sdo_check_object(&payload, index, sub_index)
+        }
+        .await;
+        if !channel.retry(&result) {
+            return result;
+        }
+    }
+}
 
-            // Run Init Commands
-            for cmd in cmds {
-                let slave_idx = cmd.slave_index as usize;
-                if let Some(subdevice) = group.iter(&maindevice).nth(slave_idx) {
-                    let val = u32_from_bytes(cmd.value);
-                    if cmd.command_type == 0 {
-                        let _ = subdevice.sdo_write(cmd.index, cmd.sub_index, val).await;
-                    } else {
-                        let _ = subdevice.register_write(cmd.index, val).await;
-                    }
-                }
-            }
+/// Upload an unsigned value of up to 32 bits (counts, PDO indices, mapping entries).
+async fn coe_read_uint(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    index: u16,
+    sub_index: u8,
+    timeout: Duration,
+) -> Result<u32, MailboxError> {
+    let data = coe_sdo_upload(md, configured_address, index, sub_index, timeout).await?;
+    let mut value = [0u8; 4];
+    for (dst, src) in value.iter_mut().zip(&data) {
+        *dst = *src;
+    }
+    Ok(u32::from_le_bytes(value))
+}
 
-            let pdi_buffer = Arc::new(RwLock::new([0u8; MAX_PDI]));
-            
-            let master_state = EcMasterState {
-                maindevice: maindevice.clone(),
-                group: Some(GroupState::PreOp(group)),
-                pdi_buffer,
-                pdi_size: 0,
-                input_size: 0,
-                output_size: 0,
-                expected_wkc: 0,
-                mailbox_poll_interval_ms: None,
-                pdu_timeout_ms,
-            };
+// --- CoE SDO Information (ETG1000.6 §5.6.3) ---
+
+const COE_SERVICE_SDO_INFO: u16 = 0x08;
+const SDO_INFO_HEADER_LEN: usize = 6; // CoE header (2) + SDO Info header (4)
+const SDO_INFO_GET_OD_LIST_REQ: u8 = 0x01;
+const SDO_INFO_GET_OD_LIST_RES: u8 = 0x02;
+const SDO_INFO_LIST_ALL_OBJECTS: u16 = 0x0001;
+const SDO_INFO_GET_OD_REQ: u8 = 0x03;
+const SDO_INFO_GET_OD_RES: u8 = 0x04;
+const SDO_INFO_GET_ED_REQ: u8 = 0x05;
+const SDO_INFO_GET_ED_RES: u8 = 0x06;
+const SDO_INFO_ERROR: u8 = 0x07;
+/// General category CoE details bit 1: SDO Information supported.
+const COE_DETAILS_SDO_INFO: u8 = 0x02;
+
+/// Result of an SDO Information "Get Entry Description" request.
+#[derive(Clone, Debug)]
+struct SdoEntryDescription {
+    data_type: u16,
+    bit_len: u16,
+    access: ObjectAccess,
+    name: String,
+}
 
-            let mut guard = STATE.write();
-            *guard = Some(master_state);
-            Ok(0)
-        });
+fn encode_sdo_info_request(opcode: u8, body: &[u8]) -> Vec<u8> {
+    let mut request = Vec::with_capacity(SDO_INFO_HEADER_LEN + body.len());
+    request.extend_from_slice(&(COE_SERVICE_SDO_INFO << 12).to_le_bytes());
+    request.extend_from_slice(&[opcode & 0x7F, 0x00, 0x00, 0x00]);
+    request.extend_from_slice(body);
+    request
+}
 
-        match result {
-            Ok(v) => v,
-            Err(e) => e,
-        }
+/// Split an SDO Information response into (opcode, incomplete, data).
+/// Returns `None` for CoE frames of other services (e.g. emergencies).
+fn decode_sdo_info_response(payload: &[u8]) -> Option<(u8, bool, &[u8])> {
+    if payload.len() < SDO_INFO_HEADER_LEN {
+        return None;
+    }
+    let service = u16::from_le_bytes([payload[0], payload[1]]) >> 12;
+    if service != COE_SERVICE_SDO_INFO {
+        return None;
+    }
+    Some((payload[2] & 0x7F, (payload[2] & 0x80) != 0, &payload[SDO_INFO_HEADER_LEN..]))
+}
+
+/// Entry description data: index, subindex, value info, data type, bit length,
+/// object access, then the name (no value info requested).
+fn parse_sdo_entry_description(data: &[u8]) -> Option<SdoEntryDescription> {
+    if data.len() < 10 {
+        return None;
+    }
+    Some(SdoEntryDescription {
+        data_type: u16::from_le_bytes([data[4], data[5]]),
+        bit_len: u16::from_le_bytes([data[6], data[7]]),
+        access: ObjectAccess::from_u16(u16::from_le_bytes([data[8], data[9]])),
+        name: data[10..].iter().take_while(|&&b| b != 0).map(|&b| b as char).collect(),
     })
 }
 
-#[no_mangle]
-pub extern "C" fn ethercrab_verify_topology(
-    expected: *const SlaveIdentity,
-    expected_count: usize,
-) -> c_int {
-    with_ffi_guard(-1, || {
-        if expected.is_null() || expected_count == 0 { return -1; }
-
-        let expected_slaves = unsafe { std::slice::from_raw_parts(expected, expected_count) };
+/// Send one SDO Information request and collect the (possibly fragmented) response data.
+async fn sdo_info_request(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    opcode: u8,
+    body: &[u8],
+    expected_opcode: u8,
+    timeout: Duration,
+) -> Result<Vec<u8>, MailboxError> {
+    let mut channel = mailbox_channel(configured_address).await;
+    let cfg = mailbox_config(md, configured_address).await?;
+    loop {
+        let deadline = Instant::now() + timeout;
+        let result =
+            sdo_info_request_once(md, configured_address, &cfg, &mut channel, opcode, body, expected_opcode, deadline).await;
+        if !channel.retry(&result) {
+            return result;
+        }
+    }
+}
 
-        // We need to access the group to iterate.
-        // We can do this synchronously because we are just reading memory, not doing I/O.
-        let guard = STATE.read();
-        let state = match guard.as_ref() {
-            Some(s) => s,
-            None => return -1,
+#[allow(clippy::too_many_arguments)]
+async fn sdo_info_request_once(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    cfg: &MailboxConfig,
+    channel: &mut MailboxChannel,
+    opcode: u8,
+    body: &[u8],
+    expected_opcode: u8,
+    deadline: Instant,
+) -> Result<Vec<u8>, MailboxError> {
+    let counter = channel.next_counter();
+    let request = encode_sdo_info_request(opcode, body);
+    mailbox_send(md, configured_address, cfg, MAILBOX_TYPE_COE, counter, &request, deadline).await?;
+
+    let mut data = Vec::new();
+    loop {
+        let frame = mailbox_receive(md, configured_address, cfg, deadline).await?;
+        let decoded = match frame.mailbox_type {
+            MAILBOX_TYPE_COE => decode_sdo_info_response(&frame.payload),
+            _ => None,
         };
-
-        // Helper to get group count regardless of state
-        let discovered_count = match &state.group {
-            Some(GroupState::PreOp(g)) => g.len(),
-            Some(GroupState::SafeOp(g)) => g.len(),
-            Some(GroupState::Op(g)) => g.len(),
-            None => return -1,
+        let (res_opcode, incomplete, chunk) = match decoded {
+            Some(r) => r,
+            None => {
+                mailbox_unsolicited(configured_address, &frame);
+                continue;
+            }
         };
+        if res_opcode == SDO_INFO_ERROR {
+            let abort = chunk.get(0..4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]])).unwrap_or(0);
+            return Err(MailboxError::SdoInfo(abort));
+        }
+        if res_opcode != expected_opcode {
+            return Err(MailboxError::Protocol(format!(
+                "unexpected SDO Information opcode {} (expected {})",
+                res_opcode, expected_opcode
+            )));
+        }
+        data.extend_from_slice(chunk);
+        if !incomplete {
+            return Ok(data);
+        }
+    }
+}
 
-        if discovered_count != expected_count { return -1; }
+async fn sdo_info_entry_description(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    index: u16,
+    sub_index: u8,
+    timeout: Duration,
+) -> Result<SdoEntryDescription, MailboxError> {
+    let idx = index.to_le_bytes();
+    let data = sdo_info_request(
+        md,
+        configured_address,
+        SDO_INFO_GET_ED_REQ,
+        &[idx[0], idx[1], sub_index, 0x00],
+        SDO_INFO_GET_ED_RES,
+        timeout,
+    )
+    .await?;
+    parse_sdo_entry_description(&data)
+        .ok_or_else(|| MailboxError::Protocol("SDO entry description too short".to_string()))
+}
 
-        for (idx, expected_slave) in expected_slaves.iter().enumerate() {
-            let identity = match &state.group {
-                Some(GroupState::PreOp(g)) => g.iter(&state.maindevice).nth(idx).map(|s| s.identity()),
-                Some(GroupState::SafeOp(g)) => g.iter(&state.maindevice).nth(idx).map(|s| s.identity()),
-                Some(GroupState::Op(g)) => g.iter(&state.maindevice).nth(idx).map(|s| s.identity()),
-                None => return -1,
-            };
+/// Object indices from a "Get OD List" response: list type (u16), then one u16 per object.
+fn parse_od_list(data: &[u8]) -> Vec<u16> {
+    data.get(2..)
+        .unwrap_or(&[])
+        .chunks_exact(2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .collect()
+}
 
-            if let Some(id) = identity {
-                if id.vendor_id != expected_slave.vendor_id || id.product_id != expected_slave.product_code {
-                    return -1;
-                }
-                if expected_slave.serial_number != 0 && id.serial != expected_slave.serial_number {
-                    return -1;
-                }
-            } else {
-                return -1;
-            }
+/// All object indices of the slave's object dictionary.
+async fn sdo_info_od_list(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    timeout: Duration,
+) -> Result<Vec<u16>, MailboxError> {
+    let data = sdo_info_request(
+        md,
+        configured_address,
+        SDO_INFO_GET_OD_LIST_REQ,
+        &SDO_INFO_LIST_ALL_OBJECTS.to_le_bytes(),
+        SDO_INFO_GET_OD_LIST_RES,
+        timeout,
+    )
+    .await?;
+    Ok(parse_od_list(&data))
+}
+
+/// Object name from "Get Object Description": index, data type, max subindex, object code, name.
+async fn sdo_info_object_name(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    index: u16,
+    timeout: Duration,
+) -> Result<String, MailboxError> {
+    let data = sdo_info_request(
+        md,
+        configured_address,
+        SDO_INFO_GET_OD_REQ,
+        &index.to_le_bytes(),
+        SDO_INFO_GET_OD_RES,
+        timeout,
+    )
+    .await?;
+    Ok(data.iter().skip(6).take_while(|&&b| b != 0).map(|&b| b as char).collect())
+}
+
+// --- SII EEPROM Access (ETG1000.4 §6.4, ETG2010) ---
+
+const SII_REG_CONFIG: u16 = 0x0500;
+const SII_REG_PDI_ACCESS: u16 = 0x0501;
+const SII_REG_CONTROL: u16 = 0x0502;
+const SII_REG_DATA: u16 = 0x0508;
+const SII_CONFIG_ASSIGN_PDI: u8 = 0x01;
+const SII_CONFIG_FORCE_ECAT: u8 = 0x02;
+const SII_CTRL_WRITE_ENABLE: u16 = 0x0001;
+const SII_CTRL_READ_8_BYTES: u16 = 0x0040;
+const SII_CMD_READ: u16 = 0x0100;
+const SII_CMD_WRITE: u16 = 0x0200;
+const SII_STATUS_ACK_ERROR: u16 = 0x2000;
+const SII_STATUS_WRITE_ENABLE_ERROR: u16 = 0x4000;
+const SII_STATUS_BUSY: u16 = 0x8000;
+/// Words 0-6 hold the ESC configuration area; the low byte of word 7 is its CRC.
+const SII_CONFIG_CRC_WORD: u16 = 7;
+
+/// Serialises SII register sequences so concurrent callers cannot mix address/command writes.
+/// Also held around ethercrab's own EEPROM reads, which drive the same registers.
+static SII_LOCK: Lazy<smol::lock::Mutex<()>> = Lazy::new(|| smol::lock::Mutex::new(()));
+
+#[derive(Debug)]
+enum SiiError {
+    Io(ethercrab::error::Error),
+    Timeout,
+    /// EEPROM did not acknowledge the command (0x0502 bit 13).
+    Nack,
+    /// Write command issued without write enable (0x0502 bit 14).
+    WriteEnable,
+    /// PDI kept EEPROM control after a forced release.
+    PdiBusy,
+    /// Read-back after write did not match.
+    Verify { word: u16, expected: u16, actual: u16 },
+}
+
+impl std::fmt::Display for SiiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SiiError::Io(e) => write!(f, "{}", e),
+            SiiError::Timeout => write!(f, "EEPROM busy timeout"),
+            SiiError::Nack => write!(f, "EEPROM did not acknowledge command"),
+            SiiError::WriteEnable => write!(f, "EEPROM write enable error"),
+            SiiError::PdiBusy => write!(f, "EEPROM is assigned to PDI and could not be taken over"),
+            SiiError::Verify { word, expected, actual } => write!(
+                f,
+                "EEPROM verify failed at word 0x{:04X}: expected 0x{:04X}, read 0x{:04X}",
+                word, expected, actual
+            ),
         }
+    }
+}
 
-        0
-    })
+impl From<ethercrab::error::Error> for SiiError {
+    fn from(e: ethercrab::error::Error) -> Self {
+        SiiError::Io(e)
+    }
 }
 
-#[no_mangle]
-pub extern "C" fn ethercrab_request_state(target_state: u8) -> c_int {
-    with_ffi_guard(-1, || {
-        // If no master is initialized, allow INIT/PRE-OP as a no-op.
-        // This prevents noisy warnings during teardown after failed init.
-        if STATE.read().is_none() {
-            if target_state == 0 || target_state == 1 {
-                return 0;
-            }
-            set_error_ctx(
-                FfiErrorCode::NotInitialized,
-                format!("No master available for state transition to {}", state_name(target_state)),
-                &[("op", "request_state"), ("target_state", state_name(target_state))],
-            );
-            return -1;
+/// CRC-8 of the SII configuration area (ETG2010 §2.3): polynomial 0x07, init 0xFF,
+/// over the 14 bytes of words 0-6.
+fn sii_config_crc(config: &[u8]) -> u8 {
+    let mut crc: u8 = 0xFF;
+    for &byte in config.iter().take(14) {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if (crc & 0x80) != 0 { (crc << 1) ^ 0x07 } else { crc << 1 };
         }
+    }
+    crc
+}
 
-        let mut guard = STATE.write();
-        let state = match guard.as_mut() {
-            Some(s) => s,
-            None => return -1,
-        };
+/// Recalculate the CRC in word 7 of an image holding at least words 0-7.
+/// Returns true if the stored CRC was changed.
+fn sii_fix_config_crc(image: &mut [u8]) -> bool {
+    let crc_offset = SII_CONFIG_CRC_WORD as usize * 2;
+    if image.len() < crc_offset + 2 {
+        return false;
+    }
+    let crc = sii_config_crc(&image[..crc_offset]);
+    let changed = image[crc_offset] != crc;
+    image[crc_offset] = crc;
+    changed
+}
 
-        let group_enum = state.group.take(); 
-        let maindevice = state.maindevice.clone();
-        // Capture current state values before async block
-        let current_input_size = state.input_size;
-        let current_output_size = state.output_size;
-        let current_expected_wkc = state.expected_wkc;
+/// Word addresses where `current` and `target` differ (compared over the shorter length).
+fn sii_diff_words(current: &[u8], target: &[u8]) -> Vec<u16> {
+    current
+        .chunks(2)
+        .zip(target.chunks(2))
+        .enumerate()
+        .filter(|(_, (a, b))| a != b)
+        .map(|(i, _)| i as u16)
+        .collect()
+}
 
-        let result = smol::block_on(async {
-            match (target_state, group_enum) {
-                // Init (0) / PreOp (1) handled below
+/// Take EEPROM control from the PDI if it is assigned there. Returns the original
+/// 0x0500 value so it can be restored with `sii_release`.
+async fn sii_take_control(md: &MainDevice<'_>, configured_address: u16) -> Result<u8, SiiError> {
+    let config = esc_read(md, configured_address, SII_REG_CONFIG, 1).await?[0];
+    if (config & SII_CONFIG_ASSIGN_PDI) == 0 {
+        return Ok(config);
+    }
+    esc_write(md, configured_address, SII_REG_CONFIG, &[0x00]).await?;
+    if (esc_read(md, configured_address, SII_REG_PDI_ACCESS, 1).await?[0] & 0x01) != 0 {
+        // PDI is mid-access: force the release
+        esc_write(md, configured_address, SII_REG_CONFIG, &[SII_CONFIG_FORCE_ECAT]).await?;
+        if (esc_read(md, configured_address, SII_REG_PDI_ACCESS, 1).await?[0] & 0x01) != 0 {
+            return Err(SiiError::PdiBusy);
+        }
+    }
+    Ok(config)
+}
 
-                
-                // To SafeOp (2)
-                (2, Some(GroupState::PreOp(g))) => {
-                    let g_safe = match g.into_safe_op(&maindevice).await {
-                        Ok(g) => g,
-                        Err(e) => {
-                            set_error_ctx(
-                                FfiErrorCode::StateTransitionFailed,
-                                format!("State transition PreOp→SafeOp failed: {:?}", e),
-                                &[("op", "request_state"), ("from", "PreOp"), ("to", "SafeOp"), ("error_detail", &format!("{:?}", e)), ("suggestion", "Increase runtimeOptions.stateTransitionTimeoutMs or check slave AL status")],
-                            );
-                            return Err(-3);
-                        }
-                    };
-                    
-                    let mut in_sz = 0;
-                    let mut out_sz = 0;
-                    for slave in g_safe.iter(&maindevice) {
-                        let io = slave.io_raw();
-                        in_sz += io.inputs().len();
-                        out_sz += io.outputs().len();
-                    }
-                    
-                    // WKC counts all slaves in the group, not just those with PDI
-                    // This is because the LRW frame passes through all slaves
-                    let wkc_count = g_safe.len() as u16;
-                    
-                    Ok((Some(GroupState::SafeOp(g_safe)), in_sz, out_sz, wkc_count))
-                },
-                (2, Some(GroupState::SafeOp(g))) => Ok((Some(GroupState::SafeOp(g)), current_input_size, current_output_size, current_expected_wkc)),
-                (2, Some(GroupState::Op(g))) => {
-                    // Op -> SafeOp
-                    let g_safe = match g.into_safe_op(&maindevice).await {
-                        Ok(g) => g,
-                        Err(e) => {
-                            set_error_ctx(
-                                FfiErrorCode::StateTransitionFailed,
-                                format!("State transition Op→SafeOp failed: {:?}", e),
-                                &[("op", "request_state"), ("from", "Op"), ("to", "SafeOp"), ("error_detail", &format!("{:?}", e)), ("suggestion", "Increase runtimeOptions.stateTransitionTimeoutMs or check slave AL status")],
-                            );
-                            return Err(-3);
-                        }
-                    };
-                    Ok((Some(GroupState::SafeOp(g_safe)), current_input_size, current_output_size, current_expected_wkc))
-                },
-                
-                // To Op (3)
-                (3, Some(GroupState::SafeOp(g))) => {
-                    let g_op = match g.into_op(&maindevice).await {
-                        Ok(g) => g,
-                        Err(e) => {
-                            set_error_ctx(
-                                FfiErrorCode::StateTransitionFailed,
-                                format!("State transition SafeOp→Op failed: {:?}", e),
-                                &[("op", "request_state"), ("from", "SafeOp"), ("to", "Op"), ("error_detail", &format!("{:?}", e)), ("suggestion", "Increase runtimeOptions.stateTransitionTimeoutMs or check slave AL status")],
-                            );
-                            return Err(-3);
-                        }
-                    };
-                    Ok((Some(GroupState::Op(g_op)), current_input_size, current_output_size, current_expected_wkc))
-                },
-                (3, Some(GroupState::Op(g))) => Ok((Some(GroupState::Op(g)), current_input_size, current_output_size, current_expected_wkc)),
-                
-                // To PreOp (1) or Init (0)
-                (0 | 1, Some(g_any)) => {
-                     match g_any {
-                         GroupState::PreOp(g) => Ok((Some(GroupState::PreOp(g)), 0, 0, 0)),
-                         GroupState::SafeOp(g) => {
-                            let g_pre = match g.into_pre_op(&maindevice).await {
-                                Ok(g) => g,
-                                Err(e) => {
-                                    set_error_ctx(
-                                        FfiErrorCode::StateTransitionFailed,
-                                        format!("State transition SafeOp→PreOp failed: {:?}", e),
-                                        &[("op", "request_state"), ("from", "SafeOp"), ("to", "PreOp"), ("error_detail", &format!("{:?}", e)), ("suggestion", "Increase runtimeOptions.stateTransitionTimeoutMs or check slave AL status")],
-                                    );
-                                    return Err(-3);
-                                }
-                            };
-                            Ok((Some(GroupState::PreOp(g_pre)), 0, 0, 0))
-                         },
-                         GroupState::Op(g) => {
-                            // Op -> SafeOp -> PreOp
-                            let g_safe = match g.into_safe_op(&maindevice).await {
-                                Ok(g) => g,
-                                Err(e) => {
-                                    set_error_ctx(
-                                        FfiErrorCode::StateTransitionFailed,
-                                        format!("State transition Op→SafeOp failed (during Op→PreOp): {:?}", e),
-                                        &[("op", "request_state"), ("from", "Op"), ("to", "PreOp"), ("step", "Op→SafeOp"), ("error_detail", &format!("{:?}", e)), ("suggestion", "Increase runtimeOptions.stateTransitionTimeoutMs or check slave AL status")],
-                                    );
-                                    return Err(-3);
-                                }
-                            };
-                            let g_pre = match g_safe.into_pre_op(&maindevice).await {
-                                Ok(g) => g,
-                                Err(e) => {
-                                    set_error_ctx(
-                                        FfiErrorCode::StateTransitionFailed,
-                                        format!("State transition SafeOp→PreOp failed (during Op→PreOp): {:?}", e),
-                                        &[("op", "request_state"), ("from", "Op"), ("to", "PreOp"), ("step", "SafeOp→PreOp"), ("error_detail", &format!("{:?}", e)), ("suggestion", "Increase runtimeOptions.stateTransitionTimeoutMs or check slave AL status")],
-                                    );
-                                    return Err(-3);
-                                }
-                            };
-                            Ok((Some(GroupState::PreOp(g_pre)), 0, 0, 0))
-                         }
-                     }
-                },
-                
-                // Invalid Transitions - return the group as-is since no transition was attempted
-                (_, Some(g)) => {
-                    // Return the original group and current state values since no transition occurred
-                    Ok((Some(g), current_input_size, current_output_size, current_expected_wkc))
-                }, 
+async fn sii_release(md: &MainDevice<'_>, configured_address: u16, original_config: u8) -> Result<(), SiiError> {
+    if (original_config & SII_CONFIG_ASSIGN_PDI) != 0 {
+        esc_write(md, configured_address, SII_REG_CONFIG, &[SII_CONFIG_ASSIGN_PDI]).await?;
+    }
+    Ok(())
+}
 
-                (_, None) => {
-                    set_error_ctx(
-                        FfiErrorCode::NotInitialized,
-                        format!("No group available for state transition to {}", state_name(target_state)),
-                        &[("op", "request_state"), ("target_state", state_name(target_state))],
-                    );
-                    Err(-2)
-                },
-            }
-        });
+/// Hand EEPROM control back to PDI after an access and return the access result. A failed
+/// release is recorded in the error ring rather than replacing that result.
+async fn sii_finish<T>(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    original_config: u8,
+    result: Result<T, SiiError>,
+) -> Result<T, SiiError> {
+    if let Err(e) = sii_release(md, configured_address, original_config).await {
+        set_error_ctx(
+            FfiErrorCode::EepromError,
+            format!("Returning EEPROM control to PDI at 0x{:04X} failed: {}", configured_address, e),
+            &[("op", "sii_release"), ("address", &format!("0x{:04X}", configured_address)), ("error_detail", &format!("{:?}", e))],
+        );
+    }
+    result
+}
 
-        match result {
-            Ok((new_group, in_s, out_s, wkc)) => {
-                state.group = new_group;
-                state.input_size = in_s;
-                state.output_size = out_s;
-                state.pdi_size = in_s + out_s;
-                state.expected_wkc = wkc;
-                0
-            },
-            Err(e) => {
-                // Note: If a transition fails, the group is lost (consumed by the failed operation)
-                // This is a limitation of the ethercrab API - we cannot recover the group after a failed transition
-                // However, we ensure that for invalid transitions (no-op cases), the group is restored above
-                e
+/// Poll 0x0502 until the busy bit clears, then check the error bits.
+async fn sii_wait_idle(md: &MainDevice<'_>, configured_address: u16, deadline: Instant) -> Result<u16, SiiError> {
+    loop {
+        let raw = esc_read(md, configured_address, SII_REG_CONTROL, 2).await?;
+        let status = u16::from_le_bytes([raw[0], raw[1]]);
+        if (status & SII_STATUS_BUSY) == 0 {
+            if (status & SII_STATUS_WRITE_ENABLE_ERROR) != 0 {
+                return Err(SiiError::WriteEnable);
             }
+            if (status & SII_STATUS_ACK_ERROR) != 0 {
+                return Err(SiiError::Nack);
+            }
+            return Ok(status);
         }
-    })
+        if Instant::now() >= deadline {
+            return Err(SiiError::Timeout);
+        }
+        smol::Timer::after(MAILBOX_POLL_DELAY).await;
+    }
 }
 
-#[no_mangle]
-pub extern "C" fn ethercrab_get_state() -> u8 {
-    with_ffi_guard(0, || {
-        let guard = STATE.read();
-        if let Some(state) = guard.as_ref() {
-            match &state.group {
-                Some(GroupState::PreOp(_)) => 1,
-                Some(GroupState::SafeOp(_)) => 2,
-                Some(GroupState::Op(_)) => 3,
-                None => 0,
-            }
-        } else {
-            0
-        }
-    })
+/// Read `words` 16-bit words starting at `word_address`. Control must already be held.
+async fn sii_read_words(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    word_address: u16,
+    words: usize,
+    timeout: Duration,
+) -> Result<Vec<u8>, SiiError> {
+    let mut out = Vec::with_capacity(words * 2);
+    while out.len() < words * 2 {
+        let address = word_address as u32 + (out.len() / 2) as u32;
+        let deadline = Instant::now() + timeout;
+        let status = sii_wait_idle(md, configured_address, deadline).await?;
+        // Command and address in one write: 0x0502 control, 0x0504 address
+        let mut request = [0u8; 6];
+        request[0..2].copy_from_slice(&SII_CMD_READ.to_le_bytes());
+        request[2..6].copy_from_slice(&address.to_le_bytes());
+        esc_write(md, configured_address, SII_REG_CONTROL, &request).await?;
+        sii_wait_idle(md, configured_address, deadline).await?;
+        let chunk = if (status & SII_CTRL_READ_8_BYTES) != 0 { 8 } else { 4 };
+        let data = esc_read(md, configured_address, SII_REG_DATA, chunk).await?;
+        let take = (words * 2 - out.len()).min(chunk as usize);
+        out.extend_from_slice(&data[..take]);
+    }
+    Ok(out)
 }
 
-#[no_mangle]
-pub extern "C" fn ethercrab_get_al_status_code(slave_index: u16) -> u16 {
+/// Write one word. Write enable and the command go in the same datagram (ETG1000.4 §6.4.5).
+async fn sii_write_word(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    word_address: u16,
+    value: [u8; 2],
+    timeout: Duration,
+) -> Result<(), SiiError> {
+    let deadline = Instant::now() + timeout;
+    sii_wait_idle(md, configured_address, deadline).await?;
+    esc_write(md, configured_address, SII_REG_DATA, &value).await?;
+    let mut request = [0u8; 6];
+    request[0..2].copy_from_slice(&(SII_CMD_WRITE | SII_CTRL_WRITE_ENABLE).to_le_bytes());
+    request[2..6].copy_from_slice(&(word_address as u32).to_le_bytes());
+    esc_write(md, configured_address, SII_REG_CONTROL, &request).await?;
+    sii_wait_idle(md, configured_address, deadline).await?;
+    Ok(())
+}
+
+/// Read the SII header (words 0-0x3F) and all categories up to the end marker,
+/// with EEPROM control taken from PDI for the duration of the access.
+async fn sii_read_image(md: &MainDevice<'_>, configured_address: u16, timeout: Duration) -> Result<Vec<u8>, SiiError> {
+    let _lock = SII_LOCK.lock().await;
+    let original = sii_take_control(md, configured_address).await?;
+    let result = async {
+        let mut image = sii_read_words(md, configured_address, 0, SII_FIRST_CATEGORY_WORD as usize, timeout).await?;
+        let max_words = sii_size_bytes(&image).min(SII_MAX_WORDS * 2) / 2;
+        let mut word = SII_FIRST_CATEGORY_WORD as usize;
+        while word + 2 <= max_words {
+            let header = sii_read_words(md, configured_address, word as u16, 2, timeout).await?;
+            image.extend_from_slice(&header);
+            let category = u16::from_le_bytes([header[0], header[1]]);
+            let len = u16::from_le_bytes([header[2], header[3]]) as usize;
+            if category == SII_CATEGORY_END || word + 2 + len > max_words {
+                break;
+            }
+            let data = sii_read_words(md, configured_address, (word + 2) as u16, len, timeout).await?;
+            image.extend_from_slice(&data);
+            word += 2 + len;
+        }
+        Ok(image)
+    }
+    .await;
+    sii_finish(md, configured_address, original, result).await
+}
+
+/// Write `target` (word aligned) starting at `word_address`, skipping words that already
+/// match. If the range touches words 0-7 the configuration CRC is recalculated. Written
+/// words are read back and verified. Returns the word addresses that differed; with
+/// `dry_run` nothing is written.
+async fn sii_write(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    word_address: u16,
+    target: &[u8],
+    dry_run: bool,
+    timeout: Duration,
+) -> Result<Vec<u16>, SiiError> {
+    let _lock = SII_LOCK.lock().await;
+    let original = sii_take_control(md, configured_address).await?;
+    let result = async {
+        let words = target.len() / 2;
+        let current = sii_read_words(md, configured_address, word_address, words, timeout).await?;
+        let mut desired = target.to_vec();
+
+        // Keep the configuration area consistent: splice into words 0-7 and fix the CRC.
+        let mut config_fix = None;
+        if word_address <= SII_CONFIG_CRC_WORD {
+            let mut config = sii_read_words(md, configured_address, 0, 8, timeout).await?;
+            let start = word_address as usize * 2;
+            let end = (start + desired.len()).min(16);
+            config[start..end].copy_from_slice(&desired[..end - start]);
+            sii_fix_config_crc(&mut config);
+            let crc_offset = SII_CONFIG_CRC_WORD as usize * 2;
+            if end > crc_offset {
+                desired[crc_offset - start] = config[crc_offset];
+            } else {
+                config_fix = Some([config[crc_offset], config[crc_offset + 1]]);
+            }
+        }
+
+        let mut diffs = sii_diff_words(&current, &desired)
+            .into_iter()
+            .map(|w| w + word_address)
+            .collect::<Vec<_>>();
+        if let Some(crc_word) = config_fix {
+            let stored = sii_read_words(md, configured_address, SII_CONFIG_CRC_WORD, 1, timeout).await?;
+            if stored[..] != crc_word[..] {
+                diffs.push(SII_CONFIG_CRC_WORD);
+            }
+        }
+        if dry_run {
+            return Ok(diffs);
+        }
+
+        for &word in &diffs {
+            let value = match config_fix {
+                Some(crc_word) if word == SII_CONFIG_CRC_WORD => crc_word,
+                _ => {
+                    let offset = (word - word_address) as usize * 2;
+                    [desired[offset], desired[offset + 1]]
+                }
+            };
+            sii_write_word(md, configured_address, word, value, timeout).await?;
+            let readback = sii_read_words(md, configured_address, word, 1, timeout).await?;
+            if readback[..] != value[..] {
+                return Err(SiiError::Verify {
+                    word,
+                    expected: u16::from_le_bytes(value),
+                    actual: u16::from_le_bytes([readback[0], readback[1]]),
+                });
+            }
+        }
+        Ok(diffs)
+    }
+    .await;
+    sii_finish(md, configured_address, original, result).await
+}
+
+// --- SII Category Parsing (ETG2010 §2) ---
+
+const SII_FIRST_CATEGORY_WORD: u16 = 0x40;
+/// Upper bound on the SII size (ETG2010 allows up to 32 KiB words of EEPROM).
+const SII_MAX_WORDS: usize = 0x4000;
+const SII_CATEGORY_STRINGS: u16 = 10;
+const SII_CATEGORY_GENERAL: u16 = 30;
+const SII_CATEGORY_FMMU: u16 = 40;
+const SII_CATEGORY_SYNCM: u16 = 41;
+const SII_CATEGORY_TXPDO: u16 = 50;
+const SII_CATEGORY_RXPDO: u16 = 51;
+const SII_CATEGORY_DC: u16 = 60;
+const SII_CATEGORY_END: u16 = 0xFFFF;
+
+#[derive(Clone, Debug, Default)]
+struct SiiGeneral {
+    group: String,
+    image: String,
+    order: String,
+    name: String,
+    coe_details: u8,
+    foe_details: u8,
+    eoe_details: u8,
+    soe_channels: u8,
+    ds402_channels: u8,
+    sysman_class: u8,
+    flags: u8,
+    current_on_ebus_ma: i16,
+    physical_port: u16,
+    physical_memory_address: u16,
+}
+
+#[derive(Clone, Debug, Default)]
+struct SiiSyncManager {
+    start_address: u16,
+    length: u16,
+    control: u8,
+    status: u8,
+    enable: u8,
+    /// 0 = unused, 1 = mailbox out, 2 = mailbox in, 3 = process data out, 4 = process data in.
+    sm_type: u8,
+}
+
+#[derive(Clone, Debug, Default)]
+struct SiiPdoEntry {
+    index: u16,
+    sub_index: u8,
+    name: String,
+    data_type: u8,
+    bit_len: u8,
+    flags: u16,
+}
+
+#[derive(Clone, Debug, Default)]
+struct SiiPdo {
+    index: u16,
+    sync_manager: u8,
+    dc_sync: u8,
+    name: String,
+    flags: u16,
+    entries: Vec<SiiPdoEntry>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct SiiDcSync {
+    cycle_time_0: u32,
+    shift_time_0: u32,
+    shift_time_1: u32,
+    sync1_cycle_factor: i16,
+    assign_activate: u16,
+    sync0_cycle_factor: i16,
+    name: String,
+    description: String,
+}
+
+#[derive(Clone, Debug, Default)]
+struct SiiInfo {
+    station_alias: u16,
+    vendor_id: u32,
+    product_code: u32,
+    revision: u32,
+    serial_number: u32,
+    rx_mailbox_offset: u16,
+    rx_mailbox_size: u16,
+    tx_mailbox_offset: u16,
+    tx_mailbox_size: u16,
+    mailbox_protocols: u16,
+    size_bytes: usize,
+    version: u16,
+    strings: Vec<String>,
+    general: Option<SiiGeneral>,
+    /// FMMU usage per FMMU: 0 = unused, 1 = outputs, 2 = inputs, 3 = SM status.
+    fmmus: Vec<u8>,
+    sync_managers: Vec<SiiSyncManager>,
+    tx_pdos: Vec<SiiPdo>,
+    rx_pdos: Vec<SiiPdo>,
+    dc_syncs: Vec<SiiDcSync>,
+}
+
+fn le_u16(data: &[u8], offset: usize) -> u16 {
+    data.get(offset..offset + 2).map(|b| u16::from_le_bytes([b[0], b[1]])).unwrap_or(0)
+}
+
+fn le_u32(data: &[u8], offset: usize) -> u32 {
+    data.get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]])).unwrap_or(0)
+}
+
+/// EEPROM size in bytes from header word 0x3E (size in KiBit - 1).
+fn sii_size_bytes(image: &[u8]) -> usize {
+    (le_u16(image, 0x3E * 2) as usize + 1) * 128
+}
+
+/// Strings category: count byte, then length-prefixed Latin-1 strings. Index 0 means "no string".
+fn parse_sii_strings(data: &[u8]) -> Vec<String> {
+    let count = data.first().copied().unwrap_or(0) as usize;
+    let mut strings = Vec::with_capacity(count);
+    let mut pos = 1;
+    for _ in 0..count {
+        let len = match data.get(pos) {
+            Some(&l) => l as usize,
+            None => break,
+        };
+        let bytes = match data.get(pos + 1..pos + 1 + len) {
+            Some(b) => b,
+            None => break,
+        };
+        strings.push(bytes.iter().map(|&b| b as char).collect());
+        pos += 1 + len;
+    }
+    strings
+}
+
+fn sii_string(strings: &[String], index: u8) -> String {
+    match index {
+        0 => String::new(),
+        i => strings.get(i as usize - 1).cloned().unwrap_or_default(),
+    }
+}
+
+fn parse_sii_pdos(data: &[u8], strings: &[String]) -> Vec<SiiPdo> {
+    let mut pdos = Vec::new();
+    let mut pos = 0;
+    while pos + 8 <= data.len() {
+        let entry_count = data[pos + 2] as usize;
+        let mut pdo = SiiPdo {
+            index: le_u16(data, pos),
+            sync_manager: data[pos + 3],
+            dc_sync: data[pos + 4],
+            name: sii_string(strings, data[pos + 5]),
+            flags: le_u16(data, pos + 6),
+            entries: Vec::with_capacity(entry_count),
+        };
+        pos += 8;
+        for _ in 0..entry_count {
+            if pos + 8 > data.len() {
+                break;
+            }
+            pdo.entries.push(SiiPdoEntry {
+                index: le_u16(data, pos),
+                sub_index: data[pos + 2],
+                name: sii_string(strings, data[pos + 3]),
+                data_type: data[pos + 4],
+                bit_len: data[pos + 5],
+                flags: le_u16(data, pos + 6),
+            });
+            pos += 8;
+        }
+        pdos.push(pdo);
+    }
+    pdos
+}
+
+/// Parse an SII image (header plus categories). Truncated images yield whatever
+/// categories are complete.
+fn parse_sii(image: &[u8]) -> SiiInfo {
+    let mut info = SiiInfo {
+        station_alias: le_u16(image, 0x04 * 2),
+        vendor_id: le_u32(image, 0x08 * 2),
+        product_code: le_u32(image, 0x0A * 2),
+        revision: le_u32(image, 0x0C * 2),
+        serial_number: le_u32(image, 0x0E * 2),
+        rx_mailbox_offset: le_u16(image, 0x18 * 2),
+        rx_mailbox_size: le_u16(image, 0x19 * 2),
+        tx_mailbox_offset: le_u16(image, 0x1A * 2),
+        tx_mailbox_size: le_u16(image, 0x1B * 2),
+        mailbox_protocols: le_u16(image, 0x1C * 2),
+        size_bytes: sii_size_bytes(image),
+        version: le_u16(image, 0x3F * 2),
+        ..Default::default()
+    };
+
+    // Collect category slices first: string indices may be referenced before the strings category.
+    let mut categories = Vec::new();
+    let mut pos = SII_FIRST_CATEGORY_WORD as usize * 2;
+    while pos + 4 <= image.len() {
+        let category = le_u16(image, pos);
+        let len = le_u16(image, pos + 2) as usize * 2;
+        if category == SII_CATEGORY_END || pos + 4 + len > image.len() {
+            break;
+        }
+        categories.push((category, &image[pos + 4..pos + 4 + len]));
+        pos += 4 + len;
+    }
+
+    if let Some((_, data)) = categories.iter().find(|(c, _)| *c == SII_CATEGORY_STRINGS) {
+        info.strings = parse_sii_strings(data);
+    }
+    let strings = info.strings.clone();
+
+    for (category, data) in categories {
+        match category {
+            SII_CATEGORY_GENERAL if data.len() >= 20 => {
+                info.general = Some(SiiGeneral {
+                    group: sii_string(&strings, data[0]),
+                    image: sii_string(&strings, data[1]),
+                    order: sii_string(&strings, data[2]),
+                    name: sii_string(&strings, data[3]),
+                    coe_details: data[5],
+                    foe_details: data[6],
+                    eoe_details: data[7],
+                    soe_channels: data[8],
+                    ds402_channels: data[9],
+                    sysman_class: data[10],
+                    flags: data[11],
+                    current_on_ebus_ma: le_u16(data, 12) as i16,
+                    physical_port: le_u16(data, 16),
+                    physical_memory_address: le_u16(data, 18),
+                });
+            }
+            SII_CATEGORY_FMMU => {
+                info.fmmus = data.iter().map(|&u| if u == 0xFF { 0 } else { u }).collect();
+            }
+            SII_CATEGORY_SYNCM => {
+                info.sync_managers = data
+                    .chunks_exact(8)
+                    .map(|sm| SiiSyncManager {
+                        start_address: le_u16(sm, 0),
+                        length: le_u16(sm, 2),
+                        control: sm[4],
+                        status: sm[5],
+                        enable: sm[6],
+                        sm_type: sm[7],
+                    })
+                    .collect();
+            }
+            SII_CATEGORY_TXPDO => info.tx_pdos.extend(parse_sii_pdos(data, &strings)),
+            SII_CATEGORY_RXPDO => info.rx_pdos.extend(parse_sii_pdos(data, &strings)),
+            SII_CATEGORY_DC => {
+                info.dc_syncs = data
+                    .chunks_exact(24)
+                    .map(|dc| SiiDcSync {
+                        cycle_time_0: le_u32(dc, 0),
+                        shift_time_0: le_u32(dc, 4),
+                        shift_time_1: le_u32(dc, 8),
+                        sync1_cycle_factor: le_u16(dc, 12) as i16,
+                        assign_activate: le_u16(dc, 14),
+                        sync0_cycle_factor: le_u16(dc, 16) as i16,
+                        name: sii_string(&strings, dc[18]),
+                        description: sii_string(&strings, dc[19]),
+                    })
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+    info
+}
+
+fn json_string(s: &str) -> String {
+    format!("\"{}\"", json_escape(s))
+}
+
+fn sii_pdos_json(pdos: &[SiiPdo]) -> String {
+    let items: Vec<String> = pdos
+        .iter()
+        .map(|pdo| {
+            let entries: Vec<String> = pdo
+                .entries
+                .iter()
+                .map(|e| {
+                    format!(
+                        "{{\"index\":{},\"subIndex\":{},\"name\":{},\"dataType\":{},\"bitLen\":{},\"flags\":{}}}",
+                        e.index, e.sub_index, json_string(&e.name), e.data_type, e.bit_len, e.flags
+                    )
+                })
+                .collect();
+            format!(
+                "{{\"index\":{},\"syncManager\":{},\"dcSync\":{},\"name\":{},\"flags\":{},\"entries\":[{}]}}",
+                pdo.index, pdo.sync_manager, pdo.dc_sync, json_string(&pdo.name), pdo.flags, entries.join(",")
+            )
+        })
+        .collect();
+    format!("[{}]", items.join(","))
+}
+
+impl SiiInfo {
+    fn to_json(&self) -> String {
+        let general = match &self.general {
+            Some(g) => format!(
+                "{{\"group\":{},\"image\":{},\"order\":{},\"name\":{},\"coeDetails\":{},\"foeDetails\":{},\"eoeDetails\":{},\"soeChannels\":{},\"ds402Channels\":{},\"sysmanClass\":{},\"flags\":{},\"currentOnEbusMa\":{},\"physicalPort\":{},\"physicalMemoryAddress\":{}}}",
+                json_string(&g.group),
+                json_string(&g.image),
+                json_string(&g.order),
+                json_string(&g.name),
+                g.coe_details,
+                g.foe_details,
+                g.eoe_details,
+                g.soe_channels,
+                g.ds402_channels,
+                g.sysman_class,
+                g.flags,
+                g.current_on_ebus_ma,
+                g.physical_port,
+                g.physical_memory_address,
+            ),
+            None => "null".to_string(),
+        };
+        let strings: Vec<String> = self.strings.iter().map(|s| json_string(s)).collect();
+        let fmmus: Vec<String> = self.fmmus.iter().map(|f| f.to_string()).collect();
+        let sync_managers: Vec<String> = self
+            .sync_managers
+            .iter()
+            .map(|sm| {
+                format!(
+                    "{{\"startAddress\":{},\"length\":{},\"control\":{},\"status\":{},\"enable\":{},\"type\":{}}}",
+                    sm.start_address, sm.length, sm.control, sm.status, sm.enable, sm.sm_type
+                )
+            })
+            .collect();
+        let dc_syncs: Vec<String> = self
+            .dc_syncs
+            .iter()
+            .map(|dc| {
+                format!(
+                    "{{\"cycleTime0\":{},\"shiftTime0\":{},\"shiftTime1\":{},\"sync1CycleFactor\":{},\"assignActivate\":{},\"sync0CycleFactor\":{},\"name\":{},\"description\":{}}}",
+                    dc.cycle_time_0,
+                    dc.shift_time_0,
+                    dc.shift_time_1,
+                    dc.sync1_cycle_factor,
+                    dc.assign_activate,
+                    dc.sync0_cycle_factor,
+                    json_string(&dc.name),
+                    json_string(&dc.description),
+                )
+            })
+            .collect();
+        format!(
+            "{{\"stationAlias\":{},\"vendorId\":{},\"productCode\":{},\"revision\":{},\"serialNumber\":{},\"mailbox\":{{\"rxOffset\":{},\"rxSize\":{},\"txOffset\":{},\"txSize\":{},\"protocols\":{}}},\"sizeBytes\":{},\"version\":{},\"strings\":[{}],\"general\":{},\"fmmus\":[{}],\"syncManagers\":[{}],\"txPdos\":{},\"rxPdos\":{},\"dc\":[{}]}}",
+            self.station_alias,
+            self.vendor_id,
+            self.product_code,
+            self.revision,
+            self.serial_number,
+            self.rx_mailbox_offset,
+            self.rx_mailbox_size,
+            self.tx_mailbox_offset,
+            self.tx_mailbox_size,
+            self.mailbox_protocols,
+            self.size_bytes,
+            self.version,
+            strings.join(","),
+            general,
+            fmmus.join(","),
+            sync_managers.join(","),
+            sii_pdos_json(&self.tx_pdos),
+            sii_pdos_json(&self.rx_pdos),
+            dc_syncs.join(","),
+        )
+    }
+}
+
+// --- FFI Exports ---
+
+#[no_mangle]
+pub extern "C" fn ethercrab_version(buffer: *mut u8, len: usize) -> c_int {
+    with_ffi_guard(0, || {
+        let version = env!("CARGO_PKG_VERSION");
+        let version_bytes = version.as_bytes();
+        if buffer.is_null() || len == 0 {
+            return version_bytes.len() as c_int;
+        }
+        let to_copy = version_bytes.len().min(len);
+        unsafe {
+            std::ptr::copy_nonoverlapping(version_bytes.as_ptr(), buffer, to_copy);
+        }
+        to_copy as c_int
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn ethercrab_get_last_error(buffer: *mut u8, len: usize) -> c_int {
+    with_ffi_guard(0, || {
+        if buffer.is_null() || len == 0 {
+            return 0;
+        }
+        let ring = ERROR_RING.lock();
+        let formatted = match ring.latest() {
+            Some(entry) => format_error_for_ffi(entry),
+            None => return 0,
+        };
+        let error_bytes = formatted.as_bytes();
+        let to_copy = error_bytes.len().min(len);
+        unsafe {
+            std::ptr::copy_nonoverlapping(error_bytes.as_ptr(), buffer, to_copy);
+        }
+        to_copy as c_int
+    })
+}
+
+/// Returns 1 if the network is healthy, 0 if not.
+#[no_mangle]
+pub extern "C" fn ethercrab_get_network_healthy() -> c_int {
+    if NETWORK_HEALTHY.load(Ordering::Relaxed) { 1 } else { 0 }
+}
+
+/// Returns the total number of errors recorded since process start.
+#[no_mangle]
+pub extern "C" fn ethercrab_get_error_count() -> u64 {
+    ERROR_RING.lock().total_count
+}
+
+/// Get a specific error from the ring buffer as JSON.
+/// index: 0 = oldest available, use ethercrab_get_error_count to find range.
+/// Pass index = -1 (as u32::MAX) for the latest error.
+/// Returns bytes written, or 0 if no error at that index.
+#[no_mangle]
+pub extern "C" fn ethercrab_get_error_detail(buffer: *mut u8, len: usize, index: i32) -> c_int {
+    with_ffi_guard(0, || {
+        if buffer.is_null() || len == 0 {
+            return 0;
+        }
+        let ring = ERROR_RING.lock();
+        let entry = if index < 0 {
+            ring.latest()
+        } else {
+            ring.get(index as usize)
+        };
+        let entry = match entry {
+            Some(e) => e,
+            None => return 0,
+        };
+        let json = format!(
+            "{{\"code\":{},\"message\":\"{}\",\"context\":{},\"timestamp_ms\":{}}}",
+            entry.code as u8,
+            json_escape(&entry.message),
+            if entry.context_json.is_empty() { "null".to_string() } else { entry.context_json.clone() },
+            entry.timestamp_ms,
+        );
+        let json_bytes = json.as_bytes();
+        let to_copy = json_bytes.len().min(len);
+        unsafe {
+            std::ptr::copy_nonoverlapping(json_bytes.as_ptr(), buffer, to_copy);
+        }
+        to_copy as c_int
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn is_raw_socket_available() -> c_int {
+    with_ffi_guard(0, || {
+        #[cfg(target_os = "linux")]
+        unsafe {
+            let fd = libc::socket(libc::AF_PACKET, libc::SOCK_RAW, 0);
+            if fd >= 0 { 
+                libc::close(fd); 
+                return 1; 
+            }
+            return 0;
+        }
+
+        #[cfg(target_os = "macos")]
+        unsafe {
+            for i in 0..256 {
+                let dev = format!("/dev/bpf{}\0", i);
+                match libc::open(dev.as_ptr() as *const libc::c_char, libc::O_RDWR | libc::O_NONBLOCK) {
+                    -1 => continue,
+                    fd => {
+                        libc::close(fd);
+                        return 1;
+                    }
+                }
+            }
+            return 0;
+        }
+        
+        #[cfg(target_os = "windows")]
+        {
+            use pnet_datalink;
+            let interfaces = pnet_datalink::interfaces();
+            if interfaces.is_empty() { return 0; }
+            if let Some(interface) = interfaces.first() {
+                 if let Ok(cap_builder) = pcap::Capture::from_device(interface.name.as_str()) {
+                     if cap_builder.open().is_ok() { return 1; }
+                 }
+            }
+            return 0;
+        }
+
+        #[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+        return 0;
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn ethercrab_init(
+    interface: *const c_char,
+    _expected_slaves: *const SlaveIdentity,
+    _expected_count: usize,
+    init_commands: *const FfiInitCommand,
+    init_command_count: usize,
+    pdu_timeout_ms: u64,
+    state_transition_timeout_ms: u64,
+    mailbox_response_timeout_ms: u64,
+    eeprom_timeout_ms: u64,
+    pdu_retries: usize,
+) -> c_int {
+    with_ffi_guard(-1, || {
+        if interface.is_null() { return -1; }
+        
+        // Idempotency check: if already initialized, return 0
+        if STATE.read().is_some() {
+            return 0;
+        }
+
+        let interface_str = unsafe {
+            match CStr::from_ptr(interface).to_str() {
+                Ok(s) => s.to_string(),
+                Err(e) => {
+                    set_error_ctx(FfiErrorCode::InvalidArgument, format!("Invalid interface string: {}", e), &[("op", "init")]);
+                    return -2;
+                }
+            }
+        };
+
+        let cmds = if !init_commands.is_null() && init_command_count > 0 {
+            unsafe { std::slice::from_raw_parts(init_commands, init_command_count).to_vec() }
+        } else {
+            Vec::new()
+        };
+
+        // Build custom Timeouts from parameters
+        let timeouts = Timeouts {
+            pdu: Duration::from_millis(pdu_timeout_ms),
+            state_transition: Duration::from_millis(state_transition_timeout_ms),
+            mailbox_response: Duration::from_millis(mailbox_response_timeout_ms),
+            // Configurable eeprom timeout
+            eeprom: Duration::from_millis(eeprom_timeout_ms),
+            wait_loop_delay: Duration::from_millis(0),
+            mailbox_echo: Duration::from_millis(100),
+        };
+
+        // Run purely on this thread. smol::block_on spins a local executor.
+        let result = smol::block_on(async move {
+            // Reset health status
+            NETWORK_HEALTHY.store(true, Ordering::Relaxed);
+
+            #[cfg(target_os = "windows")]
+            let maindevice = {
+                let guard = GLOBAL_DEVICE.read();
+                if let Some(md) = guard.as_ref() {
+                    // Reuse existing maindevice - TX_RX_RESOURCES already has the thread info
+                    md.clone()
+                } else {
+                    drop(guard); // Release read lock before acquiring write lock
+
+                    // Double-check locking pattern
+                    let mut guard = GLOBAL_DEVICE.write();
+                    // Check if someone else initialized while we were waiting
+                    if let Some(md) = guard.as_ref() {
+                        md.clone()
+                    } else {
+                        // Create storage on heap and convert to raw pointer to manage lifetime manually
+                        let storage_box = Box::new(PduStorage::<MAX_FRAMES, MAX_PDU_DATA>::new());
+                        let ptr = Box::into_raw(storage_box);
+                        let storage_ptr_val = ptr as usize;
+                        let storage = unsafe { &mut *ptr };
+
+                        let (mut tx, mut rx, pdu_loop) = storage.try_split().map_err(|_| {
+                            set_error("Failed to split PDU storage - likely already in use");
+                            -3
+                        })?;
+
+                        let maindevice = Arc::new(MainDevice::new(
+                            pdu_loop,
+                            timeouts,
+                            MainDeviceConfig {
+                                dc_static_sync_iterations: 0,  // Disable DC to avoid timeouts
+                                retry_behaviour: ethercrab::RetryBehaviour::Count(pdu_retries),
+                                ..MainDeviceConfig::default()
+                            },
+                        ));
+
+                        // Spawn the network TX/RX task on a dedicated thread.
+                        let iface = interface_str.clone();
+                        let shutdown = Arc::new(AtomicBool::new(false));
+                        let shutdown_clone = shutdown.clone();
+
+                        // Validate interface before spawning thread
+                        validate_windows_interface(&iface)?;
+
+                        let handle = std::thread::Builder::new()
+                            .name("ethercrab-tx-rx".into())
+                            .stack_size(8 * 1024 * 1024)
+                            .spawn(move || {
+                                let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                                    windows_tx_rx_loop!(iface.as_str(), tx, rx, shutdown_clone);
+                                }));
+
+                                if let Err(panic) = result {
+                                    set_error(format!("TX/RX thread panicked: {}", panic_message(&panic)));
+                                    NETWORK_HEALTHY.store(false, Ordering::Relaxed);
+                                }
+                            })
+                            .map_err(|e| {
+                                set_error(format!("Failed to spawn TX/RX thread: {}", e));
+                                -3
+                            })?;
+
+                        // Store TX/RX resources globally for cleanup
+                        *TX_RX_RESOURCES.lock() = Some(TxRxResources {
+                            thread_handle: Some(handle),
+                            shutdown_signal: shutdown,
+                            storage_ptr: storage_ptr_val,
+                        });
+
+                        *guard = Some(maindevice.clone());
+                        
+                        // Give the TX/RX thread time to start up and open the interface
+                        std::thread::sleep(Duration::from_millis(100));
+                        
+                        maindevice
+                    }
+                }
+            };
+
+            #[cfg(not(target_os = "windows"))]
+            let maindevice = {
+                let guard = GLOBAL_DEVICE.read();
+                if let Some(md) = guard.as_ref() {
+                    // Reuse existing maindevice - TX_RX_RESOURCES already has the thread info
+                    md.clone()
+                } else {
+                    drop(guard); // Release read lock before acquiring write lock
+
+                    // Double-check locking pattern
+                    let mut guard = GLOBAL_DEVICE.write();
+                    // Check if someone else initialized while we were waiting
+                    if let Some(md) = guard.as_ref() {
+                        md.clone()
+                    } else {
+                        // Create storage on heap and convert to raw pointer to manage lifetime manually
+                        let storage_box = Box::new(PduStorage::<MAX_FRAMES, MAX_PDU_DATA>::new());
+                        let ptr = Box::into_raw(storage_box);
+                        let storage_ptr_val = ptr as usize;
+                        let storage = unsafe { &mut *ptr };
+
+                        let (tx, rx, pdu_loop) = storage.try_split().map_err(|_| {
+                            set_error("Failed to split PDU storage - likely already in use");
+                            -3
+                        })?;
+
+                        let maindevice = Arc::new(MainDevice::new(
+                            pdu_loop,
+                            timeouts,
+                            MainDeviceConfig {
+                                dc_static_sync_iterations: 0,  // Disable DC to avoid timeouts
+                                retry_behaviour: ethercrab::RetryBehaviour::Count(pdu_retries),
+                                ..MainDeviceConfig::default()
+                            },
+                        ));
+
+                        // Spawn the network TX/RX task on a dedicated thread.
+                        let iface = interface_str.clone();
+                        let shutdown = Arc::new(AtomicBool::new(false));
+                        let shutdown_clone = shutdown.clone();
+
+                        let handle = std::thread::Builder::new()
+                            .name("ethercrab-tx-rx".into())
+                            .stack_size(8 * 1024 * 1024)
+                            .spawn(move || {
+                                let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                                    // Use tx_rx_task which returns a Result<Future, Error>
+                                    match ethercrab::std::tx_rx_task(&iface, tx, rx) {
+                                        Ok(task) => {
+                                            // Map task output to () so types match with shutdown_fut
+                                            let task = async move {
+                                                task.await.map(|_| ())
+                                            };
+                                            let shutdown_fut = async {
+                                                while !shutdown_clone.load(Ordering::SeqCst) {
+                                                    smol::Timer::after(Duration::from_millis(50)).await;
+                                                }
+                                                Ok::<(), ethercrab::error::Error>(())
+                                            };
+                                            
+                                            match smol::block_on(future::race(task, shutdown_fut)) {
+                                                Ok(_) => {},
+                                                Err(e) => set_error(format!("TX/RX loop failed: {}", e)),
+                                            }
+                                        },
+                                        Err(e) => {
+                                            set_error(format!("Failed to create tx_rx task: {}", e));
+                                        }
+                                    }
+                                }));
+
+                                if let Err(panic) = result {
+                                    set_error(format!("TX/RX thread panicked: {}", panic_message(&panic)));
+                                    NETWORK_HEALTHY.store(false, Ordering::Relaxed);
+                                }
+                            })
+                            .map_err(|e| {
+                                set_error(format!("Failed to spawn TX/RX thread: {}", e));
+                                -3
+                            })?;
+
+                        // Store TX/RX resources globally for cleanup
+                        *TX_RX_RESOURCES.lock() = Some(TxRxResources {
+                            thread_handle: Some(handle),
+                            shutdown_signal: shutdown,
+                            storage_ptr: storage_ptr_val,
+                        });
+
+                        *guard = Some(maindevice.clone());
+                        
+                        // Give the TX/RX thread time to start up and open the interface
+                        std::thread::sleep(Duration::from_millis(100));
+                        
+                        maindevice
+                    }
+                }
+            };
+
+            // Init Group
+            let group = maindevice.init_single_group::<MAX_SUBDEVICES, MAX_PDI>(ethercat_now)
+                .await.map_err(|e| {
+                    set_error_ctx(
+                        FfiErrorCode::NetworkError,
+                        format!("Failed to init single group: {:?}", e),
+                        &[("op", "init"), ("step", "init_single_group"), ("interface", &interface_str), ("error_detail", &format!("{:?}", e)), ("suggestion", "Check network interface name, cable connections, and that slaves are powered on")],
+                    );
+                    -5
+                })?;
+            mailbox_channels_invalidate();
+
+            // Run Init Commands
+            for cmd in cmds {
+                let slave_idx = cmd.slave_index as usize;
+                if let Some(subdevice) = group.iter(&maindevice).nth(slave_idx) {
+                    let val = u32_from_bytes(cmd.value);
+                    match cmd.command_type {
+                        0 => {
+                            let _ = coe_sdo_download(
+                                &maindevice,
+                                subdevice.configured_address(),
+                                cmd.index,
+                                cmd.sub_index,
+                                &cmd.value,
+                                Duration::from_millis(mailbox_response_timeout_ms),
+                            ).await;
+                        }
+                        2 => {
+                            let result = soe_write_init_value(
+                                &maindevice,
+                                subdevice.configured_address(),
+                                cmd.sub_index,
+                                cmd.index,
+                                cmd.value,
+                                Duration::from_millis(mailbox_response_timeout_ms),
+                            ).await;
+                            if let Err(e) = result {
+                                soe_error_ctx("init_cmd", cmd.slave_index, cmd.sub_index, cmd.index, SOE_ELEMENT_VALUE, &e);
+                            }
+                        }
+                        _ => { let _ = subdevice.register_write(cmd.index, val).await; }
+                    }
+                }
+            }
+
+            let pdi_buffer = Arc::new(RwLock::new([0u8; MAX_PDI]));
+            
+            let master_state = EcMasterState {
+                maindevice: maindevice.clone(),
+                group: Some(GroupState::PreOp(group)),
+                pdi_buffer,
+                pdi_size: 0,
+                input_size: 0,
+                output_size: 0,
+                expected_wkc: 0,
+                mailbox_poll_interval_ms: None,
+                pdu_timeout_ms,
+                mailbox_timeout_ms: mailbox_response_timeout_ms,
+                eeprom_timeout_ms,
+                process_image: ProcessImage::default(),
+                fail_safe: Mutex::new(FailSafe::default()),
+                esc_errors: Mutex::new(EscErrorHistory::default()),
+                link_monitor: Mutex::new(None),
+            };
+
+            let mut guard = STATE.write();
+            *guard = Some(master_state);
+            Ok(0)
+        });
+
+        match result {
+            Ok(v) => v,
+            Err(e) => e,
+        }
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn ethercrab_verify_topology(
+    expected: *const SlaveIdentity,
+    expected_count: usize,
+) -> c_int {
+    with_ffi_guard(-1, || {
+        if expected.is_null() || expected_count == 0 { return -1; }
+
+        let expected_slaves = unsafe { std::slice::from_raw_parts(expected, expected_count) };
+
+        // We need to access the group to iterate.
+        // We can do this synchronously because we are just reading memory, not doing I/O.
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+
+        // Helper to get group count regardless of state
+        let discovered_count = match &state.group {
+            Some(GroupState::PreOp(g)) => g.len(),
+            Some(GroupState::SafeOp(g)) => g.len(),
+            Some(GroupState::Op(g)) => g.len(),
+            None => return -1,
+        };
+
+        if discovered_count != expected_count { return -1; }
+
+        for (idx, expected_slave) in expected_slaves.iter().enumerate() {
+            let identity = match &state.group {
+                Some(GroupState::PreOp(g)) => g.iter(&state.maindevice).nth(idx).map(|s| s.identity()),
+                Some(GroupState::SafeOp(g)) => g.iter(&state.maindevice).nth(idx).map(|s| s.identity()),
+                Some(GroupState::Op(g)) => g.iter(&state.maindevice).nth(idx).map(|s| s.identity()),
+                None => return -1,
+            };
+
+            if let Some(id) = identity {
+                if id.vendor_id != expected_slave.vendor_id || id.product_id != expected_slave.product_code {
+                    return -1;
+                }
+                if expected_slave.serial_number != 0 && id.serial != expected_slave.serial_number {
+                    return -1;
+                }
+            } else {
+                return -1;
+            }
+        }
+
+        0
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn ethercrab_request_state(target_state: u8) -> c_int {
+    with_ffi_guard(-1, || {
+        // If no master is initialized, allow INIT/PRE-OP as a no-op.
+        // This prevents noisy warnings during teardown after failed init.
+        if STATE.read().is_none() {
+            if target_state == 0 || target_state == 1 {
+                return 0;
+            }
+            set_error_ctx(
+                FfiErrorCode::NotInitialized,
+                format!("No master available for state transition to {}", state_name(target_state)),
+                &[("op", "request_state"), ("target_state", state_name(target_state))],
+            );
+            return -1;
+        }
+
+        // Op would also drop the fail-safe outputs the supervisor put on the wire
+        if target_state == 3 && HOST_WATCHDOG.lock().as_ref().is_some_and(|wd| wd.latched) {
+            set_error_ctx(
+                FfiErrorCode::HostWatchdogTimeout,
+                "Op refused while the host watchdog is latched after a missed heartbeat",
+                &[
+                    ("op", "request_state"),
+                    ("target_state", state_name(target_state)),
+                    ("suggestion", "Call ethercrab_watchdog_acknowledge first"),
+                ],
+            );
+            return -3;
+        }
+
+        let mut guard = STATE.write();
+        let state = match guard.as_mut() {
+            Some(s) => s,
+            None => return -1,
+        };
+
+        // Leaving Op: put the fail-safe images on the wire while the slaves still apply outputs
+        if target_state < 3 {
+            if let Some(GroupState::Op(g)) = &state.group {
+                send_fail_safe(state, g, FAILSAFE_REASON_OP_TO_SAFEOP);
+            }
+        }
+
+        let group_enum = state.group.take(); 
+        let maindevice = state.maindevice.clone();
+        // Capture current state values before async block
+        let current_input_size = state.input_size;
+        let current_output_size = state.output_size;
+        let current_expected_wkc = state.expected_wkc;
+        let eeprom_timeout = Duration::from_millis(state.eeprom_timeout_ms);
+        let mailbox_timeout = Duration::from_millis(state.mailbox_timeout_ms);
+        // ethercrab runs its own CoE traffic during transitions (PDO configuration)
+        mailbox_channels_invalidate();
+
+        let result = smol::block_on(async {
+            match (target_state, group_enum) {
+                // Init (0) / PreOp (1) handled below
+
+                
+                // To SafeOp (2)
+                (2, Some(GroupState::PreOp(g))) => {
+                    let g_safe = match g.into_safe_op(&maindevice).await {
+                        Ok(g) => g,
+                        Err(e) => {
+                            set_error_ctx(
+                                FfiErrorCode::StateTransitionFailed,
+                                format!("State transition PreOp→SafeOp failed: {:?}", e),
+                                &[("op", "request_state"), ("from", "PreOp"), ("to", "SafeOp"), ("error_detail", &format!("{:?}", e)), ("suggestion", "Increase runtimeOptions.stateTransitionTimeoutMs or check slave AL status")],
+                            );
+                            return Err(-3);
+                        }
+                    };
+                    
+                    let mut in_sz = 0;
+                    let mut out_sz = 0;
+                    for slave in g_safe.iter(&maindevice) {
+                        let io = slave.io_raw();
+                        in_sz += io.inputs().len();
+                        out_sz += io.outputs().len();
+                    }
+                    
+                    // WKC counts all slaves in the group, not just those with PDI
+                    // This is because the LRW frame passes through all slaves
+                    let wkc_count = g_safe.len() as u16;
+
+                    // The mapping is fixed from here on, resolve it into the symbol table
+                    let image = build_process_image(&maindevice, &g_safe, mailbox_timeout, eeprom_timeout).await;
+                    
+                    Ok((Some(GroupState::SafeOp(g_safe)), in_sz, out_sz, wkc_count, Some(image)))
+                },
+                (2, Some(GroupState::SafeOp(g))) => Ok((Some(GroupState::SafeOp(g)), current_input_size, current_output_size, current_expected_wkc, None)),
+                (2, Some(GroupState::Op(g))) => {
+                    // Op -> SafeOp
+                    let g_safe = match g.into_safe_op(&maindevice).await {
+                        Ok(g) => g,
+                        Err(e) => {
+                            set_error_ctx(
+                                FfiErrorCode::StateTransitionFailed,
+                                format!("State transition Op→SafeOp failed: {:?}", e),
+                                &[("op", "request_state"), ("from", "Op"), ("to", "SafeOp"), ("error_detail", &format!("{:?}", e)), ("suggestion", "Increase runtimeOptions.stateTransitionTimeoutMs or check slave AL status")],
+                            );
+                            return Err(-3);
+                        }
+                    };
+                    Ok((Some(GroupState::SafeOp(g_safe)), current_input_size, current_output_size, current_expected_wkc, None))
+                },
+                
+                // To Op (3)
+                (3, Some(GroupState::SafeOp(g))) => {
+                    let g_op = match g.into_op(&maindevice).await {
+                        Ok(g) => g,
+                        Err(e) => {
+                            set_error_ctx(
+                                FfiErrorCode::StateTransitionFailed,
+                                format!("State transition SafeOp→Op failed: {:?}", e),
+                                &[("op", "request_state"), ("from", "SafeOp"), ("to", "Op"), ("error_detail", &format!("{:?}", e)), ("suggestion", "Increase runtimeOptions.stateTransitionTimeoutMs or check slave AL status")],
+                            );
+                            return Err(-3);
+                        }
+                    };
+                    Ok((Some(GroupState::Op(g_op)), current_input_size, current_output_size, current_expected_wkc, None))
+                },
+                (3, Some(GroupState::Op(g))) => Ok((Some(GroupState::Op(g)), current_input_size, current_output_size, current_expected_wkc, None)),
+                
+                // To PreOp (1) or Init (0)
+                (0 | 1, Some(g_any)) => {
+                     match g_any {
+                         GroupState::PreOp(g) => Ok((Some(GroupState::PreOp(g)), 0, 0, 0, Some(ProcessImage::default()))),
+                         GroupState::SafeOp(g) => {
+                            let g_pre = match g.into_pre_op(&maindevice).await {
+                                Ok(g) => g,
+                                Err(e) => {
+                                    set_error_ctx(
+                                        FfiErrorCode::StateTransitionFailed,
+                                        format!("State transition SafeOp→PreOp failed: {:?}", e),
+                                        &[("op", "request_state"), ("from", "SafeOp"), ("to", "PreOp"), ("error_detail", &format!("{:?}", e)), ("suggestion", "Increase runtimeOptions.stateTransitionTimeoutMs or check slave AL status")],
+                                    );
+                                    return Err(-3);
+                                }
+                            };
+                            Ok((Some(GroupState::PreOp(g_pre)), 0, 0, 0, Some(ProcessImage::default())))
+                         },
+                         GroupState::Op(g) => {
+                            // Op -> SafeOp -> PreOp
+                            let g_safe = match g.into_safe_op(&maindevice).await {
+                                Ok(g) => g,
+                                Err(e) => {
+                                    set_error_ctx(
+                                        FfiErrorCode::StateTransitionFailed,
+                                        format!("State transition Op→SafeOp failed (during Op→PreOp): {:?}", e),
+                                        &[("op", "request_state"), ("from", "Op"), ("to", "PreOp"), ("step", "Op→SafeOp"), ("error_detail", &format!("{:?}", e)), ("suggestion", "Increase runtimeOptions.stateTransitionTimeoutMs or check slave AL status")],
+                                    );
+                                    return Err(-3);
+                                }
+                            };
+                            let g_pre = match g_safe.into_pre_op(&maindevice).await {
+                                Ok(g) => g,
+                                Err(e) => {
+                                    set_error_ctx(
+                                        FfiErrorCode::StateTransitionFailed,
+                                        format!("State transition SafeOp→PreOp failed (during Op→PreOp): {:?}", e),
+                                        &[("op", "request_state"), ("from", "Op"), ("to", "PreOp"), ("step", "SafeOp→PreOp"), ("error_detail", &format!("{:?}", e)), ("suggestion", "Increase runtimeOptions.stateTransitionTimeoutMs or check slave AL status")],
+                                    );
+                                    return Err(-3);
+                                }
+                            };
+                            Ok((Some(GroupState::PreOp(g_pre)), 0, 0, 0, Some(ProcessImage::default())))
+                         }
+                     }
+                },
+                
+                // Invalid Transitions - return the group as-is since no transition was attempted
+                (_, Some(g)) => {
+                    // Return the original group and current state values since no transition occurred
+                    Ok((Some(g), current_input_size, current_output_size, current_expected_wkc, None))
+                }, 
+
+                (_, None) => {
+                    set_error_ctx(
+                        FfiErrorCode::NotInitialized,
+                        format!("No group available for state transition to {}", state_name(target_state)),
+                        &[("op", "request_state"), ("target_state", state_name(target_state))],
+                    );
+                    Err(-2)
+                },
+            }
+        });
+
+        match result {
+            Ok((new_group, in_s, out_s, wkc, image)) => {
+                state.group = new_group;
+                state.input_size = in_s;
+                state.output_size = out_s;
+                state.pdi_size = in_s + out_s;
+                state.expected_wkc = wkc;
+                if let Some(image) = image {
+                    state.process_image = image;
+                }
+                // Requesting Op acknowledges fail-safe
+                if target_state == 3 {
+                    state.fail_safe.lock().reset();
+                }
+                0
+            },
+            Err(e) => {
+                // Note: If a transition fails, the group is lost (consumed by the failed operation)
+                // This is a limitation of the ethercrab API - we cannot recover the group after a failed transition
+                // However, we ensure that for invalid transitions (no-op cases), the group is restored above
+                e
+            }
+        }
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn ethercrab_get_state() -> u8 {
+    with_ffi_guard(0, || {
+        let guard = STATE.read();
+        if let Some(state) = guard.as_ref() {
+            match &state.group {
+                Some(GroupState::PreOp(_)) => 1,
+                Some(GroupState::SafeOp(_)) => 2,
+                Some(GroupState::Op(_)) => 3,
+                None => 0,
+            }
+        } else {
+            0
+        }
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn ethercrab_get_al_status_code(slave_index: u16) -> u16 {
+    with_ffi_guard(0, || {
+        let result = smol::block_on(async move {
+            let guard = STATE.read();
+            if let Some(ref master_state) = *guard {
+                let maindevice = &master_state.maindevice;
+                let idx = slave_index as usize;
+                
+                let status_result = match &master_state.group {
+                    Some(GroupState::PreOp(g)) => {
+                        if let Some(subdevice) = g.iter(maindevice).nth(idx) {
+                            subdevice.status().await
+                        } else {
+                            return Err(0);
+                        }
+                    }
+                    Some(GroupState::SafeOp(g)) => {
+                        if let Some(subdevice) = g.iter(maindevice).nth(idx) {
+                            subdevice.status().await
+                        } else {
+                            return Err(0);
+                        }
+                    }
+                    Some(GroupState::Op(g)) => {
+                        if let Some(subdevice) = g.iter(maindevice).nth(idx) {
+                            subdevice.status().await
+                        } else {
+                            return Err(0);
+                        }
+                    }
+                    None => return Err(0),
+                };
+                
+                match status_result {
+                    Ok((_, status_code)) => Ok(u16::from(status_code)),
+                    Err(_) => Err(0),
+                }
+            } else {
+                Err(0)
+            }
+        });
+
+        match result {
+            Ok(code) => code,
+            Err(_) => 0,
+        }
+    })
+}
+
+/// Returns the PDI buffer pointer directly.
+/// Note: Deno FFI doesn't correctly marshal pointers in struct returns, so we use separate functions.
+#[no_mangle]
+pub extern "C" fn ethercrab_get_pdi_buffer_ptr() -> *mut u8 {
+    with_ffi_guard(std::ptr::null_mut(), || {
+        let guard = STATE.read();
+        if let Some(state) = guard.as_ref() {
+            let buf_guard = state.pdi_buffer.read();
+            let ptr = buf_guard.as_ptr() as *mut u8;
+            drop(buf_guard);
+            ptr
+        } else {
+            std::ptr::null_mut()
+        }
+    })
+}
+
+/// Returns total PDI size in bytes.
+#[no_mangle]
+pub extern "C" fn ethercrab_get_pdi_total_size() -> u32 {
+    with_ffi_guard(0, || {
+        let guard = STATE.read();
+        if let Some(state) = guard.as_ref() {
+            state.pdi_size as u32
+        } else {
+            0
+        }
+    })
+}
+
+/// Exchange process data once. In Op the host outputs (or the fail-safe image) are sent; in
+/// SafeOp the outputs are left at their safe values and only the inputs are refreshed.
+/// Returns the working counter, -1 (not initialized) or -2 (not in SafeOp/Op, or the
+/// exchange failed).
+#[no_mangle]
+pub extern "C" fn ethercrab_cyclic_tx_rx() -> c_int {
+    with_ffi_guard(-1, || {
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        let maindevice = &state.maindevice;
+
+        // Perform IO (Blocking call on this thread) and copy the inputs
+        // (EtherCAT Frame -> Shared Memory)
+        // We rely on ethercrab's internal PDU timeout configuration.
+        // Lock contention from background tasks is handled by pausing them via NETWORK_HEALTHY flag.
+        macro_rules! exchange {
+            ($group:expr) => {
+                smol::block_on($group.tx_rx(maindevice)).map(|res| {
+                    let mut buffer = state.pdi_buffer.write();
+                    for (layout, slave) in state.process_image.layouts.iter().zip($group.iter(maindevice)) {
+                        copy_slave_inputs(&mut buffer[..], layout, slave.inputs_raw());
+                    }
+                    res.working_counter
+                })
+            };
+        }
+
+        // Fast path check
+        let result = match &state.group {
+            Some(GroupState::Op(group)) => {
+                // Sync Shared Memory -> EtherCrab SubDevice (before tx_rx)
+                // The PDI buffer is the only output image: pdi_buffer writes, per-slave byte writes and
+                // typed/bit writes all land there and are copied to the slaves here
+                // Slaves in fail-safe send their fail-safe image instead
+                sync_outputs(state, group);
+                exchange!(group)
+            }
+            Some(GroupState::SafeOp(group)) => exchange!(group),
+            _ => return -2,
+        };
+        let wkc = match result {
+            Ok(wkc) => {
+                NETWORK_HEALTHY.store(true, Ordering::Relaxed);
+                wkc
+            },
+            Err(e) => {
+                NETWORK_HEALTHY.store(false, Ordering::Relaxed);
+                enter_fail_safe(state, FAILSAFE_REASON_WKC_LOSS);
+                let err_detail = format!("{:?}", e);
+                let expected_wkc = state.expected_wkc;
+                let pdu_timeout = state.pdu_timeout_ms;
+                set_error_ctx(
+                    FfiErrorCode::PduTimeout,
+                    format!("Cyclic tx_rx failed: {}", err_detail),
+                    &[
+                        ("op", "cyclic_tx_rx"),
+                        ("expected_wkc", &expected_wkc.to_string()),
+                        ("pdu_timeout_ms", &pdu_timeout.to_string()),
+                        ("error_detail", &err_detail),
+                        ("suggestion", "Increase runtimeOptions.pduTimeoutMs, increase master.cycleTime, or check slave wiring"),
+                    ],
+                );
+                return -2;
+            },
+        };
+
+        // Slaves only count output writes in Op, so the fail-safe WKC check applies there
+        let expected = expected_lrw_wkc(&state.process_image.layouts);
+        if matches!(state.group, Some(GroupState::Op(_))) && wkc < expected && enter_fail_safe(state, FAILSAFE_REASON_WKC_LOSS) > 0 {
+            set_error_ctx(
+                FfiErrorCode::WkcMismatch,
+                format!("Working counter {} below {}, configured slaves switched to fail-safe outputs", wkc, expected),
+                &[("op", "cyclic_tx_rx"), ("wkc", &wkc.to_string()), ("expected_wkc", &expected.to_string())],
+            );
+        }
+
+        // Note: WKC can vary depending on network topology and frame structure
+        // Strict checking disabled - return actual WKC and let application decide
+        wkc as c_int
+    })
+}
+
+/// SDO error context shared by `ethercrab_sdo_read` and `ethercrab_sdo_write`.
+fn sdo_error_ctx(op: &str, slave_index: u16, index: u16, sub_index: u8, e: &MailboxError, suggestion: &str) {
+    set_error_ctx(
+        FfiErrorCode::SdoError,
+        format!("SDO {} failed on slave {} (0x{:04X}:{}): {}", op.trim_start_matches("sdo_"), slave_index, index, sub_index, e),
+        &[
+            ("op", op),
+            ("slave_index", &slave_index.to_string()),
+            ("sdo_index", &format!("0x{:04X}", index)),
+            ("sdo_sub_index", &sub_index.to_string()),
+            ("error_detail", &format!("{:?}", e)),
+            ("suggestion", suggestion),
+        ],
+    );
+}
+
+/// Read an object entry (expedited, normal or segmented upload) into `data_out`.
+/// Returns the number of bytes read, or -1 (not initialized), -2 (slave not found),
+/// -3 (SDO abort or mailbox failure), -4 (invalid argument or buffer too small).
+/// Objects larger than `max_len` are not truncated: the read fails with -4 and the error
+/// context carries the object size as `required_len`, so the caller can retry with a
+/// buffer that fits.
+#[no_mangle]
+pub extern "C" fn ethercrab_sdo_read(
+    slave_index: u16,
+    index: u16,
+    sub_index: u8,
+    data_out: *mut u8,
+    max_len: usize,
+) -> c_int {
+    with_ffi_guard(-1, || {
+        if data_out.is_null() || max_len == 0 { return -4; }
+
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        let address = match slave_configured_address(state, slave_index as usize) {
+            Some(a) => a,
+            None => {
+                set_error_ctx(
+                    FfiErrorCode::NotInitialized,
+                    format!("No group or slave available for SDO read on slave {} (0x{:04X}:{})", slave_index, index, sub_index),
+                    &[("op", "sdo_read"), ("slave_index", &slave_index.to_string())],
+                );
+                return -2;
+            }
+        };
+        let timeout = Duration::from_millis(state.mailbox_timeout_ms);
+
+        match smol::block_on(coe_sdo_upload(&state.maindevice, address, index, sub_index, timeout)) {
+            Ok(data) if data.len() > max_len => {
+                set_error_ctx(
+                    FfiErrorCode::InvalidArgument,
+                    format!("SDO read buffer too small: 0x{:04X}:{} has {} bytes, {} provided", index, sub_index, data.len(), max_len),
+                    &[("op", "sdo_read"), ("slave_index", &slave_index.to_string()), ("required_len", &data.len().to_string())],
+                );
+                -4
+            }
+            Ok(data) => {
+                unsafe { std::ptr::copy_nonoverlapping(data.as_ptr(), data_out, data.len()); }
+                data.len() as c_int
+            }
+            Err(e) => {
+                sdo_error_ctx("sdo_read", slave_index, index, sub_index, &e, "Verify SDO index/sub-index exist on slave. Try increasing runtimeOptions.mailboxResponseTimeoutMs");
+                -3
+            }
+        }
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn ethercrab_sdo_write(
+    slave_index: u16,
+    index: u16,
+    sub_index: u8,
+    data: *const u8,
+    len: usize,
+) -> c_int {
+    with_ffi_guard(-1, || {
+        if data.is_null() || len == 0 || len > 4 { return -4; }
+        let data = unsafe { std::slice::from_raw_parts(data, len) };
+
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        let address = match slave_configured_address(state, slave_index as usize) {
+            Some(a) => a,
+            None => {
+                set_error_ctx(
+                    FfiErrorCode::NotInitialized,
+                    format!("No group or slave available for SDO write on slave {} (0x{:04X}:{})", slave_index, index, sub_index),
+                    &[("op", "sdo_write"), ("slave_index", &slave_index.to_string())],
+                );
+                return -2;
+            }
+        };
+        let timeout = Duration::from_millis(state.mailbox_timeout_ms);
+
+        match smol::block_on(coe_sdo_download(&state.maindevice, address, index, sub_index, data, timeout)) {
+            Ok(()) => 0,
+            Err(e) => {
+                sdo_error_ctx("sdo_write", slave_index, index, sub_index, &e, "Verify SDO index/sub-index exist and are writable. Try increasing runtimeOptions.mailboxResponseTimeoutMs");
+                -3
+            }
+        }
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn ethercrab_eeprom_read(
+    slave_index: u16,
+    address: u16,
+    data_out: *mut u8,
+    len: usize,
+) -> c_int {
+    with_ffi_guard(-1, || {
+        if data_out.is_null() || len == 0 { return -4; }
+
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+
+        let result = smol::block_on(async {
+            let idx = slave_index as usize;
+            let md = &state.maindevice;
+            let mut buffer = vec![0u8; len];
+            let _lock = SII_LOCK.lock().await;
+            
+            let group = match state.group.as_ref() {
+                Some(g) => g,
+                None => {
+                    set_error_ctx(
+                        FfiErrorCode::NotInitialized,
+                        format!("No group available for EEPROM read on slave {}", slave_index),
+                        &[("op", "eeprom_read"), ("slave_index", &slave_index.to_string())],
+                    );
+                    return Err(-2);
+                }
+            };
+            
+            let read_res = match group {
+                GroupState::PreOp(g) => g.iter(md).nth(idx).ok_or(-2)?.eeprom_read_raw(md, address, &mut buffer).await,
+                GroupState::SafeOp(g) => g.iter(md).nth(idx).ok_or(-2)?.eeprom_read_raw(md, address, &mut buffer).await,
+                GroupState::Op(g) => g.iter(md).nth(idx).ok_or(-2)?.eeprom_read_raw(md, address, &mut buffer).await,
+            };
+
+            match read_res {
+                 Ok(bytes) => Ok((bytes, buffer)),
+                 Err(e) => {
+                     set_error_ctx(
+                         FfiErrorCode::EepromError,
+                         format!("EEPROM read failed on slave {} at address 0x{:04X}: {:?}", slave_index, address, e),
+                         &[
+                             ("op", "eeprom_read"),
+                             ("slave_index", &slave_index.to_string()),
+                             ("address", &format!("0x{:04X}", address)),
+                             ("length", &len.to_string()),
+                             ("error_detail", &format!("{:?}", e)),
+                             ("suggestion", "Try increasing runtimeOptions.eepromTimeoutMs"),
+                         ],
+                     );
+                     Err(-3)
+                 }
+            }
+        });
+
+        match result {
+            Ok((bytes_read, buffer)) => {
+                unsafe { std::ptr::copy_nonoverlapping(buffer.as_ptr(), data_out, bytes_read as usize); }
+                bytes_read as c_int
+            },
+            Err(e) => e,
+        }
+    })
+}
+
+fn sii_error_ctx(op: &str, slave_index: u16, word_address: u16, len: usize, e: &SiiError) {
+    let code = match e {
+        SiiError::Timeout => FfiErrorCode::PduTimeout,
+        SiiError::PdiBusy => FfiErrorCode::ResourceBusy,
+        _ => FfiErrorCode::EepromError,
+    };
+    set_error_ctx(
+        code,
+        format!("EEPROM {} failed on slave {} at word 0x{:04X}: {}", op, slave_index, word_address, e),
+        &[
+            ("op", op),
+            ("slave_index", &slave_index.to_string()),
+            ("address", &format!("0x{:04X}", word_address)),
+            ("length", &len.to_string()),
+            ("error_detail", &format!("{:?}", e)),
+            ("suggestion", "Check the EEPROM is not write protected and try increasing runtimeOptions.eepromTimeoutMs"),
+        ],
+    );
+}
+
+/// Write 16-bit words to the SII EEPROM starting at `word_address` (`len` must be even).
+///
+/// Only words that differ are written, and each is read back to verify. Writes touching
+/// words 0-7 also update the configuration area CRC in word 7.
+/// Returns the number of words written, or -1 (not initialized), -2 (slave not found),
+/// -3 (EEPROM failure), -4 (invalid argument).
+#[no_mangle]
+pub extern "C" fn ethercrab_eeprom_write(
+    slave_index: u16,
+    word_address: u16,
+    data: *const u8,
+    len: usize,
+) -> c_int {
+    with_ffi_guard(-1, || {
+        if data.is_null() || len == 0 || len % 2 == 1 { return -4; }
+        let payload = unsafe { std::slice::from_raw_parts(data, len) };
+
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        let address = match slave_configured_address(state, slave_index as usize) {
+            Some(a) => a,
+            None => return -2,
+        };
+        let timeout = Duration::from_millis(state.eeprom_timeout_ms);
+
+        match smol::block_on(sii_write(&state.maindevice, address, word_address, payload, false, timeout)) {
+            Ok(written) => written.len() as c_int,
+            Err(e) => {
+                sii_error_ctx("write", slave_index, word_address, len, &e);
+                -3
+            }
+        }
+    })
+}
+
+/// Write a complete SII image starting at word 0 and verify it.
+///
+/// The configuration area CRC (word 7) is recalculated from the image. With `dry_run`
+/// set nothing is written and only the differences are reported. Up to `max_diffs`
+/// differing word addresses are stored in `diff_out` (may be null).
+/// Returns the number of differing words, or -1 (not initialized), -2 (slave not found),
+/// -3 (EEPROM failure or verify mismatch), -4 (invalid argument).
+#[no_mangle]
+pub extern "C" fn ethercrab_eeprom_write_image(
+    slave_index: u16,
+    image: *const u8,
+    len: usize,
+    dry_run: u8,
+    diff_out: *mut u16,
+    max_diffs: usize,
+) -> c_int {
+    with_ffi_guard(-1, || {
+        if image.is_null() || len < 16 || len % 2 == 1 { return -4; }
+        let image = unsafe { std::slice::from_raw_parts(image, len) };
+
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        let address = match slave_configured_address(state, slave_index as usize) {
+            Some(a) => a,
+            None => return -2,
+        };
+        let timeout = Duration::from_millis(state.eeprom_timeout_ms);
+
+        match smol::block_on(sii_write(&state.maindevice, address, 0, image, dry_run != 0, timeout)) {
+            Ok(diffs) => {
+                if !diff_out.is_null() {
+                    let n = diffs.len().min(max_diffs);
+                    unsafe { std::ptr::copy_nonoverlapping(diffs.as_ptr(), diff_out, n); }
+                }
+                diffs.len() as c_int
+            }
+            Err(e) => {
+                let op = if dry_run != 0 { "image_diff" } else { "image_write" };
+                sii_error_ctx(op, slave_index, 0, len, &e);
+                -3
+            }
+        }
+    })
+}
+
+/// Copy a JSON or XML document into a caller buffer. Returns bytes written, or -4 with
+/// `required_len` in the error context if the buffer is too small.
+fn copy_text_out(op: &str, text: &str, buffer: *mut u8, len: usize) -> c_int {
+    let bytes = text.as_bytes();
+    if bytes.len() > len {
+        set_error_ctx(
+            FfiErrorCode::InvalidArgument,
+            format!("{} buffer too small: {} bytes needed, {} provided", op, bytes.len(), len),
+            &[("op", op), ("required_len", &bytes.len().to_string())],
+        );
+        return -4;
+    }
+    unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer, bytes.len()); }
+    bytes.len() as c_int
+}
+
+/// Read a slave's SII and return it parsed as JSON: identity, mailbox, strings,
+/// general, FMMU, sync manager, TxPDO/RxPDO and DC categories.
+/// Returns bytes written, or -1 (not initialized), -2 (slave not found),
+/// -3 (EEPROM failure), -4 (invalid argument or buffer too small).
+#[no_mangle]
+pub extern "C" fn ethercrab_sii_read_json(slave_index: u16, buffer: *mut u8, len: usize) -> c_int {
+    with_ffi_guard(-1, || {
+        if buffer.is_null() || len == 0 { return -4; }
+
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        let address = match slave_configured_address(state, slave_index as usize) {
+            Some(a) => a,
+            None => return -2,
+        };
+        let timeout = Duration::from_millis(state.eeprom_timeout_ms);
+
+        match smol::block_on(sii_read_image(&state.maindevice, address, timeout)) {
+            Ok(image) => copy_text_out("sii_read_json", &parse_sii(&image).to_json(), buffer, len),
+            Err(e) => {
+                sii_error_ctx("sii_read", slave_index, 0, 0, &e);
+                -3
+            }
+        }
+    })
+}
+
+/// Parse an SII image obtained elsewhere (file, `ethercrab_eeprom_read`) into the
+/// same JSON as `ethercrab_sii_read_json`. Does not require an initialized master.
+/// Returns bytes written, or -4 (invalid argument or buffer too small).
+#[no_mangle]
+pub extern "C" fn ethercrab_sii_parse_json(
+    image: *const u8,
+    image_len: usize,
+    buffer: *mut u8,
+    len: usize,
+) -> c_int {
+    with_ffi_guard(-1, || {
+        if image.is_null() || image_len == 0 || buffer.is_null() || len == 0 { return -4; }
+        let image = unsafe { std::slice::from_raw_parts(image, image_len) };
+        copy_text_out("sii_parse_json", &parse_sii(image).to_json(), buffer, len)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn ethercrab_configure_mailbox_polling(interval_ms: u32) -> c_int {
+    with_ffi_guard(-1, || {
+        let mut guard = STATE.write();
+        if let Some(state) = guard.as_mut() {
+            if interval_ms == 0 {
+                state.mailbox_poll_interval_ms = None;
+            } else {
+                state.mailbox_poll_interval_ms = Some(interval_ms);
+            }
+            0
+        } else {
+            -1
+        }
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn ethercrab_check_mailbox(slave_index: u16, mailbox_status_addr: u16) -> c_int {
+    with_ffi_guard(-1, || {
+        // If network is unhealthy, skip IO to avoid lock contention
+        if !NETWORK_HEALTHY.load(Ordering::Relaxed) {
+            return -1;
+        }
+
+        // We don't need manual timeouts here as PDU timeouts are handled by ethercrab
+
+        let result = smol::block_on(async move {
+            let guard = STATE.read();
+            if let Some(ref master_state) = *guard {
+                    let maindevice = &master_state.maindevice;
+                    let idx = slave_index as usize;
+                    
+                    // Check we have a group
+                    let group = match master_state.group.as_ref() {
+                        Some(g) => g,
+                        None => return Err(-1),
+                    };
+                    
+                    let val_res = match group {
+                        GroupState::PreOp(g) => g.iter(maindevice).nth(idx).ok_or(-1)?.register_read::<u8>(mailbox_status_addr).await,
+                        GroupState::SafeOp(g) => g.iter(maindevice).nth(idx).ok_or(-1)?.register_read::<u8>(mailbox_status_addr).await,
+                        GroupState::Op(g) => g.iter(maindevice).nth(idx).ok_or(-1)?.register_read::<u8>(mailbox_status_addr).await,
+                    };
+                    
+                    match val_res {
+                        Ok(val) => Ok(if (val & 0x08) != 0 { 1 } else { 0 }),
+                        Err(_) => Err(-2)
+                    }
+            } else {
+                Err(-1)
+            }
+        });
+
+        match result {
+            Ok(v) => v,
+            Err(e) => e,
+        }
+    })
+}
+
+/// Feature 402: Mailbox Resilient Layer
+/// Checks mailbox status with toggle-bit verification and retry mechanism
+/// Returns: 0 (Empty), 1 (New Mail/Success), -1 (Error/State not ready), -2 (Retry Failed)
+#[no_mangle]
+pub extern "C" fn ethercrab_check_mailbox_resilient(
+    slave_index: u16,
+    mailbox_status_addr: u16,
+    last_toggle_bit: u8, // 0 or 1. If > 1, ignore toggle check (first run)
+) -> c_int {
+    with_ffi_guard(-1, || {
+        // If network is unhealthy, skip IO to avoid lock contention
+        if !NETWORK_HEALTHY.load(Ordering::Relaxed) {
+            return -1;
+        }
+
+        // Timeout is handled internally by ethercrab
+
+        let result = smol::block_on(async move {
+            let guard = STATE.read();
+            if let Some(ref master_state) = *guard {
+                let maindevice = &master_state.maindevice;
+                let idx = slave_index as usize;
+
+                // Check we have a group
+                let group = match master_state.group.as_ref() {
+                    Some(g) => g,
+                    None => return Err(-1),
+                };
+
+                // Retry Loop (Resilient Layer) - max 3 attempts
+                for _attempt in 0..MAILBOX_RESILIENT_ATTEMPTS {
+                    // 1. Read Register
+                    let val_res = match group {
+                        GroupState::PreOp(g) => {
+                            g.iter(maindevice).nth(idx).ok_or(-1)?.register_read::<u8>(mailbox_status_addr).await
+                        }
+                        GroupState::SafeOp(g) => {
+                            g.iter(maindevice).nth(idx).ok_or(-1)?.register_read::<u8>(mailbox_status_addr).await
+                        }
+                        GroupState::Op(g) => {
+                            g.iter(maindevice).nth(idx).ok_or(-1)?.register_read::<u8>(mailbox_status_addr).await
+                        }
+                    };
+
+                    match val_res {
+                        // Bit 3 = Mailbox Full (Input) (0x08)
+                        // Bit 1 = Toggle Bit (Input) (0x02)
+                        // Note: Check ETG.1000.4 spec for specific bit offsets per device
+                        Ok(val) => match classify_mailbox_status(val, last_toggle_bit) {
+                            MailboxPoll::Empty => return Ok(0),
+                            MailboxPoll::NewMail => return Ok(1), // Success: New valid mail
+                            // Mailbox is full but Toggle Bit didn't change.
+                            // This implies we might be re-reading an old frame or a lost update.
+                            // Retry immediately.
+                            MailboxPoll::Stale => continue,
+                        },
+                        Err(_) => continue, // Read failed, retry
+                    }
+                }
+
+                // Retries exhausted
+                Err(-2)
+            } else {
+                Err(-1)
+            }
+        });
+
+        match result {
+            Ok(v) => v,
+            Err(e) => e,
+        }
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn ethercrab_get_last_emergency(out: *mut EmergencyInfo) -> c_int {
+    with_ffi_guard(-1, || {
+        if out.is_null() { return -1; }
+
+        let emergency = *LAST_EMERGENCY.lock();
+        if let Some(emergency) = emergency {
+            // ethercrab assigns station addresses 0x1000 + position when the group is not available
+            let slave_index = STATE
+                .read()
+                .as_ref()
+                .and_then(|state| slave_configured_addresses(state).iter().position(|&a| a == emergency.configured_address))
+                .map(|i| i as u16)
+                .unwrap_or(emergency.configured_address.wrapping_sub(0x1000));
+            unsafe {
+                (*out).slave_index = slave_index;
+                (*out).error_code = emergency.error_code;
+                (*out).error_register = emergency.error_register;
+            }
+            0
+        } else {
+            -1
+        }
+    })
+}
+
+/// Write one byte of a slave's outputs. Goes through the PDI buffer like every other output
+/// write, so it is sent with the next ethercrab_cyclic_tx_rx; when the same byte is also
+/// written through the buffer pointer, the last write before the cycle wins.
+/// Returns 1 on success, 0 otherwise.
+#[no_mangle]
+pub extern "C" fn ethercrab_write_process_data_byte(
+    slave_index: u16,
+    byte_offset: u32,
+    value: u8,
+) -> c_int {
+    with_ffi_guard(0, || {
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return 0,
+        };
+        if !matches!(state.group, Some(GroupState::Op(_))) {
+            return 0; // Not in OP state
+        }
+        match pd_slave_range(&state.process_image, "write_process_data_byte", slave_index, true, byte_offset as usize) {
+            Ok((offset, available)) if available > 0 => {
+                state.pdi_buffer.write()[offset] = value;
+                1
+            }
+            _ => 0,
+        }
+    })
+}
+
+/// Read one byte of a slave's inputs (as received in the last cycle) or outputs (as they
+/// will be sent with the next one, including ethercrab_write_process_data_byte writes).
+/// Both come from the PDI buffer. Returns the byte, or 0 if it is not available.
+#[no_mangle]
+pub extern "C" fn ethercrab_read_process_data_byte(
+    slave_index: u16,
+    byte_offset: u32,
+    is_output: bool,
+) -> u8 {
+    with_ffi_guard(0, || {
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return 0,
+        };
+        match pd_slave_range(&state.process_image, "read_process_data_byte", slave_index, is_output, byte_offset as usize) {
+            Ok((offset, available)) if available > 0 => state.pdi_buffer.read()[offset],
+            _ => 0,
+        }
+    })
+}
+
+// --- Process Data Symbols ---
+
+/// Position of one slave's process data in the PDI buffer (outputs first, then inputs), and
+/// in the logical address space of the cyclic frame as configured in its FMMUs.
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+struct PdiSlaveLayout {
+    output_offset: usize,
+    output_len: usize,
+    input_offset: usize,
+    input_len: usize,
+    output_logical: u32,
+    input_logical: u32,
+}
+
+/// Logical address reported for a direction without an active FMMU.
+pub const PDI_NO_LOGICAL_ADDRESS: u32 = 0xFFFF_FFFF;
+
+/// One slave's layout as returned by ethercrab_pd_get_layout.
+#[repr(C)]
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct FfiPdiSlaveLayout {
+    pub output_offset: u32,  // Byte offset in the PDI buffer (ethercrab_get_pdi_buffer_ptr)
+    pub output_len: u32,
+    pub input_offset: u32,
+    pub input_len: u32,
+    pub output_logical: u32, // Logical start address in the frame, or PDI_NO_LOGICAL_ADDRESS
+    pub input_logical: u32,
+}
+
+impl From<&PdiSlaveLayout> for FfiPdiSlaveLayout {
+    fn from(l: &PdiSlaveLayout) -> Self {
+        Self {
+            output_offset: l.output_offset as u32,
+            output_len: l.output_len as u32,
+            input_offset: l.input_offset as u32,
+            input_len: l.input_len as u32,
+            output_logical: l.output_logical,
+            input_logical: l.input_logical,
+        }
+    }
+}
+
+/// FMMUs read per slave; ethercrab uses one per direction.
+const FMMU_SCAN_COUNT: u16 = 4;
+
+/// Logical start addresses of the active write (outputs) and read (inputs) FMMUs, from raw
+/// FMMU register blocks (ETG1000.4 §6.6: 0x0600 + n * 16).
+fn fmmu_logical_addresses(fmmus: &[[u8; 16]]) -> (u32, u32) {
+    let mut output_logical = PDI_NO_LOGICAL_ADDRESS;
+    let mut input_logical = PDI_NO_LOGICAL_ADDRESS;
+    for fmmu in fmmus {
+        if fmmu[12] & 0x01 == 0 {
+            continue;
+        }
+        let logical = u32::from_le_bytes([fmmu[0], fmmu[1], fmmu[2], fmmu[3]]);
+        // Type: 0x01 = read (slave inputs), 0x02 = write (slave outputs)
+        if fmmu[11] & 0x02 != 0 {
+            output_logical = output_logical.min(logical);
+        }
+        if fmmu[11] & 0x01 != 0 {
+            input_logical = input_logical.min(logical);
+        }
+    }
+    (output_logical, input_logical)
+}
+
+/// One mapped PDO entry of the active process image.
+#[repr(C)]
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct FfiProcessSymbol {
+    pub slave_index: u16,
+    pub pdo_index: u16,      // 0x16xx (outputs) or 0x1Axx (inputs)
+    pub index: u16,          // Mapped object
+    pub sub_index: u8,
+    pub is_output: u8,       // 1 = output (RxPDO), 0 = input (TxPDO)
+    pub bit_offset: u32,     // Within the slave's input or output image
+    pub bit_len: u16,
+    pub data_type: u16,      // CoE data type from SDO Information or the SII, 0 if neither describes it
+    pub pdi_bit_offset: u32, // Within the PDI buffer (ethercrab_get_pdi_buffer_ptr)
+}
+
+/// Symbol table and per-slave layout, built on PreOp→SafeOp and cleared on return to PreOp.
+#[derive(Default)]
+struct ProcessImage {
+    layouts: Vec<PdiSlaveLayout>,
+    symbols: Vec<FfiProcessSymbol>,
+}
+
+impl ProcessImage {
+    fn find(&self, slave_index: u16, index: u16, sub_index: u8) -> Option<&FfiProcessSymbol> {
+        self.symbols
+            .iter()
+            .find(|s| s.slave_index == slave_index && s.index == index && s.sub_index == sub_index)
+    }
+}
+
+/// Symbols of one slave from its assigned PDOs. Entry bits are packed in assignment order;
+/// a direction whose mapping does not fit the slave's image is left out.
+fn slave_symbols(slave_index: u16, pdos: &[DiscoveredPdo], layout: &PdiSlaveLayout) -> Vec<FfiProcessSymbol> {
+    let mut symbols = Vec::new();
+    // RxPDO = outputs = SM2, TxPDO = inputs = SM3 (canonical numbers)
+    for (sync_manager, image_offset, image_len) in [
+        (2u8, layout.output_offset, layout.output_len),
+        (3u8, layout.input_offset, layout.input_len),
+    ] {
+        let mut direction = Vec::new();
+        let mut bit_offset = 0u32;
+        for pdo in pdos.iter().filter(|p| p.info.sync_manager == sync_manager) {
+            for entry in &pdo.entries {
+                let e = &entry.info;
+                // Index 0 entries are gaps (padding)
+                if e.index != 0 {
+                    direction.push(FfiProcessSymbol {
+                        slave_index,
+                        pdo_index: pdo.info.index,
+                        index: e.index,
+                        sub_index: e.sub_index,
+                        is_output: (sync_manager == 2) as u8,
+                        bit_offset,
+                        bit_len: e.bit_len as u16,
+                        data_type: e.data_type,
+                        pdi_bit_offset: (image_offset * 8) as u32 + bit_offset,
+                    });
+                }
+                bit_offset += e.bit_len as u32;
+            }
+        }
+        if bit_offset as usize <= image_len * 8 {
+            symbols.extend(direction);
+        }
+    }
+    symbols
+}
+
+/// Read the active PDO assignment of every slave (CoE 0x1C12/0x1C13, else the SII
+/// categories) and resolve it against the PDI layout. Entry data types come from SDO
+/// Information or the SII; an entry neither describes keeps data type 0.
+async fn build_process_image(
+    md: &MainDevice<'_>,
+    group: &SubDeviceGroup<MAX_SUBDEVICES, MAX_PDI, spin::rwlock::RwLock<(), spin::Yield>, SafeOp>,
+    mailbox_timeout: Duration,
+    eeprom_timeout: Duration,
+) -> ProcessImage {
+    let mut image = ProcessImage::default();
+    let total_outputs: usize = group.iter(md).map(|s| s.io_raw().outputs().len()).sum();
+    let mut output_offset = 0;
+    let mut input_offset = total_outputs;
+
+    for (slave_index, subdevice) in group.iter(md).enumerate() {
+        let io = subdevice.io_raw();
+        let mut layout = PdiSlaveLayout {
+            output_offset,
+            output_len: io.outputs().len(),
+            input_offset,
+            input_len: io.inputs().len(),
+            output_logical: PDI_NO_LOGICAL_ADDRESS,
+            input_logical: PDI_NO_LOGICAL_ADDRESS,
+        };
+        output_offset += layout.output_len;
+        input_offset += layout.input_len;
+        if layout.output_len == 0 && layout.input_len == 0 {
+            image.layouts.push(layout);
+            continue;
+        }
+
+        let mut fmmus = Vec::new();
+        for n in 0..FMMU_SCAN_COUNT {
+            match subdevice.register_read::<[u8; 16]>(0x0600 + n * 16).await {
+                Ok(fmmu) => fmmus.push(fmmu),
+                Err(_) => break,
+            }
+        }
+        (layout.output_logical, layout.input_logical) = fmmu_logical_addresses(&fmmus);
+        image.layouts.push(layout);
+
+        let address = subdevice.configured_address();
+        let sii = sii_read_image(md, address, eeprom_timeout).await.ok().map(|image| parse_sii(&image));
+        let mut sdo_info = sii.as_ref().is_some_and(|s| s.sdo_info_supported());
+        let mut pdos = coe_assigned_pdos(md, address, sii.as_ref(), &mut sdo_info, mailbox_timeout).await;
+        if pdos.is_empty() {
+            if let Some(ref sii) = sii {
+                pdos = sii_discovered_pdos(sii);
+            }
+        }
+        image.symbols.extend(slave_symbols(slave_index as u16, &pdos, &layout));
+    }
+    image
+}
+
+/// Copy a slave's slice of the PDI output area into its frame image.
+fn copy_slave_outputs(buffer: &[u8], layout: &PdiSlaveLayout, outs: &mut [u8]) {
+    let len = layout.output_len.min(outs.len());
+    if let Some(src) = buffer.get(layout.output_offset..layout.output_offset + len) {
+        outs[..len].copy_from_slice(src);
+    }
+}
+
+/// Copy a slave's frame inputs into its slice of the PDI input area.
+fn copy_slave_inputs(buffer: &mut [u8], layout: &PdiSlaveLayout, ins: &[u8]) {
+    let len = layout.input_len.min(ins.len());
+    if let Some(dst) = buffer.get_mut(layout.input_offset..layout.input_offset + len) {
+        dst.copy_from_slice(&ins[..len]);
+    }
+}
+
+/// Little-endian bit field of up to 64 bits starting at `bit_offset`.
+fn read_bits(buf: &[u8], bit_offset: usize, bit_len: usize) -> u64 {
+    let mut value = 0u64;
+    for i in 0..bit_len {
+        let bit = bit_offset + i;
+        if (buf[bit / 8] >> (bit % 8)) & 1 != 0 {
+            value |= 1 << i;
+        }
+    }
+    value
+}
+
+/// Store the low `bit_len` bits of `value` at `bit_offset`, leaving neighbouring bits intact.
+fn write_bits(buf: &mut [u8], bit_offset: usize, bit_len: usize, value: u64) {
+    for i in 0..bit_len {
+        let bit = bit_offset + i;
+        let mask = 1u8 << (bit % 8);
+        if (value >> i) & 1 != 0 {
+            buf[bit / 8] |= mask;
+        } else {
+            buf[bit / 8] &= !mask;
+        }
+    }
+}
+
+/// Look up a symbol for a typed access and check that the access width matches the entry
+/// and, when the entry's data type is known, that it is one of `data_types`.
+fn pd_symbol(
+    state: &EcMasterState,
+    op: &str,
+    slave_index: u16,
+    index: u16,
+    sub_index: u8,
+    bit_len: u16,
+    data_types: &[u16],
+) -> Result<FfiProcessSymbol, c_int> {
+    let ctx = |code: FfiErrorCode, msg: String| {
+        set_error_ctx(
+            code,
+            msg,
+            &[
+                ("op", op),
+                ("slave_index", &slave_index.to_string()),
+                ("sdo_index", &format!("0x{:04X}", index)),
+                ("sdo_sub_index", &sub_index.to_string()),
+            ],
+        );
+    };
+    let symbol = match state.process_image.find(slave_index, index, sub_index) {
+        Some(s) => *s,
+        None => {
+            ctx(
+                FfiErrorCode::InvalidArgument,
+                format!("0x{:04X}:{} is not mapped on slave {} (process image is built in SafeOp)", index, sub_index, slave_index),
+            );
+            return Err(-2);
+        }
+    };
+    if symbol.bit_len != bit_len {
+        ctx(
+            FfiErrorCode::InvalidArgument,
+            format!("0x{:04X}:{} on slave {} is {} bits, access is {} bits", index, sub_index, slave_index, symbol.bit_len, bit_len),
+        );
+        return Err(-4);
+    }
+    if symbol.data_type != 0 && !data_types.contains(&symbol.data_type) {
+        ctx(
+            FfiErrorCode::InvalidArgument,
+            format!(
+                "0x{:04X}:{} on slave {} is {}, access is {}",
+                index,
+                sub_index,
+                slave_index,
+                eni_data_type_name(symbol.data_type, bit_len as u8),
+                eni_data_type_name(data_types[0], bit_len as u8),
+            ),
+        );
+        return Err(-4);
+    }
+    Ok(symbol)
+}
+
+fn pd_read(slave_index: u16, index: u16, sub_index: u8, bit_len: u16, data_types: &[u16]) -> Result<u64, c_int> {
+    let guard = STATE.read();
+    let state = guard.as_ref().ok_or(-1)?;
+    let symbol = pd_symbol(state, "pd_read", slave_index, index, sub_index, bit_len, data_types)?;
+    let buffer = state.pdi_buffer.read();
+    Ok(read_bits(&buffer[..], symbol.pdi_bit_offset as usize, bit_len as usize))
+}
+
+fn pd_write(slave_index: u16, index: u16, sub_index: u8, bit_len: u16, data_types: &[u16], value: u64) -> Result<(), c_int> {
+    let guard = STATE.read();
+    let state = guard.as_ref().ok_or(-1)?;
+    let symbol = pd_symbol(state, "pd_write", slave_index, index, sub_index, bit_len, data_types)?;
+    if symbol.is_output == 0 {
+        set_error_ctx(
+            FfiErrorCode::InvalidArgument,
+            format!("0x{:04X}:{} on slave {} is an input", index, sub_index, slave_index),
+            &[("op", "pd_write"), ("slave_index", &slave_index.to_string())],
+        );
+        return Err(-4);
+    }
+    let mut buffer = state.pdi_buffer.write();
+    write_bits(&mut buffer[..], symbol.pdi_bit_offset as usize, bit_len as usize, value);
+    Ok(())
+}
+
+/// Number of entries in the symbol table (0 before SafeOp).
+#[no_mangle]
+pub extern "C" fn ethercrab_pd_symbol_count() -> u32 {
+    with_ffi_guard(0, || {
+        let guard = STATE.read();
+        guard.as_ref().map(|s| s.process_image.symbols.len() as u32).unwrap_or(0)
+    })
+}
+
+/// Copy symbol `idx` of the table. Returns 0, -1 (not initialized) or -4 (null/out of range).
+#[no_mangle]
+pub extern "C" fn ethercrab_pd_get_symbol(idx: u32, out: *mut FfiProcessSymbol) -> c_int {
+    with_ffi_guard(-1, || {
+        if out.is_null() { return -4; }
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        match state.process_image.symbols.get(idx as usize) {
+            Some(symbol) => {
+                unsafe { *out = *symbol; }
+                0
+            }
+            None => -4,
+        }
+    })
+}
+
+/// Number of slaves in the process image layout (0 before SafeOp).
+#[no_mangle]
+pub extern "C" fn ethercrab_pd_layout_count() -> u32 {
     with_ffi_guard(0, || {
+        let guard = STATE.read();
+        guard.as_ref().map(|s| s.process_image.layouts.len() as u32).unwrap_or(0)
+    })
+}
+
+/// Copy the layout of `slave_index`: where its outputs and inputs sit in the PDI buffer and
+/// their logical addresses in the frame. Slaves without process data are included with
+/// zero lengths. Returns 0, -1 (not initialized), -2 (slave not in the layout) or -4 (null).
+#[no_mangle]
+pub extern "C" fn ethercrab_pd_get_layout(slave_index: u16, out: *mut FfiPdiSlaveLayout) -> c_int {
+    with_ffi_guard(-1, || {
+        if out.is_null() { return -4; }
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        match state.process_image.layouts.get(slave_index as usize) {
+            Some(layout) => {
+                unsafe { *out = layout.into(); }
+                0
+            }
+            None => -2,
+        }
+    })
+}
+
+// Typed get/set by (slave, index, sub index) on the PDI buffer. Outputs written here are
+// sent with the next ethercrab_cyclic_tx_rx. Each accessor lists the CoE data types it
+// reads faithfully (its own, plus the bit string of the same width). Returns 0, -1 (not
+// initialized), -2 (not mapped) or -4 (null pointer, width or type mismatch, or writing an input).
+macro_rules! pd_typed_access {
+    ($($get:ident, $set:ident, $ty:ty, $bits:expr, $types:expr, $from_raw:expr, $to_raw:expr;)*) => {
+        $(
+            #[no_mangle]
+            pub extern "C" fn $get(slave_index: u16, index: u16, sub_index: u8, out: *mut $ty) -> c_int {
+                with_ffi_guard(-1, || {
+                    if out.is_null() { return -4; }
+                    match pd_read(slave_index, index, sub_index, $bits, &$types) {
+                        Ok(raw) => {
+                            unsafe { *out = $from_raw(raw); }
+                            0
+                        }
+                        Err(e) => e,
+                    }
+                })
+            }
+
+            #[no_mangle]
+            pub extern "C" fn $set(slave_index: u16, index: u16, sub_index: u8, value: $ty) -> c_int {
+                with_ffi_guard(-1, || match pd_write(slave_index, index, sub_index, $bits, &$types, $to_raw(value)) {
+                    Ok(()) => 0,
+                    Err(e) => e,
+                })
+            }
+        )*
+    };
+}
+
+pd_typed_access! {
+    ethercrab_pd_get_bool, ethercrab_pd_set_bool, u8, 1, [0x0001, 0x0030], |r: u64| r as u8, |v: u8| (v != 0) as u64;
+    ethercrab_pd_get_i8, ethercrab_pd_set_i8, i8, 8, [0x0002, 0x001E, 0x0037], |r: u64| r as u8 as i8, |v: i8| v as u8 as u64;
+    ethercrab_pd_get_i16, ethercrab_pd_set_i16, i16, 16, [0x0003, 0x001F], |r: u64| r as u16 as i16, |v: i16| v as u16 as u64;
+    ethercrab_pd_get_i32, ethercrab_pd_set_i32, i32, 32, [0x0004, 0x0020], |r: u64| r as u32 as i32, |v: i32| v as u32 as u64;
+    ethercrab_pd_get_i64, ethercrab_pd_set_i64, i64, 64, [0x0015], |r: u64| r as i64, |v: i64| v as u64;
+    ethercrab_pd_get_u8, ethercrab_pd_set_u8, u8, 8, [0x0005, 0x001E, 0x0037], |r: u64| r as u8, |v: u8| v as u64;
+    ethercrab_pd_get_u16, ethercrab_pd_set_u16, u16, 16, [0x0006, 0x001F], |r: u64| r as u16, |v: u16| v as u64;
+    ethercrab_pd_get_u32, ethercrab_pd_set_u32, u32, 32, [0x0007, 0x0020], |r: u64| r as u32, |v: u32| v as u64;
+    ethercrab_pd_get_u64, ethercrab_pd_set_u64, u64, 64, [0x001B], |r: u64| r, |v: u64| v;
+    ethercrab_pd_get_f32, ethercrab_pd_set_f32, f32, 32, [0x0008], |r: u64| f32::from_bits(r as u32), |v: f32| v.to_bits() as u64;
+    ethercrab_pd_get_f64, ethercrab_pd_set_f64, f64, 64, [0x0011], f64::from_bits, f64::to_bits;
+}
+
+// --- Process Data Bits ---
+
+pub const PD_BIT_READ: u8 = 0;
+pub const PD_BIT_SET: u8 = 1;
+pub const PD_BIT_CLEAR: u8 = 2;
+pub const PD_BIT_TOGGLE: u8 = 3;
+
+/// Apply a bit operation in place and return the resulting bit value.
+fn apply_bit_op(buf: &mut [u8], bit: usize, op: u8) -> Option<u8> {
+    let mask = 1u8 << (bit % 8);
+    let byte = buf.get_mut(bit / 8)?;
+    match op {
+        PD_BIT_READ => {}
+        PD_BIT_SET => *byte |= mask,
+        PD_BIT_CLEAR => *byte &= !mask,
+        PD_BIT_TOGGLE => *byte ^= mask,
+        _ => return None,
+    }
+    Some((*byte & mask != 0) as u8)
+}
+
+/// Run `op` on a PDI bit under the buffer write lock. ethercrab_cyclic_tx_rx holds the same
+/// lock while copying outputs to the slaves, so a set/clear/toggle is never torn by a cycle.
+fn pd_bit_op(state: &EcMasterState, op_name: &str, pdi_bit: usize, op: u8) -> c_int {
+    if op != PD_BIT_READ && pdi_bit >= state.output_size * 8 {
+        set_error_ctx(
+            FfiErrorCode::InvalidArgument,
+            format!("PDI bit {} is not an output (outputs are bits 0..{})", pdi_bit, state.output_size * 8),
+            &[("op", op_name), ("pdi_bit_offset", &pdi_bit.to_string())],
+        );
+        return -4;
+    }
+    if pdi_bit >= state.pdi_size * 8 {
+        set_error_ctx(
+            FfiErrorCode::InvalidArgument,
+            format!("PDI bit {} is outside the process image ({} bytes)", pdi_bit, state.pdi_size),
+            &[("op", op_name), ("pdi_bit_offset", &pdi_bit.to_string())],
+        );
+        return -4;
+    }
+    let mut buffer = state.pdi_buffer.write();
+    match apply_bit_op(&mut buffer[..], pdi_bit, op) {
+        Some(bit) => bit as c_int,
+        None => {
+            set_error_ctx(
+                FfiErrorCode::InvalidArgument,
+                format!("Unknown bit operation {}", op),
+                &[("op", op_name), ("bit_op", &op.to_string())],
+            );
+            -4
+        }
+    }
+}
+
+/// Read, set, clear or toggle bit `bit_offset` of a slave's output (`is_output`) or input
+/// image. Inputs are read-only. Returns the bit value after the operation (0/1), -1 (not
+/// initialized), -2 (slave not in the process image, built in SafeOp) or -4 (out of range,
+/// writing an input, or unknown op).
+#[no_mangle]
+pub extern "C" fn ethercrab_pd_bit(slave_index: u16, is_output: bool, bit_offset: u32, op: u8) -> c_int {
+    with_ffi_guard(-1, || {
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        let layout = match state.process_image.layouts.get(slave_index as usize) {
+            Some(l) => *l,
+            None => {
+                set_error_ctx(
+                    FfiErrorCode::InvalidArgument,
+                    format!("Slave {} is not in the process image (process image is built in SafeOp)", slave_index),
+                    &[("op", "pd_bit"), ("slave_index", &slave_index.to_string())],
+                );
+                return -2;
+            }
+        };
+        let (offset, len) = if is_output {
+            (layout.output_offset, layout.output_len)
+        } else {
+            (layout.input_offset, layout.input_len)
+        };
+        if bit_offset as usize >= len * 8 {
+            set_error_ctx(
+                FfiErrorCode::InvalidArgument,
+                format!(
+                    "Bit {} is outside the {} image of slave {} ({} bytes)",
+                    bit_offset,
+                    if is_output { "output" } else { "input" },
+                    slave_index,
+                    len
+                ),
+                &[("op", "pd_bit"), ("slave_index", &slave_index.to_string()), ("bit_offset", &bit_offset.to_string())],
+            );
+            return -4;
+        }
+        if !is_output && op != PD_BIT_READ {
+            set_error_ctx(
+                FfiErrorCode::InvalidArgument,
+                format!("Bit {} of slave {} is an input", bit_offset, slave_index),
+                &[("op", "pd_bit"), ("slave_index", &slave_index.to_string()), ("bit_offset", &bit_offset.to_string())],
+            );
+            return -4;
+        }
+        pd_bit_op(state, "pd_bit", offset * 8 + bit_offset as usize, op)
+    })
+}
+
+/// Same as ethercrab_pd_bit, addressed by bit offset in the whole PDI buffer
+/// (`[Outputs | Inputs]`). Only bits below output_size * 8 can be changed.
+#[no_mangle]
+pub extern "C" fn ethercrab_pdi_bit(pdi_bit_offset: u32, op: u8) -> c_int {
+    with_ffi_guard(-1, || {
+        let guard = STATE.read();
+        match guard.as_ref() {
+            Some(state) => pd_bit_op(state, "pdi_bit", pdi_bit_offset as usize, op),
+            None => -1,
+        }
+    })
+}
+
+// --- Process Data Slices ---
+
+/// PDI byte range of a slave's input or output image starting at `offset`. Returns the
+/// absolute start and the number of bytes left in the slave's image.
+fn pd_slave_range(image: &ProcessImage, op: &str, slave_index: u16, is_output: bool, offset: usize) -> Result<(usize, usize), c_int> {
+    let layout = match image.layouts.get(slave_index as usize) {
+        Some(l) => *l,
+        None => {
+            set_error_ctx(
+                FfiErrorCode::InvalidArgument,
+                format!("Slave {} is not in the process image (process image is built in SafeOp)", slave_index),
+                &[("op", op), ("slave_index", &slave_index.to_string())],
+            );
+            return Err(-2);
+        }
+    };
+    let (start, len) = if is_output {
+        (layout.output_offset, layout.output_len)
+    } else {
+        (layout.input_offset, layout.input_len)
+    };
+    if offset > len {
+        set_error_ctx(
+            FfiErrorCode::InvalidArgument,
+            format!(
+                "Offset {} is outside the {} image of slave {} ({} bytes)",
+                offset,
+                if is_output { "output" } else { "input" },
+                slave_index,
+                len
+            ),
+            &[("op", op), ("slave_index", &slave_index.to_string()), ("offset", &offset.to_string())],
+        );
+        return Err(-4);
+    }
+    Ok((start + offset, len - offset))
+}
+
+/// Input and output image sizes of a slave in bytes. Returns 0, -1 (not initialized),
+/// -2 (slave not in the process image) or -4 (null pointer).
+#[no_mangle]
+pub extern "C" fn ethercrab_pd_slave_sizes(slave_index: u16, input_size: *mut u32, output_size: *mut u32) -> c_int {
+    with_ffi_guard(-1, || {
+        if input_size.is_null() || output_size.is_null() { return -4; }
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        match state.process_image.layouts.get(slave_index as usize) {
+            Some(layout) => {
+                unsafe {
+                    *input_size = layout.input_len as u32;
+                    *output_size = layout.output_len as u32;
+                }
+                0
+            }
+            None => -2,
+        }
+    })
+}
+
+/// Copy up to `len` bytes of a slave's input (or output) image, starting at `offset`, into
+/// `buf` under a single PDI lock. Inputs are refreshed by ethercrab_cyclic_tx_rx in SafeOp and
+/// Op. Returns the number of bytes copied, -1 (not initialized), -2 (slave not in the process
+/// image) or -4 (null buffer, offset out of range).
+#[no_mangle]
+pub extern "C" fn ethercrab_pd_read_slave(slave_index: u16, is_output: bool, offset: u32, buf: *mut u8, len: usize) -> c_int {
+    with_ffi_guard(-1, || {
+        if buf.is_null() { return -4; }
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        let (start, available) = match pd_slave_range(&state.process_image, "pd_read_slave", slave_index, is_output, offset as usize) {
+            Ok(r) => r,
+            Err(e) => return e,
+        };
+        let count = available.min(len);
+        let buffer = state.pdi_buffer.read();
+        let out = unsafe { std::slice::from_raw_parts_mut(buf, count) };
+        out.copy_from_slice(&buffer[start..start + count]);
+        count as c_int
+    })
+}
+
+/// Copy `len` bytes from `data` into a slave's output image at `offset` under a single PDI
+/// lock. Sent with the next ethercrab_cyclic_tx_rx. Returns the number of bytes written,
+/// -1 (not initialized), -2 (slave not in the process image) or -4 (null data, range outside
+/// the slave's outputs).
+#[no_mangle]
+pub extern "C" fn ethercrab_pd_write_slave(slave_index: u16, offset: u32, data: *const u8, len: usize) -> c_int {
+    with_ffi_guard(-1, || {
+        if data.is_null() { return -4; }
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        let (start, available) = match pd_slave_range(&state.process_image, "pd_write_slave", slave_index, true, offset as usize) {
+            Ok(r) => r,
+            Err(e) => return e,
+        };
+        if len > available {
+            set_error_ctx(
+                FfiErrorCode::InvalidArgument,
+                format!("{} bytes at offset {} exceed the output image of slave {}", len, offset, slave_index),
+                &[("op", "pd_write_slave"), ("slave_index", &slave_index.to_string()), ("len", &len.to_string())],
+            );
+            return -4;
+        }
+        let input = unsafe { std::slice::from_raw_parts(data, len) };
+        let mut buffer = state.pdi_buffer.write();
+        buffer[start..start + len].copy_from_slice(input);
+        len as c_int
+    })
+}
+
+// --- Fail-Safe Outputs ---
+
+// Per-slave fail-safe modes. Slaves left at NONE keep whatever the host last wrote.
+pub const FAILSAFE_MODE_NONE: u8 = 0;
+pub const FAILSAFE_MODE_ZERO: u8 = 1;
+pub const FAILSAFE_MODE_HOLD: u8 = 2;
+pub const FAILSAFE_MODE_USER: u8 = 3;
+
+// Why the configured slaves entered fail-safe (first trigger wins until reset).
+pub const FAILSAFE_REASON_NONE: u8 = 0;
+pub const FAILSAFE_REASON_WKC_LOSS: u8 = 1;
+pub const FAILSAFE_REASON_HEARTBEAT: u8 = 2;
+pub const FAILSAFE_REASON_OP_TO_SAFEOP: u8 = 3;
+pub const FAILSAFE_REASON_DESTROY: u8 = 4;
+
+#[derive(Clone, Default)]
+struct FailSafeSlave {
+    mode: u8,
+    user_image: Vec<u8>,
+    /// Image sent instead of the PDI outputs while in fail-safe.
+    active: Option<Vec<u8>>,
+}
+
+#[derive(Default)]
+struct FailSafe {
+    slaves: Vec<FailSafeSlave>,
+    reason: u8,
+}
+
+/// Output image for `mode`, sized to the slave's current outputs.
+fn fail_safe_image(mode: u8, user_image: &[u8], current: &[u8]) -> Option<Vec<u8>> {
+    match mode {
+        FAILSAFE_MODE_ZERO => Some(vec![0; current.len()]),
+        FAILSAFE_MODE_HOLD => Some(current.to_vec()),
+        FAILSAFE_MODE_USER => {
+            let mut image = user_image.to_vec();
+            image.resize(current.len(), 0);
+            Some(image)
+        }
+        _ => None,
+    }
+}
+
+impl FailSafe {
+    /// Latch every configured slave with outputs into fail-safe and write its image into the
+    /// PDI buffer. Slaves already in fail-safe keep their image. Returns the number of slaves
+    /// that entered fail-safe.
+    fn enter(&mut self, reason: u8, layouts: &[PdiSlaveLayout], pdi: &mut [u8]) -> usize {
+        let mut entered = 0;
+        for (slave, layout) in self.slaves.iter_mut().zip(layouts) {
+            if slave.active.is_some() || layout.output_len == 0 {
+                continue;
+            }
+            let Some(outputs) = pdi.get_mut(layout.output_offset..layout.output_offset + layout.output_len) else { continue };
+            if let Some(image) = fail_safe_image(slave.mode, &slave.user_image, outputs) {
+                outputs.copy_from_slice(&image);
+                slave.active = Some(image);
+                entered += 1;
+            }
+        }
+        if entered > 0 && self.reason == FAILSAFE_REASON_NONE {
+            self.reason = reason;
+        }
+        entered
+    }
+
+    fn active_image(&self, slave_index: usize) -> Option<&[u8]> {
+        self.slaves.get(slave_index).and_then(|s| s.active.as_deref())
+    }
+
+    fn reset(&mut self) {
+        for slave in &mut self.slaves {
+            slave.active = None;
+        }
+        self.reason = FAILSAFE_REASON_NONE;
+    }
+}
+
+/// Working counter of a healthy LRW exchange: +1 per slave with inputs, +2 per slave with
+/// outputs (ETG1000.4 §5.4.4).
+fn expected_lrw_wkc(layouts: &[PdiSlaveLayout]) -> u16 {
+    layouts
+        .iter()
+        .map(|l| (l.input_len > 0) as u16 + 2 * (l.output_len > 0) as u16)
+        .sum()
+}
+
+fn enter_fail_safe(state: &EcMasterState, reason: u8) -> usize {
+    let mut fail_safe = state.fail_safe.lock();
+    let mut buffer = state.pdi_buffer.write();
+    fail_safe.enter(reason, &state.process_image.layouts, &mut buffer[..])
+}
+
+/// Copy the output image of every slave into the frame: the fail-safe image for slaves in
+/// fail-safe, the PDI buffer for the rest.
+fn sync_outputs(
+    state: &EcMasterState,
+    group: &SubDeviceGroup<MAX_SUBDEVICES, MAX_PDI, spin::rwlock::RwLock<(), spin::Yield>, Op>,
+) {
+    // Lock order: fail_safe, then pdi_buffer
+    let fail_safe = state.fail_safe.lock();
+    let buffer = state.pdi_buffer.read();
+    for (i, (layout, slave)) in state.process_image.layouts.iter().zip(group.iter(&state.maindevice)).enumerate() {
+        match fail_safe.active_image(i) {
+            Some(image) => {
+                let mut outs = slave.outputs_raw_mut();
+                let len = image.len().min(outs.len());
+                outs[..len].copy_from_slice(&image[..len]);
+            }
+            None => copy_slave_outputs(&buffer[..], layout, &mut slave.outputs_raw_mut()),
+        }
+    }
+}
+
+/// Enter fail-safe and send the images in one exchange, while the slaves still apply outputs.
+fn send_fail_safe(
+    state: &EcMasterState,
+    group: &SubDeviceGroup<MAX_SUBDEVICES, MAX_PDI, spin::rwlock::RwLock<(), spin::Yield>, Op>,
+    reason: u8,
+) {
+    if enter_fail_safe(state, reason) == 0 {
+        return;
+    }
+    sync_outputs(state, group);
+    if let Err(e) = smol::block_on(group.tx_rx(&state.maindevice)) {
+        set_error_ctx(
+            FfiErrorCode::PduTimeout,
+            format!("Failed to send fail-safe outputs: {:?}", e),
+            &[("op", "fail_safe"), ("reason", &reason.to_string()), ("error_detail", &format!("{:?}", e))],
+        );
+    }
+}
+
+/// Configure the fail-safe outputs of a slave: FAILSAFE_MODE_NONE, _ZERO, _HOLD (last value)
+/// or _USER (`data`, `len` bytes, zero-padded to the slave's outputs). Applied on WKC loss,
+/// host heartbeat timeout, Op→SafeOp and before destroy. Returns 0, -1 (not initialized) or
+/// -4 (unknown mode, missing data, or data longer than the slave's outputs).
+#[no_mangle]
+pub extern "C" fn ethercrab_failsafe_configure(slave_index: u16, mode: u8, data: *const u8, len: usize) -> c_int {
+    with_ffi_guard(-1, || {
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        let invalid = |msg: String| {
+            set_error_ctx(
+                FfiErrorCode::InvalidArgument,
+                msg,
+                &[("op", "failsafe_configure"), ("slave_index", &slave_index.to_string()), ("mode", &mode.to_string())],
+            );
+            -4
+        };
+        if mode > FAILSAFE_MODE_USER {
+            return invalid(format!("Unknown fail-safe mode {}", mode));
+        }
+        if slave_index as usize >= MAX_SUBDEVICES {
+            return invalid(format!("Slave index {} out of range", slave_index));
+        }
+        let user_image = if mode == FAILSAFE_MODE_USER {
+            if data.is_null() {
+                return invalid("User fail-safe mode needs an output image".to_string());
+            }
+            if let Some(layout) = state.process_image.layouts.get(slave_index as usize) {
+                if len > layout.output_len {
+                    return invalid(format!("{} bytes exceed the {} output bytes of slave {}", len, layout.output_len, slave_index));
+                }
+            }
+            unsafe { std::slice::from_raw_parts(data, len) }.to_vec()
+        } else {
+            Vec::new()
+        };
+
+        let mut fail_safe = state.fail_safe.lock();
+        if fail_safe.slaves.len() <= slave_index as usize {
+            fail_safe.slaves.resize(slave_index as usize + 1, FailSafeSlave::default());
+        }
+        let slave = &mut fail_safe.slaves[slave_index as usize];
+        slave.mode = mode;
+        slave.user_image = user_image;
+        0
+    })
+}
+
+/// Write the indices of the slaves currently in fail-safe to `out` (up to `max`) and return
+/// how many there are, or -1 (not initialized) / -4 (null `out` with `max` > 0).
+#[no_mangle]
+pub extern "C" fn ethercrab_failsafe_get_active(out: *mut u16, max: usize) -> c_int {
+    with_ffi_guard(-1, || {
+        if out.is_null() && max > 0 { return -4; }
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        let fail_safe = state.fail_safe.lock();
+        let active: Vec<u16> = (0..fail_safe.slaves.len())
+            .filter(|&i| fail_safe.active_image(i).is_some())
+            .map(|i| i as u16)
+            .collect();
+        let count = active.len().min(max);
+        if count > 0 {
+            unsafe { std::slice::from_raw_parts_mut(out, count) }.copy_from_slice(&active[..count]);
+        }
+        active.len() as c_int
+    })
+}
+
+/// Reason the slaves entered fail-safe (FAILSAFE_REASON_*), 0 when none is active.
+#[no_mangle]
+pub extern "C" fn ethercrab_failsafe_get_reason() -> u8 {
+    with_ffi_guard(FAILSAFE_REASON_NONE, || {
+        let guard = STATE.read();
+        guard.as_ref().map(|s| s.fail_safe.lock().reason).unwrap_or(FAILSAFE_REASON_NONE)
+    })
+}
+
+/// Leave fail-safe: host outputs in the PDI buffer are sent again from the next cycle. The
+/// buffer still holds the fail-safe images until the host overwrites them. Requesting Op does
+/// the same. Returns 0 or -1 (not initialized).
+#[no_mangle]
+pub extern "C" fn ethercrab_failsafe_reset() -> c_int {
+    with_ffi_guard(-1, || {
+        let guard = STATE.read();
+        match guard.as_ref() {
+            Some(state) => {
+                state.fail_safe.lock().reset();
+                0
+            }
+            None => -1,
+        }
+    })
+}
+
+// --- Host Liveness Watchdog ---
+
+struct HostWatchdog {
+    timeout: Duration,
+    last_kick: Instant,
+    latched: bool,
+    trip_count: u32,
+    /// Trip number and error message of the latest miss, once its handling finished
+    last_trip: Option<(u32, String)>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl HostWatchdog {
+    fn new(timeout: Duration, now: Instant) -> Self {
+        Self { timeout, last_kick: now, latched: false, trip_count: 0, last_trip: None, thread: None }
+    }
+
+    fn kick(&mut self, now: Instant) {
+        self.last_kick = now;
+    }
+
+    /// True when the deadline was just missed. Stays quiet while latched.
+    fn check(&mut self, now: Instant) -> bool {
+        if self.latched || now.duration_since(self.last_kick) <= self.timeout {
+            return false;
+        }
+        self.latched = true;
+        self.trip_count += 1;
+        true
+    }
+
+    fn acknowledge(&mut self, now: Instant) {
+        self.latched = false;
+        self.last_kick = now;
+    }
+}
+
+static HOST_WATCHDOG: Lazy<Mutex<Option<HostWatchdog>>> = Lazy::new(|| Mutex::new(None));
+static HOST_WATCHDOG_STOP: AtomicBool = AtomicBool::new(false);
+
+/// Missed heartbeat: fail-safe outputs on the wire, then SafeOp, then the error.
+/// Returns the error message for the watchdog's trip record.
+fn on_host_heartbeat_missed(timeout: Duration) -> String {
+    let was_op = {
+        let guard = STATE.read();
+        match guard.as_ref() {
+            Some(state) => match &state.group {
+                Some(GroupState::Op(g)) => {
+                    send_fail_safe(state, g, FAILSAFE_REASON_HEARTBEAT);
+                    true
+                }
+                _ => false,
+            },
+            None => false,
+        }
+    };
+    if was_op {
+        ethercrab_request_state(2);
+    }
+    let message = format!("Host heartbeat missed for more than {} ms, outputs set to fail-safe", timeout.as_millis());
+    set_error_ctx(
+        FfiErrorCode::HostWatchdogTimeout,
+        &message,
+        &[
+            ("op", "host_watchdog"),
+            ("timeout_ms", &timeout.as_millis().to_string()),
+            ("requested_safe_op", &was_op.to_string()),
+            ("suggestion", "Call ethercrab_watchdog_acknowledge, then request Op again"),
+        ],
+    );
+    message
+}
+
+fn host_watchdog_loop() {
+    while !HOST_WATCHDOG_STOP.load(Ordering::Acquire) {
+        let (tripped, timeout, trip) = match HOST_WATCHDOG.lock().as_mut() {
+            Some(wd) => (wd.check(Instant::now()), wd.timeout, wd.trip_count),
+            None => return,
+        };
+        if tripped {
+            let message = on_host_heartbeat_missed(timeout);
+            if let Some(wd) = HOST_WATCHDOG.lock().as_mut() {
+                wd.last_trip = Some((trip, message));
+            }
+        }
+        std::thread::sleep((timeout / 4).clamp(Duration::from_millis(1), Duration::from_millis(20)));
+    }
+}
+
+/// Start (or retune) the supervisor. The host must call ethercrab_watchdog_kick at least
+/// every `timeout_ms`; otherwise the supervisor sends the fail-safe outputs, requests SafeOp,
+/// records a HostWatchdogTimeout error and latches. While latched, requests for Op fail with
+/// -3. Returns 0, or -4 for a zero timeout.
+#[no_mangle]
+pub extern "C" fn ethercrab_watchdog_start(timeout_ms: u32) -> c_int {
+    with_ffi_guard(-1, || {
+        if timeout_ms == 0 {
+            set_error_ctx(FfiErrorCode::InvalidArgument, "Watchdog timeout must be > 0", &[("op", "watchdog_start")]);
+            return -4;
+        }
+        let timeout = Duration::from_millis(timeout_ms as u64);
+        let mut watchdog = HOST_WATCHDOG.lock();
+        if let Some(wd) = watchdog.as_mut() {
+            wd.timeout = timeout;
+            wd.kick(Instant::now());
+            return 0;
+        }
+        HOST_WATCHDOG_STOP.store(false, Ordering::Release);
+        let mut wd = HostWatchdog::new(timeout, Instant::now());
+        wd.thread = Some(std::thread::spawn(host_watchdog_loop));
+        *watchdog = Some(wd);
+        0
+    })
+}
+
+/// Stop the supervisor. Returns 0, or -1 if it was not running.
+#[no_mangle]
+pub extern "C" fn ethercrab_watchdog_stop() -> c_int {
+    with_ffi_guard(-1, || {
+        let Some(mut wd) = HOST_WATCHDOG.lock().take() else { return -1 };
+        HOST_WATCHDOG_STOP.store(true, Ordering::Release);
+        if let Some(handle) = wd.thread.take() {
+            let _ = handle.join();
+        }
+        0
+    })
+}
+
+/// Heartbeat from the host. Returns 0, 1 while latched after a miss (kicks do not clear it),
+/// or -1 if the supervisor is not running.
+#[no_mangle]
+pub extern "C" fn ethercrab_watchdog_kick() -> c_int {
+    with_ffi_guard(-1, || match HOST_WATCHDOG.lock().as_mut() {
+        Some(wd) => {
+            wd.kick(Instant::now());
+            wd.latched as c_int
+        }
+        None => -1,
+    })
+}
+
+/// Clear the latch after a miss and restart the deadline. Fail-safe outputs stay until the
+/// host requests Op or calls ethercrab_failsafe_reset. Returns 0, or -1 if not running.
+#[no_mangle]
+pub extern "C" fn ethercrab_watchdog_acknowledge() -> c_int {
+    with_ffi_guard(-1, || match HOST_WATCHDOG.lock().as_mut() {
+        Some(wd) => {
+            wd.acknowledge(Instant::now());
+            0
+        }
+        None => -1,
+    })
+}
+
+/// Number of missed heartbeats since ethercrab_watchdog_start (0 if not running).
+#[no_mangle]
+pub extern "C" fn ethercrab_watchdog_get_trip_count() -> u32 {
+    with_ffi_guard(0, || HOST_WATCHDOG.lock().as_ref().map(|wd| wd.trip_count).unwrap_or(0))
+}
+
+/// The latest handled miss: its trip number (1 = first since start) goes to `trip_count` and
+/// its error message into `buffer`. Unlike ethercrab_get_last_error this is not overwritten by
+/// later errors. Returns the bytes copied, 0 if no miss was recorded yet (`trip_count` = 0),
+/// -1 if the supervisor is not running or -4 for null pointers.
+#[no_mangle]
+pub extern "C" fn ethercrab_watchdog_get_last_trip(trip_count: *mut u32, buffer: *mut u8, len: usize) -> c_int {
+    with_ffi_guard(-1, || {
+        if trip_count.is_null() || (buffer.is_null() && len > 0) {
+            return -4;
+        }
+        let watchdog = HOST_WATCHDOG.lock();
+        let Some(wd) = watchdog.as_ref() else { return -1 };
+        let (trip, message) = match &wd.last_trip {
+            Some((trip, message)) => (*trip, message.as_bytes()),
+            None => (0, &[][..]),
+        };
+        let to_copy = message.len().min(len);
+        unsafe {
+            *trip_count = trip;
+            if to_copy > 0 {
+                std::ptr::copy_nonoverlapping(message.as_ptr(), buffer, to_copy);
+            }
+        }
+        to_copy as c_int
+    })
+}
+
+/// Read a 16-bit register value from a slave.
+/// 
+/// Common watchdog-related registers:
+/// - 0x0400: Watchdog Divider (default ~2498, gives ~100µs per count)
+/// - 0x0410: PDI Watchdog timeout
+/// - 0x0420: SM Watchdog timeout (default ~1000 with default divider = ~100ms)
+/// - 0x0440: SM Watchdog status
+#[no_mangle]
+pub extern "C" fn ethercrab_register_read_u16(
+    slave_index: u16,
+    register_address: u16,
+) -> i32 {
+    with_ffi_guard(-1, || {
         let result = smol::block_on(async move {
             let guard = STATE.read();
-            if let Some(ref master_state) = *guard {
-                let maindevice = &master_state.maindevice;
-                let idx = slave_index as usize;
-                
-                let status_result = match &master_state.group {
-                    Some(GroupState::PreOp(g)) => {
-                        if let Some(subdevice) = g.iter(maindevice).nth(idx) {
-                            subdevice.status().await
-                        } else {
-                            return Err(0);
-                        }
-                    }
-                    Some(GroupState::SafeOp(g)) => {
-                        if let Some(subdevice) = g.iter(maindevice).nth(idx) {
-                            subdevice.status().await
-                        } else {
-                            return Err(0);
-                        }
-                    }
-                    Some(GroupState::Op(g)) => {
-                        if let Some(subdevice) = g.iter(maindevice).nth(idx) {
-                            subdevice.status().await
-                        } else {
-                            return Err(0);
-                        }
-                    }
-                    None => return Err(0),
-                };
-                
-                match status_result {
-                    Ok((_, status_code)) => Ok(u16::from(status_code)),
-                    Err(_) => Err(0),
+            let state = match guard.as_ref() {
+                Some(s) => s,
+                None => return Err(-1),
+            };
+
+            let idx = slave_index as usize;
+            let md = &state.maindevice;
+            
+            let val_res = match state.group.as_ref() {
+                Some(GroupState::PreOp(g)) => g.iter(md).nth(idx).ok_or(-2)?.register_read::<u16>(register_address).await,
+                Some(GroupState::SafeOp(g)) => g.iter(md).nth(idx).ok_or(-2)?.register_read::<u16>(register_address).await,
+                Some(GroupState::Op(g)) => g.iter(md).nth(idx).ok_or(-2)?.register_read::<u16>(register_address).await,
+                None => return Err(-1),
+            };
+            
+            match val_res {
+                Ok(val) => Ok(val as i32),
+                Err(e) => {
+                    set_error_ctx(
+                        FfiErrorCode::RegisterError,
+                        format!("Register read failed on slave {} at 0x{:04X}: {:?}", slave_index, register_address, e),
+                        &[
+                            ("op", "register_read"),
+                            ("slave_index", &slave_index.to_string()),
+                            ("register", &format!("0x{:04X}", register_address)),
+                            ("error_detail", &format!("{:?}", e)),
+                            ("suggestion", "Check slave is reachable and register address is valid"),
+                        ],
+                    );
+                    Err(-3)
+                }
+            }
+        });
+
+        match result {
+            Ok(v) => v,
+            Err(e) => e,
+        }
+    })
+}
+
+/// Write a 16-bit register value to a slave.
+/// 
+/// Common watchdog-related registers:
+/// - 0x0400: Watchdog Divider
+/// - 0x0410: PDI Watchdog timeout  
+/// - 0x0420: SM Watchdog timeout
+#[no_mangle]
+pub extern "C" fn ethercrab_register_write_u16(
+    slave_index: u16,
+    register_address: u16,
+    value: u16,
+) -> i32 {
+    with_ffi_guard(-1, || {
+        let result = smol::block_on(async move {
+            let guard = STATE.read();
+            let state = match guard.as_ref() {
+                Some(s) => s,
+                None => return Err(-1),
+            };
+
+            let idx = slave_index as usize;
+            let md = &state.maindevice;
+            
+            let write_res = match state.group.as_ref() {
+                Some(GroupState::PreOp(g)) => g.iter(md).nth(idx).ok_or(-2)?.register_write(register_address, value).await,
+                Some(GroupState::SafeOp(g)) => g.iter(md).nth(idx).ok_or(-2)?.register_write(register_address, value).await,
+                Some(GroupState::Op(g)) => g.iter(md).nth(idx).ok_or(-2)?.register_write(register_address, value).await,
+                None => return Err(-1),
+            };
+            
+            match write_res {
+                Ok(_) => Ok(0),
+                Err(e) => {
+                    set_error_ctx(
+                        FfiErrorCode::RegisterError,
+                        format!("Register write failed on slave {} at 0x{:04X} (value={}): {:?}", slave_index, register_address, value, e),
+                        &[
+                            ("op", "register_write"),
+                            ("slave_index", &slave_index.to_string()),
+                            ("register", &format!("0x{:04X}", register_address)),
+                            ("value", &value.to_string()),
+                            ("error_detail", &format!("{:?}", e)),
+                            ("suggestion", "Check slave is reachable and register address is writable"),
+                        ],
+                    );
+                    Err(-3)
                 }
-            } else {
-                Err(0)
             }
         });
 
         match result {
-            Ok(code) => code,
-            Err(_) => 0,
+            Ok(v) => v,
+            Err(e) => e,
         }
     })
 }
 
-/// Returns the PDI buffer pointer directly.
-/// Note: Deno FFI doesn't correctly marshal pointers in struct returns, so we use separate functions.
+// --- Slave Lookup (station alias, configured address) ---
+
+/// Position of the slave with `alias` (0x0012, loaded from SII word 4). Err holds every
+/// position using it: empty when not found, two or more when the alias is not unique.
+fn find_slave_by_alias(aliases: &[u16], alias: u16) -> Result<usize, Vec<usize>> {
+    let matches: Vec<usize> = aliases.iter().enumerate().filter(|(_, &a)| a == alias).map(|(i, _)| i).collect();
+    match matches.as_slice() {
+        [single] => Ok(*single),
+        _ => Err(matches),
+    }
+}
+
+/// Station aliases of all slaves in the group, by position.
+fn slave_aliases(state: &EcMasterState) -> Vec<u16> {
+    let md = &state.maindevice;
+    match state.group.as_ref() {
+        Some(GroupState::PreOp(g)) => g.iter(md).map(|s| s.alias_address()).collect(),
+        Some(GroupState::SafeOp(g)) => g.iter(md).map(|s| s.alias_address()).collect(),
+        Some(GroupState::Op(g)) => g.iter(md).map(|s| s.alias_address()).collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Slave index (position) for a station alias, to pass to any slave-level call.
+/// Returns the index, -1 (not initialized), -2 (no slave has the alias), -3 (the alias is
+/// used by more than one slave, recorded as DuplicateAlias) or -4 (alias 0, i.e. unset).
 #[no_mangle]
-pub extern "C" fn ethercrab_get_pdi_buffer_ptr() -> *mut u8 {
-    with_ffi_guard(std::ptr::null_mut(), || {
+pub extern "C" fn ethercrab_find_slave_by_alias(alias: u16) -> c_int {
+    with_ffi_guard(-1, || {
         let guard = STATE.read();
-        if let Some(state) = guard.as_ref() {
-            let buf_guard = state.pdi_buffer.read();
-            let ptr = buf_guard.as_ptr() as *mut u8;
-            drop(buf_guard);
-            ptr
-        } else {
-            std::ptr::null_mut()
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        if alias == 0 {
+            set_error_ctx(FfiErrorCode::InvalidArgument, "Alias 0 means no alias is set", &[("op", "find_slave_by_alias")]);
+            return -4;
+        }
+        match find_slave_by_alias(&slave_aliases(state), alias) {
+            Ok(idx) => idx as c_int,
+            Err(positions) if positions.is_empty() => {
+                set_error_ctx(
+                    FfiErrorCode::InvalidArgument,
+                    format!("No slave with alias 0x{:04X}", alias),
+                    &[("op", "find_slave_by_alias"), ("alias", &format!("0x{:04X}", alias))],
+                );
+                -2
+            }
+            Err(positions) => {
+                let list = positions.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(", ");
+                set_error_ctx(
+                    FfiErrorCode::DuplicateAlias,
+                    format!("Alias 0x{:04X} is used by slaves {}", alias, list),
+                    &[
+                        ("op", "find_slave_by_alias"),
+                        ("alias", &format!("0x{:04X}", alias)),
+                        ("slave_indices", &list),
+                        ("suggestion", "Give every slave a unique alias (SII word 4) or address it by position"),
+                    ],
+                );
+                -3
+            }
         }
     })
 }
 
-/// Returns total PDI size in bytes.
+/// Slave index (position) for a configured station address (e.g. 0x1001).
+/// Returns the index, -1 (not initialized) or -2 (no slave has the address).
 #[no_mangle]
-pub extern "C" fn ethercrab_get_pdi_total_size() -> u32 {
-    with_ffi_guard(0, || {
+pub extern "C" fn ethercrab_find_slave_by_address(configured_address: u16) -> c_int {
+    with_ffi_guard(-1, || {
         let guard = STATE.read();
-        if let Some(state) = guard.as_ref() {
-            state.pdi_size as u32
-        } else {
-            0
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        match slave_configured_addresses(state).iter().position(|&a| a == configured_address) {
+            Some(idx) => idx as c_int,
+            None => {
+                set_error_ctx(
+                    FfiErrorCode::InvalidArgument,
+                    format!("No slave with configured address 0x{:04X}", configured_address),
+                    &[("op", "find_slave_by_address"), ("address", &format!("0x{:04X}", configured_address))],
+                );
+                -2
+            }
         }
     })
 }
 
+// --- Register Access (FPRD/FPWR, APRD/APWR, BRD/BWR) ---
+
+pub const REGISTER_ADDR_CONFIGURED: u8 = 0;
+pub const REGISTER_ADDR_POSITION: u8 = 1;
+pub const REGISTER_ADDR_BROADCAST: u8 = 2;
+
+/// Largest register/memory block moved by one datagram.
+const REGISTER_MAX_LEN: u16 = 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RegisterTarget {
+    /// Configured station address (FPRD/FPWR).
+    Configured(u16),
+    /// Position in the ring (APRD/APWR auto-increment).
+    Position(u16),
+    /// Every slave (BRD/BWR).
+    Broadcast,
+}
+
+impl RegisterTarget {
+    fn from_ffi(mode: u8, address: u16) -> Option<Self> {
+        match mode {
+            REGISTER_ADDR_CONFIGURED => Some(RegisterTarget::Configured(address)),
+            REGISTER_ADDR_POSITION => Some(RegisterTarget::Position(address)),
+            REGISTER_ADDR_BROADCAST => Some(RegisterTarget::Broadcast),
+            _ => None,
+        }
+    }
+
+    fn read(self, register: u16) -> ethercrab::WrappedRead {
+        match self {
+            RegisterTarget::Configured(a) => Command::fprd(a, register),
+            RegisterTarget::Position(p) => Command::aprd(p, register),
+            RegisterTarget::Broadcast => Command::brd(register),
+        }
+    }
+
+    fn write(self, register: u16) -> ethercrab::WrappedWrite {
+        match self {
+            RegisterTarget::Configured(a) => Command::fpwr(a, register),
+            RegisterTarget::Position(p) => Command::apwr(p, register),
+            RegisterTarget::Broadcast => Command::bwr(register),
+        }
+    }
+
+    /// Working counter when every addressed slave answers.
+    fn expected_wkc(self, slave_count: u16) -> u16 {
+        match self {
+            RegisterTarget::Broadcast => slave_count,
+            _ => 1,
+        }
+    }
+}
+
+/// Read `len` bytes and the working counter with a single datagram. ethercrab drops the
+/// payload of a response whose WKC falls short, so the data is empty in that case; the read
+/// is not repeated because registers such as AL status or the mailbox are cleared on read.
+async fn register_read_wkc(
+    md: &MainDevice<'_>,
+    target: RegisterTarget,
+    register: u16,
+    len: u16,
+    expected_wkc: u16,
+) -> Result<(Vec<u8>, u16), ethercrab::error::Error> {
+    match target.read(register).with_wkc(expected_wkc).receive_slice(md, len).await {
+        Ok(data) => Ok((data.to_vec(), expected_wkc)),
+        Err(ethercrab::error::Error::WorkingCounter { received, .. }) => Ok((Vec::new(), received)),
+        Err(e) => Err(e),
+    }
+}
+
+/// Write `data` and return the working counter.
+async fn register_write_wkc(
+    md: &MainDevice<'_>,
+    target: RegisterTarget,
+    register: u16,
+    data: &[u8],
+    expected_wkc: u16,
+) -> Result<u16, ethercrab::error::Error> {
+    match target.write(register).with_wkc(expected_wkc).send(md, data).await {
+        Ok(()) => Ok(expected_wkc),
+        Err(ethercrab::error::Error::WorkingCounter { received, .. }) => Ok(received),
+        Err(e) => Err(e),
+    }
+}
+
+fn register_access_error(op: &str, mode: u8, address: u16, register: u16, msg: String, detail: &str) {
+    set_error_ctx(
+        FfiErrorCode::RegisterError,
+        msg,
+        &[
+            ("op", op),
+            ("mode", &mode.to_string()),
+            ("address", &format!("0x{:04X}", address)),
+            ("register", &format!("0x{:04X}", register)),
+            ("error_detail", detail),
+        ],
+    );
+}
+
+/// Check the common arguments of the register calls and return the target and the WKC
+/// expected for it.
+fn register_access_args(state: &EcMasterState, op: &str, mode: u8, address: u16, register: u16, len: u16) -> Result<(RegisterTarget, u16), c_int> {
+    let Some(target) = RegisterTarget::from_ffi(mode, address) else {
+        register_access_error(op, mode, address, register, format!("Unknown addressing mode {}", mode), "invalid mode");
+        return Err(-4);
+    };
+    if len == 0 || len > REGISTER_MAX_LEN {
+        register_access_error(op, mode, address, register, format!("Length {} outside 1..={}", len, REGISTER_MAX_LEN), "invalid length");
+        return Err(-4);
+    }
+    let slave_count = match &state.group {
+        Some(GroupState::PreOp(g)) => g.len(),
+        Some(GroupState::SafeOp(g)) => g.len(),
+        Some(GroupState::Op(g)) => g.len(),
+        None => 0,
+    } as u16;
+    Ok((target, target.expected_wkc(slave_count)))
+}
+
+/// Read `len` bytes from `register` with FPRD (mode 0, `address` = configured station
+/// address), APRD (mode 1, `address` = ring position) or BRD (mode 2, `address` ignored, data
+/// OR-ed over all slaves). The working counter goes to `wkc_out`. Returns the bytes read,
+/// -1 (not initialized), -3 (no slave answered or I/O error) or -4 (bad mode, length or pointer).
+/// A broadcast answered by only some slaves returns 0 bytes with the partial WKC and records a
+/// WKC mismatch: ethercrab does not hand out the payload of a short response.
 #[no_mangle]
-pub extern "C" fn ethercrab_cyclic_tx_rx() -> c_int {
+pub extern "C" fn ethercrab_register_read(
+    mode: u8,
+    address: u16,
+    register: u16,
+    buf: *mut u8,
+    len: u16,
+    wkc_out: *mut u16,
+) -> c_int {
     with_ffi_guard(-1, || {
+        if buf.is_null() || wkc_out.is_null() { return -4; }
         let guard = STATE.read();
         let state = match guard.as_ref() {
             Some(s) => s,
             None => return -1,
         };
-
-        // Fast path check
-        let group = match &state.group {
-            Some(GroupState::Op(g)) => g,
-            _ => return -2,
+        let (target, expected_wkc) = match register_access_args(state, "register_read", mode, address, register, len) {
+            Ok(a) => a,
+            Err(e) => return e,
         };
-
-        let maindevice = &state.maindevice;
-
-        // Sync Shared Memory -> EtherCrab SubDevice (before tx_rx)
-        // Copy outputs from shared buffer to slaves so writes to pdi_buffer[0..output_size] are sent
-        {
-            let buffer = state.pdi_buffer.read(); // Read lock
-            let mut offset = 0; // Outputs start at 0
-            for slave in group.iter(maindevice) {
-                let mut outs = slave.outputs_raw_mut();
-                if outs.is_empty() { continue; }
-                let len = outs.len();
-                if offset + len <= buffer.len() {
-                    outs.copy_from_slice(&buffer[offset..offset+len]);
-                    offset += len;
+        match smol::block_on(register_read_wkc(&state.maindevice, target, register, len, expected_wkc)) {
+            Ok((data, wkc)) => {
+                unsafe { *wkc_out = wkc; }
+                if wkc == 0 {
+                    register_access_error("register_read", mode, address, register, format!("No slave answered the read of 0x{:04X}", register), "WKC 0");
+                    return -3;
+                }
+                if wkc != expected_wkc {
+                    set_error_ctx(
+                        FfiErrorCode::WkcMismatch,
+                        format!("Read of 0x{:04X} answered by {} of {} slaves, data unavailable", register, wkc, expected_wkc),
+                        &[
+                            ("op", "register_read"),
+                            ("mode", &mode.to_string()),
+                            ("register", &format!("0x{:04X}", register)),
+                            ("wkc", &wkc.to_string()),
+                            ("expected_wkc", &expected_wkc.to_string()),
+                        ],
+                    );
                 }
+                let count = data.len().min(len as usize);
+                unsafe { std::slice::from_raw_parts_mut(buf, count) }.copy_from_slice(&data[..count]);
+                count as c_int
+            }
+            Err(e) => {
+                unsafe { *wkc_out = 0; }
+                register_access_error("register_read", mode, address, register, format!("Register read of 0x{:04X} failed: {:?}", register, e), &format!("{:?}", e));
+                -3
             }
         }
+    })
+}
 
-        // Perform IO (Blocking call on this thread)
-        // We rely on ethercrab's internal PDU timeout configuration.
-        // Lock contention from background tasks is handled by pausing them via NETWORK_HEALTHY flag.
-        let wkc = match smol::block_on(group.tx_rx(maindevice)) {
-            Ok(res) => {
-                NETWORK_HEALTHY.store(true, Ordering::Relaxed);
-                res.working_counter
-            },
-            Err(e) => {
-                NETWORK_HEALTHY.store(false, Ordering::Relaxed);
-                let err_detail = format!("{:?}", e);
-                let expected_wkc = state.expected_wkc;
-                let pdu_timeout = state.pdu_timeout_ms;
-                set_error_ctx(
-                    FfiErrorCode::PduTimeout,
-                    format!("Cyclic tx_rx failed: {}", err_detail),
-                    &[
-                        ("op", "cyclic_tx_rx"),
-                        ("expected_wkc", &expected_wkc.to_string()),
-                        ("pdu_timeout_ms", &pdu_timeout.to_string()),
-                        ("error_detail", &err_detail),
-                        ("suggestion", "Increase runtimeOptions.pduTimeoutMs, increase master.cycleTime, or check slave wiring"),
-                    ],
-                );
-                return -2;
-            },
+/// Write `len` bytes to `register` with FPWR, APWR or BWR (modes as for
+/// ethercrab_register_read). The working counter goes to `wkc_out`. Returns 0, -1 (not
+/// initialized), -3 (no slave answered or I/O error) or -4 (bad mode, length or pointer).
+#[no_mangle]
+pub extern "C" fn ethercrab_register_write(
+    mode: u8,
+    address: u16,
+    register: u16,
+    data: *const u8,
+    len: u16,
+    wkc_out: *mut u16,
+) -> c_int {
+    with_ffi_guard(-1, || {
+        if data.is_null() || wkc_out.is_null() { return -4; }
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
         };
-        // Copy Inputs (EtherCAT Frame -> Shared Memory)
-        {
-            let mut buffer = state.pdi_buffer.write();
-            let mut offset = state.output_size;
-            for slave in group.iter(maindevice) {
-                let ins = slave.inputs_raw();
-                if ins.is_empty() { continue; }
-                let len = ins.len();
-                if offset + len <= buffer.len() {
-                    buffer[offset..offset+len].copy_from_slice(&ins);
-                    offset += len;
+        let (target, expected_wkc) = match register_access_args(state, "register_write", mode, address, register, len) {
+            Ok(a) => a,
+            Err(e) => return e,
+        };
+        let payload = unsafe { std::slice::from_raw_parts(data, len as usize) };
+        match smol::block_on(register_write_wkc(&state.maindevice, target, register, payload, expected_wkc)) {
+            Ok(wkc) => {
+                unsafe { *wkc_out = wkc; }
+                if wkc == 0 {
+                    register_access_error("register_write", mode, address, register, format!("No slave answered the write of 0x{:04X}", register), "WKC 0");
+                    return -3;
                 }
+                0
+            }
+            Err(e) => {
+                unsafe { *wkc_out = 0; }
+                register_access_error("register_write", mode, address, register, format!("Register write of 0x{:04X} failed: {:?}", register, e), &format!("{:?}", e));
+                -3
             }
         }
-
-        // Note: WKC can vary depending on network topology and frame structure
-        // Strict checking disabled - return actual WKC and let application decide
-        wkc as c_int
     })
 }
 
+// --- Raw Datagrams ---
+
+// Command types as encoded in the datagram header (ETG1000.4 Table 11).
+pub const DATAGRAM_LRD: u8 = 10;
+pub const DATAGRAM_LWR: u8 = 11;
+pub const DATAGRAM_LRW: u8 = 12;
+pub const DATAGRAM_FRMW: u8 = 14;
+
+/// Datagram commands `ethercrab_raw_datagram` can send.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RawCommand {
+    Lrd,
+    Lwr,
+    Lrw,
+    Frmw,
+}
+
+impl RawCommand {
+    fn from_ffi(command: u8) -> Option<Self> {
+        match command {
+            DATAGRAM_LRD => Some(RawCommand::Lrd),
+            DATAGRAM_LWR => Some(RawCommand::Lwr),
+            DATAGRAM_LRW => Some(RawCommand::Lrw),
+            DATAGRAM_FRMW => Some(RawCommand::Frmw),
+            _ => None,
+        }
+    }
+}
+
+/// Split a 32-bit datagram address into station address (ADP) and register (ADO).
+fn split_physical_address(address: u32) -> (u16, u16) {
+    (address as u16, (address >> 16) as u16)
+}
+
+fn raw_datagram_error(code: FfiErrorCode, command: u8, address: u32, msg: String, detail: &str) {
+    set_error_ctx(
+        code,
+        msg,
+        &[
+            ("op", "raw_datagram"),
+            ("command", &command.to_string()),
+            ("address", &format!("0x{:08X}", address)),
+            ("error_detail", detail),
+        ],
+    );
+}
+
+/// Send one datagram through the PDU loop. Returns the response and the working counter. When
+/// the WKC misses `expected_wkc` the response is empty: ethercrab discards the payload of such
+/// a PDU, and sending the datagram again would repeat its side effects.
+async fn raw_datagram(
+    md: &MainDevice<'_>,
+    command: RawCommand,
+    address: u32,
+    payload: &[u8],
+    expected_wkc: u16,
+) -> Result<(Vec<u8>, u16), ethercrab::error::Error> {
+    let len = payload.len() as u16;
+    let result = match command {
+        RawCommand::Lrd => Command::lrd(address).with_wkc(expected_wkc).receive_slice(md, len).await.map(|d| d.to_vec()),
+        RawCommand::Frmw => {
+            let (station, register) = split_physical_address(address);
+            Command::frmw(station, register).with_wkc(expected_wkc).receive_slice(md, len).await.map(|d| d.to_vec())
+        }
+        RawCommand::Lwr => Command::lwr(address).with_wkc(expected_wkc).send(md, payload).await.map(|_| payload.to_vec()),
+        RawCommand::Lrw => Command::lrw(address).with_wkc(expected_wkc).send_receive_slice(md, payload).await.map(|d| d.to_vec()),
+    };
+    match result {
+        Ok(data) => Ok((data, expected_wkc)),
+        Err(ethercrab::error::Error::WorkingCounter { received, .. }) => Ok((Vec::new(), received)),
+        Err(e) => Err(e),
+    }
+}
+
+/// Submit a single LRD, LWR, LRW or FRMW datagram through the PDU loop, independent of the
+/// slave group. `address` is the 32-bit logical address, or for FRMW the station address in
+/// the low and the register in the high 16 bits. `data` holds `len` payload bytes (may be
+/// null for reads) and `response` receives `len` bytes. Other commands, ARMW included (ethercrab
+/// has no constructor for it), are rejected with -4.
+/// Returns the number of response bytes (0 when the datagram missed `expected_wkc`, since
+/// ethercrab does not expose the payload of such a response),
+/// -1 (not initialized), -3 (I/O error) or -4 (invalid argument). `wkc_out` gets the working
+/// counter in every non-negative case.
 #[no_mangle]
-pub extern "C" fn ethercrab_sdo_read(
-    slave_index: u16,
-    index: u16,
-    sub_index: u8,
-    data_out: *mut u8,
-    max_len: usize,
+pub extern "C" fn ethercrab_raw_datagram(
+    command: u8,
+    address: u32,
+    data: *const u8,
+    len: u16,
+    expected_wkc: u16,
+    response: *mut u8,
+    wkc_out: *mut u16,
 ) -> c_int {
     with_ffi_guard(-1, || {
-        if data_out.is_null() || max_len == 0 { return -4; }
-
+        if response.is_null() || wkc_out.is_null() { return -4; }
         let guard = STATE.read();
         let state = match guard.as_ref() {
             Some(s) => s,
             None => return -1,
         };
+        let Some(kind) = RawCommand::from_ffi(command) else {
+            raw_datagram_error(FfiErrorCode::InvalidArgument, command, address, format!("Unsupported datagram command {}", command), "unsupported command");
+            return -4;
+        };
+        if len == 0 || len > REGISTER_MAX_LEN {
+            raw_datagram_error(FfiErrorCode::InvalidArgument, command, address, format!("Length {} outside 1..={}", len, REGISTER_MAX_LEN), "invalid length");
+            return -4;
+        }
+        let writes = matches!(kind, RawCommand::Lwr | RawCommand::Lrw);
+        if writes && data.is_null() {
+            raw_datagram_error(FfiErrorCode::InvalidArgument, command, address, "Payload required for LWR/LRW".to_string(), "null data");
+            return -4;
+        }
+        let payload = if data.is_null() {
+            vec![0u8; len as usize]
+        } else {
+            unsafe { std::slice::from_raw_parts(data, len as usize) }.to_vec()
+        };
 
-        // We can only support small SDOs efficiently in this synchronous blocking manner
-        if max_len > 4 { return -4; }
-
-        let result = smol::block_on(async {
-            let idx = slave_index as usize;
-            
-            // Generic helper to read
-            async fn read_sdo(
-                group: &GroupState,
-                md: &MainDevice<'_>,
-                idx: usize,
-                i: u16,
-                si: u8
-            ) -> Result<[u8; 4], i32> {
-                let sdo_err = |e: ethercrab::error::Error| {
-                    let err_detail = format!("{:?}", e);
-                    set_error_ctx(
-                        FfiErrorCode::SdoError,
-                        format!("SDO read failed on slave {} (0x{:04X}:{}): {}", idx, i, si, err_detail),
-                        &[
-                            ("op", "sdo_read"),
-                            ("slave_index", &idx.to_string()),
-                            ("sdo_index", &format!("0x{:04X}", i)),
-                            ("sdo_sub_index", &si.to_string()),
-                            ("error_detail", &err_detail),
-                            ("suggestion", "Verify SDO index/sub-index exist on slave. Try increasing runtimeOptions.mailboxResponseTimeoutMs"),
-                        ],
+        match smol::block_on(raw_datagram(&state.maindevice, kind, address, &payload, expected_wkc)) {
+            Ok((out, wkc)) => {
+                unsafe { *wkc_out = wkc; }
+                if wkc != expected_wkc {
+                    raw_datagram_error(
+                        FfiErrorCode::WkcMismatch,
+                        command,
+                        address,
+                        format!("Datagram WKC {} (expected {})", wkc, expected_wkc),
+                        "wkc mismatch",
                     );
-                    -3
-                };
-                match group {
-                    GroupState::PreOp(g) => g.iter(md).nth(idx).ok_or(-2)?.sdo_read(i, si).await.map_err(sdo_err),
-                    GroupState::SafeOp(g) => g.iter(md).nth(idx).ok_or(-2)?.sdo_read(i, si).await.map_err(sdo_err),
-                    GroupState::Op(g) => g.iter(md).nth(idx).ok_or(-2)?.sdo_read(i, si).await.map_err(sdo_err),
                 }
+                let count = out.len().min(len as usize);
+                unsafe { std::slice::from_raw_parts_mut(response, count) }.copy_from_slice(&out[..count]);
+                count as c_int
+            }
+            Err(e) => {
+                unsafe { *wkc_out = 0; }
+                raw_datagram_error(FfiErrorCode::NetworkError, command, address, format!("Datagram failed: {:?}", e), &format!("{:?}", e));
+                -3
             }
+        }
+    })
+}
 
-            match state.group.as_ref() {
-                Some(group) => read_sdo(group, &state.maindevice, idx, index, sub_index).await,
-                None => {
-                    set_error_ctx(
-                        FfiErrorCode::NotInitialized,
-                        format!("No group available for SDO read on slave {} (0x{:04X}:{})", idx, index, sub_index),
-                        &[("op", "sdo_read"), ("slave_index", &idx.to_string())],
-                    );
-                    Err(-2)
-                }
+// --- ESC Watchdogs (ETG1000.4 §6.3) ---
+
+const ESC_WD_DIVIDER: u16 = 0x0400;
+const ESC_WD_PDI_TIME: u16 = 0x0410;
+const ESC_WD_SM_TIME: u16 = 0x0420;
+/// 0x0400..=0x0443: divider, PDI/SM times, SM watchdog status and expiry counters.
+const ESC_WD_BLOCK_LEN: u16 = 0x44;
+/// ESC clock ticks per microsecond (25 MHz, 40 ns per tick).
+const ESC_TICKS_PER_US: u64 = 25;
+/// Divider register value giving the default 100 µs watchdog increment.
+const ESC_WD_DEFAULT_DIVIDER: u16 = 2498;
+
+/// Passed as pdi_timeout_ms to keep each slave's current PDI watchdog time.
+pub const ESC_WATCHDOG_KEEP: u32 = u32::MAX;
+
+/// Watchdog settings and status of one slave, as read back from the ESC.
+#[repr(C)]
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct FfiEscWatchdog {
+    pub slave_index: u16,
+    pub divider: u16,            // 0x0400, increment = (divider + 2) * 40 ns
+    pub pdi_count: u16,          // 0x0410
+    pub sm_count: u16,           // 0x0420
+    pub pdi_time_us: u32,        // Effective PDI watchdog time, 0 = disabled
+    pub sm_time_us: u32,         // Effective SM (process data) watchdog time, 0 = disabled
+    pub status: u16,             // 0x0440, bit 0 = 0: SM watchdog expired
+    pub sm_expired_count: u8,    // 0x0442
+    pub pdi_expired_count: u8,   // 0x0443
+    pub valid: u8,               // 1 = fields above were read from the ESC
+    pub error: i8,               // Per-slave result of a configure: 0, -2 (not found), -3 (failed)
+    pub _padding: [u8; 2],
+}
+
+/// Watchdog time in µs for a divider register value and a count.
+fn esc_watchdog_time_us(divider: u16, count: u16) -> u32 {
+    ((divider as u64 + 2) * count as u64 / ESC_TICKS_PER_US) as u32
+}
+
+/// Divider and PDI/SM counts closest to the requested times. Keeps the default 100 µs
+/// increment unless the longer time needs a coarser one to fit in 16 bits.
+fn esc_watchdog_settings(sm_us: u64, pdi_us: u64) -> (u16, u16, u16) {
+    let max_ticks = sm_us.max(pdi_us) * ESC_TICKS_PER_US;
+    let increment = max_ticks
+        .div_ceil(u16::MAX as u64)
+        .clamp(ESC_WD_DEFAULT_DIVIDER as u64 + 2, u16::MAX as u64 + 2);
+    let count = |us: u64| -> u16 {
+        if us == 0 {
+            return 0;
+        }
+        let ticks = us * ESC_TICKS_PER_US;
+        ((ticks + increment / 2) / increment).clamp(1, u16::MAX as u64) as u16
+    };
+    ((increment - 2) as u16, count(pdi_us), count(sm_us))
+}
+
+fn parse_esc_watchdog(slave_index: u16, block: &[u8]) -> FfiEscWatchdog {
+    let divider = le_u16(block, 0x00);
+    let pdi_count = le_u16(block, 0x10);
+    let sm_count = le_u16(block, 0x20);
+    FfiEscWatchdog {
+        slave_index,
+        divider,
+        pdi_count,
+        sm_count,
+        pdi_time_us: esc_watchdog_time_us(divider, pdi_count),
+        sm_time_us: esc_watchdog_time_us(divider, sm_count),
+        status: le_u16(block, 0x40),
+        sm_expired_count: block.get(0x42).copied().unwrap_or(0),
+        pdi_expired_count: block.get(0x43).copied().unwrap_or(0),
+        valid: 1,
+        ..Default::default()
+    }
+}
+
+async fn read_esc_watchdog(md: &MainDevice<'_>, slave_index: u16, address: u16) -> Result<FfiEscWatchdog, ethercrab::error::Error> {
+    let block = esc_read(md, address, ESC_WD_DIVIDER, ESC_WD_BLOCK_LEN).await?;
+    Ok(parse_esc_watchdog(slave_index, &block))
+}
+
+fn esc_watchdog_error(slave_index: u16, msg: String, detail: &str) {
+    set_error_ctx(
+        FfiErrorCode::RegisterError,
+        msg,
+        &[
+            ("op", "esc_watchdog_configure"),
+            ("slave_index", &slave_index.to_string()),
+            ("error_detail", detail),
+        ],
+    );
+}
+
+/// Write and read back the watchdog settings of one slave. Errors are recorded per slave.
+async fn esc_watchdog_apply(
+    md: &MainDevice<'_>,
+    slave_index: u16,
+    address: u16,
+    sm_timeout_ms: u32,
+    pdi_timeout_ms: u32,
+) -> Result<FfiEscWatchdog, i8> {
+    let pdi_us = if pdi_timeout_ms == ESC_WATCHDOG_KEEP {
+        match read_esc_watchdog(md, slave_index, address).await {
+            Ok(current) => current.pdi_time_us as u64,
+            Err(e) => {
+                esc_watchdog_error(slave_index, format!("Reading watchdogs of slave {} failed: {:?}", slave_index, e), &format!("{:?}", e));
+                return Err(-3);
             }
-        });
+        }
+    } else {
+        pdi_timeout_ms as u64 * 1000
+    };
+    let (divider, pdi_count, sm_count) = esc_watchdog_settings(sm_timeout_ms as u64 * 1000, pdi_us);
+
+    for (register, value) in [(ESC_WD_DIVIDER, divider), (ESC_WD_PDI_TIME, pdi_count), (ESC_WD_SM_TIME, sm_count)] {
+        if let Err(e) = esc_write(md, address, register, &value.to_le_bytes()).await {
+            esc_watchdog_error(
+                slave_index,
+                format!("Writing 0x{:04X} on slave {} failed: {:?}", register, slave_index, e),
+                &format!("{:?}", e),
+            );
+            return Err(-3);
+        }
+    }
 
-        match result {
-            Ok(data) => {
-                let len = max_len.min(4);
-                unsafe { std::ptr::copy_nonoverlapping(data.as_ptr(), data_out, len); }
-                len as c_int
-            },
-            Err(e) => e,
+    let read_back = match read_esc_watchdog(md, slave_index, address).await {
+        Ok(w) => w,
+        Err(e) => {
+            esc_watchdog_error(slave_index, format!("Reading back watchdogs of slave {} failed: {:?}", slave_index, e), &format!("{:?}", e));
+            return Err(-3);
         }
-    })
+    };
+    if (read_back.divider, read_back.pdi_count, read_back.sm_count) != (divider, pdi_count, sm_count) {
+        esc_watchdog_error(
+            slave_index,
+            format!(
+                "Watchdog read-back mismatch on slave {}: wrote divider {} PDI {} SM {}, read {} {} {}",
+                slave_index, divider, pdi_count, sm_count, read_back.divider, read_back.pdi_count, read_back.sm_count
+            ),
+            "read-back mismatch",
+        );
+        return Err(-3);
+    }
+    Ok(read_back)
 }
 
+/// Set the SM (process data) and PDI watchdogs of the selected slaves (`slave_indices`, or
+/// all when null) from times in milliseconds; 0 disables a watchdog, ESC_WATCHDOG_KEEP as
+/// `pdi_timeout_ms` keeps the current PDI time. PreOp only. A failing slave does not stop
+/// the others: `out` (up to `out_len`) gets one entry per requested slave, in order, with the
+/// read-back settings or `valid` = 0 and the slave's `error` (-2 not found, -3 write failed or
+/// read-back mismatch; details in the error ring). Returns the number of slaves configured,
+/// -1 (not initialized), -3 (not PreOp) or -4 (null `out` with `out_len` > 0).
 #[no_mangle]
-pub extern "C" fn ethercrab_sdo_write(
-    slave_index: u16,
-    index: u16,
-    sub_index: u8,
-    data: *const u8,
-    len: usize,
+pub extern "C" fn ethercrab_esc_watchdog_configure(
+    sm_timeout_ms: u32,
+    pdi_timeout_ms: u32,
+    slave_indices: *const u16,
+    slave_count: usize,
+    out: *mut FfiEscWatchdog,
+    out_len: usize,
 ) -> c_int {
     with_ffi_guard(-1, || {
-        if data.is_null() || len == 0 || len > 4 { return -4; }
-        
-        let mut data_buf = [0u8; 4];
-        unsafe { std::ptr::copy_nonoverlapping(data, data_buf.as_mut_ptr(), len); }
-
+        if out.is_null() && out_len > 0 { return -4; }
         let guard = STATE.read();
         let state = match guard.as_ref() {
             Some(s) => s,
             None => return -1,
         };
-
-        let result = smol::block_on(async {
-            let idx = slave_index as usize;
-            let md = &state.maindevice;
-            let g = match state.group.as_ref() {
-                Some(g) => g,
-                None => {
-                    set_error_ctx(
-                        FfiErrorCode::NotInitialized,
-                        format!("No group available for SDO write on slave {} (0x{:04X}:{})", idx, index, sub_index),
-                        &[("op", "sdo_write"), ("slave_index", &idx.to_string())],
-                    );
-                    return Err(-2);
-                }
-            };
-
-            let sdo_err = |e: ethercrab::error::Error| {
-                let err_detail = format!("{:?}", e);
+        let group = match &state.group {
+            Some(GroupState::PreOp(g)) => g,
+            _ => {
                 set_error_ctx(
-                    FfiErrorCode::SdoError,
-                    format!("SDO write failed on slave {} (0x{:04X}:{}): {}", idx, index, sub_index, err_detail),
-                    &[
-                        ("op", "sdo_write"),
-                        ("slave_index", &idx.to_string()),
-                        ("sdo_index", &format!("0x{:04X}", index)),
-                        ("sdo_sub_index", &sub_index.to_string()),
-                        ("data_len", &len.to_string()),
-                        ("error_detail", &err_detail),
-                        ("suggestion", "Verify SDO index/sub-index exist and are writable. Try increasing runtimeOptions.mailboxResponseTimeoutMs"),
-                    ],
+                    FfiErrorCode::InvalidState,
+                    "Watchdog configuration requires PreOp",
+                    &[("op", "esc_watchdog_configure")],
                 );
-                -3
-            };
-
-            match len {
-                1 => match g {
-                    GroupState::PreOp(g) => g.iter(md).nth(idx).ok_or(-2)?.sdo_write(index, sub_index, data_buf[0]).await.map_err(&sdo_err),
-                    GroupState::SafeOp(g) => g.iter(md).nth(idx).ok_or(-2)?.sdo_write(index, sub_index, data_buf[0]).await.map_err(&sdo_err),
-                    GroupState::Op(g) => g.iter(md).nth(idx).ok_or(-2)?.sdo_write(index, sub_index, data_buf[0]).await.map_err(&sdo_err),
-                },
-                2 => {
-                    let val = u16::from_le_bytes([data_buf[0], data_buf[1]]);
-                    match g {
-                        GroupState::PreOp(g) => g.iter(md).nth(idx).ok_or(-2)?.sdo_write(index, sub_index, val).await.map_err(&sdo_err),
-                        GroupState::SafeOp(g) => g.iter(md).nth(idx).ok_or(-2)?.sdo_write(index, sub_index, val).await.map_err(&sdo_err),
-                        GroupState::Op(g) => g.iter(md).nth(idx).ok_or(-2)?.sdo_write(index, sub_index, val).await.map_err(&sdo_err),
-                    }
-                },
-                4 => {
-                    let val = u32_from_bytes(data_buf);
-                    match g {
-                        GroupState::PreOp(g) => g.iter(md).nth(idx).ok_or(-2)?.sdo_write(index, sub_index, val).await.map_err(&sdo_err),
-                        GroupState::SafeOp(g) => g.iter(md).nth(idx).ok_or(-2)?.sdo_write(index, sub_index, val).await.map_err(&sdo_err),
-                        GroupState::Op(g) => g.iter(md).nth(idx).ok_or(-2)?.sdo_write(index, sub_index, val).await.map_err(&sdo_err),
+                return -3;
+            }
+        };
+        let slaves: Vec<u16> = if slave_indices.is_null() {
+            (0..group.len() as u16).collect()
+        } else {
+            unsafe { std::slice::from_raw_parts(slave_indices, slave_count) }.to_vec()
+        };
+        let md = &state.maindevice;
+
+        let results: Vec<FfiEscWatchdog> = smol::block_on(async {
+            let mut results = Vec::with_capacity(slaves.len());
+            for &slave_index in &slaves {
+                let applied = match slave_configured_address(state, slave_index as usize) {
+                    Some(address) => esc_watchdog_apply(md, slave_index, address, sm_timeout_ms, pdi_timeout_ms).await,
+                    None => {
+                        esc_watchdog_error(slave_index, format!("Slave {} not found", slave_index), "not found");
+                        Err(-2)
                     }
-                },
-                _ => return Err(-4)
+                };
+                results.push(applied.unwrap_or_else(|error| FfiEscWatchdog { slave_index, error, ..Default::default() }));
             }
+            results
         });
 
-        match result {
-            Ok(_) => 0,
-            Err(e) => e,
+        let count = results.len().min(out_len);
+        if count > 0 {
+            unsafe { std::slice::from_raw_parts_mut(out, count) }.copy_from_slice(&results[..count]);
         }
+        results.iter().filter(|w| w.valid == 1).count() as c_int
     })
 }
 
+/// Read the watchdog settings, effective times and status (0x0440) of one slave in any
+/// state. Returns 0, -1 (not initialized), -2 (slave not found), -3 (read failed) or -4 (null).
 #[no_mangle]
-pub extern "C" fn ethercrab_eeprom_read(
-    slave_index: u16,
-    address: u16,
-    data_out: *mut u8,
-    len: usize,
-) -> c_int {
+pub extern "C" fn ethercrab_esc_watchdog_read(slave_index: u16, out: *mut FfiEscWatchdog) -> c_int {
     with_ffi_guard(-1, || {
-        if data_out.is_null() || len == 0 { return -4; }
-
+        if out.is_null() { return -4; }
         let guard = STATE.read();
         let state = match guard.as_ref() {
             Some(s) => s,
             None => return -1,
         };
+        let Some(address) = slave_configured_address(state, slave_index as usize) else { return -2 };
+        match smol::block_on(read_esc_watchdog(&state.maindevice, slave_index, address)) {
+            Ok(watchdog) => {
+                unsafe { *out = watchdog; }
+                0
+            }
+            Err(e) => {
+                set_error_ctx(
+                    FfiErrorCode::RegisterError,
+                    format!("Reading watchdogs of slave {} failed: {:?}", slave_index, e),
+                    &[("op", "esc_watchdog_read"), ("slave_index", &slave_index.to_string()), ("error_detail", &format!("{:?}", e))],
+                );
+                -3
+            }
+        }
+    })
+}
 
-        let result = smol::block_on(async {
-            let idx = slave_index as usize;
-            let md = &state.maindevice;
-            let mut buffer = vec![0u8; len];
-            
-            let group = match state.group.as_ref() {
-                Some(g) => g,
-                None => {
-                    set_error_ctx(
-                        FfiErrorCode::NotInitialized,
-                        format!("No group available for EEPROM read on slave {}", slave_index),
-                        &[("op", "eeprom_read"), ("slave_index", &slave_index.to_string())],
-                    );
-                    return Err(-2);
-                }
-            };
-            
-            let read_res = match group {
-                GroupState::PreOp(g) => g.iter(md).nth(idx).ok_or(-2)?.eeprom_read_raw(md, address, &mut buffer).await,
-                GroupState::SafeOp(g) => g.iter(md).nth(idx).ok_or(-2)?.eeprom_read_raw(md, address, &mut buffer).await,
-                GroupState::Op(g) => g.iter(md).nth(idx).ok_or(-2)?.eeprom_read_raw(md, address, &mut buffer).await,
-            };
+// --- ESC Error Counters (0x0300-0x0313) ---
 
-            match read_res {
-                 Ok(bytes) => Ok((bytes, buffer)),
-                 Err(e) => {
-                     set_error_ctx(
-                         FfiErrorCode::EepromError,
-                         format!("EEPROM read failed on slave {} at address 0x{:04X}: {:?}", slave_index, address, e),
-                         &[
-                             ("op", "eeprom_read"),
-                             ("slave_index", &slave_index.to_string()),
-                             ("address", &format!("0x{:04X}", address)),
-                             ("length", &len.to_string()),
-                             ("error_detail", &format!("{:?}", e)),
-                             ("suggestion", "Try increasing runtimeOptions.eepromTimeoutMs"),
-                         ],
-                     );
-                     Err(-3)
-                 }
-            }
-        });
+const ESC_ERROR_COUNTERS: u16 = 0x0300;
+/// 0x0300..=0x0313: RX/invalid frame, forwarded RX, processing unit, PDI and lost link counters.
+const ESC_ERROR_COUNTERS_LEN: u16 = 0x14;
+/// Writing any of 0x0300..=0x030D clears the RX, forwarded, processing unit and PDI counters.
+const ESC_ERROR_CLEAR_LEN: u16 = 0x0E;
+const ESC_LOST_LINK_COUNTERS: u16 = 0x0310;
 
-        match result {
-            Ok((bytes_read, buffer)) => {
-                unsafe { std::ptr::copy_nonoverlapping(buffer.as_ptr(), data_out, bytes_read as usize); }
-                bytes_read as c_int
-            },
-            Err(e) => e,
+/// ESC error counters of one slave. Each counter stops at 0xFF.
+#[repr(C)]
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct FfiEscErrorCounters {
+    pub invalid_frame: [u8; 4],      // 0x0300 + 2 * port
+    pub rx_error: [u8; 4],           // 0x0301 + 2 * port
+    pub forwarded_rx_error: [u8; 4], // 0x0308 + port
+    pub lost_link: [u8; 4],          // 0x0310 + port
+    pub processing_unit: u8,         // 0x030C
+    pub pdi: u8,                     // 0x030D
+}
+
+/// Counters of one slave with the increase since the previous read (or clear).
+#[repr(C)]
+#[derive(Clone, Copy, Default, Debug)]
+pub struct FfiEscErrorReport {
+    pub slave_index: u16,
+    pub counters: FfiEscErrorCounters,
+    pub delta: FfiEscErrorCounters,
+    pub valid: u8, // 0 = slave did not answer, counters and delta are zero
+    pub _padding: u8,
+    pub interval_ms: u32, // Time covered by `delta`
+}
+
+/// One port in the ranking returned by `ethercrab_esc_error_rank`.
+#[repr(C)]
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct FfiEscPortRank {
+    pub slave_index: u16,
+    pub port: u8,
+    pub _padding: u8,
+    pub errors: u32, // Invalid frames + RX errors + lost links in the last interval
+    pub errors_per_second: f32,
+}
+
+impl FfiEscErrorCounters {
+    fn from_registers(raw: &[u8]) -> Self {
+        let b = |i: usize| raw.get(i).copied().unwrap_or(0);
+        Self {
+            invalid_frame: [0, 1, 2, 3].map(|p| b(2 * p)),
+            rx_error: [0, 1, 2, 3].map(|p| b(2 * p + 1)),
+            forwarded_rx_error: [0, 1, 2, 3].map(|p| b(0x08 + p)),
+            lost_link: [0, 1, 2, 3].map(|p| b(0x10 + p)),
+            processing_unit: b(0x0C),
+            pdi: b(0x0D),
         }
-    })
+    }
+
+    /// Increase since `previous`. A counter that went down was cleared in between and
+    /// counts from zero.
+    fn since(&self, previous: &Self) -> Self {
+        let d = |now: u8, before: u8| if now >= before { now - before } else { now };
+        let d4 = |now: [u8; 4], before: [u8; 4]| [0, 1, 2, 3].map(|p| d(now[p], before[p]));
+        Self {
+            invalid_frame: d4(self.invalid_frame, previous.invalid_frame),
+            rx_error: d4(self.rx_error, previous.rx_error),
+            forwarded_rx_error: d4(self.forwarded_rx_error, previous.forwarded_rx_error),
+            lost_link: d4(self.lost_link, previous.lost_link),
+            processing_unit: d(self.processing_unit, previous.processing_unit),
+            pdi: d(self.pdi, previous.pdi),
+        }
+    }
+
+    /// Errors detected on a port itself. Forwarded RX errors are left out: they were
+    /// already counted by the slave that first saw the broken frame.
+    fn port_errors(&self, port: usize) -> u32 {
+        self.invalid_frame[port] as u32 + self.rx_error[port] as u32 + self.lost_link[port] as u32
+    }
 }
 
+/// Counters of the previous read per slave position, the baseline for deltas.
+struct EscErrorHistory {
+    baseline: Vec<Option<(Instant, FfiEscErrorCounters)>>,
+    /// Start of the first interval: init or the last clear.
+    since: Instant,
+    last_reports: Vec<FfiEscErrorReport>,
+}
+
+impl Default for EscErrorHistory {
+    fn default() -> Self {
+        Self { baseline: Vec::new(), since: Instant::now(), last_reports: Vec::new() }
+    }
+}
+
+impl EscErrorHistory {
+    /// Turn fresh counters (None = slave did not answer) into reports and move the baseline.
+    fn update(&mut self, readings: &[Option<FfiEscErrorCounters>], now: Instant) -> Vec<FfiEscErrorReport> {
+        self.baseline.resize(readings.len(), None);
+        let reports: Vec<FfiEscErrorReport> = readings
+            .iter()
+            .enumerate()
+            .map(|(idx, reading)| {
+                let mut report = FfiEscErrorReport { slave_index: idx as u16, ..Default::default() };
+                if let Some(counters) = reading {
+                    let (then, previous) = self.baseline[idx].unwrap_or((self.since, FfiEscErrorCounters::default()));
+                    report.counters = *counters;
+                    report.delta = counters.since(&previous);
+                    report.valid = 1;
+                    report.interval_ms = now.saturating_duration_since(then).as_millis().min(u32::MAX as u128) as u32;
+                    self.baseline[idx] = Some((now, *counters));
+                }
+                report
+            })
+            .collect();
+        self.last_reports = reports.clone();
+        reports
+    }
+
+    fn clear(&mut self, now: Instant) {
+        self.baseline.clear();
+        self.last_reports.clear();
+        self.since = now;
+    }
+}
+
+/// Ports with errors in the last interval, highest error rate first.
+fn rank_port_errors(reports: &[FfiEscErrorReport]) -> Vec<FfiEscPortRank> {
+    let mut ranks: Vec<FfiEscPortRank> = reports
+        .iter()
+        .filter(|r| r.valid != 0)
+        .flat_map(|r| {
+            (0..4).map(move |port| {
+                let errors = r.delta.port_errors(port);
+                let seconds = r.interval_ms as f32 / 1000.0;
+                FfiEscPortRank {
+                    slave_index: r.slave_index,
+                    port: port as u8,
+                    _padding: 0,
+                    errors,
+                    errors_per_second: if seconds > 0.0 { errors as f32 / seconds } else { 0.0 },
+                }
+            })
+        })
+        .filter(|rank| rank.errors > 0)
+        .collect();
+    ranks.sort_by(|a, b| {
+        b.errors_per_second
+            .total_cmp(&a.errors_per_second)
+            .then(b.errors.cmp(&a.errors))
+    });
+    ranks
+}
+
+/// Read the error counters of every slave and the increase since the previous read (or
+/// since init / the last clear). A slave that does not answer is reported with `valid` = 0.
+/// Returns the number of slaves (reports beyond `max` are dropped), -1 (not initialized)
+/// or -4 (null).
 #[no_mangle]
-pub extern "C" fn ethercrab_configure_mailbox_polling(interval_ms: u32) -> c_int {
+pub extern "C" fn ethercrab_esc_error_counters_read(out: *mut FfiEscErrorReport, max: usize) -> c_int {
     with_ffi_guard(-1, || {
-        let mut guard = STATE.write();
-        if let Some(state) = guard.as_mut() {
-            if interval_ms == 0 {
-                state.mailbox_poll_interval_ms = None;
-            } else {
-                state.mailbox_poll_interval_ms = Some(interval_ms);
-            }
-            0
-        } else {
-            -1
+        if out.is_null() && max > 0 { return -4; }
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        let readings: Vec<Option<FfiEscErrorCounters>> = slave_configured_addresses(state)
+            .into_iter()
+            .enumerate()
+            .map(|(idx, address)| {
+                match smol::block_on(esc_read(&state.maindevice, address, ESC_ERROR_COUNTERS, ESC_ERROR_COUNTERS_LEN)) {
+                    Ok(raw) => Some(FfiEscErrorCounters::from_registers(&raw)),
+                    Err(e) => {
+                        set_error_ctx(
+                            FfiErrorCode::RegisterError,
+                            format!("Reading error counters of slave {} failed: {:?}", idx, e),
+                            &[("op", "esc_error_counters_read"), ("slave_index", &idx.to_string()), ("error_detail", &format!("{:?}", e))],
+                        );
+                        None
+                    }
+                }
+            })
+            .collect();
+        let reports = state.esc_errors.lock().update(&readings, Instant::now());
+        let count = reports.len().min(max);
+        if count > 0 {
+            unsafe { std::slice::from_raw_parts_mut(out, count) }.copy_from_slice(&reports[..count]);
         }
+        reports.len() as c_int
     })
 }
 
+/// Clear the error and lost link counters of all slaves with two broadcast writes and
+/// restart the delta baseline. Returns 0, -1 (not initialized) or -3 (not every slave
+/// acknowledged the writes).
 #[no_mangle]
-pub extern "C" fn ethercrab_check_mailbox(slave_index: u16, mailbox_status_addr: u16) -> c_int {
+pub extern "C" fn ethercrab_esc_error_counters_clear() -> c_int {
     with_ffi_guard(-1, || {
-        // If network is unhealthy, skip IO to avoid lock contention
-        if !NETWORK_HEALTHY.load(Ordering::Relaxed) {
-            return -1;
-        }
-
-        // We don't need manual timeouts here as PDU timeouts are handled by ethercrab
-
-        let result = smol::block_on(async move {
-            let guard = STATE.read();
-            if let Some(ref master_state) = *guard {
-                    let maindevice = &master_state.maindevice;
-                    let idx = slave_index as usize;
-                    
-                    // Check we have a group
-                    let group = match master_state.group.as_ref() {
-                        Some(g) => g,
-                        None => return Err(-1),
-                    };
-                    
-                    let val_res = match group {
-                        GroupState::PreOp(g) => g.iter(maindevice).nth(idx).ok_or(-1)?.register_read::<u8>(mailbox_status_addr).await,
-                        GroupState::SafeOp(g) => g.iter(maindevice).nth(idx).ok_or(-1)?.register_read::<u8>(mailbox_status_addr).await,
-                        GroupState::Op(g) => g.iter(maindevice).nth(idx).ok_or(-1)?.register_read::<u8>(mailbox_status_addr).await,
-                    };
-                    
-                    match val_res {
-                        Ok(val) => Ok(if (val & 0x08) != 0 { 1 } else { 0 }),
-                        Err(_) => Err(-2)
-                    }
-            } else {
-                Err(-1)
-            }
-        });
-
-        match result {
-            Ok(v) => v,
-            Err(e) => e,
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        let slave_count = slave_configured_addresses(state).len() as u16;
+        let md = &state.maindevice;
+        for (register, len) in [(ESC_ERROR_COUNTERS, ESC_ERROR_CLEAR_LEN), (ESC_LOST_LINK_COUNTERS, 4)] {
+            let zeros = vec![0u8; len as usize];
+            let result = smol::block_on(register_write_wkc(md, RegisterTarget::Broadcast, register, &zeros, slave_count));
+            let detail = match result {
+                Ok(wkc) if wkc == slave_count => continue,
+                Ok(wkc) => format!("WKC {} (expected {})", wkc, slave_count),
+                Err(e) => format!("{:?}", e),
+            };
+            set_error_ctx(
+                FfiErrorCode::RegisterError,
+                format!("Clearing error counters at 0x{:04X} failed: {}", register, detail),
+                &[("op", "esc_error_counters_clear"), ("register", &format!("0x{:04X}", register)), ("error_detail", &detail)],
+            );
+            return -3;
         }
+        state.esc_errors.lock().clear(Instant::now());
+        0
     })
 }
 
-/// Feature 402: Mailbox Resilient Layer
-/// Checks mailbox status with toggle-bit verification and retry mechanism
-/// Returns: 0 (Empty), 1 (New Mail/Success), -1 (Error/State not ready), -2 (Retry Failed)
+/// Rank the ports by error rate over the interval of the last
+/// `ethercrab_esc_error_counters_read`. Only ports with errors are listed.
+/// Returns the number of ranked ports (entries beyond `max` are dropped), -1 or -4.
 #[no_mangle]
-pub extern "C" fn ethercrab_check_mailbox_resilient(
-    slave_index: u16,
-    mailbox_status_addr: u16,
-    last_toggle_bit: u8, // 0 or 1. If > 1, ignore toggle check (first run)
-) -> c_int {
+pub extern "C" fn ethercrab_esc_error_rank(out: *mut FfiEscPortRank, max: usize) -> c_int {
     with_ffi_guard(-1, || {
-        // If network is unhealthy, skip IO to avoid lock contention
-        if !NETWORK_HEALTHY.load(Ordering::Relaxed) {
-            return -1;
+        if out.is_null() && max > 0 { return -4; }
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        let ranks = rank_port_errors(&state.esc_errors.lock().last_reports);
+        let count = ranks.len().min(max);
+        if count > 0 {
+            unsafe { std::slice::from_raw_parts_mut(out, count) }.copy_from_slice(&ranks[..count]);
         }
+        ranks.len() as c_int
+    })
+}
 
-        // Timeout is handled internally by ethercrab
+// --- Link Monitor ---
 
-        let result = smol::block_on(async move {
-            let guard = STATE.read();
-            if let Some(ref master_state) = *guard {
-                let maindevice = &master_state.maindevice;
-                let idx = slave_index as usize;
+/// Link lost or restored, from `ethercrab_link_monitor_check`.
+#[repr(C)]
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct FfiLinkEvent {
+    pub parent_index: i16,     // Slave on the master side of the link, -1 = the master
+    pub parent_port: u8,       // Port of that slave, 0xFF for the master
+    pub lost: u8,              // 1 = link lost, 0 = restored
+    pub child_index: u16,      // First slave behind the link
+    pub slave_count: u16,      // Slaves answering the broadcast read
+    pub expected_count: u16,   // Slaves when the monitor was started
+    pub parent_dl_status: u16, // DL status (0x0110) of the parent slave, 0 for the master or when not read
+}
 
-                // Check we have a group
-                let group = match master_state.group.as_ref() {
-                    Some(g) => g,
-                    None => return Err(-1),
-                };
+/// A link of the topology captured at start: `parent` (slave, port), or the master when
+/// None, feeds `child`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct TopologyLink {
+    parent: Option<(u16, u8)>,
+    child: u16,
+}
 
-                // Retry Loop (Resilient Layer) - max 3 attempts
-                for _attempt in 0..3 {
-                    // 1. Read Register
-                    let val_res = match group {
-                        GroupState::PreOp(g) => {
-                            g.iter(maindevice).nth(idx).ok_or(-1)?.register_read::<u8>(mailbox_status_addr).await
-                        }
-                        GroupState::SafeOp(g) => {
-                            g.iter(maindevice).nth(idx).ok_or(-1)?.register_read::<u8>(mailbox_status_addr).await
-                        }
-                        GroupState::Op(g) => {
-                            g.iter(maindevice).nth(idx).ok_or(-1)?.register_read::<u8>(mailbox_status_addr).await
-                        }
-                    };
+struct LinkMonitor {
+    addresses: Vec<u16>,
+    links: Vec<TopologyLink>,
+    /// Per child slave: the link in front of it is currently reported as lost.
+    lost: Vec<bool>,
+    /// Broadcast WKC of the last pass that located the breaks.
+    last_count: u16,
+}
 
-                    match val_res {
-                        Ok(val) => {
-                            // Bit 3 = Mailbox Full (Input) (0x08)
-                            // Bit 1 = Toggle Bit (Input) (0x02)
-                            // Note: Check ETG.1000.4 spec for specific bit offsets per device
+impl LinkMonitor {
+    /// Baseline from the configured addresses and DL status of every slave, in ring order.
+    fn new(addresses: Vec<u16>, dl_status: &[u16]) -> Self {
+        let active: Vec<[bool; 4]> = dl_status.iter().map(|&s| dl_status_ports(s).2).collect();
+        let links = compute_parents(&active)
+            .into_iter()
+            .enumerate()
+            .map(|(child, parent)| TopologyLink {
+                parent: parent.map(|(slave, port)| (slave as u16, port)),
+                child: child as u16,
+            })
+            .collect();
+        let lost = vec![false; addresses.len()];
+        let last_count = addresses.len() as u16;
+        Self { addresses, links, lost, last_count }
+    }
 
-                            let mailbox_full = (val & 0x08) != 0;
-                            let current_toggle = (val & 0x02) >> 1;
+    /// Links whose state changed, given which slaves answered. A link is lost when its child
+    /// is silent while the slave in front of it (or the master) still answers; the slaves
+    /// further down went away through the same break and are not reported again.
+    fn changes(&mut self, answering: &[bool]) -> Vec<(TopologyLink, bool)> {
+        let mut changes = Vec::new();
+        for link in &self.links {
+            let child = link.child as usize;
+            let child_ok = answering.get(child).copied().unwrap_or(false);
+            let parent_ok = link.parent.is_none_or(|(p, _)| answering.get(p as usize).copied().unwrap_or(false));
+            if !child_ok && parent_ok && !self.lost[child] {
+                self.lost[child] = true;
+                changes.push((*link, true));
+            } else if child_ok && self.lost[child] {
+                self.lost[child] = false;
+                changes.push((*link, false));
+            }
+        }
+        changes
+    }
+}
 
-                            if !mailbox_full {
-                                return Ok(0); // Empty
-                            }
+/// Find which slaves answer when `missing` of them are silent. Slaves are probed in ring order
+/// with `probe` (DL status, None = silent); a slave behind a silent one is silent without being
+/// read, and once `missing` silent slaves are found the rest are taken as answering unread.
+/// Returns who answers and the DL status of the slaves that were read.
+fn locate_silent_slaves(
+    links: &[TopologyLink],
+    missing: usize,
+    mut probe: impl FnMut(usize) -> Option<u16>,
+) -> (Vec<bool>, Vec<Option<u16>>) {
+    let mut answering = vec![true; links.len()];
+    let mut dl_status = vec![None; links.len()];
+    let mut silent = 0;
+    for link in links {
+        let child = link.child as usize;
+        if link.parent.is_some_and(|(p, _)| !answering[p as usize]) {
+            answering[child] = false;
+            silent += 1;
+        } else if silent < missing {
+            dl_status[child] = probe(child);
+            answering[child] = dl_status[child].is_some();
+            silent += !answering[child] as usize;
+        }
+    }
+    (answering, dl_status)
+}
 
-                            // If it's the first run (last_toggle_bit > 1), accept it.
-                            // If toggle bit changed, accept it.
-                            if last_toggle_bit > 1 || current_toggle != last_toggle_bit {
-                                return Ok(1); // Success: New valid mail
-                            }
+fn link_description(link: &TopologyLink) -> String {
+    match link.parent {
+        Some((slave, port)) => format!("slave {} port {} and slave {}", slave, port, link.child),
+        None => format!("the master and slave {}", link.child),
+    }
+}
 
-                            // If we are here, Mailbox is full but Toggle Bit didn't change.
-                            // This implies we might be re-reading an old frame or a lost update.
-                            // Retry immediately.
-                            continue;
-                        }
-                        Err(_) => continue, // Read failed, retry
-                    }
+/// Capture the current topology (DL status of every slave) as the monitor baseline.
+/// Returns the number of slaves, -1 (not initialized), -2 (no slaves) or -3 (read failed).
+#[no_mangle]
+pub extern "C" fn ethercrab_link_monitor_start() -> c_int {
+    with_ffi_guard(-1, || {
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        let addresses = slave_configured_addresses(state);
+        if addresses.is_empty() { return -2; }
+        let mut dl_status = Vec::with_capacity(addresses.len());
+        for (idx, &address) in addresses.iter().enumerate() {
+            match smol::block_on(esc_read(&state.maindevice, address, DL_STATUS_REGISTER, 2)) {
+                Ok(raw) => dl_status.push(le_u16(&raw, 0)),
+                Err(e) => {
+                    set_error_ctx(
+                        FfiErrorCode::RegisterError,
+                        format!("Reading DL status of slave {} failed: {:?}", idx, e),
+                        &[("op", "link_monitor_start"), ("slave_index", &idx.to_string()), ("error_detail", &format!("{:?}", e))],
+                    );
+                    return -3;
                 }
-
-                // Retries exhausted
-                Err(-2)
-            } else {
-                Err(-1)
             }
-        });
-
-        match result {
-            Ok(v) => v,
-            Err(e) => e,
         }
+        let count = addresses.len();
+        *state.link_monitor.lock() = Some(LinkMonitor::new(addresses, &dl_status));
+        count as c_int
     })
 }
 
+/// One monitor pass: a broadcast read of DL status counts the answering slaves. When that
+/// count differs from the previous pass, slaves are read one by one to locate the breaks,
+/// skipping everything behind a silent slave and stopping once the missing slaves are found.
+/// The master state is not held while probing; destroy waits for the pass. Each lost link goes into the error ring as
+/// LinkLost. Returns the number of events written to `out` (lost and restored links),
+/// -1 (not initialized), -2 (monitor not started), -3 (broadcast failed) or -4 (null).
 #[no_mangle]
-pub extern "C" fn ethercrab_get_last_emergency(out: *mut EmergencyInfo) -> c_int {
+pub extern "C" fn ethercrab_link_monitor_check(out: *mut FfiLinkEvent, max: usize) -> c_int {
     with_ffi_guard(-1, || {
-        if out.is_null() { return -1; }
+        if out.is_null() && max > 0 { return -4; }
+        let (md, addresses, links, last_count) = {
+            let guard = STATE.read();
+            let state = match guard.as_ref() {
+                Some(s) => s,
+                None => return -1,
+            };
+            let monitor_guard = state.link_monitor.lock();
+            let Some(monitor) = monitor_guard.as_ref() else { return -2 };
+            (DetachedMaster::new(state), monitor.addresses.clone(), monitor.links.clone(), monitor.last_count)
+        };
+        let expected = addresses.len() as u16;
 
-        let guard = LAST_EMERGENCY.lock();
-        if let Some(ref emergency) = *guard {
-            unsafe {
-                (*out).slave_index = emergency.slave_index;
-                (*out).error_code = emergency.error_code;
-                (*out).error_register = emergency.error_register;
+        let slave_count = match smol::block_on(Command::brd(DL_STATUS_REGISTER).receive_wkc::<u16>(&md)) {
+            Ok(wkc) => wkc,
+            Err(e) => {
+                set_error_ctx(
+                    FfiErrorCode::NetworkError,
+                    format!("Link monitor broadcast failed: {:?}", e),
+                    &[("op", "link_monitor"), ("error_detail", &format!("{:?}", e))],
+                );
+                return -3;
             }
-            0
-        } else {
-            -1
+        };
+        if slave_count == last_count {
+            return 0;
+        }
+
+        let missing = expected.saturating_sub(slave_count) as usize;
+        let (answering, dl_status) = locate_silent_slaves(&links, missing, |idx| {
+            smol::block_on(esc_read(&md, addresses[idx], DL_STATUS_REGISTER, 2)).ok().map(|raw| le_u16(&raw, 0))
+        });
+
+        let guard = STATE.read();
+        let Some(state) = guard.as_ref() else { return -1 };
+        let mut monitor_guard = state.link_monitor.lock();
+        // Restarted or stopped while probing: the result belongs to another baseline
+        let Some(monitor) = monitor_guard.as_mut().filter(|m| m.addresses == addresses) else { return -2 };
+        monitor.last_count = slave_count;
+
+        let mut events = Vec::new();
+        for (link, lost) in monitor.changes(&answering) {
+            let parent_dl_status = link.parent.and_then(|(p, _)| dl_status[p as usize]).unwrap_or(0);
+            if lost {
+                let (parent_index, parent_port) = link.parent.map_or(("master".to_string(), "-".to_string()), |(s, p)| (s.to_string(), p.to_string()));
+                set_error_ctx(
+                    FfiErrorCode::LinkLost,
+                    format!("Link lost between {}", link_description(&link)),
+                    &[
+                        ("op", "link_monitor"),
+                        ("parent_index", &parent_index),
+                        ("parent_port", &parent_port),
+                        ("child_index", &link.child.to_string()),
+                        ("slave_count", &slave_count.to_string()),
+                        ("expected_count", &expected.to_string()),
+                        ("parent_dl_status", &format!("0x{:04X}", parent_dl_status)),
+                    ],
+                );
+            }
+            events.push(FfiLinkEvent {
+                parent_index: link.parent.map_or(-1, |(s, _)| s as i16),
+                parent_port: link.parent.map_or(0xFF, |(_, p)| p),
+                lost: lost as u8,
+                child_index: link.child,
+                slave_count,
+                expected_count: expected,
+                parent_dl_status,
+            });
+        }
+        let count = events.len().min(max);
+        if count > 0 {
+            unsafe { std::slice::from_raw_parts_mut(out, count) }.copy_from_slice(&events[..count]);
         }
+        events.len() as c_int
     })
 }
 
+/// Drop the monitor baseline. Returns 0, -1 (not initialized) or -2 (not started).
 #[no_mangle]
-pub extern "C" fn ethercrab_write_process_data_byte(
+pub extern "C" fn ethercrab_link_monitor_stop() -> c_int {
+    with_ffi_guard(-1, || {
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        let monitor = state.link_monitor.lock().take();
+        if monitor.is_some() { 0 } else { -2 }
+    })
+}
+
+fn soe_error_ctx(op: &str, slave_index: u16, drive_no: u8, idn: u16, elements: u8, e: &MailboxError) {
+    let code = match e {
+        MailboxError::Soe(_) => FfiErrorCode::SoeError,
+        MailboxError::Timeout => FfiErrorCode::PduTimeout,
+        _ => FfiErrorCode::MailboxError,
+    };
+    set_error_ctx(
+        code,
+        format!("SoE {} failed on slave {} (drive {}, IDN 0x{:04X}): {}", op, slave_index, drive_no, idn, e),
+        &[
+            ("op", op),
+            ("slave_index", &slave_index.to_string()),
+            ("drive_no", &drive_no.to_string()),
+            ("idn", &format!("0x{:04X}", idn)),
+            ("elements", &format!("0x{:02X}", elements)),
+            ("error_detail", &format!("{:?}", e)),
+            ("suggestion", "Verify the slave supports SoE and the IDN exists. Try increasing runtimeOptions.mailboxResponseTimeoutMs"),
+        ],
+    );
+}
+
+/// Read elements of an SoE IDN from a drive.
+///
+/// `elements` selects what is returned (see `SOE_ELEMENT_*`): 0x40 = value,
+/// 0x04 = attribute, 0x02 = name, 0x08 = unit, 0x10/0x20 = min/max.
+/// Returns bytes written, or -1 (not initialized), -2 (slave not found),
+/// -3 (SoE/mailbox failure), -4 (invalid argument or buffer too small).
+#[no_mangle]
+pub extern "C" fn ethercrab_soe_read(
     slave_index: u16,
-    byte_offset: u32,
-    value: u8,
+    drive_no: u8,
+    idn: u16,
+    elements: u8,
+    data_out: *mut u8,
+    max_len: usize,
 ) -> c_int {
-    with_ffi_guard(0, || {
-        let result = smol::block_on(async {
-            let mut guard = STATE.write();
-            let state = match guard.as_mut() {
-                Some(s) => s,
-                None => return 0,
-            };
+    with_ffi_guard(-1, || {
+        if data_out.is_null() || max_len == 0 || elements == 0 || drive_no > 7 { return -4; }
 
-            let group = match &mut state.group {
-                Some(GroupState::Op(g)) => g,
-                _ => return 0, // Not in OP state
-            };
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        let address = match slave_configured_address(state, slave_index as usize) {
+            Some(a) => a,
+            None => return -2,
+        };
+        let timeout = Duration::from_millis(state.mailbox_timeout_ms);
 
-            let idx = slave_index as usize;
-            if let Some(subdevice) = group.iter(&state.maindevice).nth(idx) {
-                let mut outputs = subdevice.outputs_raw_mut();
-                if let Some(byte) = outputs.get_mut(byte_offset as usize) {
-                    *byte = value;
-                    return 1;
+        let result = smol::block_on(soe_read(&state.maindevice, address, drive_no, idn, elements, timeout));
+        match result {
+            Ok(data) => {
+                if data.len() > max_len {
+                    set_error_ctx(
+                        FfiErrorCode::InvalidArgument,
+                        format!("SoE read buffer too small: {} bytes needed, {} provided", data.len(), max_len),
+                        &[("op", "soe_read"), ("required_len", &data.len().to_string())],
+                    );
+                    return -4;
                 }
+                unsafe { std::ptr::copy_nonoverlapping(data.as_ptr(), data_out, data.len()); }
+                data.len() as c_int
             }
-            0
-        });
-
-        result
+            Err(e) => {
+                soe_error_ctx("soe_read", slave_index, drive_no, idn, elements, &e);
+                -3
+            }
+        }
     })
 }
 
+/// Write an element of an SoE IDN on a drive (usually `SOE_ELEMENT_VALUE`).
+/// Data larger than the mailbox is sent as fragments.
+/// Returns 0 on success, or -1 (not initialized), -2 (slave not found),
+/// -3 (SoE/mailbox failure), -4 (invalid argument).
 #[no_mangle]
-pub extern "C" fn ethercrab_read_process_data_byte(
+pub extern "C" fn ethercrab_soe_write(
     slave_index: u16,
-    byte_offset: u32,
-    is_output: bool,
-) -> u8 {
-    with_ffi_guard(0, || {
-        let result = smol::block_on(async {
-            let guard = STATE.read();
-            let state = match guard.as_ref() {
-                Some(s) => s,
-                None => return 0,
-            };
+    drive_no: u8,
+    idn: u16,
+    elements: u8,
+    data: *const u8,
+    len: usize,
+) -> c_int {
+    with_ffi_guard(-1, || {
+        if data.is_null() || len == 0 || elements == 0 || drive_no > 7 { return -4; }
+        let payload = unsafe { std::slice::from_raw_parts(data, len) };
 
-            let group = match &state.group {
-                Some(GroupState::Op(g)) => g,
-                _ => return 0, // Not in OP state
-            };
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        let address = match slave_configured_address(state, slave_index as usize) {
+            Some(a) => a,
+            None => return -2,
+        };
+        let timeout = Duration::from_millis(state.mailbox_timeout_ms);
 
-            let idx = slave_index as usize;
-            if let Some(subdevice) = group.iter(&state.maindevice).nth(idx) {
-                let io = subdevice.io_raw();
-                if is_output {
-                    if let Some(&byte) = io.outputs().get(byte_offset as usize) {
-                        return byte;
-                    }
-                } else {
-                    if let Some(&byte) = io.inputs().get(byte_offset as usize) {
-                        return byte;
-                    }
-                }
+        match smol::block_on(soe_write(&state.maindevice, address, drive_no, idn, elements, payload, timeout)) {
+            Ok(()) => 0,
+            Err(e) => {
+                soe_error_ctx("soe_write", slave_index, drive_no, idn, elements, &e);
+                -3
             }
-            0
-        });
-
-        result
+        }
     })
 }
 
-/// Read a 16-bit register value from a slave.
-/// 
-/// Common watchdog-related registers:
-/// - 0x0400: Watchdog Divider (default ~2498, gives ~100µs per count)
-/// - 0x0410: PDI Watchdog timeout
-/// - 0x0420: SM Watchdog timeout (default ~1000 with default divider = ~100ms)
-/// - 0x0440: SM Watchdog status
+fn mailbox_error_ctx(op: &str, slave_index: u16, mailbox_type: Option<u8>, e: &MailboxError) {
+    let code = match e {
+        MailboxError::Timeout => FfiErrorCode::PduTimeout,
+        _ => FfiErrorCode::MailboxError,
+    };
+    let type_str = mailbox_type.map(|t| format!("0x{:X}", t)).unwrap_or_else(|| "-".to_string());
+    set_error_ctx(
+        code,
+        format!("Mailbox {} failed on slave {} (type {}): {}", op, slave_index, type_str, e),
+        &[
+            ("op", op),
+            ("slave_index", &slave_index.to_string()),
+            ("mailbox_type", &type_str),
+            ("error_detail", &format!("{:?}", e)),
+            ("suggestion", "Verify the slave is in PreOp or higher and supports this mailbox protocol. Try increasing the timeout"),
+        ],
+    );
+}
+
+/// Send a raw mailbox frame to a slave's SM0 (e.g. VoE 0x0F, AoE 0x01).
+///
+/// The caller supplies the mailbox type (low nibble of header byte 5) and
+/// counter (1..=7, 0 = no repeat detection). `timeout_ms` = 0 uses the
+/// configured mailbox response timeout.
+/// Returns 0 on success, or -1 (not initialized), -2 (slave not found),
+/// -3 (mailbox failure or timeout), -4 (invalid argument or payload too large).
 #[no_mangle]
-pub extern "C" fn ethercrab_register_read_u16(
+pub extern "C" fn ethercrab_mailbox_send(
     slave_index: u16,
-    register_address: u16,
-) -> i32 {
+    mailbox_type: u8,
+    counter: u8,
+    data: *const u8,
+    len: usize,
+    timeout_ms: u32,
+) -> c_int {
     with_ffi_guard(-1, || {
-        let result = smol::block_on(async move {
-            let guard = STATE.read();
-            let state = match guard.as_ref() {
-                Some(s) => s,
-                None => return Err(-1),
-            };
+        if mailbox_type == MAILBOX_TYPE_ERR || mailbox_type > 0x0F || counter > 7 { return -4; }
+        if data.is_null() && len > 0 { return -4; }
+        let payload = if len == 0 { &[][..] } else { unsafe { std::slice::from_raw_parts(data, len) } };
 
-            let idx = slave_index as usize;
-            let md = &state.maindevice;
-            
-            let val_res = match state.group.as_ref() {
-                Some(GroupState::PreOp(g)) => g.iter(md).nth(idx).ok_or(-2)?.register_read::<u16>(register_address).await,
-                Some(GroupState::SafeOp(g)) => g.iter(md).nth(idx).ok_or(-2)?.register_read::<u16>(register_address).await,
-                Some(GroupState::Op(g)) => g.iter(md).nth(idx).ok_or(-2)?.register_read::<u16>(register_address).await,
-                None => return Err(-1),
-            };
-            
-            match val_res {
-                Ok(val) => Ok(val as i32),
-                Err(e) => {
-                    set_error_ctx(
-                        FfiErrorCode::RegisterError,
-                        format!("Register read failed on slave {} at 0x{:04X}: {:?}", slave_index, register_address, e),
-                        &[
-                            ("op", "register_read"),
-                            ("slave_index", &slave_index.to_string()),
-                            ("register", &format!("0x{:04X}", register_address)),
-                            ("error_detail", &format!("{:?}", e)),
-                            ("suggestion", "Check slave is reachable and register address is valid"),
-                        ],
-                    );
-                    Err(-3)
-                }
-            }
-        });
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        let address = match slave_configured_address(state, slave_index as usize) {
+            Some(a) => a,
+            None => return -2,
+        };
+        let timeout_ms = if timeout_ms == 0 { state.mailbox_timeout_ms } else { timeout_ms as u64 };
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+        let md = &state.maindevice;
 
+        let result = smol::block_on(async {
+            let mut channel = mailbox_channel(address).await;
+            channel.note_counter(counter);
+            let cfg = mailbox_config(md, address).await?;
+            mailbox_send(md, address, &cfg, mailbox_type, counter, payload, deadline).await
+        });
         match result {
-            Ok(v) => v,
-            Err(e) => e,
+            Ok(()) => 0,
+            Err(e @ MailboxError::TooLarge { .. }) => {
+                mailbox_error_ctx("send", slave_index, Some(mailbox_type), &e);
+                -4
+            }
+            Err(e) => {
+                mailbox_error_ctx("send", slave_index, Some(mailbox_type), &e);
+                -3
+            }
         }
     })
 }
 
-/// Write a 16-bit register value to a slave.
-/// 
-/// Common watchdog-related registers:
-/// - 0x0400: Watchdog Divider
-/// - 0x0410: PDI Watchdog timeout  
-/// - 0x0420: SM Watchdog timeout
+/// Receive the next raw mailbox frame from a slave's SM1 into `data_out`.
+///
+/// Frames repeating the mailbox counter of the previous frame are discarded, and
+/// SM1 repeat requests recover lost reads. Mailbox error replies are returned as
+/// frames of type 0x00. `type_out` / `counter_out` may be null.
+/// `max_len` must hold the full SM1 payload area (SM1 length - 6).
+/// Returns payload bytes written, or -1 (not initialized), -2 (slave not found),
+/// -3 (mailbox failure or timeout), -4 (invalid argument or buffer too small).
 #[no_mangle]
-pub extern "C" fn ethercrab_register_write_u16(
+pub extern "C" fn ethercrab_mailbox_receive(
     slave_index: u16,
-    register_address: u16,
-    value: u16,
-) -> i32 {
+    data_out: *mut u8,
+    max_len: usize,
+    type_out: *mut u8,
+    counter_out: *mut u8,
+    timeout_ms: u32,
+) -> c_int {
     with_ffi_guard(-1, || {
-        let result = smol::block_on(async move {
-            let guard = STATE.read();
-            let state = match guard.as_ref() {
-                Some(s) => s,
-                None => return Err(-1),
-            };
+        if data_out.is_null() || max_len == 0 { return -4; }
 
-            let idx = slave_index as usize;
-            let md = &state.maindevice;
-            
-            let write_res = match state.group.as_ref() {
-                Some(GroupState::PreOp(g)) => g.iter(md).nth(idx).ok_or(-2)?.register_write(register_address, value).await,
-                Some(GroupState::SafeOp(g)) => g.iter(md).nth(idx).ok_or(-2)?.register_write(register_address, value).await,
-                Some(GroupState::Op(g)) => g.iter(md).nth(idx).ok_or(-2)?.register_write(register_address, value).await,
-                None => return Err(-1),
-            };
-            
-            match write_res {
-                Ok(_) => Ok(0),
-                Err(e) => {
-                    set_error_ctx(
-                        FfiErrorCode::RegisterError,
-                        format!("Register write failed on slave {} at 0x{:04X} (value={}): {:?}", slave_index, register_address, value, e),
-                        &[
-                            ("op", "register_write"),
-                            ("slave_index", &slave_index.to_string()),
-                            ("register", &format!("0x{:04X}", register_address)),
-                            ("value", &value.to_string()),
-                            ("error_detail", &format!("{:?}", e)),
-                            ("suggestion", "Check slave is reachable and register address is writable"),
-                        ],
-                    );
-                    Err(-3)
-                }
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        let address = match slave_configured_address(state, slave_index as usize) {
+            Some(a) => a,
+            None => return -2,
+        };
+        let timeout_ms = if timeout_ms == 0 { state.mailbox_timeout_ms } else { timeout_ms as u64 };
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+        let md = &state.maindevice;
+
+        let result = smol::block_on(async {
+            let _channel = mailbox_channel(address).await;
+            let cfg = mailbox_config(md, address).await?;
+            // Check before reading: a consumed SM1 frame cannot be put back.
+            let capacity = (cfg.read_len as usize).saturating_sub(MAILBOX_HEADER_LEN);
+            if capacity > max_len {
+                return Ok(Err(capacity));
             }
+            mailbox_receive_raw(md, address, &cfg, deadline).await.map(Ok)
         });
-
         match result {
-            Ok(v) => v,
-            Err(e) => e,
+            Ok(Ok(frame)) => {
+                unsafe {
+                    std::ptr::copy_nonoverlapping(frame.payload.as_ptr(), data_out, frame.payload.len());
+                    if !type_out.is_null() { *type_out = frame.mailbox_type; }
+                    if !counter_out.is_null() { *counter_out = frame.counter; }
+                }
+                frame.payload.len() as c_int
+            }
+            Ok(Err(required)) => {
+                set_error_ctx(
+                    FfiErrorCode::InvalidArgument,
+                    format!("Mailbox receive buffer too small: {} bytes needed, {} provided", required, max_len),
+                    &[("op", "mailbox_receive"), ("required_len", &required.to_string())],
+                );
+                -4
+            }
+            Err(e) => {
+                mailbox_error_ctx("receive", slave_index, None, &e);
+                -3
+            }
         }
     })
 }
@@ -1830,45 +6273,393 @@ pub extern "C" fn ethercrab_register_write_u16(
 #[no_mangle]
 pub extern "C" fn ethercrab_destroy() {
     with_ffi_guard((), || {
-        // 1. Take TX_RX_RESOURCES first
+        // 0. Stop the host watchdog, then send the fail-safe outputs while the TX/RX thread is still running
+        ethercrab_watchdog_stop();
+        {
+            let guard = STATE.read();
+            if let Some(state) = guard.as_ref() {
+                if let Some(GroupState::Op(g)) = &state.group {
+                    send_fail_safe(state, g, FAILSAFE_REASON_DESTROY);
+                }
+            }
+        }
+
+        // 1. Take the state so no new call can reach the master, then let detached users
+        //    (live scan, link monitor) finish while the TX/RX thread still serves their PDUs
+        let state = STATE.write().take();
+        if DETACHED_USERS.load(Ordering::SeqCst) > 0 {
+            SCAN_CANCEL.store(true, Ordering::SeqCst);
+            while DETACHED_USERS.load(Ordering::SeqCst) > 0 {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        }
+
+        // 2. Take TX_RX_RESOURCES
         let resources = TX_RX_RESOURCES.lock().take();
         if let Some(mut res) = resources {
-            // 2. Signal shutdown with SeqCst for full memory ordering guarantee
+            // 3. Signal shutdown with SeqCst for full memory ordering guarantee
             //    This ensures the TX/RX thread sees the signal before we proceed
             res.shutdown_signal.store(true, Ordering::SeqCst);
             
-            // 3. JOIN THE THREAD FIRST - Wait for TX/RX thread to fully stop
+            // 4. JOIN THE THREAD FIRST - Wait for TX/RX thread to fully stop
             //    BEFORE dropping any shared resources. This prevents use-after-free.
             if let Some(handle) = res.thread_handle.take() {
                 let _ = handle.join();
             }
             
-            // 4. NOW clear GLOBAL_DEVICE (drops the MainDevice Arc)
+            // 5. NOW clear GLOBAL_DEVICE (drops the MainDevice Arc)
             //    Thread has stopped, so this is safe
             *GLOBAL_DEVICE.write() = None;
             
-            // 5. Clear STATE (drops the group and maindevice clone)
-            let mut guard = STATE.write();
-            if let Some(state) = guard.take() {
+            // 6. Drop the state taken in step 1 (the group and maindevice clone)
+            if let Some(state) = state {
                 drop(state.group);
                 drop(state.maindevice);
             }
-            drop(guard);
 
-            // 6. Free storage LAST - after everything else is cleaned up
+            // 7. Free storage LAST - after everything else is cleaned up
             if res.storage_ptr != 0 {
                 unsafe {
                     let _ = Box::from_raw(res.storage_ptr as *mut PduStorage<MAX_FRAMES, MAX_PDU_DATA>);
                 }
             }
         } else {
-            // No TX_RX_RESOURCES, but still clear STATE and GLOBAL_DEVICE
+            // No TX_RX_RESOURCES, but still clear GLOBAL_DEVICE and the state
             *GLOBAL_DEVICE.write() = None;
-            *STATE.write() = None;
+            drop(state);
         }
         *LAST_EMERGENCY.lock() = None;
+        MAILBOX_CHANNELS.lock().clear();
+        MAILBOX_RX_COUNTERS.lock().clear();
+    })
+}
+// --- PDO Remapping (ETG1000.6 §5.6.7.4) ---
+
+const SM_RXPDO_ASSIGN: u16 = 0x1C12;
+const SM_TXPDO_ASSIGN: u16 = 0x1C13;
+/// Sub-index 0 of an assignment or mapping object counts the entries in 1..=254;
+/// sub-index 255 is not an entry.
+const PDO_MAX_SUB_ENTRIES: usize = 254;
+
+/// Mapping objects allowed in an assignment object: 0x1600-0x17FF for 0x1C12 (RxPDO),
+/// 0x1A00-0x1BFF for 0x1C13 (TxPDO).
+fn pdo_mapping_range(assign_index: u16) -> Option<std::ops::RangeInclusive<u16>> {
+    match assign_index {
+        SM_RXPDO_ASSIGN => Some(0x1600..=0x17FF),
+        SM_TXPDO_ASSIGN => Some(0x1A00..=0x1BFF),
+        _ => None,
+    }
+}
+
+/// Split a mapping entry (index << 16 | sub index << 8 | bit length).
+fn split_pdo_entry(entry: u32) -> (u16, u8, u8) {
+    ((entry >> 16) as u16, (entry >> 8) as u8, entry as u8)
+}
+
+/// One PDO of a remap request: mapping object and its entries in mapping format.
+#[derive(Clone, Debug, PartialEq)]
+struct PdoRemap {
+    index: u16,
+    entries: Vec<u32>,
+}
+
+/// Build the PDO list from the flat FFI arrays and check indices and counts.
+fn build_pdo_remap(
+    assign_index: u16,
+    pdo_indices: &[u16],
+    entry_counts: &[u8],
+    entries: &[u32],
+) -> Result<Vec<PdoRemap>, String> {
+    let range = pdo_mapping_range(assign_index)
+        .ok_or_else(|| format!("0x{:04X} is not a PDO assignment object (0x1C12/0x1C13)", assign_index))?;
+    if pdo_indices.len() != entry_counts.len() {
+        return Err("pdo_indices and entry_counts differ in length".to_string());
+    }
+    if pdo_indices.len() > PDO_MAX_SUB_ENTRIES {
+        return Err(format!(
+            "{} PDOs for 0x{:04X}, an assignment holds at most {}",
+            pdo_indices.len(), assign_index, PDO_MAX_SUB_ENTRIES
+        ));
+    }
+    let total: usize = entry_counts.iter().map(|&c| c as usize).sum();
+    if total != entries.len() {
+        return Err(format!("entry_counts sum to {} but {} entries were given", total, entries.len()));
+    }
+
+    let mut remap: Vec<PdoRemap> = Vec::with_capacity(pdo_indices.len());
+    let mut offset = 0;
+    for (&index, &count) in pdo_indices.iter().zip(entry_counts) {
+        if !range.contains(&index) {
+            return Err(format!(
+                "PDO 0x{:04X} outside 0x{:04X}-0x{:04X} for 0x{:04X}",
+                index, range.start(), range.end(), assign_index
+            ));
+        }
+        if remap.iter().any(|p| p.index == index) {
+            return Err(format!("PDO 0x{:04X} assigned twice", index));
+        }
+        if count as usize > PDO_MAX_SUB_ENTRIES {
+            return Err(format!("PDO 0x{:04X} has {} entries, at most {}", index, count, PDO_MAX_SUB_ENTRIES));
+        }
+        let pdo_entries = entries[offset..offset + count as usize].to_vec();
+        offset += count as usize;
+        if let Some(&bad) = pdo_entries.iter().find(|&&e| split_pdo_entry(e).2 == 0) {
+            return Err(format!("PDO 0x{:04X} entry 0x{:08X} has a bit length of 0", index, bad));
+        }
+        remap.push(PdoRemap { index, entries: pdo_entries });
+    }
+    Ok(remap)
+}
+
+/// Check a mapped entry against its SDO Information description: the object must be
+/// mappable into this PDO direction and the bit length must match.
+fn check_pdo_entry_mappable(assign_index: u16, entry: u32, desc: &SdoEntryDescription) -> Result<(), String> {
+    let (index, sub_index, bit_len) = split_pdo_entry(entry);
+    let (mappable, direction) = if assign_index == SM_RXPDO_ASSIGN {
+        (desc.access.rx_pdo_mapping(), "RxPDO")
+    } else {
+        (desc.access.tx_pdo_mapping(), "TxPDO")
+    };
+    if !mappable {
+        return Err(format!("0x{:04X}:{} is not {} mappable", index, sub_index, direction));
+    }
+    if desc.bit_len != bit_len as u16 {
+        return Err(format!(
+            "0x{:04X}:{} is {} bits, mapping requests {}",
+            index, sub_index, desc.bit_len, bit_len
+        ));
+    }
+    Ok(())
+}
+
+/// Set the PDO assignment of one sync manager (`assign_index` 0x1C12 or 0x1C13) and the
+/// contents of every assigned mapping object (0x16xx/0x1Axx). PreOp only.
+///
+/// `pdo_indices[i]` gets `entry_counts[i]` consecutive entries from `entries`, each in
+/// mapping format (index << 16 | sub index << 8 | bit length, index 0 = padding).
+/// Entries are validated via SDO Information; only a slave whose SII reports no SDO
+/// Information is remapped without validation, and that skip is recorded in the error ring.
+/// The sequence is
+/// disable assignment, per PDO disable/write/enable mapping, write and enable assignment,
+/// then everything is read back. If a step fails after the assignment was disabled, the
+/// assignment read before the remap is written back (the mapping objects are not).
+///
+/// Returns 0, -1 (not initialized), -2 (slave not found), -3 (not in PreOp, SDO or SDO
+/// Information failure, read-back mismatch), -4 (invalid arguments or entry not mappable).
+#[no_mangle]
+pub extern "C" fn ethercrab_pdo_remap(
+    slave_index: u16,
+    assign_index: u16,
+    pdo_indices: *const u16,
+    entry_counts: *const u8,
+    pdo_count: usize,
+    entries: *const u32,
+    entry_count: usize,
+) -> c_int {
+    with_ffi_guard(-1, || {
+        if (pdo_count > 0 && (pdo_indices.is_null() || entry_counts.is_null()))
+            || (entry_count > 0 && entries.is_null())
+        {
+            return -4;
+        }
+        let (pdo_indices, entry_counts, entries) = unsafe {
+            (
+                if pdo_count > 0 { std::slice::from_raw_parts(pdo_indices, pdo_count) } else { &[][..] },
+                if pdo_count > 0 { std::slice::from_raw_parts(entry_counts, pdo_count) } else { &[][..] },
+                if entry_count > 0 { std::slice::from_raw_parts(entries, entry_count) } else { &[][..] },
+            )
+        };
+        let remap = match build_pdo_remap(assign_index, pdo_indices, entry_counts, entries) {
+            Ok(r) => r,
+            Err(msg) => {
+                set_error_ctx(
+                    FfiErrorCode::InvalidArgument,
+                    msg,
+                    &[("op", "pdo_remap"), ("slave_index", &slave_index.to_string())],
+                );
+                return -4;
+            }
+        };
+
+        let guard = STATE.read();
+        let state = match guard.as_ref() {
+            Some(s) => s,
+            None => return -1,
+        };
+        let group = match state.group.as_ref() {
+            Some(GroupState::PreOp(g)) => g,
+            _ => {
+                set_error_ctx(
+                    FfiErrorCode::InvalidState,
+                    format!("PDO remapping of slave {} requires PreOp", slave_index),
+                    &[("op", "pdo_remap"), ("slave_index", &slave_index.to_string())],
+                );
+                return -3;
+            }
+        };
+        let md = &state.maindevice;
+        let timeout = Duration::from_millis(state.mailbox_timeout_ms);
+        let eeprom_timeout = Duration::from_millis(state.eeprom_timeout_ms);
+
+        let result: Result<(), i32> = smol::block_on(async {
+            let subdevice = group.iter(md).nth(slave_index as usize).ok_or(-2)?;
+            let address = subdevice.configured_address();
+            let ctx = |index: u16, sub_index: u8| {
+                [
+                    ("op", "pdo_remap".to_string()),
+                    ("slave_index", slave_index.to_string()),
+                    ("sdo_index", format!("0x{:04X}", index)),
+                    ("sdo_sub_index", sub_index.to_string()),
+                ]
+            };
+            let fail = |code: FfiErrorCode, msg: String, index: u16, sub_index: u8| {
+                let ctx = ctx(index, sub_index);
+                let pairs: Vec<(&str, &str)> = ctx.iter().map(|(k, v)| (*k, v.as_str())).collect();
+                set_error_ctx(code, msg, &pairs);
+            };
+            let sdo_err = |step: &'static str, index: u16, sub_index: u8| {
+                move |e: MailboxError| {
+                    fail(
+                        FfiErrorCode::SdoError,
+                        format!("PDO remap of slave {} failed to {} (0x{:04X}:{}): {}", slave_index, step, index, sub_index, e),
+                        index,
+                        sub_index,
+                    );
+                    -3
+                }
+            };
+
+            // Validate against the object dictionary before touching the slave. Only the SII
+            // can excuse a slave from it; an unreadable SII does not.
+            let sdo_info = sii_read_image(md, address, eeprom_timeout)
+                .await
+                .map_or(true, |image| parse_sii(&image).sdo_info_supported());
+            if !sdo_info {
+                fail(
+                    FfiErrorCode::SdoError,
+                    format!("PDO remap of slave {} is not validated: its SII reports no SDO Information", slave_index),
+                    assign_index,
+                    0,
+                );
+            }
+            if sdo_info {
+                for &entry in remap.iter().flat_map(|p| p.entries.iter()) {
+                    let (index, sub_index, _) = split_pdo_entry(entry);
+                    if index == 0 {
+                        continue;
+                    }
+                    match sdo_info_entry_description(md, address, index, sub_index, timeout).await {
+                        Ok(desc) => {
+                            if let Err(msg) = check_pdo_entry_mappable(assign_index, entry, &desc) {
+                                fail(FfiErrorCode::InvalidArgument, msg, index, sub_index);
+                                return Err(-4);
+                            }
+                        }
+                        Err(MailboxError::SdoInfo(abort)) => {
+                            fail(
+                                FfiErrorCode::InvalidArgument,
+                                format!("0x{:04X}:{} rejected by the object dictionary (abort 0x{:08X})", index, sub_index, abort),
+                                index,
+                                sub_index,
+                            );
+                            return Err(-4);
+                        }
+                        Err(e) => {
+                            fail(
+                                FfiErrorCode::SdoError,
+                                format!("PDO remap of slave {}: validating 0x{:04X}:{} failed: {}", slave_index, index, sub_index, e),
+                                index,
+                                sub_index,
+                            );
+                            return Err(-3);
+                        }
+                    }
+                }
+            }
+
+            let write = |index: u16, sub_index: u8, data: Vec<u8>| async move {
+                coe_sdo_download(md, address, index, sub_index, &data, timeout).await
+            };
+            let read = |index: u16, sub_index: u8| coe_read_uint(md, address, index, sub_index, timeout);
+
+            // Keep the current assignment so a failed remap does not leave the SM unassigned
+            let original_count =
+                read(assign_index, 0).await.map_err(sdo_err("read assignment", assign_index, 0))? as u8;
+            let mut original = Vec::with_capacity(original_count as usize);
+            for sub_index in 1..=original_count {
+                original.push(read(assign_index, sub_index).await.map_err(sdo_err("read assignment", assign_index, sub_index))? as u16);
+            }
+
+            let result = async {
+                write(assign_index, 0, vec![0]).await.map_err(sdo_err("disable assignment", assign_index, 0))?;
+                for pdo in &remap {
+                    write(pdo.index, 0, vec![0]).await.map_err(sdo_err("disable mapping", pdo.index, 0))?;
+                    for (i, &entry) in pdo.entries.iter().enumerate() {
+                        let sub_index = i as u8 + 1;
+                        write(pdo.index, sub_index, entry.to_le_bytes().to_vec()).await.map_err(sdo_err("write mapping", pdo.index, sub_index))?;
+                    }
+                    write(pdo.index, 0, vec![pdo.entries.len() as u8]).await.map_err(sdo_err("enable mapping", pdo.index, 0))?;
+                }
+                for (i, pdo) in remap.iter().enumerate() {
+                    let sub_index = i as u8 + 1;
+                    write(assign_index, sub_index, pdo.index.to_le_bytes().to_vec()).await.map_err(sdo_err("write assignment", assign_index, sub_index))?;
+                }
+                write(assign_index, 0, vec![remap.len() as u8]).await.map_err(sdo_err("enable assignment", assign_index, 0))?;
+
+                // Read back to confirm before SafeOp
+                let mut read_back = Vec::with_capacity(remap.len());
+                let count = read(assign_index, 0).await.map_err(sdo_err("read back assignment", assign_index, 0))? as u8;
+                for sub_index in 1..=count {
+                    let index = read(assign_index, sub_index).await.map_err(sdo_err("read back assignment", assign_index, sub_index))? as u16;
+                    let n = read(index, 0).await.map_err(sdo_err("read back mapping", index, 0))? as u8;
+                    let mut pdo_entries = Vec::with_capacity(n as usize);
+                    for entry_sub in 1..=n {
+                        pdo_entries.push(read(index, entry_sub).await.map_err(sdo_err("read back mapping", index, entry_sub))?);
+                    }
+                    read_back.push(PdoRemap { index, entries: pdo_entries });
+                }
+                if read_back != remap {
+                    fail(
+                        FfiErrorCode::SdoError,
+                        format!("PDO remap of slave {}: 0x{:04X} read back as {:04X?}", slave_index, assign_index, read_back),
+                        assign_index,
+                        0,
+                    );
+                    return Err(-3);
+                }
+                Ok(())
+            }
+            .await;
+
+            if result.is_err() {
+                // Best effort: the error of the failed step stays the reported one
+                let mut restore = write(assign_index, 0, vec![0]).await;
+                for (i, &index) in original.iter().enumerate() {
+                    if restore.is_ok() {
+                        restore = write(assign_index, i as u8 + 1, index.to_le_bytes().to_vec()).await;
+                    }
+                }
+                if restore.is_ok() {
+                    restore = write(assign_index, 0, vec![original_count]).await;
+                }
+                if let Err(e) = restore {
+                    let mut ring = ERROR_RING.lock();
+                    if let Some(mut entry) = ring.latest().cloned() {
+                        entry.message = format!("{}; restoring 0x{:04X} to {:04X?} also failed: {}", entry.message, assign_index, original, e);
+                        ring.push(entry);
+                    }
+                }
+            }
+            result
+        });
+
+        match result {
+            Ok(()) => 0,
+            Err(e) => e,
+        }
     })
 }
+
 // --- Discovery FFI ---
 
 #[repr(C)]
@@ -1880,9 +6671,15 @@ pub struct FfiSlaveInfo {
     pub alias_address: u16,
     pub port_count: u8,
     pub _padding: [u8; 1],
-    pub mailbox_protocols: u16, // Bitmask: 0x01=CoE, 0x02=FoE, 0x04=EoE, 0x08=SoE
+    pub mailbox_protocols: u16, // Bitmask: 0x01=CoE, 0x02=FoE, 0x04=EoE, 0x08=SoE, 0x10=AoE, 0x20=VoE
     pub dc_supported: u8,       // 0=false, 1=true
     pub _padding2: [u8; 1],     // Padding for alignment
+    pub dc_64bit: u8,           // 0=32-bit system time, 1=64-bit (0x0008 bit 3)
+    pub fmmu_count: u8,         // FMMUs supported (0x0004)
+    pub sm_count: u8,           // Sync managers supported (0x0005)
+    pub esc_type: u8,           // ESC type (0x0000)
+    pub port_types: [u8; 4],    // Per port (0x0007): 0=not implemented, 1=not configured, 2=EBUS, 3=MII/RMII
+    pub esc_features: u16,      // Raw ESC features register (0x0008)
 }
 
 #[repr(C)]
@@ -1906,20 +6703,89 @@ pub struct FfiPdoEntryInfo {
 
 pub struct DiscoveredEntry {
     pub info: FfiPdoEntryInfo,
+    /// Full name; `info.name` is truncated to fit the FFI struct.
+    pub name: String,
+}
+
+impl DiscoveredEntry {
+    pub fn new(index: u16, sub_index: u8, bit_len: u8, data_type: u16, name: String) -> Self {
+        let mut fixed_name = [0u8; 64];
+        string_to_fixed_bytes(&name, &mut fixed_name);
+        Self {
+            info: FfiPdoEntryInfo { index, sub_index, bit_len, data_type, name: fixed_name },
+            name,
+        }
+    }
 }
 
 pub struct DiscoveredPdo {
     pub info: FfiPdoInfo,
     pub entries: Vec<DiscoveredEntry>,
+    /// Full name; `info.name` is truncated to fit the FFI struct.
+    pub name: String,
+}
+
+impl DiscoveredPdo {
+    pub fn new(index: u16, sync_manager: u8, name: String, entries: Vec<DiscoveredEntry>) -> Self {
+        let mut fixed_name = [0u8; 64];
+        string_to_fixed_bytes(&name, &mut fixed_name);
+        Self {
+            info: FfiPdoInfo { index, num_entries: entries.len() as u8, sync_manager, name: fixed_name },
+            entries,
+            name,
+        }
+    }
+}
+
+/// Physical topology of one slave: port states from DL status (0x0110), the parent
+/// derived from frame processing order, and DC receive times where available.
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct FfiSlaveTopology {
+    pub dl_status: u16,                 // Raw DL status register (0x0110)
+    pub parent_index: i16,              // Parent slave position, -1 = connected to the master
+    pub parent_port: u8,                // Port on the parent (0-3), 0xFF for the first slave
+    pub entry_port: u8,                 // Port facing the master (normally 0)
+    pub link: [u8; 4],                  // Physical link per port
+    pub loop_closed: [u8; 4],           // Loop closed per port
+    pub communication: [u8; 4],         // Communication established per port
+    pub dc_valid: u8,                   // 1 = receive times/delays below are valid
+    pub _padding: [u8; 1],
+    pub port_receive_time_ns: [u32; 4], // DC receive time latched per port (0x0900-0x090F)
+    pub port_delay_ns: [u32; 4],        // Round trip through the segment behind each port
+}
+
+/// Object dictionary entry found by walking the OD (`SCAN_FLAG_WALK_OD`).
+pub struct DiscoveredObject {
+    pub index: u16,
+    pub name: String,
 }
 
 pub struct DiscoveredSlave {
     pub info: FfiSlaveInfo,
+    /// Full name; `info.name` is truncated to fit the FFI struct.
+    pub name: String,
+    pub topology: FfiSlaveTopology,
     pub pdos: Vec<DiscoveredPdo>,
+    /// Empty unless the object dictionary was walked.
+    pub objects: Vec<DiscoveredObject>,
+    pub(crate) sii: Option<SiiInfo>,
 }
 
 pub struct ScanContext {
     pub slaves: Vec<DiscoveredSlave>,
+    /// Completion time of the scan, milliseconds since the Unix epoch.
+    pub scan_time_ms: u64,
+}
+
+impl ScanContext {
+    pub fn new(slaves: Vec<DiscoveredSlave>) -> Self {
+        let scan_time_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        Self { slaves, scan_time_ms }
+    }
 }
 
 fn string_to_fixed_bytes(s: &str, out: &mut [u8; 64]) {
@@ -1929,208 +6795,647 @@ fn string_to_fixed_bytes(s: &str, out: &mut [u8; 64]) {
     out[len] = 0;
 }
 
-// Static PDU storage for the scanner (separate from the main master)
-// We need this because PduStorage::new() is const, but we want a fresh one for the scanner
-// However, PduStorage cannot be easily created on stack and passed around if we want to be static?
-// Actually, we can create it on the stack inside the scan function if we don't need it to outlive the scan.
-// But PduStorage needs to be split, and the split parts live in the future.
-// We can allocate it on the heap? `Box::new(PduStorage::new())`? PduStorage might be large.
-// ethercrab::PduStorage is generic over N and size.
-// Let's just use a static Mutex for the scan lock to prevent concurrent scans if we use a static storage?
-// Or better: Allocate it on the heap.
-// Note: `PduStorage` creates a `PduLoop` which has a lifetime.
-// The `MainDevice` takes the `PduLoop`.
-// We'll try to do it all inside the async block.
+// Static PDU storage for the scanner (separate from the main master)
+// We need this because PduStorage::new() is const, but we want a fresh one for the scanner
+// However, PduStorage cannot be easily created on stack and passed around if we want to be static?
+// Actually, we can create it on the stack inside the scan function if we don't need it to outlive the scan.
+// But PduStorage needs to be split, and the split parts live in the future.
+// We can allocate it on the heap? `Box::new(PduStorage::new())`? PduStorage might be large.
+// ethercrab::PduStorage is generic over N and size.
+// Let's just use a static Mutex for the scan lock to prevent concurrent scans if we use a static storage?
+// Or better: Allocate it on the heap.
+// Note: `PduStorage` creates a `PduLoop` which has a lifetime.
+// The `MainDevice` takes the `PduLoop`.
+// We'll try to do it all inside the async block.
+
+const MAILBOX_PROTO_COE: u16 = 0x01;
+const MAILBOX_PROTO_FOE: u16 = 0x02;
+const MAILBOX_PROTO_EOE: u16 = 0x04;
+const MAILBOX_PROTO_SOE: u16 = 0x08;
+const MAILBOX_PROTO_AOE: u16 = 0x10;
+const MAILBOX_PROTO_VOE: u16 = 0x20;
+
+const ESC_FEATURE_DC: u16 = 0x0004;
+const ESC_FEATURE_DC_64BIT: u16 = 0x0008;
+
+/// ESC information registers 0x0000-0x0009 (ETG1000.4 Table 31).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct EscInfo {
+    esc_type: u8,
+    fmmu_count: u8,
+    sm_count: u8,
+    port_types: [u8; 4],
+    features: u16,
+}
+
+impl EscInfo {
+    fn from_registers(raw: &[u8]) -> Self {
+        let descriptor = raw.get(7).copied().unwrap_or(0);
+        Self {
+            esc_type: raw.first().copied().unwrap_or(0),
+            fmmu_count: raw.get(4).copied().unwrap_or(0),
+            sm_count: raw.get(5).copied().unwrap_or(0),
+            port_types: [0, 1, 2, 3].map(|port| (descriptor >> (port * 2)) & 0x03),
+            features: le_u16(raw, 8),
+        }
+    }
+
+    fn port_count(&self) -> u8 {
+        self.port_types.iter().filter(|&&t| t != 0).count() as u8
+    }
+
+    fn dc_supported(&self) -> bool {
+        (self.features & ESC_FEATURE_DC) != 0
+    }
+
+    fn dc_64bit(&self) -> bool {
+        (self.features & ESC_FEATURE_DC_64BIT) != 0
+    }
+}
+
+/// Mailbox protocols in the `FfiSlaveInfo` bitmask, from the SII header (word 0x1C)
+/// and the general category protocol details.
+fn sii_mailbox_protocols(sii: &SiiInfo) -> u16 {
+    // SII word 0x1C: 0x01=AoE, 0x02=EoE, 0x04=CoE, 0x08=FoE, 0x10=SoE, 0x20=VoE
+    let header = sii.mailbox_protocols;
+    let mut protocols = 0u16;
+    for (sii_bit, flag) in [
+        (0x01, MAILBOX_PROTO_AOE),
+        (0x02, MAILBOX_PROTO_EOE),
+        (0x04, MAILBOX_PROTO_COE),
+        (0x08, MAILBOX_PROTO_FOE),
+        (0x10, MAILBOX_PROTO_SOE),
+        (0x20, MAILBOX_PROTO_VOE),
+    ] {
+        if (header & sii_bit) != 0 {
+            protocols |= flag;
+        }
+    }
+    if let Some(ref general) = sii.general {
+        if general.coe_details != 0 {
+            protocols |= MAILBOX_PROTO_COE;
+        }
+        if general.foe_details != 0 {
+            protocols |= MAILBOX_PROTO_FOE;
+        }
+        if general.eoe_details != 0 {
+            protocols |= MAILBOX_PROTO_EOE;
+        }
+        if general.soe_channels != 0 {
+            protocols |= MAILBOX_PROTO_SOE;
+        }
+    }
+    protocols
+}
+
+/// Order in which an ESC forwards a frame through its ports (ETG1000.4 §3.2).
+const PORT_PROCESSING_ORDER: [usize; 4] = [0, 3, 1, 2];
+const DL_STATUS_REGISTER: u16 = 0x0110;
+const DC_RECEIVE_TIME_PORT0: u16 = 0x0900;
+
+/// Per-port (link, loop closed, communication established) from DL status 0x0110.
+fn dl_status_ports(dl_status: u16) -> ([bool; 4], [bool; 4], [bool; 4]) {
+    let link = [0, 1, 2, 3].map(|p| (dl_status & (1 << (4 + p))) != 0);
+    let loop_closed = [0, 1, 2, 3].map(|p| (dl_status & (1 << (8 + 2 * p))) != 0);
+    let communication = [0, 1, 2, 3].map(|p| (dl_status & (1 << (9 + 2 * p))) != 0);
+    (link, loop_closed, communication)
+}
+
+/// Entry port and downstream ports (in processing order) of a slave's active ports.
+fn split_ports(active: &[bool; 4]) -> (Option<u8>, Vec<u8>) {
+    let mut ports = PORT_PROCESSING_ORDER.iter().filter(|&&p| active[p]).map(|&p| p as u8);
+    let entry = ports.next();
+    (entry, ports.collect())
+}
+
+/// Parent (slave position, parent port) for each slave, given the ports with
+/// communication established. Slaves are in frame propagation order, so each slave
+/// hangs off the most recent slave that still has an unvisited downstream port.
+fn compute_parents(active_ports: &[[bool; 4]]) -> Vec<Option<(usize, u8)>> {
+    let mut parents = Vec::with_capacity(active_ports.len());
+    // (slave position, downstream ports not yet assigned a child)
+    let mut open: Vec<(usize, std::collections::VecDeque<u8>)> = Vec::new();
+    for (idx, active) in active_ports.iter().enumerate() {
+        while open.last().is_some_and(|(_, ports)| ports.is_empty()) {
+            open.pop();
+        }
+        let parent = open.last_mut().and_then(|(parent, ports)| ports.pop_front().map(|port| (*parent, port)));
+        parents.push(parent);
+        let (_, downstream) = split_ports(active);
+        open.push((idx, downstream.into_iter().collect()));
+    }
+    parents
+}
+
+/// Round trip time through the segment behind each downstream port: the receive time
+/// on that port minus the receive time of the port the frame left from.
+fn port_delays(receive_times: &[u32; 4], active: &[bool; 4]) -> [u32; 4] {
+    let mut delays = [0u32; 4];
+    let (entry, downstream) = split_ports(active);
+    if let Some(entry) = entry {
+        let mut previous = receive_times[entry as usize];
+        for port in downstream {
+            let time = receive_times[port as usize];
+            delays[port as usize] = time.wrapping_sub(previous);
+            previous = time;
+        }
+    }
+    delays
+}
+
+/// Read only the SII PDO categories, no CoE (0x1C12/0x1C13, SDO Information).
+pub const SCAN_FLAG_SII_ONLY: u32 = 0x01;
+/// Walk the whole object dictionary via SDO Information and record every object name.
+pub const SCAN_FLAG_WALK_OD: u32 = 0x02;
+
+const SCAN_DEFAULT_EEPROM_TIMEOUT_MS: u32 = 100;
+const SCAN_DEFAULT_MAILBOX_TIMEOUT_MS: u32 = 1000;
+
+/// Options for `ethercrab_scan_new_with_options` and `ethercrab_scan_live`.
+/// A zeroed struct (or a null pointer) selects the defaults.
+#[repr(C)]
+#[derive(Clone, Copy, Default, Debug)]
+pub struct FfiScanOptions {
+    pub pdu_timeout_ms: u32,     // 0 = ethercrab default
+    pub mailbox_timeout_ms: u32, // Per SDO Information request, 0 = 1000 ms
+    pub eeprom_timeout_ms: u32,  // Per SII image read, 0 = 100 ms
+    pub pdu_retries: u32,        // PDU retries on timeout, 0 = none
+    pub max_slaves: u32,         // Scan only the first N slaves, 0 = all
+    pub flags: u32,              // SCAN_FLAG_*
+}
+
+impl FfiScanOptions {
+    /// Copy the options behind `ptr`, or the defaults for null.
+    fn from_ptr(ptr: *const FfiScanOptions) -> Self {
+        if ptr.is_null() { Self::default() } else { unsafe { *ptr } }
+    }
+
+    fn mailbox_timeout(&self) -> Duration {
+        let ms = if self.mailbox_timeout_ms == 0 { SCAN_DEFAULT_MAILBOX_TIMEOUT_MS } else { self.mailbox_timeout_ms };
+        Duration::from_millis(ms as u64)
+    }
+
+    fn eeprom_timeout(&self) -> Duration {
+        let ms = if self.eeprom_timeout_ms == 0 { SCAN_DEFAULT_EEPROM_TIMEOUT_MS } else { self.eeprom_timeout_ms };
+        Duration::from_millis(ms as u64)
+    }
+
+    fn timeouts(&self) -> Timeouts {
+        let defaults = Timeouts::default();
+        Timeouts {
+            pdu: if self.pdu_timeout_ms == 0 { defaults.pdu } else { Duration::from_millis(self.pdu_timeout_ms as u64) },
+            mailbox_response: self.mailbox_timeout(),
+            eeprom: self.eeprom_timeout(),
+            ..defaults
+        }
+    }
+
+    fn main_device_config(&self) -> MainDeviceConfig {
+        MainDeviceConfig {
+            dc_static_sync_iterations: 0, // Disable DC
+            retry_behaviour: if self.pdu_retries == 0 {
+                ethercrab::RetryBehaviour::None
+            } else {
+                ethercrab::RetryBehaviour::Count(self.pdu_retries as usize)
+            },
+            ..MainDeviceConfig::default()
+        }
+    }
+
+    fn use_coe(&self) -> bool {
+        (self.flags & SCAN_FLAG_SII_ONLY) == 0
+    }
+
+    fn walk_object_dictionary(&self) -> bool {
+        self.use_coe() && (self.flags & SCAN_FLAG_WALK_OD) != 0
+    }
+
+    fn max_slaves(&self) -> usize {
+        if self.max_slaves == 0 { usize::MAX } else { self.max_slaves as usize }
+    }
+}
+
+// Progress of the running scan, polled with `ethercrab_scan_get_progress`.
+static SCAN_ACTIVE: AtomicBool = AtomicBool::new(false);
+static SCAN_CANCEL: AtomicBool = AtomicBool::new(false);
+static SCAN_SLAVES_DONE: AtomicU32 = AtomicU32::new(0);
+static SCAN_SLAVES_TOTAL: AtomicU32 = AtomicU32::new(0);
+
+/// Marks a scan as running for its lifetime and resets the progress counters.
+struct ScanProgressGuard;
+
+impl ScanProgressGuard {
+    fn start() -> Self {
+        SCAN_CANCEL.store(false, Ordering::SeqCst);
+        SCAN_SLAVES_DONE.store(0, Ordering::Relaxed);
+        SCAN_SLAVES_TOTAL.store(0, Ordering::Relaxed);
+        SCAN_ACTIVE.store(true, Ordering::SeqCst);
+        ScanProgressGuard
+    }
+}
+
+impl Drop for ScanProgressGuard {
+    fn drop(&mut self) {
+        SCAN_ACTIVE.store(false, Ordering::SeqCst);
+        SCAN_CANCEL.store(false, Ordering::SeqCst);
+    }
+}
+
+fn scan_cancelled() -> bool {
+    SCAN_CANCEL.load(Ordering::SeqCst)
+}
+
+/// Data type guess from the bit length. Only used by the scan when neither SDO
+/// Information nor the SII describes an entry.
+fn guess_data_type(bit_len: u8) -> u16 {
+    match bit_len {
+        1 => 0x0001,  // BOOL
+        8 => 0x0005,  // UINT8
+        16 => 0x0006, // UINT16
+        32 => 0x0007, // UINT32
+        64 => 0x0015, // INT64/UINT64
+        _ => 0x0000,  // Unknown
+    }
+}
+
+impl SiiInfo {
+    fn find_pdo(&self, index: u16) -> Option<&SiiPdo> {
+        self.tx_pdos.iter().chain(self.rx_pdos.iter()).find(|p| p.index == index)
+    }
+
+    fn find_pdo_entry(&self, index: u16, sub_index: u8) -> Option<&SiiPdoEntry> {
+        self.tx_pdos
+            .iter()
+            .chain(self.rx_pdos.iter())
+            .flat_map(|p| p.entries.iter())
+            .find(|e| e.index == index && e.sub_index == sub_index)
+    }
+
+    fn sdo_info_supported(&self) -> bool {
+        self.general.as_ref().is_some_and(|g| (g.coe_details & COE_DETAILS_SDO_INFO) != 0)
+    }
+}
+
+/// Resolve a mapped entry's name and data type: SDO Information first, then the SII
+/// PDO categories, then a placeholder name. The data type is 0 if neither describes it.
+/// `entry` is (index, sub index, bit length) of the mapping.
+/// `sdo_info` is cleared after a timeout so a slow slave does not stall the scan.
+async fn resolve_pdo_entry(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    sii: Option<&SiiInfo>,
+    sdo_info: &mut bool,
+    entry: (u16, u8, u8),
+    timeout: Duration,
+) -> (String, u16) {
+    let (index, sub_index, bit_len) = entry;
+    let mut name = String::new();
+    let mut data_type = 0u16;
+
+    // Index 0 entries are gaps (padding) and have no description
+    if index != 0 && *sdo_info {
+        match sdo_info_entry_description(md, configured_address, index, sub_index, timeout).await {
+            Ok(desc) => {
+                name = desc.name;
+                // A data type only describes the mapping if the whole object is mapped
+                if desc.bit_len == bit_len as u16 {
+                    data_type = desc.data_type;
+                }
+            }
+            Err(MailboxError::Timeout) => *sdo_info = false,
+            Err(_) => {}
+        }
+    }
+    if index != 0 && (name.is_empty() || data_type == 0) {
+        if let Some(entry) = sii.and_then(|s| s.find_pdo_entry(index, sub_index)) {
+            if name.is_empty() {
+                name = entry.name.clone();
+            }
+            if data_type == 0 {
+                data_type = entry.data_type as u16;
+            }
+        }
+    }
+
+    if name.is_empty() {
+        name = format!("Entry_0x{:04x}_{:02x}", index, sub_index);
+    }
+    (name, data_type)
+}
+
+/// Active PDO assignment from the CoE assignment objects 0x1C12/0x1C13, each mapped entry
+/// described by `resolve_pdo_entry`. Empty when the slave has no (or only empty) assignment
+/// objects; not all slaves have them (e.g. EK1100 couplers), so missing objects are skipped.
+async fn coe_assigned_pdos(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    sii: Option<&SiiInfo>,
+    sdo_info: &mut bool,
+    timeout: Duration,
+) -> Vec<DiscoveredPdo> {
+    let mut pdos = Vec::new();
+    // SM2 (Outputs/RxPDO) and SM3 (Inputs/TxPDO)
+    for (assign_index, sync_manager) in [(SM_RXPDO_ASSIGN, 2u8), (SM_TXPDO_ASSIGN, 3u8)] {
+        let Ok(count) = coe_read_uint(md, configured_address, assign_index, 0, timeout).await else { continue };
+        for i in 1..=count as u8 {
+            let Ok(pdo_index) = coe_read_uint(md, configured_address, assign_index, i, timeout).await else { continue };
+            let pdo_index = pdo_index as u16;
+            let pdo_name = resolve_pdo_name(md, configured_address, sii, sdo_info, pdo_index, timeout).await;
+            let mut entries = Vec::new();
+            if let Ok(entry_count) = coe_read_uint(md, configured_address, pdo_index, 0, timeout).await {
+                for j in 1..=entry_count as u8 {
+                    if let Ok(mapping) = coe_read_uint(md, configured_address, pdo_index, j, timeout).await {
+                        let entry = split_pdo_entry(mapping);
+                        let (name, data_type) = resolve_pdo_entry(md, configured_address, sii, sdo_info, entry, timeout).await;
+                        entries.push(DiscoveredEntry::new(entry.0, entry.1, entry.2, data_type, name));
+                    }
+                }
+            }
+            pdos.push(DiscoveredPdo::new(pdo_index, sync_manager, pdo_name, entries));
+        }
+    }
+    pdos
+}
+
+/// PDO name from the SII TxPDO/RxPDO categories, else from SDO Information.
+async fn resolve_pdo_name(
+    md: &MainDevice<'_>,
+    configured_address: u16,
+    sii: Option<&SiiInfo>,
+    sdo_info: &mut bool,
+    pdo_index: u16,
+    timeout: Duration,
+) -> String {
+    if let Some(pdo) = sii.and_then(|s| s.find_pdo(pdo_index)).filter(|p| !p.name.is_empty()) {
+        return pdo.name.clone();
+    }
+    if *sdo_info {
+        match sdo_info_object_name(md, configured_address, pdo_index, timeout).await {
+            Ok(name) => return name,
+            Err(MailboxError::Timeout) => *sdo_info = false,
+            Err(_) => {}
+        }
+    }
+    String::new()
+}
+
+/// PDOs described by the SII categories, for slaves without CoE PDO assignment objects.
+/// PDOs not assigned to a sync manager (0xFF) are alternatives and are skipped.
+fn sii_discovered_pdos(sii: &SiiInfo) -> Vec<DiscoveredPdo> {
+    let assigned = |pdos: &[SiiPdo], sync_manager: u8| -> Vec<DiscoveredPdo> {
+        pdos.iter()
+            .filter(|p| p.sync_manager != 0xFF)
+            .map(|p| {
+                let entries: Vec<DiscoveredEntry> = p
+                    .entries
+                    .iter()
+                    .map(|e| {
+                        let name = if e.name.is_empty() {
+                            format!("Entry_0x{:04x}_{:02x}", e.index, e.sub_index)
+                        } else {
+                            e.name.clone()
+                        };
+                        DiscoveredEntry::new(e.index, e.sub_index, e.bit_len, e.data_type as u16, name)
+                    })
+                    .collect();
+                DiscoveredPdo::new(p.index, sync_manager, p.name.clone(), entries)
+            })
+            .collect()
+    };
+    // TxPDO = inputs = SM3, RxPDO = outputs = SM2 (canonical numbers)
+    let mut pdos = assigned(&sii.tx_pdos, 3);
+    pdos.extend(assigned(&sii.rx_pdos, 2));
+    pdos
+}
 
-async fn perform_scan(maindevice: &MainDevice<'_>) -> Result<Vec<DiscoveredSlave>, i32> {
+async fn perform_scan(maindevice: &MainDevice<'_>, options: &FfiScanOptions) -> Result<Vec<DiscoveredSlave>, i32> {
     let group = maindevice.init_single_group::<MAX_SUBDEVICES, MAX_PDI>(ethercat_now)
         .await.map_err(|e| {
             set_error(format!("Scan init_single_group failed: {:?}", e));
             -2
         })?;
 
+    let group = GroupState::PreOp(group);
+    let targets = scan_targets(maindevice, &group, options.max_slaves());
+    scan_group(maindevice, &targets, Some(&group), options).await
+}
+
+/// What ethercrab cached about a subdevice during init, copied out of the group so
+/// a live scan does not have to hold the master state while it runs.
+struct ScanTarget {
+    identity: SubDeviceIdentity,
+    name: String,
+    configured_address: u16,
+    alias_address: u16,
+}
+
+fn scan_targets(maindevice: &MainDevice<'_>, group: &GroupState, max_slaves: usize) -> Vec<ScanTarget> {
+    macro_rules! targets {
+        ($g:expr) => {
+            $g.iter(maindevice)
+                .take(max_slaves)
+                .map(|s| ScanTarget {
+                    identity: s.identity(),
+                    name: s.name().to_string(),
+                    configured_address: s.configured_address(),
+                    alias_address: s.alias_address(),
+                })
+                .collect()
+        };
+    }
+    match group {
+        GroupState::PreOp(g) => targets!(g),
+        GroupState::SafeOp(g) => targets!(g),
+        GroupState::Op(g) => targets!(g),
+    }
+}
+
+/// Per-PDO bit lengths from the EEPROM, for slaves with neither CoE assignment
+/// objects nor SII PDO categories.
+async fn eeprom_discovered_pdos(maindevice: &MainDevice<'_>, group: &GroupState, position: usize) -> Vec<DiscoveredPdo> {
+    let subdevice = match group {
+        GroupState::PreOp(g) => g.iter(maindevice).nth(position),
+        GroupState::SafeOp(g) => g.iter(maindevice).nth(position),
+        GroupState::Op(g) => g.iter(maindevice).nth(position),
+    };
+    let Some(subdevice) = subdevice else {
+        return Vec::new();
+    };
+    let _lock = SII_LOCK.lock().await;
+    let mut pdos = Vec::new();
+    // Read TxPDO (Inputs from device perspective = SM3)
+    if let Ok(eeprom_tx_pdos) = subdevice.eeprom().maindevice_read_pdos().await {
+        for (pdo_idx, pdo) in eeprom_tx_pdos.iter().enumerate() {
+            // EEPROM PDOs provide total bit_len but not individual entry details
+            // Create a single entry representing the whole PDO
+            let entry_name = format!("Input_PDO_{}", pdo_idx);
+            
+            let data_type = match pdo.bit_len {
+                1 => 0x0001,  // BOOL
+                8 => 0x0005,  // UINT8
+                16 => 0x0006, // UINT16
+                32 => 0x0007, // UINT32
+                _ if pdo.bit_len <= 8 => 0x0005,   // UINT8
+                _ if pdo.bit_len <= 16 => 0x0006,  // UINT16
+                _ if pdo.bit_len <= 32 => 0x0007,  // UINT32
+                _ => 0x0000,
+            };
+            
+            let entries = vec![DiscoveredEntry::new(
+                0x6000 + pdo_idx as u16,  // Synthetic index
+                0,
+                pdo.bit_len as u8,
+                data_type,
+                entry_name,
+            )];
+            
+            pdos.push(DiscoveredPdo::new(
+                0x1A00 + pdo_idx as u16,  // Synthetic TxPDO index
+                3,  // TxPDO = inputs = SM3 (canonical number)
+                String::new(),
+                entries,
+            ));
+        }
+    }
+    
+    // Read RxPDO (Outputs from device perspective = SM2)
+    if let Ok(eeprom_rx_pdos) = subdevice.eeprom().maindevice_write_pdos().await {
+        for (pdo_idx, pdo) in eeprom_rx_pdos.iter().enumerate() {
+            let entry_name = format!("Output_PDO_{}", pdo_idx);
+            
+            let data_type = match pdo.bit_len {
+                1 => 0x0001,  // BOOL
+                8 => 0x0005,  // UINT8
+                16 => 0x0006, // UINT16
+                32 => 0x0007, // UINT32
+                _ if pdo.bit_len <= 8 => 0x0005,   // UINT8
+                _ if pdo.bit_len <= 16 => 0x0006,  // UINT16
+                _ if pdo.bit_len <= 32 => 0x0007,  // UINT32
+                _ => 0x0000,
+            };
+            
+            let entries = vec![DiscoveredEntry::new(
+                0x7000 + pdo_idx as u16,  // Synthetic index
+                0,
+                pdo.bit_len as u8,
+                data_type,
+                entry_name,
+            )];
+            
+            pdos.push(DiscoveredPdo::new(
+                0x1600 + pdo_idx as u16,  // Synthetic RxPDO index
+                2,  // RxPDO = outputs = SM2 (canonical number)
+                String::new(),
+                entries,
+            ));
+        }
+    }
+    pdos
+}
+
+/// Reads identities, SII, ESC info, topology and PDO assignments of an already
+/// initialized group. Only acyclic FPRD/SDO/SII traffic is used, so this is safe
+/// to run in any group state without touching the process data.
+///
+/// `group` is `None` for a live scan of the running master: the SII is read under
+/// `SII_LOCK` as usual, but there is no EEPROM PDO fallback through the group and the
+/// DC receive times are not re-latched, so the master's DC setup is left alone.
+/// Returns -3 if the scan was cancelled with `ethercrab_scan_cancel`.
+async fn scan_group(
+    maindevice: &MainDevice<'_>,
+    targets: &[ScanTarget],
+    group: Option<&GroupState>,
+    options: &FfiScanOptions,
+) -> Result<Vec<DiscoveredSlave>, i32> {
+    let live = group.is_none();
+    SCAN_SLAVES_TOTAL.store(targets.len() as u32, Ordering::Relaxed);
+    let mailbox_timeout = options.mailbox_timeout();
+
     let mut discovered_slaves = Vec::new();
 
-    for subdevice in group.iter(maindevice) {
-        let identity = subdevice.identity();
-        let name_str = subdevice.name();
-        
-        let mut name = [0u8; 64];
-        string_to_fixed_bytes(name_str, &mut name);
-        
+    // Latch the DC receive times on all ports of all slaves (broadcast write to 0x0900)
+    let receive_times_latched =
+        !live && Command::bwr(DC_RECEIVE_TIME_PORT0).send(maindevice, 0u32).await.is_ok();
+
+    for (position, target) in targets.iter().enumerate() {
+        if scan_cancelled() {
+            set_error_ctx(FfiErrorCode::Cancelled, "Scan cancelled", &[("op", "scan")]);
+            return Err(-3);
+        }
+        let identity = target.identity;
+
         // Detect capabilities
         let mut mailbox_protocols = 0u16;
         let mut dc_supported = 0u8;
 
-        let mut pdos = Vec::new();
-        
-        // Scan SM2 (Outputs/RxPDO, sync_manager=2) and SM3 (Inputs/TxPDO, sync_manager=3)
-        // Note: Not all slaves have 0x1C12/0x1C13 (e.g., EK1100 couplers), so we skip them gracefully
-        let sm_configs = [
-            (0x1C12, 2), // RxPDO
-            (0x1C13, 3), // TxPDO
-        ];
-
-        for (sm_idx, sm_num) in sm_configs {
-            // Skip if slave doesn't have this SM assignment object
-            if let Ok(count) = subdevice.sdo_read::<u8>(sm_idx, 0).await {
-                if count == 0 {
-                    continue;
-                }
-                // If we successfully read SDOs, CoE is supported
-                mailbox_protocols |= 0x01; // CoE flag
-                for i in 1..=count {
-                    if let Ok(pdo_index) = subdevice.sdo_read::<u16>(sm_idx, i).await {
-                        let pdo_name = [0u8; 64];
-                        
-                        let mut entries = Vec::new();
-                        
-                        if let Ok(entry_count) = subdevice.sdo_read::<u8>(pdo_index, 0).await {
-                            for j in 1..=entry_count {
-                                if let Ok(mapping) = subdevice.sdo_read::<u32>(pdo_index, j).await {
-                                    let target_idx = (mapping >> 16) as u16;
-                                    let target_sub = ((mapping >> 8) & 0xFF) as u8;
-                                    let bit_len = (mapping & 0xFF) as u8;
-                                    
-                                    let mut entry_name = [0u8; 64];
-                                    let mut data_type = 0u16;
-                                    
-                                    // Generate a name if we can't get one
-                                    if entry_name[0] == 0 {
-                                        let name_str = format!("Entry_0x{:04x}_{:02x}", target_idx, target_sub);
-                                        string_to_fixed_bytes(&name_str, &mut entry_name);
-                                    }
-                                    
-                                    // Guess type from bit_len
-                                    if data_type == 0 {
-                                        data_type = match bit_len {
-                                            1 => 0x0001,
-                                            8 => 0x0005,
-                                            16 => 0x0006,
-                                            32 => 0x0007,
-                                            64 => 0x0015, // INT64/UINT64
-                                            _ => 0x0000, // Unknown
-                                        };
-                                    }
+        // Names and data types come from SDO Information and the SII categories
+        let address = target.configured_address;
+        let sii = sii_read_image(maindevice, address, options.eeprom_timeout())
+            .await
+            .ok()
+            .map(|image| parse_sii(&image));
+        // Without the SII, a live scan tries SDO Information and drops it on the first timeout
+        let mut sdo_info = options.use_coe() && sii.as_ref().map_or(live, |s| s.sdo_info_supported());
+        if let Some(ref sii) = sii {
+            mailbox_protocols |= sii_mailbox_protocols(sii);
+        }
 
-                                    entries.push(DiscoveredEntry {
-                                        info: FfiPdoEntryInfo {
-                                            index: target_idx,
-                                            sub_index: target_sub,
-                                            bit_len,
-                                            data_type,
-                                            name: entry_name,
-                                        }
-                                    });
-                                }
-                            }
-                        }
+        // The SII name is not length limited, prefer it over the subdevice name
+        let full_name = sii
+            .as_ref()
+            .and_then(|s| s.general.as_ref())
+            .map(|g| g.name.clone())
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| target.name.clone());
+        let mut name = [0u8; 64];
+        string_to_fixed_bytes(&full_name, &mut name);
+
+        // ESC type, FMMU/SM counts, port descriptor and feature flags (0x0000-0x0009)
+        let esc = esc_read(maindevice, address, 0x0000, 10)
+            .await
+            .map(|raw| EscInfo::from_registers(&raw))
+            .unwrap_or_default();
+        if esc.dc_supported() {
+            dc_supported = 1;
+        }
 
-                        pdos.push(DiscoveredPdo {
-                            info: FfiPdoInfo {
-                                index: pdo_index,
-                                num_entries: entries.len() as u8,
-                                sync_manager: sm_num,
-                                name: pdo_name,
-                            },
-                            entries,
-                        });
-                    }
-                }
+        let mut pdos = if options.use_coe() {
+            coe_assigned_pdos(maindevice, address, sii.as_ref(), &mut sdo_info, mailbox_timeout).await
+        } else {
+            Vec::new()
+        };
+        // If we successfully read an assignment, CoE is supported
+        if !pdos.is_empty() {
+            mailbox_protocols |= MAILBOX_PROTO_COE;
+        }
+
+        // If no CoE PDOs were found, use the SII PDO categories
+        if pdos.is_empty() {
+            if let Some(ref sii) = sii {
+                pdos = sii_discovered_pdos(sii);
             }
         }
-        
-        // If no CoE PDOs were found, try reading from EEPROM
+
+        // Without SII PDO categories, fall back to the per-PDO bit lengths from EEPROM.
+        // A live scan leaves the EEPROM alone.
         if pdos.is_empty() {
-            // Read TxPDO (Inputs from device perspective = SM3)
-            if let Ok(eeprom_tx_pdos) = subdevice.eeprom().maindevice_read_pdos().await {
-                for (pdo_idx, pdo) in eeprom_tx_pdos.iter().enumerate() {
-                    // EEPROM PDOs provide total bit_len but not individual entry details
-                    // Create a single entry representing the whole PDO
-                    let mut entry_name = [0u8; 64];
-                    let name_str = format!("Input_PDO_{}", pdo_idx);
-                    string_to_fixed_bytes(&name_str, &mut entry_name);
-                    
-                    let data_type = match pdo.bit_len {
-                        1 => 0x0001,  // BOOL
-                        8 => 0x0005,  // UINT8
-                        16 => 0x0006, // UINT16
-                        32 => 0x0007, // UINT32
-                        _ if pdo.bit_len <= 8 => 0x0005,   // UINT8
-                        _ if pdo.bit_len <= 16 => 0x0006,  // UINT16
-                        _ if pdo.bit_len <= 32 => 0x0007,  // UINT32
-                        _ => 0x0000,
-                    };
-                    
-                    let entries = vec![DiscoveredEntry {
-                        info: FfiPdoEntryInfo {
-                            index: 0x6000 + pdo_idx as u16,  // Synthetic index
-                            sub_index: 0,
-                            bit_len: pdo.bit_len as u8,
-                            data_type,
-                            name: entry_name,
-                        }
-                    }];
-                    
-                    pdos.push(DiscoveredPdo {
-                        info: FfiPdoInfo {
-                            index: 0x1A00 + pdo_idx as u16,  // Synthetic TxPDO index
-                            num_entries: 1,
-                            sync_manager: 3,  // TxPDO = inputs = SM3 (canonical number)
-                            name: [0u8; 64],
-                        },
-                        entries,
-                    });
-                }
+            if let Some(group) = group {
+                pdos = eeprom_discovered_pdos(maindevice, group, position).await;
             }
-            
-            // Read RxPDO (Outputs from device perspective = SM2)
-            if let Ok(eeprom_rx_pdos) = subdevice.eeprom().maindevice_write_pdos().await {
-                for (pdo_idx, pdo) in eeprom_rx_pdos.iter().enumerate() {
-                    let mut entry_name = [0u8; 64];
-                    let name_str = format!("Output_PDO_{}", pdo_idx);
-                    string_to_fixed_bytes(&name_str, &mut entry_name);
-                    
-                    let data_type = match pdo.bit_len {
-                        1 => 0x0001,  // BOOL
-                        8 => 0x0005,  // UINT8
-                        16 => 0x0006, // UINT16
-                        32 => 0x0007, // UINT32
-                        _ if pdo.bit_len <= 8 => 0x0005,   // UINT8
-                        _ if pdo.bit_len <= 16 => 0x0006,  // UINT16
-                        _ if pdo.bit_len <= 32 => 0x0007,  // UINT32
-                        _ => 0x0000,
-                    };
-                    
-                    let entries = vec![DiscoveredEntry {
-                        info: FfiPdoEntryInfo {
-                            index: 0x7000 + pdo_idx as u16,  // Synthetic index
-                            sub_index: 0,
-                            bit_len: pdo.bit_len as u8,
-                            data_type,
-                            name: entry_name,
-                        }
-                    }];
-                    
-                    pdos.push(DiscoveredPdo {
-                        info: FfiPdoInfo {
-                            index: 0x1600 + pdo_idx as u16,  // Synthetic RxPDO index
-                            num_entries: 1,
-                            sync_manager: 2,  // RxPDO = outputs = SM2 (canonical number)
-                            name: [0u8; 64],
-                        },
-                        entries,
-                    });
-                }
+        }
+
+        // Entries neither SDO Information nor the SII describes get a type from their bit length
+        for entry in pdos.iter_mut().flat_map(|p| p.entries.iter_mut()) {
+            if entry.info.data_type == 0 {
+                entry.info.data_type = guess_data_type(entry.info.bit_len);
             }
         }
-        
-        // Check DC support by attempting to read DC System Time register (0x0910)
-        // If the read succeeds, the device supports DC. If it fails, we assume no DC support.
-        // This is a non-blocking check - errors are ignored to avoid interrupting the scan.
-        let _ = subdevice.register_read::<u32>(0x0910u16).await.map(|_| {
+
+        // Without ESC feature info, probe the DC System Time register (0x0910) instead.
+        // Errors are ignored to avoid interrupting the scan.
+        if esc == EscInfo::default() && esc_read(maindevice, address, 0x0910, 4).await.is_ok() {
             dc_supported = 1;
-        });
+        }
         
         let slave_info = FfiSlaveInfo {
             identity: SlaveIdentity {
@@ -2140,19 +7445,79 @@ async fn perform_scan(maindevice: &MainDevice<'_>) -> Result<Vec<DiscoveredSlave
                 serial_number: identity.serial,
             },
             name,
-            configured_address: subdevice.configured_address(),
-            alias_address: subdevice.alias_address(),
-            port_count: 0, // TODO: Get port count if possible
+            configured_address: target.configured_address,
+            alias_address: target.alias_address,
+            port_count: esc.port_count(),
             _padding: [0],
             mailbox_protocols,
             dc_supported,
             _padding2: [0],
+            dc_64bit: esc.dc_64bit() as u8,
+            fmmu_count: esc.fmmu_count,
+            sm_count: esc.sm_count,
+            esc_type: esc.esc_type,
+            port_types: esc.port_types,
+            esc_features: esc.features,
         };
         
+        let mut topology = FfiSlaveTopology {
+            parent_index: -1,
+            parent_port: 0xFF,
+            ..Default::default()
+        };
+        if let Ok(raw) = esc_read(maindevice, address, DL_STATUS_REGISTER, 2).await {
+            topology.dl_status = le_u16(&raw, 0);
+            let (link, loop_closed, communication) = dl_status_ports(topology.dl_status);
+            topology.link = link.map(u8::from);
+            topology.loop_closed = loop_closed.map(u8::from);
+            topology.communication = communication.map(u8::from);
+            topology.entry_port = split_ports(&communication).0.unwrap_or(0);
+            if receive_times_latched && dc_supported == 1 {
+                if let Ok(raw) = esc_read(maindevice, address, DC_RECEIVE_TIME_PORT0, 16).await {
+                    topology.port_receive_time_ns = [0, 1, 2, 3].map(|p| le_u32(&raw, p * 4));
+                    topology.port_delay_ns = port_delays(&topology.port_receive_time_ns, &communication);
+                    topology.dc_valid = 1;
+                }
+            }
+        }
+
+        let mut objects = Vec::new();
+        if options.walk_object_dictionary() && sdo_info && mailbox_protocols & MAILBOX_PROTO_COE != 0 {
+            if let Ok(indices) = sdo_info_od_list(maindevice, address, mailbox_timeout).await {
+                for index in indices {
+                    if scan_cancelled() {
+                        break;
+                    }
+                    match sdo_info_object_name(maindevice, address, index, mailbox_timeout).await {
+                        Ok(name) => objects.push(DiscoveredObject { index, name }),
+                        Err(MailboxError::Timeout) => break,
+                        Err(_) => objects.push(DiscoveredObject { index, name: String::new() }),
+                    }
+                }
+            }
+        }
+
         discovered_slaves.push(DiscoveredSlave {
             info: slave_info,
+            name: full_name,
+            topology,
             pdos,
+            objects,
+            sii,
         });
+        SCAN_SLAVES_DONE.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // Parents follow from propagation order and the open ports of every slave
+    let active_ports: Vec<[bool; 4]> = discovered_slaves
+        .iter()
+        .map(|s| s.topology.communication.map(|c| c != 0))
+        .collect();
+    for (slave, parent) in discovered_slaves.iter_mut().zip(compute_parents(&active_ports)) {
+        if let Some((parent_index, parent_port)) = parent {
+            slave.topology.parent_index = parent_index as i16;
+            slave.topology.parent_port = parent_port;
+        }
     }
     
     Ok(discovered_slaves)
@@ -2160,6 +7525,17 @@ async fn perform_scan(maindevice: &MainDevice<'_>) -> Result<Vec<DiscoveredSlave
 
 #[no_mangle]
 pub extern "C" fn ethercrab_scan_new(interface: *const c_char) -> *mut ScanContext {
+    ethercrab_scan_new_with_options(interface, std::ptr::null())
+}
+
+/// `ethercrab_scan_new` with timeouts, retries, CoE/SII selection, OD walk and a
+/// slave limit. `options` may be null for the defaults. Progress can be polled with
+/// `ethercrab_scan_get_progress` and the scan aborted with `ethercrab_scan_cancel`.
+#[no_mangle]
+pub extern "C" fn ethercrab_scan_new_with_options(
+    interface: *const c_char,
+    options: *const FfiScanOptions,
+) -> *mut ScanContext {
     with_ffi_guard(std::ptr::null_mut(), || {
         if interface.is_null() { return std::ptr::null_mut(); }
 
@@ -2168,6 +7544,9 @@ pub extern "C" fn ethercrab_scan_new(interface: *const c_char) -> *mut ScanConte
             return std::ptr::null_mut();
         }
 
+        let options = FfiScanOptions::from_ptr(options);
+        let _progress = ScanProgressGuard::start();
+
         let interface_str = unsafe {
             match CStr::from_ptr(interface).to_str() {
                 Ok(s) => s.to_string(),
@@ -2191,11 +7570,8 @@ pub extern "C" fn ethercrab_scan_new(interface: *const c_char) -> *mut ScanConte
                 
                 let maindevice = MainDevice::new(
                     pdu_loop,
-                    Timeouts::default(),
-                    MainDeviceConfig {
-                        dc_static_sync_iterations: 0,  // Disable DC
-                        ..MainDeviceConfig::default()
-                    },
+                    options.timeouts(),
+                    options.main_device_config(),
                 );
                 
                 let iface = interface_str.clone();
@@ -2221,8 +7597,8 @@ pub extern "C" fn ethercrab_scan_new(interface: *const c_char) -> *mut ScanConte
 
                 // Task 2: Scan Logic
                 let scan_fut = async {
-                    let slaves = perform_scan(&maindevice).await?;
-                    Ok(Box::into_raw(Box::new(ScanContext { slaves })))
+                    let slaves = perform_scan(&maindevice, &options).await?;
+                    Ok(Box::into_raw(Box::new(ScanContext::new(slaves))))
                 };
                 
                 // Race them. When scan_fut completes, network_fut is dropped.
@@ -2245,11 +7621,8 @@ pub extern "C" fn ethercrab_scan_new(interface: *const c_char) -> *mut ScanConte
                 
                 let maindevice = MainDevice::new(
                     pdu_loop,
-                    Timeouts::default(),
-                    MainDeviceConfig {
-                        dc_static_sync_iterations: 0,  // Disable DC
-                        ..MainDeviceConfig::default()
-                    },
+                    options.timeouts(),
+                    options.main_device_config(),
                 );
                 
                 let iface = interface_str.clone();
@@ -2290,7 +7663,7 @@ pub extern "C" fn ethercrab_scan_new(interface: *const c_char) -> *mut ScanConte
                 std::thread::sleep(Duration::from_millis(50));
 
                 // Perform the scan
-                let scan_result = perform_scan(&maindevice).await;
+                let scan_result = perform_scan(&maindevice, &options).await;
 
                 // Signal shutdown and wait for thread to stop
                 shutdown.store(true, Ordering::SeqCst);
@@ -2301,7 +7674,7 @@ pub extern "C" fn ethercrab_scan_new(interface: *const c_char) -> *mut ScanConte
 
                 // Return scan result
                 match scan_result {
-                    Ok(slaves) => Ok(Box::into_raw(Box::new(ScanContext { slaves }))),
+                    Ok(slaves) => Ok(Box::into_raw(Box::new(ScanContext::new(slaves)))),
                     Err(e) => Err(e),
                 }
             }
@@ -2314,6 +7687,72 @@ pub extern "C" fn ethercrab_scan_new(interface: *const c_char) -> *mut ScanConte
     })
 }
 
+/// Scan the network through the running master instead of opening the interface
+/// again. Works in PreOp, SafeOp and Op: only acyclic reads are issued, so the
+/// cyclic exchange keeps running. The result is freed with `ethercrab_scan_free`.
+/// `options` may be null; its PDU timeout and retries do not apply here, the running
+/// master keeps its own. Returns null if the master is not initialized or has no
+/// group, or if the scan was cancelled.
+#[no_mangle]
+pub extern "C" fn ethercrab_scan_live(options: *const FfiScanOptions) -> *mut ScanContext {
+    with_ffi_guard(std::ptr::null_mut(), || {
+        let options = FfiScanOptions::from_ptr(options);
+        // Started first so a destroy that cancels the scan is not reset by it
+        let _progress = ScanProgressGuard::start();
+        // Snapshot the group and release the state: the scan takes seconds and must
+        // not block state changes meanwhile. Destroy waits for (and cancels) the scan.
+        let (maindevice, targets) = {
+            let guard = STATE.read();
+            let state = match guard.as_ref() {
+                Some(s) => s,
+                None => {
+                    set_error_ctx(FfiErrorCode::NotInitialized, "Live scan requires an initialized master", &[("op", "scan_live")]);
+                    return std::ptr::null_mut();
+                }
+            };
+            let group = match state.group.as_ref() {
+                Some(g) => g,
+                None => {
+                    set_error_ctx(FfiErrorCode::NotInitialized, "Live scan requires a configured group", &[("op", "scan_live")]);
+                    return std::ptr::null_mut();
+                }
+            };
+            (DetachedMaster::new(state), scan_targets(&state.maindevice, group, options.max_slaves()))
+        };
+
+        match smol::block_on(scan_group(&maindevice, &targets, None, &options)) {
+            Ok(slaves) => Box::into_raw(Box::new(ScanContext::new(slaves))),
+            Err(_) => std::ptr::null_mut(),
+        }
+    })
+}
+
+/// Progress of the running scan in slaves. Returns 1 while a scan is running, else 0
+/// (the counters then hold the last scan). The total is 0 until the slaves are known.
+#[no_mangle]
+pub extern "C" fn ethercrab_scan_get_progress(done: *mut u32, total: *mut u32) -> c_int {
+    with_ffi_guard(-1, || {
+        unsafe {
+            if !done.is_null() { *done = SCAN_SLAVES_DONE.load(Ordering::Relaxed); }
+            if !total.is_null() { *total = SCAN_SLAVES_TOTAL.load(Ordering::Relaxed); }
+        }
+        SCAN_ACTIVE.load(Ordering::SeqCst) as c_int
+    })
+}
+
+/// Ask the running scan to stop before the next slave (or OD object). The scan call
+/// then returns null with a `Cancelled` error. Returns 0, or -1 if no scan is running.
+#[no_mangle]
+pub extern "C" fn ethercrab_scan_cancel() -> c_int {
+    with_ffi_guard(-1, || {
+        if !SCAN_ACTIVE.load(Ordering::SeqCst) {
+            return -1;
+        }
+        SCAN_CANCEL.store(true, Ordering::SeqCst);
+        0
+    })
+}
+
 #[no_mangle]
 pub extern "C" fn ethercrab_scan_get_slave_count(ctx: *const ScanContext) -> u32 {
     with_ffi_guard(0, || {
@@ -2337,6 +7776,27 @@ pub extern "C" fn ethercrab_scan_get_slave(ctx: *const ScanContext, idx: u32, ou
     })
 }
 
+/// Physical topology of a scanned slave (port states, parent, DC port delays).
+#[no_mangle]
+pub extern "C" fn ethercrab_scan_get_slave_topology(
+    ctx: *const ScanContext,
+    idx: u32,
+    out_topology: *mut FfiSlaveTopology,
+) -> c_int {
+    with_ffi_guard(-1, || {
+        if ctx.is_null() || out_topology.is_null() { return -1; }
+        unsafe {
+            let ctx = &*ctx;
+            if let Some(slave) = ctx.slaves.get(idx as usize) {
+                *out_topology = slave.topology;
+                0
+            } else {
+                -1
+            }
+        }
+    })
+}
+
 #[no_mangle]
 pub extern "C" fn ethercrab_scan_get_pdo_count(ctx: *const ScanContext, slave_idx: u32) -> u32 {
     with_ffi_guard(0, || {
@@ -2423,6 +7883,429 @@ pub extern "C" fn ethercrab_scan_get_pdo_entry(
     })
 }
 
+// --- Scan JSON ---
+
+/// Version of the document produced by `ScanContext::to_json`. Bumped on breaking changes;
+/// added fields do not change it. Schema: docs/compliance/03_DISCOVERY_mode_SPEC.md §4.
+pub const SCAN_JSON_SCHEMA_VERSION: u32 = 1;
+
+fn port_physics_name(port_type: u8) -> &'static str {
+    match port_type {
+        1 => "unconfigured",
+        2 => "EBUS",
+        3 => "MII",
+        _ => "none",
+    }
+}
+
+fn scan_pdos_json(pdos: &[&DiscoveredPdo]) -> String {
+    let items: Vec<String> = pdos
+        .iter()
+        .map(|pdo| {
+            let entries: Vec<String> = pdo
+                .entries
+                .iter()
+                .map(|e| {
+                    format!(
+                        "{{\"index\":{},\"subIndex\":{},\"bitLen\":{},\"dataType\":{},\"name\":{}}}",
+                        e.info.index, e.info.sub_index, e.info.bit_len, e.info.data_type, json_string(&e.name)
+                    )
+                })
+                .collect();
+            format!(
+                "{{\"index\":{},\"name\":{},\"syncManager\":{},\"entries\":[{}]}}",
+                pdo.info.index, json_string(&pdo.name), pdo.info.sync_manager, entries.join(",")
+            )
+        })
+        .collect();
+    format!("[{}]", items.join(","))
+}
+
+impl DiscoveredSlave {
+    fn to_json(&self, position: usize) -> String {
+        let info = &self.info;
+        let protocol = |flag: u16| info.mailbox_protocols & flag != 0;
+        let physics: Vec<String> = info.port_types.iter().map(|&t| json_string(port_physics_name(t))).collect();
+
+        let t = &self.topology;
+        let dc_valid = t.dc_valid == 1;
+        let ports: Vec<String> = (0..4)
+            .map(|p| {
+                let (receive_time, delay) = if dc_valid {
+                    (t.port_receive_time_ns[p].to_string(), t.port_delay_ns[p].to_string())
+                } else {
+                    ("null".to_string(), "null".to_string())
+                };
+                format!(
+                    "{{\"link\":{},\"loopClosed\":{},\"communication\":{},\"receiveTimeNs\":{},\"delayNs\":{}}}",
+                    t.link[p] != 0, t.loop_closed[p] != 0, t.communication[p] != 0, receive_time, delay
+                )
+            })
+            .collect();
+        let (parent_index, parent_port) = if t.parent_index >= 0 {
+            (t.parent_index.to_string(), t.parent_port.to_string())
+        } else {
+            ("null".to_string(), "null".to_string())
+        };
+
+        let inputs: Vec<&DiscoveredPdo> = self.pdos.iter().filter(|p| p.info.sync_manager == 3).collect();
+        let outputs: Vec<&DiscoveredPdo> = self.pdos.iter().filter(|p| p.info.sync_manager == 2).collect();
+        let objects: Vec<String> = self
+            .objects
+            .iter()
+            .map(|o| format!("{{\"index\":{},\"name\":{}}}", o.index, json_string(&o.name)))
+            .collect();
+
+        format!(
+            concat!(
+                "{{\"position\":{},\"name\":{},",
+                "\"identity\":{{\"vendorId\":{},\"productCode\":{},\"revision\":{},\"serialNo\":{}}},",
+                "\"config\":{{\"configuredAddress\":{},\"aliasAddress\":{},\"portPhysics\":[{}]}},",
+                "\"capabilities\":{{\"mailbox\":{{\"coe\":{},\"foe\":{},\"eoe\":{},\"soe\":{},\"aoe\":{},\"voe\":{}}},",
+                "\"dc\":{},\"dc64Bit\":{},\"portCount\":{},\"fmmuCount\":{},\"smCount\":{},\"escType\":{},\"escFeatures\":{}}},",
+                "\"topology\":{{\"dlStatus\":{},\"parentIndex\":{},\"parentPort\":{},\"entryPort\":{},\"ports\":[{}]}},",
+                "\"pdos\":{{\"inputs\":{},\"outputs\":{}}},\"objects\":[{}]}}"
+            ),
+            position,
+            json_string(&self.name),
+            info.identity.vendor_id,
+            info.identity.product_code,
+            info.identity.revision,
+            info.identity.serial_number,
+            info.configured_address,
+            info.alias_address,
+            physics.join(","),
+            protocol(MAILBOX_PROTO_COE),
+            protocol(MAILBOX_PROTO_FOE),
+            protocol(MAILBOX_PROTO_EOE),
+            protocol(MAILBOX_PROTO_SOE),
+            protocol(MAILBOX_PROTO_AOE),
+            protocol(MAILBOX_PROTO_VOE),
+            info.dc_supported == 1,
+            info.dc_64bit == 1,
+            info.port_count,
+            info.fmmu_count,
+            info.sm_count,
+            info.esc_type,
+            info.esc_features,
+            t.dl_status,
+            parent_index,
+            parent_port,
+            t.entry_port,
+            ports.join(","),
+            scan_pdos_json(&inputs),
+            scan_pdos_json(&outputs),
+            objects.join(","),
+        )
+    }
+}
+
+impl ScanContext {
+    /// Render the whole scan as one JSON document, see `SCAN_JSON_SCHEMA_VERSION`.
+    pub fn to_json(&self) -> String {
+        let slaves: Vec<String> = self.slaves.iter().enumerate().map(|(i, s)| s.to_json(i)).collect();
+        format!(
+            "{{\"schemaVersion\":{},\"scanTime\":{},\"slaves\":[{}]}}",
+            SCAN_JSON_SCHEMA_VERSION,
+            self.scan_time_ms,
+            slaves.join(",")
+        )
+    }
+}
+
+/// Write the scan as a single JSON document (see `ScanContext::to_json`).
+/// Returns bytes written, -1 for a null context, or -4 (invalid argument or buffer too
+/// small, with `required_len` in the error context).
+#[no_mangle]
+pub extern "C" fn ethercrab_scan_get_json(ctx: *const ScanContext, buffer: *mut u8, len: usize) -> c_int {
+    with_ffi_guard(-1, || {
+        if ctx.is_null() { return -1; }
+        if buffer.is_null() || len == 0 { return -4; }
+        let json = unsafe { (*ctx).to_json() };
+        copy_text_out("scan_get_json", &json, buffer, len)
+    })
+}
+
+// --- ENI Export (ETG.2100) ---
+
+/// Mailbox polling defaults written to exported ENI files, same as `discoverNetwork()`.
+const ENI_MAILBOX_STATUS_ADDR: u16 = 0x080D;
+const ENI_MAILBOX_POLL_TIME_MS: u32 = 20;
+
+fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c if (c as u32) < 0x20 && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// ETG.2100 data type name for a CoE data type code (ETG1000.6 Table 64).
+fn eni_data_type_name(data_type: u16, bit_len: u8) -> String {
+    match data_type {
+        0x0001 => "BOOL".into(),
+        0x0002 => "SINT".into(),
+        0x0003 => "INT".into(),
+        0x0004 => "DINT".into(),
+        0x0005 => "USINT".into(),
+        0x0006 => "UINT".into(),
+        0x0007 => "UDINT".into(),
+        0x0008 => "REAL".into(),
+        0x0009 => format!("STRING({})", bit_len / 8),
+        0x0011 => "LREAL".into(),
+        0x0015 => "LINT".into(),
+        0x001B => "ULINT".into(),
+        0x001E => "BYTE".into(),
+        0x001F => "WORD".into(),
+        0x0020 => "DWORD".into(),
+        0x0030..=0x0037 => format!("BIT{}", data_type - 0x002F),
+        _ if bit_len == 1 => "BIT".into(),
+        _ => format!("BIT{}", bit_len),
+    }
+}
+
+/// Little-endian hex as used by `<Data>` elements.
+fn eni_hex_data(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Bits a slave occupies in the (inputs, outputs) process image, each rounded up to whole bytes.
+fn slave_process_data_bits(slave: &DiscoveredSlave) -> (u32, u32) {
+    let mut input_bits = 0u32;
+    let mut output_bits = 0u32;
+    for pdo in &slave.pdos {
+        let bits: u32 = pdo.entries.iter().map(|e| e.info.bit_len as u32).sum();
+        match pdo.info.sync_manager {
+            2 => output_bits += bits,
+            3 => input_bits += bits,
+            _ => {}
+        }
+    }
+    (input_bits.div_ceil(8) * 8, output_bits.div_ceil(8) * 8)
+}
+
+impl ScanContext {
+    /// Serialize the scan as an ETG.2100 EtherCAT Network Information (ENI) document.
+    ///
+    /// The process image is laid out slave by slave, byte aligned, in the same way as
+    /// `discoverNetwork()`. CoE slaves get PS init commands that restore the scanned PDO
+    /// assignment (0x1C12/0x1C13).
+    pub fn to_eni_xml(&self) -> String {
+        let mut xml = String::new();
+        xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<EtherCATConfig xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" xsi:noNamespaceSchemaLocation=\"EtherCATConfig.xsd\" Version=\"1.3\">\n");
+        xml.push_str("  <Config>\n");
+        xml.push_str("    <Master>\n      <Info>\n        <Name>EtherCAT Master</Name>\n");
+        xml.push_str("        <Destination>ffffffffffff</Destination>\n        <Source>000000000000</Source>\n        <EtherType>a488</EtherType>\n");
+        xml.push_str("      </Info>\n    </Master>\n");
+
+        let mut input_variables = String::new();
+        let mut output_variables = String::new();
+        let mut input_offset = 0u32;
+        let mut output_offset = 0u32;
+
+        for (position, slave) in self.slaves.iter().enumerate() {
+            let info = &slave.info;
+            let name = &slave.name;
+            let (input_bits, output_bits) = slave_process_data_bits(slave);
+
+            xml.push_str("    <Slave>\n      <Info>\n");
+            xml.push_str(&format!("        <Name>{}</Name>\n", xml_escape(name)));
+            xml.push_str(&format!("        <PhysAddr>{}</PhysAddr>\n", info.configured_address));
+            xml.push_str(&format!("        <AutoIncAddr>{}</AutoIncAddr>\n", 0u16.wrapping_sub(position as u16)));
+            xml.push_str(&format!("        <VendorId>{}</VendorId>\n", info.identity.vendor_id));
+            xml.push_str(&format!("        <ProductCode>{}</ProductCode>\n", info.identity.product_code));
+            xml.push_str(&format!("        <RevisionNo>{}</RevisionNo>\n", info.identity.revision));
+            xml.push_str(&format!("        <SerialNo>{}</SerialNo>\n", info.identity.serial_number));
+            xml.push_str("      </Info>\n");
+
+            // Process data: image ranges, sync managers with their assigned PDOs, PDO contents
+            xml.push_str("      <ProcessData>\n");
+            if output_bits > 0 {
+                xml.push_str(&format!(
+                    "        <Send>\n          <BitStart>{}</BitStart>\n          <BitLength>{}</BitLength>\n        </Send>\n",
+                    output_offset, output_bits
+                ));
+            }
+            if input_bits > 0 {
+                xml.push_str(&format!(
+                    "        <Recv>\n          <BitStart>{}</BitStart>\n          <BitLength>{}</BitLength>\n        </Recv>\n",
+                    input_offset, input_bits
+                ));
+            }
+            let sync_managers = slave.sii.as_ref().map(|s| s.sync_managers.as_slice()).unwrap_or(&[]);
+            let mut sm_numbers = [0u8; 2];
+            for (i, (canonical, sm_type, type_name)) in [(2u8, 3u8, "Outputs"), (3, 4, "Inputs")].into_iter().enumerate() {
+                let sm = sync_managers.iter().position(|sm| sm.sm_type == sm_type);
+                let number = sm.map(|n| n as u8).unwrap_or(canonical);
+                sm_numbers[i] = number;
+                let pdos: Vec<&DiscoveredPdo> = slave.pdos.iter().filter(|p| p.info.sync_manager == canonical).collect();
+                if pdos.is_empty() && sm.is_none() {
+                    continue;
+                }
+                let size: u32 = pdos.iter().flat_map(|p| &p.entries).map(|e| e.info.bit_len as u32).sum::<u32>().div_ceil(8);
+                xml.push_str(&format!("        <Sm{}>\n          <Type>{}</Type>\n", number, type_name));
+                xml.push_str(&format!("          <DefaultSize>{}</DefaultSize>\n", size));
+                if let Some(sm) = sm.map(|n| &sync_managers[n]) {
+                    xml.push_str(&format!("          <StartAddress>{}</StartAddress>\n", sm.start_address));
+                    xml.push_str(&format!("          <ControlByte>{}</ControlByte>\n", sm.control));
+                }
+                xml.push_str(&format!("          <Enable>{}</Enable>\n", u8::from(size > 0)));
+                for pdo in &pdos {
+                    xml.push_str(&format!("          <Pdo>{}</Pdo>\n", pdo.info.index));
+                }
+                xml.push_str(&format!("        </Sm{}>\n", number));
+            }
+            for (canonical, tag, sm_number) in [(2u8, "RxPdo", sm_numbers[0]), (3, "TxPdo", sm_numbers[1])] {
+                for pdo in slave.pdos.iter().filter(|p| p.info.sync_manager == canonical) {
+                    xml.push_str(&format!("        <{} Sm=\"{}\">\n", tag, sm_number));
+                    xml.push_str(&format!("          <Index>#x{:04x}</Index>\n", pdo.info.index));
+                    xml.push_str(&format!("          <Name>{}</Name>\n", xml_escape(&pdo.name)));
+                    for entry in &pdo.entries {
+                        let e = &entry.info;
+                        xml.push_str("          <Entry>\n");
+                        xml.push_str(&format!("            <Index>#x{:04x}</Index>\n", e.index));
+                        if e.index != 0 {
+                            xml.push_str(&format!("            <SubIndex>{}</SubIndex>\n", e.sub_index));
+                        }
+                        xml.push_str(&format!("            <BitLen>{}</BitLen>\n", e.bit_len));
+                        if e.index != 0 {
+                            xml.push_str(&format!("            <Name>{}</Name>\n", xml_escape(&entry.name)));
+                            xml.push_str(&format!("            <DataType>{}</DataType>\n", eni_data_type_name(e.data_type, e.bit_len)));
+                        }
+                        xml.push_str("          </Entry>\n");
+                    }
+                    xml.push_str(&format!("        </{}>\n", tag));
+                }
+            }
+            xml.push_str("      </ProcessData>\n");
+
+            // Mailbox: SM0 (master to slave) and SM1 (slave to master) from the SII header
+            let coe = info.mailbox_protocols & MAILBOX_PROTO_COE != 0;
+            if let Some(sii) = slave.sii.as_ref().filter(|s| s.rx_mailbox_size > 0 && s.tx_mailbox_size > 0) {
+                xml.push_str("      <Mailbox DataLinkLayer=\"true\">\n");
+                xml.push_str(&format!(
+                    "        <Send>\n          <Start>{}</Start>\n          <Length>{}</Length>\n        </Send>\n",
+                    sii.rx_mailbox_offset, sii.rx_mailbox_size
+                ));
+                xml.push_str(&format!(
+                    "        <Recv>\n          <Start>{}</Start>\n          <Length>{}</Length>\n          <StatusBitAddr>{}</StatusBitAddr>\n          <PollTime>{}</PollTime>\n        </Recv>\n",
+                    sii.tx_mailbox_offset, sii.tx_mailbox_size, ENI_MAILBOX_STATUS_ADDR, ENI_MAILBOX_POLL_TIME_MS
+                ));
+                for (flag, protocol) in [
+                    (MAILBOX_PROTO_AOE, "AoE"),
+                    (MAILBOX_PROTO_EOE, "EoE"),
+                    (MAILBOX_PROTO_COE, "CoE"),
+                    (MAILBOX_PROTO_FOE, "FoE"),
+                    (MAILBOX_PROTO_SOE, "SoE"),
+                    (MAILBOX_PROTO_VOE, "VoE"),
+                ] {
+                    if info.mailbox_protocols & flag != 0 {
+                        xml.push_str(&format!("        <Protocol>{}</Protocol>\n", protocol));
+                    }
+                }
+                if coe {
+                    xml.push_str("        <CoE>\n          <InitCmds>\n");
+                    for (assign_index, canonical) in [(0x1C12u16, 2u8), (0x1C13, 3)] {
+                        let pdos: Vec<u16> =
+                            slave.pdos.iter().filter(|p| p.info.sync_manager == canonical).map(|p| p.info.index).collect();
+                        let mut commands = vec![(format!("clear pdo 0x{:04X} entries", assign_index), 0u8, vec![0u8])];
+                        for (i, pdo) in pdos.iter().enumerate() {
+                            commands.push((
+                                format!("download pdo 0x{:04X} index", assign_index),
+                                i as u8 + 1,
+                                pdo.to_le_bytes().to_vec(),
+                            ));
+                        }
+                        if !pdos.is_empty() {
+                            commands.push((format!("download pdo 0x{:04X} count", assign_index), 0, vec![pdos.len() as u8]));
+                        }
+                        for (comment, sub_index, data) in commands {
+                            xml.push_str("            <InitCmd>\n              <Transition>PS</Transition>\n");
+                            xml.push_str(&format!("              <Comment>{}</Comment>\n", comment));
+                            xml.push_str("              <Timeout>0</Timeout>\n              <Ccs>1</Ccs>\n");
+                            xml.push_str(&format!("              <Index>{}</Index>\n", assign_index));
+                            xml.push_str(&format!("              <SubIndex>{}</SubIndex>\n", sub_index));
+                            xml.push_str(&format!("              <Data>{}</Data>\n", eni_hex_data(&data)));
+                            xml.push_str("            </InitCmd>\n");
+                        }
+                    }
+                    xml.push_str("          </InitCmds>\n        </CoE>\n");
+                }
+                xml.push_str("      </Mailbox>\n");
+            }
+
+            let topology = &slave.topology;
+            if topology.parent_index >= 0 && topology.parent_port < 4 {
+                if let Some(parent) = self.slaves.get(topology.parent_index as usize) {
+                    xml.push_str(&format!(
+                        "      <PreviousPort Selected=\"true\">\n        <Port>{}</Port>\n        <PhysAddr>{}</PhysAddr>\n      </PreviousPort>\n",
+                        (b'A' + topology.parent_port) as char,
+                        parent.info.configured_address
+                    ));
+                }
+            }
+            xml.push_str("    </Slave>\n");
+
+            // Process image variables, padding entries (index 0) are left as gaps
+            for (canonical, variables, base) in [
+                (3u8, &mut input_variables, input_offset),
+                (2, &mut output_variables, output_offset),
+            ] {
+                let mut bit = base;
+                for entry in slave.pdos.iter().filter(|p| p.info.sync_manager == canonical).flat_map(|p| &p.entries) {
+                    let e = &entry.info;
+                    if e.index != 0 {
+                        variables.push_str(&format!(
+                            "        <Variable>\n          <Name>{}.{}</Name>\n          <DataType>{}</DataType>\n          <BitSize>{}</BitSize>\n          <BitOffs>{}</BitOffs>\n        </Variable>\n",
+                            xml_escape(name),
+                            xml_escape(&entry.name),
+                            eni_data_type_name(e.data_type, e.bit_len),
+                            e.bit_len,
+                            bit
+                        ));
+                    }
+                    bit += e.bit_len as u32;
+                }
+            }
+            input_offset += input_bits;
+            output_offset += output_bits;
+        }
+
+        xml.push_str("    <ProcessImage>\n");
+        xml.push_str(&format!("      <Inputs>\n        <ByteSize>{}</ByteSize>\n", input_offset / 8));
+        xml.push_str(&input_variables);
+        xml.push_str("      </Inputs>\n");
+        xml.push_str(&format!("      <Outputs>\n        <ByteSize>{}</ByteSize>\n", output_offset / 8));
+        xml.push_str(&output_variables);
+        xml.push_str("      </Outputs>\n");
+        xml.push_str("    </ProcessImage>\n");
+        xml.push_str("  </Config>\n</EtherCATConfig>\n");
+        xml
+    }
+}
+
+/// Write the scan as ETG.2100 ENI XML (see `ScanContext::to_eni_xml`).
+/// Returns bytes written, -1 for a null context, or -4 (invalid argument or buffer too
+/// small, with `required_len` in the error context).
+#[no_mangle]
+pub extern "C" fn ethercrab_scan_export_eni(ctx: *const ScanContext, buffer: *mut u8, len: usize) -> c_int {
+    with_ffi_guard(-1, || {
+        if ctx.is_null() { return -1; }
+        if buffer.is_null() || len == 0 { return -4; }
+        let xml = unsafe { (*ctx).to_eni_xml() };
+        copy_text_out("scan_export_eni", &xml, buffer, len)
+    })
+}
+
 #[no_mangle]
 pub extern "C" fn ethercrab_scan_free(ctx: *mut ScanContext) {
     with_ffi_guard((), || {
@@ -2438,3 +8321,19 @@ pub extern "C" fn ethercrab_scan_free(ctx: *mut ScanContext) {
 
 #[cfg(test)]
 mod error_tests;
+#[cfg(test)]
+mod mailbox_tests;
+#[cfg(test)]
+mod sii_tests;
+#[cfg(test)]
+mod topology_tests;
+#[cfg(test)]
+mod scan_tests;
+#[cfg(test)]
+mod pdo_tests;
+#[cfg(test)]
+mod failsafe_tests;
+#[cfg(test)]
+mod watchdog_tests;
+#[cfg(test)]
+mod register_tests;
@@ -0,0 +1,275 @@
+use super::*;
+use serial_test::serial;
+
+#[test]
+fn test_mailbox_frame_roundtrip() {
+    let frame = encode_mailbox_frame(MAILBOX_TYPE_SOE, 3, &[0x01, 0x40, 0x20, 0x00], 32);
+    assert_eq!(frame.len(), 32);
+    assert_eq!(&frame[0..2], &[4, 0]);
+    assert_eq!(frame[5], 0x35);
+
+    let decoded = decode_mailbox_frame(&frame).unwrap();
+    assert_eq!(decoded.mailbox_type, MAILBOX_TYPE_SOE);
+    assert_eq!(decoded.counter, 3);
+    assert_eq!(decoded.payload, vec![0x01, 0x40, 0x20, 0x00]);
+}
+
+#[test]
+fn test_mailbox_frame_rejects_bad_length() {
+    let mut frame = encode_mailbox_frame(MAILBOX_TYPE_SOE, 1, &[0u8; 4], 16);
+    frame[0] = 0xFF;
+    assert!(matches!(decode_mailbox_frame(&frame), Err(MailboxError::Protocol(_))));
+    assert!(matches!(decode_mailbox_frame(&[0u8; 3]), Err(MailboxError::Protocol(_))));
+}
+
+#[test]
+#[serial]
+fn test_mailbox_counter_cycles_one_to_seven() {
+    MAILBOX_CHANNELS.lock().clear();
+    let mut channel = smol::block_on(mailbox_channel(0x1001));
+    let counters: Vec<u8> = (0..9).map(|_| channel.next_counter()).collect();
+    assert_eq!(counters, vec![1, 2, 3, 4, 5, 6, 7, 1, 2]);
+    drop(channel);
+    // Counters are tracked per station address
+    assert_eq!(smol::block_on(mailbox_channel(0x1002)).next_counter(), 1);
+}
+
+#[test]
+#[serial]
+fn test_mailbox_counter_shared_across_protocols() {
+    MAILBOX_CHANNELS.lock().clear();
+    // SDO upload, SoE read, SDO Information and raw send all lock the same channel,
+    // so alternating them on one slave never repeats a counter
+    let sdo = smol::block_on(mailbox_channel(0x1001)).next_counter();
+    let soe = smol::block_on(mailbox_channel(0x1001)).next_counter();
+    let sdo_info = smol::block_on(mailbox_channel(0x1001)).next_counter();
+    assert_eq!((sdo, soe, sdo_info), (1, 2, 3));
+
+    // A counter chosen by a raw mailbox caller is followed by the next one
+    smol::block_on(mailbox_channel(0x1001)).note_counter(6);
+    assert_eq!(smol::block_on(mailbox_channel(0x1001)).next_counter(), 7);
+    smol::block_on(mailbox_channel(0x1001)).note_counter(0);
+    assert_eq!(smol::block_on(mailbox_channel(0x1001)).next_counter(), 1);
+
+    // The channel is held for a whole exchange
+    let held = smol::block_on(mailbox_channel(0x1001));
+    assert!(MAILBOX_CHANNELS.lock()[&0x1001].try_lock().is_none());
+    drop(held);
+    assert!(MAILBOX_CHANNELS.lock()[&0x1001].try_lock().is_some());
+}
+
+#[test]
+#[serial]
+fn test_mailbox_retry_once_after_ethercrab_traffic() {
+    let mut channel = MailboxChannel::new();
+    let timeout: Result<(), MailboxError> = Err(MailboxError::Timeout);
+
+    // First exchange after a group transition: one retry on timeout, then none
+    mailbox_channels_invalidate();
+    assert!(channel.retry(&timeout));
+    assert!(!channel.retry(&timeout));
+
+    // Other errors and successes are final, and also resynchronise the channel
+    mailbox_channels_invalidate();
+    assert!(!channel.retry(&Err::<(), _>(MailboxError::SdoAbort(0x0602_0000))));
+    assert!(!channel.retry(&timeout));
+    mailbox_channels_invalidate();
+    assert!(!channel.retry(&Ok(())));
+}
+
+#[test]
+fn test_sdo_upload_request_encoding() {
+    let request = encode_sdo_request(SDO_CMD_UPLOAD_REQ, 0x1018, 2, &[]);
+    // CoE header: service 2 (SDO request) in bits 12-15
+    assert_eq!(request, vec![0x00, 0x20, 0x40, 0x18, 0x10, 0x02, 0x00, 0x00, 0x00, 0x00]);
+}
+
+#[test]
+fn test_sdo_download_encoding() {
+    // Expedited: 2 bytes, 2 unused (n = 2 in bits 2-3)
+    assert_eq!(
+        encode_sdo_download(0x6040, 0, &[0x0F, 0x00]),
+        vec![0x00, 0x20, 0x2B, 0x40, 0x60, 0x00, 0x0F, 0x00, 0x00, 0x00]
+    );
+    assert_eq!(encode_sdo_download(0x1C12, 0, &[0x01])[2], 0x2F);
+    assert_eq!(encode_sdo_download(0x1C12, 1, &[0x00, 0x16, 0x00, 0x00])[2], 0x23);
+    // Normal: complete size, then the data
+    assert_eq!(
+        encode_sdo_download(0x2000, 1, &[1, 2, 3, 4, 5]),
+        vec![0x00, 0x20, 0x21, 0x00, 0x20, 0x01, 0x05, 0x00, 0x00, 0x00, 1, 2, 3, 4, 5]
+    );
+}
+
+#[test]
+fn test_sdo_upload_response_decoding() {
+    // Expedited, size indicated, 1 byte (n = 3)
+    let payload = [0x00, 0x30, 0x4F, 0x12, 0x1C, 0x00, 0x02, 0x00, 0x00, 0x00];
+    assert_eq!(decode_sdo_upload_response(&payload, 0x1C12, 0).unwrap(), (1, vec![0x02]));
+    // Answer for another object
+    assert!(matches!(decode_sdo_upload_response(&payload, 0x1C13, 0), Err(MailboxError::Protocol(_))));
+
+    // Normal: complete size 12, 4 bytes in this frame, the rest follows in segments
+    let mut payload = vec![0x00, 0x30, 0x41, 0x08, 0x10, 0x00, 0x0C, 0x00, 0x00, 0x00];
+    payload.extend_from_slice(b"EL31");
+    assert_eq!(decode_sdo_upload_response(&payload, 0x1008, 0).unwrap(), (12, b"EL31".to_vec()));
+
+    // Abort: object does not exist
+    let payload = [0x00, 0x30, 0x80, 0x00, 0x20, 0x01, 0x00, 0x00, 0x02, 0x06];
+    assert!(matches!(
+        decode_sdo_upload_response(&payload, 0x2000, 1),
+        Err(MailboxError::SdoAbort(0x0602_0000))
+    ));
+}
+
+#[test]
+fn test_sdo_segment_response_decoding() {
+    // Minimum-length last segment, toggle set, 3 of 7 bytes used (n = 4)
+    let payload = [0x00, 0x30, 0x10 | (4 << 1) | 0x01, b'5', b'2', b'-', 0, 0, 0, 0];
+    let (last, data) = decode_sdo_segment_response(&payload, true).unwrap();
+    assert!(last);
+    assert_eq!(data, b"52-");
+    assert!(matches!(decode_sdo_segment_response(&payload, false), Err(MailboxError::Protocol(_))));
+
+    // Longer segment: all bytes after the command are data
+    let mut payload = vec![0x00, 0x30, 0x00];
+    payload.extend_from_slice(b"0000-0000");
+    let (last, data) = decode_sdo_segment_response(&payload, false).unwrap();
+    assert!(!last);
+    assert_eq!(data, b"0000-0000");
+}
+
+#[test]
+fn test_soe_header_roundtrip() {
+    let header = SoeHeader {
+        opcode: SOE_OPCODE_WRITE_REQ,
+        incomplete: true,
+        error: false,
+        drive_no: 2,
+        elements: SOE_ELEMENT_VALUE,
+        idn: 0x8001,
+    };
+    let raw = header.encode();
+    assert_eq!(raw, [0x03 | 0x08 | (2 << 5), 0x40, 0x01, 0x80]);
+    assert_eq!(SoeHeader::decode(&raw), Some(header));
+    assert_eq!(SoeHeader::decode(&raw[..3]), None);
+}
+
+#[test]
+fn test_soe_error_response() {
+    // Read response with error flag, error code 0x1001 (IDN does not exist)
+    let payload = [SOE_OPCODE_READ_RES | 0x10, 0x40, 0x20, 0x00, 0x01, 0x10];
+    assert!(matches!(
+        soe_check_response(&payload, SOE_OPCODE_READ_RES),
+        Err(MailboxError::Soe(0x1001))
+    ));
+
+    let payload = [SOE_OPCODE_WRITE_RES, 0x40, 0x20, 0x00];
+    assert!(matches!(
+        soe_check_response(&payload, SOE_OPCODE_READ_RES),
+        Err(MailboxError::Protocol(_))
+    ));
+    assert!(soe_check_response(&payload, SOE_OPCODE_WRITE_RES).is_ok());
+}
+
+#[test]
+fn test_soe_value_len_from_attribute() {
+    assert_eq!(soe_value_len_from_attribute(0x0001_0000), Some(2));
+    assert_eq!(soe_value_len_from_attribute(0x0002_0000), Some(4));
+    assert_eq!(soe_value_len_from_attribute(0x0003_0000), Some(8));
+    assert_eq!(soe_value_len_from_attribute(0x0004_0000), None);
+    assert_eq!(soe_value_len_from_attribute(0), None);
+}
+
+#[test]
+fn test_classify_mailbox_status() {
+    // Empty regardless of toggle
+    assert_eq!(classify_mailbox_status(0x00, 0), MailboxPoll::Empty);
+    assert_eq!(classify_mailbox_status(0x02, 2), MailboxPoll::Empty);
+    // First run accepts any full mailbox
+    assert_eq!(classify_mailbox_status(0x08, 2), MailboxPoll::NewMail);
+    assert_eq!(classify_mailbox_status(0x0A, 2), MailboxPoll::NewMail);
+    // Toggle changed
+    assert_eq!(classify_mailbox_status(0x0A, 0), MailboxPoll::NewMail);
+    assert_eq!(classify_mailbox_status(0x08, 1), MailboxPoll::NewMail);
+    // Toggle unchanged: re-read of an old frame
+    assert_eq!(classify_mailbox_status(0x08, 0), MailboxPoll::Stale);
+    assert_eq!(classify_mailbox_status(0x0A, 1), MailboxPoll::Stale);
+}
+
+#[test]
+fn test_sdo_info_request_encoding() {
+    let request = encode_sdo_info_request(SDO_INFO_GET_ED_REQ, &[0x00, 0x60, 0x01, 0x00]);
+    // CoE header: service 8 (SDO Information) in bits 12-15
+    assert_eq!(request, vec![0x00, 0x80, 0x05, 0x00, 0x00, 0x00, 0x00, 0x60, 0x01, 0x00]);
+}
+
+#[test]
+fn test_sdo_entry_description_response() {
+    // Entry description for 0x6000:01, BOOL, 1 bit, access 0x0087 (read, TxPDO mappable), name "Input 1"
+    let mut payload = vec![0x00, 0x80, SDO_INFO_GET_ED_RES | 0x80, 0x00, 0x01, 0x00];
+    payload.extend_from_slice(&[0x00, 0x60, 0x01, 0x00, 0x01, 0x00, 0x01, 0x00, 0x87, 0x00]);
+    payload.extend_from_slice(b"Input 1");
+
+    let (opcode, incomplete, data) = decode_sdo_info_response(&payload).unwrap();
+    assert_eq!(opcode, SDO_INFO_GET_ED_RES);
+    assert!(incomplete);
+
+    let desc = parse_sdo_entry_description(data).unwrap();
+    assert_eq!(desc.data_type, 0x0001);
+    assert_eq!(desc.bit_len, 1);
+    assert_eq!(desc.name, "Input 1");
+    assert!(desc.access.read_access());
+    assert!(desc.access.tx_pdo_mapping());
+    assert!(!desc.access.rx_pdo_mapping());
+    assert!(parse_sdo_entry_description(&data[..9]).is_none());
+
+    // CoE emergency (service 1) is not an SDO Information response
+    let emergency = [0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+    assert!(decode_sdo_info_response(&emergency).is_none());
+}
+
+#[test]
+fn test_od_list_response() {
+    // List type 0x0001 (all objects), then 0x1000, 0x1018, 0x6000
+    let data = [0x01, 0x00, 0x00, 0x10, 0x18, 0x10, 0x00, 0x60];
+    assert_eq!(parse_od_list(&data), vec![0x1000, 0x1018, 0x6000]);
+    assert!(parse_od_list(&data[..2]).is_empty());
+    assert!(parse_od_list(&[]).is_empty());
+}
+
+#[test]
+#[serial]
+fn test_unsolicited_coe_emergency_is_stored() {
+    *LAST_EMERGENCY.lock() = None;
+
+    // SoE frame and an SDO response arriving out of turn are dropped
+    let soe = MailboxFrame { mailbox_type: MAILBOX_TYPE_SOE, counter: 1, payload: vec![0x02, 0x40, 0x1E, 0x00] };
+    mailbox_unsolicited(0x1002, &soe);
+    let sdo = MailboxFrame { mailbox_type: MAILBOX_TYPE_COE, counter: 2, payload: vec![0x00, 0x30, 0x60, 0, 0, 0, 0, 0, 0, 0] };
+    mailbox_unsolicited(0x1002, &sdo);
+    assert!(LAST_EMERGENCY.lock().is_none());
+
+    // CoE service 1: error code 0x8110 (CAN overrun), error register 0x11
+    let emergency = MailboxFrame {
+        mailbox_type: MAILBOX_TYPE_COE,
+        counter: 3,
+        payload: vec![0x00, 0x10, 0x10, 0x81, 0x11, 0, 0, 0, 0, 0],
+    };
+    mailbox_unsolicited(0x1002, &emergency);
+    let stored = LAST_EMERGENCY.lock().take().unwrap();
+    assert_eq!(stored.configured_address, 0x1002);
+    assert_eq!(stored.error_code, 0x8110);
+    assert_eq!(stored.error_register, 0x11);
+}
+
+#[test]
+fn test_repeated_frame_detection_uses_mailbox_counter() {
+    // Nothing received yet, or a different counter: new frame
+    assert!(!is_repeated_frame(1, None));
+    assert!(!is_repeated_frame(2, Some(1)));
+    assert!(!is_repeated_frame(1, Some(7)));
+    // Same counter as the previous frame: re-delivered
+    assert!(is_repeated_frame(3, Some(3)));
+    // Counter 0: the slave does not number its frames
+    assert!(!is_repeated_frame(0, Some(0)));
+}
@@ -0,0 +1,234 @@
+use super::*;
+
+fn desc(bit_len: u16, access: u16) -> SdoEntryDescription {
+    SdoEntryDescription {
+        data_type: 0x0006,
+        bit_len,
+        access: ObjectAccess::from_u16(access),
+        name: String::new(),
+    }
+}
+
+#[test]
+fn test_object_access_bits() {
+    // Read in all states, write in PreOp, RxPDO mappable
+    let access = ObjectAccess::from_u16(0x004F);
+    assert!(access.read_access());
+    assert!(access.write_access());
+    assert!(access.rx_pdo_mapping());
+    assert!(!access.tx_pdo_mapping());
+    assert!(!access.backup_param());
+
+    let access = ObjectAccess::from_u16(0x0387);
+    assert!(!access.write_access());
+    assert!(access.tx_pdo_mapping());
+    assert!(access.backup_param());
+    assert!(access.settings_param());
+}
+
+#[test]
+fn test_build_pdo_remap() {
+    let remap = build_pdo_remap(
+        SM_RXPDO_ASSIGN,
+        &[0x1600, 0x1601],
+        &[2, 1],
+        &[0x6040_0010, 0x607A_0020, 0x6060_0008],
+    )
+    .unwrap();
+    assert_eq!(
+        remap,
+        vec![
+            PdoRemap { index: 0x1600, entries: vec![0x6040_0010, 0x607A_0020] },
+            PdoRemap { index: 0x1601, entries: vec![0x6060_0008] },
+        ]
+    );
+
+    // Clearing the assignment is allowed
+    assert_eq!(build_pdo_remap(SM_TXPDO_ASSIGN, &[], &[], &[]).unwrap(), vec![]);
+}
+
+#[test]
+fn test_build_pdo_remap_rejects_bad_requests() {
+    // Not an assignment object
+    assert!(build_pdo_remap(0x1C10, &[0x1600], &[0], &[]).is_err());
+    // TxPDO mapping object in the RxPDO assignment
+    assert!(build_pdo_remap(SM_RXPDO_ASSIGN, &[0x1A00], &[0], &[]).is_err());
+    // Counts do not add up
+    assert!(build_pdo_remap(SM_TXPDO_ASSIGN, &[0x1A00], &[2], &[0x6041_0010]).is_err());
+    assert!(build_pdo_remap(SM_TXPDO_ASSIGN, &[0x1A00], &[], &[]).is_err());
+    // Same PDO twice
+    assert!(build_pdo_remap(SM_TXPDO_ASSIGN, &[0x1A00, 0x1A00], &[0, 0], &[]).is_err());
+    // Zero bit length
+    assert!(build_pdo_remap(SM_TXPDO_ASSIGN, &[0x1A00], &[1], &[0x6041_0000]).is_err());
+    // Sub-index 0 counts at most 254 PDOs or entries
+    let pdos: Vec<u16> = (0x1A00..0x1A00 + 255).collect();
+    assert!(build_pdo_remap(SM_TXPDO_ASSIGN, &pdos, &[0; 255], &[]).is_err());
+    assert!(build_pdo_remap(SM_TXPDO_ASSIGN, &pdos[..254], &[0; 254], &[]).is_ok());
+    assert!(build_pdo_remap(SM_TXPDO_ASSIGN, &[0x1A00], &[255], &[0x6041_0010; 255]).is_err());
+}
+
+#[test]
+fn test_check_pdo_entry_mappable() {
+    // Controlword: 16 bit, RxPDO mappable
+    let controlword = desc(16, 0x007F);
+    assert!(check_pdo_entry_mappable(SM_RXPDO_ASSIGN, 0x6040_0010, &controlword).is_ok());
+    assert!(check_pdo_entry_mappable(SM_TXPDO_ASSIGN, 0x6040_0010, &controlword).is_err());
+    // Bit length must match the object
+    assert!(check_pdo_entry_mappable(SM_RXPDO_ASSIGN, 0x6040_0008, &controlword).is_err());
+
+    // Statusword: 16 bit, TxPDO mappable
+    let statusword = desc(16, 0x0087);
+    assert!(check_pdo_entry_mappable(SM_TXPDO_ASSIGN, 0x6041_0010, &statusword).is_ok());
+    assert!(check_pdo_entry_mappable(SM_RXPDO_ASSIGN, 0x6041_0010, &statusword).is_err());
+}
+
+#[test]
+fn test_read_write_bits() {
+    let mut buf = [0u8; 4];
+    // 12-bit value straddling a byte boundary at bit 3
+    write_bits(&mut buf, 3, 12, 0xABC);
+    assert_eq!(read_bits(&buf, 3, 12), 0xABC);
+    assert_eq!(buf, [0xE0, 0x55, 0x00, 0x00]);
+
+    // Neighbouring bits are left intact
+    write_bits(&mut buf, 0, 1, 1);
+    write_bits(&mut buf, 5, 1, 0);
+    assert_eq!(read_bits(&buf, 0, 1), 1);
+    assert_eq!(read_bits(&buf, 3, 12), 0xAB8);
+
+    // Byte-aligned little-endian
+    let mut buf = [0u8; 8];
+    write_bits(&mut buf, 0, 64, 0x0102_0304_0506_0708);
+    assert_eq!(buf, [0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01]);
+    assert_eq!(read_bits(&buf, 16, 16), 0x0506);
+}
+
+#[test]
+fn test_apply_bit_op() {
+    let mut buf = [0x00u8, 0x80];
+    assert_eq!(apply_bit_op(&mut buf, 3, PD_BIT_SET), Some(1));
+    assert_eq!(buf, [0x08, 0x80]);
+    assert_eq!(apply_bit_op(&mut buf, 15, PD_BIT_READ), Some(1));
+    assert_eq!(apply_bit_op(&mut buf, 15, PD_BIT_CLEAR), Some(0));
+    assert_eq!(apply_bit_op(&mut buf, 9, PD_BIT_TOGGLE), Some(1));
+    assert_eq!(apply_bit_op(&mut buf, 9, PD_BIT_TOGGLE), Some(0));
+    assert_eq!(buf, [0x08, 0x00]);
+
+    assert_eq!(apply_bit_op(&mut buf, 16, PD_BIT_READ), None);
+    assert_eq!(apply_bit_op(&mut buf, 0, 4), None);
+    assert_eq!(buf, [0x08, 0x00]);
+}
+
+/// Slave layout with only the image offsets and sizes set, shared with the fail-safe tests
+pub(super) fn layout(output_offset: usize, output_len: usize, input_offset: usize, input_len: usize) -> PdiSlaveLayout {
+    PdiSlaveLayout { output_offset, output_len, input_offset, input_len, ..Default::default() }
+}
+
+#[test]
+fn test_output_writes_reach_slave_images() {
+    // Coupler without process data between two I/O terminals: [Out0 | Out2 | In0 | In2]
+    let image = ProcessImage {
+        layouts: vec![layout(0, 2, 3, 1), layout(2, 0, 4, 0), layout(2, 1, 4, 2)],
+        ..Default::default()
+    };
+    let mut pdi = [0u8; 6];
+
+    // Direct PDI buffer write, and a per-slave byte write (slave 2, byte 0) resolved the way
+    // ethercrab_write_process_data_byte does
+    pdi[0] = 0x11;
+    let (offset, available) = pd_slave_range(&image, "test", 2, true, 0).unwrap();
+    assert_eq!((offset, available), (2, 1));
+    pdi[offset] = 0x22;
+    assert_eq!(pd_slave_range(&image, "test", 1, true, 0), Ok((2, 0)));
+    assert_eq!(pd_slave_range(&image, "test", 2, true, 2), Err(-4));
+    assert_eq!(pd_slave_range(&image, "test", 3, true, 0), Err(-2));
+
+    let mut frames = [vec![0u8; 2], vec![], vec![0u8; 1]];
+    for (layout, frame) in image.layouts.iter().zip(frames.iter_mut()) {
+        copy_slave_outputs(&pdi, layout, frame);
+    }
+    assert_eq!(frames, [vec![0x11, 0x00], vec![], vec![0x22]]);
+
+    // A second cycle sends the same image, nothing is overwritten behind the caller's back
+    for (layout, frame) in image.layouts.iter().zip(frames.iter_mut()) {
+        copy_slave_outputs(&pdi, layout, frame);
+    }
+    assert_eq!(frames[2], vec![0x22]);
+}
+
+#[test]
+fn test_inputs_copied_to_layout_offsets() {
+    let layouts = [layout(0, 2, 3, 1), layout(2, 0, 4, 0), layout(2, 1, 4, 2)];
+    let mut pdi = [0u8; 6];
+    let frames: [&[u8]; 3] = [&[0xA0], &[], &[0xB0, 0xB1]];
+    for (layout, frame) in layouts.iter().zip(frames) {
+        copy_slave_inputs(&mut pdi, layout, frame);
+    }
+    assert_eq!(pdi, [0, 0, 0, 0xA0, 0xB0, 0xB1]);
+}
+
+fn fmmu(logical: u32, fmmu_type: u8, active: bool) -> [u8; 16] {
+    let mut raw = [0u8; 16];
+    raw[0..4].copy_from_slice(&logical.to_le_bytes());
+    raw[11] = fmmu_type;
+    raw[12] = active as u8;
+    raw
+}
+
+#[test]
+fn test_fmmu_logical_addresses() {
+    let fmmus = [fmmu(0x0000_0010, 0x02, true), fmmu(0x0000_0002, 0x01, true), fmmu(0x0000_0000, 0x02, false)];
+    assert_eq!(fmmu_logical_addresses(&fmmus), (0x10, 0x02));
+
+    // Inputs only, and no FMMUs at all
+    assert_eq!(fmmu_logical_addresses(&fmmus[1..2]), (PDI_NO_LOGICAL_ADDRESS, 0x02));
+    assert_eq!(fmmu_logical_addresses(&[]), (PDI_NO_LOGICAL_ADDRESS, PDI_NO_LOGICAL_ADDRESS));
+}
+
+fn entry(index: u16, sub_index: u8, bit_len: u8) -> DiscoveredEntry {
+    DiscoveredEntry::new(index, sub_index, bit_len, guess_data_type(bit_len), String::new())
+}
+
+#[test]
+fn test_slave_symbols_bit_packing() {
+    // EL1008-like inputs: 8 x BOOL, then a drive-like output PDO with a padding gap
+    let pdos = vec![
+        DiscoveredPdo::new(0x1A00, 3, String::new(), (1..=8).map(|i| entry(0x6000, i, 1)).collect()),
+        DiscoveredPdo::new(
+            0x1600,
+            2,
+            String::new(),
+            vec![entry(0x7000, 1, 1), entry(0, 0, 7), entry(0x7010, 1, 16)],
+        ),
+    ];
+    let layout = PdiSlaveLayout { output_offset: 4, output_len: 3, input_offset: 10, input_len: 1, ..Default::default() };
+    let symbols = slave_symbols(2, &pdos, &layout);
+
+    // Padding has no symbol
+    assert_eq!(symbols.len(), 10);
+    let output = symbols.iter().find(|s| s.index == 0x7010).unwrap();
+    assert_eq!(output.is_output, 1);
+    assert_eq!(output.pdo_index, 0x1600);
+    assert_eq!(output.bit_offset, 8);
+    assert_eq!(output.pdi_bit_offset, 4 * 8 + 8);
+    assert_eq!(output.data_type, 0x0006);
+
+    let input = symbols.iter().find(|s| s.index == 0x6000 && s.sub_index == 8).unwrap();
+    assert_eq!(input.is_output, 0);
+    assert_eq!(input.slave_index, 2);
+    assert_eq!(input.bit_offset, 7);
+    assert_eq!(input.pdi_bit_offset, 10 * 8 + 7);
+}
+
+#[test]
+fn test_slave_symbols_skip_mapping_larger_than_image() {
+    let pdos = vec![
+        DiscoveredPdo::new(0x1A00, 3, String::new(), vec![entry(0x6000, 1, 32)]),
+        DiscoveredPdo::new(0x1600, 2, String::new(), vec![entry(0x7000, 1, 8)]),
+    ];
+    // Only 2 input bytes: the 32-bit input does not fit, the output still does
+    let layout = PdiSlaveLayout { output_offset: 0, output_len: 1, input_offset: 1, input_len: 2, ..Default::default() };
+    let symbols = slave_symbols(0, &pdos, &layout);
+    assert_eq!(symbols.len(), 1);
+    assert_eq!(symbols[0].index, 0x7000);
+}
@@ -0,0 +1,94 @@
+use super::*;
+
+#[test]
+fn test_register_target_modes() {
+    assert_eq!(RegisterTarget::from_ffi(REGISTER_ADDR_CONFIGURED, 0x1001), Some(RegisterTarget::Configured(0x1001)));
+    assert_eq!(RegisterTarget::from_ffi(REGISTER_ADDR_POSITION, 3), Some(RegisterTarget::Position(3)));
+    assert_eq!(RegisterTarget::from_ffi(REGISTER_ADDR_BROADCAST, 0xFFFF), Some(RegisterTarget::Broadcast));
+    assert_eq!(RegisterTarget::from_ffi(3, 0), None);
+
+    // Only broadcasts are expected to be answered by more than one slave
+    assert_eq!(RegisterTarget::Configured(0x1001).expected_wkc(5), 1);
+    assert_eq!(RegisterTarget::Position(0).expected_wkc(5), 1);
+    assert_eq!(RegisterTarget::Broadcast.expected_wkc(5), 5);
+}
+
+#[test]
+fn test_split_physical_address() {
+    // ADP in the low word, ADO in the high word, as in the datagram header
+    assert_eq!(split_physical_address(0x0910_1001), (0x1001, 0x0910));
+    assert_eq!(split_physical_address(0x0000_FFFF), (0xFFFF, 0x0000));
+}
+
+fn counters_raw() -> Vec<u8> {
+    let mut raw = vec![0u8; ESC_ERROR_COUNTERS_LEN as usize];
+    raw[0x00] = 3; // port 0 invalid frames
+    raw[0x03] = 7; // port 1 RX errors
+    raw[0x09] = 2; // port 1 forwarded RX errors
+    raw[0x0C] = 1; // processing unit
+    raw[0x0D] = 4; // PDI
+    raw[0x0E] = 0xAA; // PDI error code, not a counter
+    raw[0x13] = 5; // port 3 lost link
+    raw
+}
+
+#[test]
+fn test_esc_error_counters_from_registers() {
+    let counters = FfiEscErrorCounters::from_registers(&counters_raw());
+    assert_eq!(counters.invalid_frame, [3, 0, 0, 0]);
+    assert_eq!(counters.rx_error, [0, 7, 0, 0]);
+    assert_eq!(counters.forwarded_rx_error, [0, 2, 0, 0]);
+    assert_eq!(counters.lost_link, [0, 0, 0, 5]);
+    assert_eq!(counters.processing_unit, 1);
+    assert_eq!(counters.pdi, 4);
+    assert_eq!(counters.port_errors(1), 7);
+    assert_eq!(counters.port_errors(3), 5);
+}
+
+#[test]
+fn test_esc_error_counter_deltas() {
+    let before = FfiEscErrorCounters { rx_error: [10, 0, 0xFE, 0], pdi: 9, ..Default::default() };
+    let now = FfiEscErrorCounters { rx_error: [12, 0, 0xFF, 0], pdi: 2, ..Default::default() };
+    let delta = now.since(&before);
+    assert_eq!(delta.rx_error, [2, 0, 1, 0]);
+    // Went down: cleared in between, counts from zero
+    assert_eq!(delta.pdi, 2);
+}
+
+#[test]
+fn test_esc_error_history_and_ranking() {
+    let start = Instant::now();
+    let mut history = EscErrorHistory { since: start, ..Default::default() };
+    let first = FfiEscErrorCounters::from_registers(&counters_raw());
+
+    // First read is measured from init
+    let reports = history.update(&[Some(first), None], start + Duration::from_secs(2));
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[0].delta, first);
+    assert_eq!(reports[0].interval_ms, 2000);
+    assert_eq!(reports[1].valid, 0);
+
+    // Port 3 lost 5 links in 2 s, port 1 saw 7 RX errors in 2 s, port 0 3 invalid frames
+    let ranks = rank_port_errors(&history.last_reports);
+    let order: Vec<(u16, u8, u32)> = ranks.iter().map(|r| (r.slave_index, r.port, r.errors)).collect();
+    assert_eq!(order, vec![(0, 1, 7), (0, 3, 5), (0, 0, 3)]);
+    assert_eq!(ranks[0].errors_per_second, 3.5);
+
+    // Second read only reports the increase
+    let mut second = first;
+    second.lost_link[0] = 1;
+    let reports = history.update(&[Some(second), Some(first)], start + Duration::from_secs(3));
+    assert_eq!(reports[0].delta.lost_link, [1, 0, 0, 0]);
+    assert_eq!(reports[0].delta.rx_error, [0; 4]);
+    assert_eq!(reports[0].interval_ms, 1000);
+    // Slave 1 answers for the first time: baseline is init
+    assert_eq!(reports[1].interval_ms, 3000);
+    // Equal rates rank the port with more errors first
+    let ranks = rank_port_errors(&history.last_reports);
+    let order: Vec<(u16, u8, u32)> = ranks.iter().map(|r| (r.slave_index, r.port, r.errors)).collect();
+    assert_eq!(order, vec![(1, 1, 7), (1, 3, 5), (1, 0, 3), (0, 0, 1)]);
+    assert_eq!(ranks[3].errors_per_second, 1.0);
+
+    history.clear(start + Duration::from_secs(4));
+    assert!(rank_port_errors(&history.last_reports).is_empty());
+}
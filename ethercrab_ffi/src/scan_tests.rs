@@ -0,0 +1,210 @@
+use super::*;
+
+fn slave(name: &str, address: u16, mailbox_protocols: u16) -> DiscoveredSlave {
+    let mut fixed_name = [0u8; 64];
+    string_to_fixed_bytes(name, &mut fixed_name);
+    DiscoveredSlave {
+        info: FfiSlaveInfo {
+            identity: SlaveIdentity { vendor_id: 2, product_code: 0x0FA23052, revision: 0x0014_0000, serial_number: 0 },
+            name: fixed_name,
+            configured_address: address,
+            alias_address: 0,
+            port_count: 2,
+            _padding: [0],
+            mailbox_protocols,
+            dc_supported: 0,
+            _padding2: [0],
+            dc_64bit: 0,
+            fmmu_count: 3,
+            sm_count: 4,
+            esc_type: 0x11,
+            port_types: [2, 2, 0, 0],
+            esc_features: 0,
+        },
+        name: name.to_string(),
+        topology: FfiSlaveTopology { parent_index: -1, parent_port: 0xFF, ..Default::default() },
+        pdos: Vec::new(),
+        objects: Vec::new(),
+        sii: None,
+    }
+}
+
+fn pdo(index: u16, sync_manager: u8, entries: &[(u16, u8, u8, u16, &str)]) -> DiscoveredPdo {
+    let entries = entries
+        .iter()
+        .map(|&(index, sub_index, bit_len, data_type, name)| {
+            DiscoveredEntry::new(index, sub_index, bit_len, data_type, name.to_string())
+        })
+        .collect();
+    DiscoveredPdo::new(index, sync_manager, String::new(), entries)
+}
+
+/// EK1100 coupler followed by an analog input terminal with a CoE mailbox.
+fn scan() -> ScanContext {
+    let coupler = slave("EK1100 <Coupler>", 1001, 0);
+    let mut terminal = slave("EL3102", 1002, MAILBOX_PROTO_COE);
+    terminal.topology.parent_index = 0;
+    terminal.topology.parent_port = 1;
+    terminal.pdos = vec![
+        pdo(0x1A00, 3, &[(0x6000, 1, 1, 0x0001, "Underrange"), (0, 0, 7, 0, ""), (0x6000, 17, 16, 0x0003, "Value")]),
+        pdo(0x1A01, 3, &[(0x6010, 17, 16, 0x0003, "Value 2")]),
+    ];
+    terminal.sii = Some(SiiInfo {
+        rx_mailbox_offset: 0x1000,
+        rx_mailbox_size: 128,
+        tx_mailbox_offset: 0x1080,
+        tx_mailbox_size: 128,
+        sync_managers: vec![
+            SiiSyncManager { start_address: 0x1000, length: 128, control: 0x26, status: 0, enable: 1, sm_type: 1 },
+            SiiSyncManager { start_address: 0x1080, length: 128, control: 0x22, status: 0, enable: 1, sm_type: 2 },
+            SiiSyncManager { start_address: 0x1100, length: 0, control: 0x24, status: 0, enable: 0, sm_type: 3 },
+            SiiSyncManager { start_address: 0x1180, length: 0, control: 0x20, status: 0, enable: 1, sm_type: 4 },
+        ],
+        ..Default::default()
+    });
+    ScanContext::new(vec![coupler, terminal])
+}
+
+#[test]
+fn test_eni_slave_info_and_topology() {
+    let xml = scan().to_eni_xml();
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+    assert!(xml.contains("<Name>EK1100 &lt;Coupler&gt;</Name>"));
+    assert!(xml.contains("<PhysAddr>1002</PhysAddr>\n        <AutoIncAddr>65535</AutoIncAddr>"));
+    assert!(xml.contains("<ProductCode>262287442</ProductCode>"));
+    assert!(xml.contains("<PreviousPort Selected=\"true\">\n        <Port>B</Port>\n        <PhysAddr>1001</PhysAddr>"));
+    assert_eq!(xml.matches("<PreviousPort").count(), 1);
+    assert!(xml.trim_end().ends_with("</EtherCATConfig>"));
+}
+
+#[test]
+fn test_eni_process_data_layout() {
+    let xml = scan().to_eni_xml();
+    // 1 + 7 + 16 + 16 bits of inputs, no outputs
+    assert!(xml.contains("<Recv>\n          <BitStart>0</BitStart>\n          <BitLength>40</BitLength>"));
+    assert!(!xml.contains("<Send>\n          <BitStart>"));
+    assert!(xml.contains("<Sm3>\n          <Type>Inputs</Type>\n          <DefaultSize>5</DefaultSize>\n          <StartAddress>4480</StartAddress>"));
+    assert!(xml.contains("<Pdo>6656</Pdo>\n          <Pdo>6657</Pdo>"));
+    assert!(xml.contains("<TxPdo Sm=\"3\">\n          <Index>#x1a00</Index>"));
+    // Padding entries keep their bit length but no name or type
+    assert!(xml.contains("<Entry>\n            <Index>#x0000</Index>\n            <BitLen>7</BitLen>\n          </Entry>"));
+
+    assert!(xml.contains("<Inputs>\n        <ByteSize>5</ByteSize>"));
+    assert!(xml.contains("<Name>EL3102.Value</Name>\n          <DataType>INT</DataType>\n          <BitSize>16</BitSize>\n          <BitOffs>8</BitOffs>"));
+    assert!(xml.contains("<Name>EL3102.Value 2</Name>\n          <DataType>INT</DataType>\n          <BitSize>16</BitSize>\n          <BitOffs>24</BitOffs>"));
+    assert!(xml.contains("<Outputs>\n        <ByteSize>0</ByteSize>"));
+}
+
+#[test]
+fn test_eni_mailbox_and_pdo_assignment() {
+    let xml = scan().to_eni_xml();
+    assert_eq!(xml.matches("<Mailbox DataLinkLayer=\"true\">").count(), 1);
+    assert!(xml.contains("<Send>\n          <Start>4096</Start>\n          <Length>128</Length>"));
+    assert!(xml.contains("<StatusBitAddr>2061</StatusBitAddr>\n          <PollTime>20</PollTime>"));
+    assert!(xml.contains("<Protocol>CoE</Protocol>"));
+    // 0x1C13: clear, two PDOs (0x1A00, 0x1A01 as little-endian data), count
+    assert!(xml.contains("<Index>7187</Index>\n              <SubIndex>1</SubIndex>\n              <Data>001a</Data>"));
+    assert!(xml.contains("<Index>7187</Index>\n              <SubIndex>2</SubIndex>\n              <Data>011a</Data>"));
+    assert!(xml.contains("<Index>7187</Index>\n              <SubIndex>0</SubIndex>\n              <Data>02</Data>"));
+    // 0x1C12 has no PDOs: cleared only
+    assert_eq!(xml.matches("<Index>7186</Index>").count(), 1);
+}
+
+// The TS loader round-trip test (src/tests/parse-eni.test.ts) reads this fixture
+#[test]
+fn test_eni_export_matches_fixture() {
+    assert_eq!(scan().to_eni_xml(), include_str!("../../src/tests/fixtures/discovered.eni.xml"));
+}
+
+#[test]
+fn test_eni_data_type_names() {
+    assert_eq!(eni_data_type_name(0x0001, 1), "BOOL");
+    assert_eq!(eni_data_type_name(0x0007, 32), "UDINT");
+    assert_eq!(eni_data_type_name(0x0009, 64), "STRING(8)");
+    assert_eq!(eni_data_type_name(0x0032, 3), "BIT3");
+    assert_eq!(eni_data_type_name(0x0000, 1), "BIT");
+    assert_eq!(eni_data_type_name(0x0000, 12), "BIT12");
+}
+
+#[test]
+fn test_scan_json_document() {
+    let mut ctx = scan();
+    ctx.scan_time_ms = 1_700_000_000_000;
+    let json = ctx.to_json();
+    assert!(json.starts_with("{\"schemaVersion\":1,\"scanTime\":1700000000000,\"slaves\":["));
+    assert!(json.contains("{\"position\":0,\"name\":\"EK1100 <Coupler>\","));
+    assert!(json.contains("\"config\":{\"configuredAddress\":1002,\"aliasAddress\":0,\"portPhysics\":[\"EBUS\",\"EBUS\",\"none\",\"none\"]}"));
+    assert!(json.contains("\"mailbox\":{\"coe\":true,\"foe\":false,\"eoe\":false,\"soe\":false,\"aoe\":false,\"voe\":false}"));
+    // First slave hangs off the master, the terminal off port 1 of the coupler
+    assert!(json.contains("\"parentIndex\":null,\"parentPort\":null"));
+    assert!(json.contains("\"parentIndex\":0,\"parentPort\":1"));
+    assert!(json.contains("\"receiveTimeNs\":null,\"delayNs\":null"));
+    assert!(json.contains(
+        "\"pdos\":{\"inputs\":[{\"index\":6656,\"name\":\"\",\"syncManager\":3,\"entries\":[{\"index\":24576,\"subIndex\":1,\"bitLen\":1,\"dataType\":1,\"name\":\"Underrange\"}"
+    ));
+    assert!(json.contains("\"outputs\":[]},\"objects\":[]}]}"));
+}
+
+#[test]
+fn test_scan_json_objects() {
+    let mut ctx = scan();
+    ctx.slaves[1].objects = vec![
+        DiscoveredObject { index: 0x1000, name: "Device type".to_string() },
+        DiscoveredObject { index: 0x6000, name: String::new() },
+    ];
+    let json = ctx.to_json();
+    assert!(json.contains("\"objects\":[{\"index\":4096,\"name\":\"Device type\"},{\"index\":24576,\"name\":\"\"}]"));
+}
+
+#[test]
+fn test_scan_json_escapes_control_bytes() {
+    // Names come byte for byte from the SII, control bytes included
+    let mut ctx = scan();
+    ctx.slaves[1].name = "EL3102\u{0}".to_string();
+    ctx.slaves[1].pdos = vec![pdo(0x1A00, 3, &[(0x6000, 17, 16, 0x0003, "Val\u{1b}ue")])];
+    let json = ctx.to_json();
+    assert!(json.contains("\"name\":\"EL3102\\u0000\""));
+    assert!(json.contains("\"name\":\"Val\\u001bue\""));
+    assert!(!json.chars().any(|c| (c as u32) < 0x20));
+}
+
+#[test]
+fn test_scan_options_defaults() {
+    let options = FfiScanOptions::from_ptr(std::ptr::null());
+    assert_eq!(options.mailbox_timeout(), Duration::from_millis(1000));
+    assert_eq!(options.eeprom_timeout(), Duration::from_millis(100));
+    assert_eq!(options.max_slaves(), usize::MAX);
+    assert!(options.use_coe());
+    assert!(!options.walk_object_dictionary());
+
+    let options = FfiScanOptions {
+        mailbox_timeout_ms: 5000,
+        eeprom_timeout_ms: 250,
+        max_slaves: 3,
+        flags: SCAN_FLAG_SII_ONLY | SCAN_FLAG_WALK_OD,
+        ..Default::default()
+    };
+    assert_eq!(options.timeouts().mailbox_response, Duration::from_millis(5000));
+    assert_eq!(options.timeouts().eeprom, Duration::from_millis(250));
+    assert_eq!(options.max_slaves(), 3);
+    // The OD walk needs CoE
+    assert!(!options.use_coe());
+    assert!(!options.walk_object_dictionary());
+}
+
+#[test]
+fn test_scan_names_are_not_truncated() {
+    let long_name = "EL6695-1001 EtherCAT Bridge terminal, primary side, with extended diagnostics";
+    assert!(long_name.len() > 64);
+    let mut ctx = scan();
+    ctx.slaves[1].name = long_name.to_string();
+    ctx.slaves[1].pdos[0].entries[0] = DiscoveredEntry::new(0x6000, 1, 1, 0x0001, format!("{} input", long_name));
+
+    // The FFI struct keeps the truncated, NUL-terminated copy
+    assert_eq!(ctx.slaves[1].pdos[0].entries[0].info.name[63], 0);
+
+    let json = ctx.to_json();
+    assert!(json.contains(&format!("\"name\":\"{}\"", long_name)));
+    assert!(json.contains(&format!("\"name\":\"{} input\"", long_name)));
+    assert!(ctx.to_eni_xml().contains(&format!("<Name>{}</Name>", long_name)));
+}
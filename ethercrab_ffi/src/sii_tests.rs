@@ -0,0 +1,266 @@
+use super::*;
+
+#[test]
+fn test_sii_config_crc_appended_is_zero() {
+    // Appending the CRC to the data must give a zero remainder (no final XOR)
+    let config = [0x80, 0x0C, 0xE0, 0x88, 0x00, 0x00, 0x00, 0x00, 0x34, 0x12, 0x00, 0x00, 0x00, 0x00];
+    let crc = sii_config_crc(&config);
+    let mut with_crc = config.to_vec();
+    with_crc.push(crc);
+
+    let mut remainder: u8 = 0xFF;
+    for &byte in &with_crc {
+        remainder ^= byte;
+        for _ in 0..8 {
+            remainder = if (remainder & 0x80) != 0 { (remainder << 1) ^ 0x07 } else { remainder << 1 };
+        }
+    }
+    assert_eq!(remainder, 0);
+}
+
+#[test]
+fn test_sii_config_crc_only_covers_words_0_to_6() {
+    let mut image = [0u8; 32];
+    image[8] = 0x34; // station alias (word 4)
+    let crc = sii_config_crc(&image);
+    image[20] = 0xAA; // outside the configuration area
+    assert_eq!(sii_config_crc(&image), crc);
+    image[2] = 0x01;
+    assert_ne!(sii_config_crc(&image), crc);
+}
+
+#[test]
+fn test_sii_fix_config_crc() {
+    let mut image = [0u8; 16];
+    image[8] = 0x01;
+    image[15] = 0x5A; // high byte of word 7 is reserved and must be preserved
+    assert!(sii_fix_config_crc(&mut image));
+    assert_eq!(image[14], sii_config_crc(&image));
+    assert_eq!(image[15], 0x5A);
+    assert!(!sii_fix_config_crc(&mut image));
+
+    // Too short to hold word 7
+    let mut short = [0u8; 14];
+    assert!(!sii_fix_config_crc(&mut short));
+}
+
+#[test]
+fn test_sii_diff_words() {
+    let current = [0x00, 0x01, 0x02, 0x03, 0x04, 0x05];
+    let target = [0x00, 0x01, 0xFF, 0x03, 0x04, 0x06];
+    assert_eq!(sii_diff_words(&current, &target), vec![1, 2]);
+    assert!(sii_diff_words(&current, &current).is_empty());
+}
+
+/// Build an SII image with header words 0-0x3F and the given categories.
+fn build_image(categories: &[(u16, Vec<u8>)]) -> Vec<u8> {
+    let mut image = vec![0u8; 0x80];
+    image[0x08..0x0A].copy_from_slice(&0x1234u16.to_le_bytes()); // alias
+    image[0x10..0x14].copy_from_slice(&0x0000_0002u32.to_le_bytes()); // vendor
+    image[0x14..0x18].copy_from_slice(&0x044C_2C52u32.to_le_bytes()); // product
+    image[0x30..0x32].copy_from_slice(&0x1000u16.to_le_bytes()); // rx mailbox offset
+    image[0x32..0x34].copy_from_slice(&128u16.to_le_bytes());
+    image[0x38..0x3A].copy_from_slice(&0x000C_u16.to_le_bytes()); // CoE + FoE
+    image[0x7C..0x7E].copy_from_slice(&7u16.to_le_bytes()); // 1 KiB
+    for (category, data) in categories {
+        let mut data = data.clone();
+        if data.len() % 2 == 1 {
+            data.push(0);
+        }
+        image.extend_from_slice(&category.to_le_bytes());
+        image.extend_from_slice(&((data.len() / 2) as u16).to_le_bytes());
+        image.extend_from_slice(&data);
+    }
+    image.extend_from_slice(&[0xFF, 0xFF]);
+    image
+}
+
+fn strings_category(strings: &[&str]) -> Vec<u8> {
+    let mut data = vec![strings.len() as u8];
+    for s in strings {
+        data.push(s.len() as u8);
+        data.extend_from_slice(s.as_bytes());
+    }
+    data
+}
+
+#[test]
+fn test_parse_sii_header() {
+    let info = parse_sii(&build_image(&[]));
+    assert_eq!(info.station_alias, 0x1234);
+    assert_eq!(info.vendor_id, 2);
+    assert_eq!(info.product_code, 0x044C_2C52);
+    assert_eq!(info.rx_mailbox_offset, 0x1000);
+    assert_eq!(info.rx_mailbox_size, 128);
+    assert_eq!(info.mailbox_protocols, 0x000C);
+    assert_eq!(info.size_bytes, 1024);
+    assert!(info.general.is_none());
+}
+
+#[test]
+fn test_parse_sii_categories() {
+    let mut general = vec![0u8; 32];
+    general[0] = 1; // group
+    general[2] = 2; // order
+    general[3] = 3; // name
+    general[5] = 0x23; // CoE details
+    general[12..14].copy_from_slice(&(-100i16).to_le_bytes());
+
+    let mut syncm = Vec::new();
+    syncm.extend_from_slice(&[0x00, 0x10, 0x80, 0x00, 0x26, 0x00, 0x01, 0x01]);
+    syncm.extend_from_slice(&[0x80, 0x10, 0x80, 0x00, 0x22, 0x00, 0x01, 0x02]);
+
+    // TxPDO 0x1A00 on SM3 with two entries, the first named "Input 1"
+    let mut txpdo = vec![0x00, 0x1A, 2, 3, 0, 4, 0, 0];
+    txpdo.extend_from_slice(&[0x00, 0x60, 1, 5, 0x01, 1, 0, 0]);
+    txpdo.extend_from_slice(&[0x00, 0x00, 0, 0, 0x00, 7, 0, 0]);
+
+    let mut dc = vec![0u8; 24];
+    dc[14..16].copy_from_slice(&0x0300u16.to_le_bytes());
+    dc[18] = 6;
+
+    let image = build_image(&[
+        (SII_CATEGORY_GENERAL, general),
+        (SII_CATEGORY_STRINGS, strings_category(&["DigIn", "EL1008", "EL1008 8Ch", "Inputs", "Input 1", "DC-Sync"])),
+        (SII_CATEGORY_FMMU, vec![0x02, 0x03, 0xFF, 0xFF]),
+        (SII_CATEGORY_SYNCM, syncm),
+        (SII_CATEGORY_TXPDO, txpdo),
+        (SII_CATEGORY_DC, dc),
+    ]);
+    let info = parse_sii(&image);
+
+    assert_eq!(info.strings.len(), 6);
+    let general = info.general.as_ref().unwrap();
+    assert_eq!(general.group, "DigIn");
+    assert_eq!(general.order, "EL1008");
+    assert_eq!(general.name, "EL1008 8Ch");
+    assert_eq!(general.image, "");
+    assert_eq!(general.coe_details, 0x23);
+    assert_eq!(general.current_on_ebus_ma, -100);
+
+    assert_eq!(info.fmmus, vec![2, 3, 0, 0]);
+    assert_eq!(info.sync_managers.len(), 2);
+    assert_eq!(info.sync_managers[1].start_address, 0x1080);
+    assert_eq!(info.sync_managers[1].sm_type, 2);
+
+    assert_eq!(info.tx_pdos.len(), 1);
+    let pdo = &info.tx_pdos[0];
+    assert_eq!(pdo.index, 0x1A00);
+    assert_eq!(pdo.sync_manager, 3);
+    assert_eq!(pdo.name, "Inputs");
+    assert_eq!(pdo.entries.len(), 2);
+    assert_eq!(pdo.entries[0].index, 0x6000);
+    assert_eq!(pdo.entries[0].name, "Input 1");
+    assert_eq!(pdo.entries[1].bit_len, 7);
+    assert!(info.rx_pdos.is_empty());
+
+    assert_eq!(info.dc_syncs.len(), 1);
+    assert_eq!(info.dc_syncs[0].assign_activate, 0x0300);
+    assert_eq!(info.dc_syncs[0].name, "DC-Sync");
+
+    let json = info.to_json();
+    assert!(json.starts_with("{\"stationAlias\":4660,"));
+    assert!(json.contains("\"name\":\"EL1008 8Ch\""));
+    assert!(json.contains("\"entries\":[{\"index\":24576,\"subIndex\":1,\"name\":\"Input 1\""));
+    assert!(json.contains("\"rxPdos\":[]"));
+}
+
+#[test]
+fn test_parse_sii_truncated_category() {
+    let mut image = build_image(&[(SII_CATEGORY_STRINGS, strings_category(&["abc"]))]);
+    // Cut into the strings category: it must be skipped, not panic
+    image.truncate(0x80 + 6);
+    let info = parse_sii(&image);
+    assert!(info.strings.is_empty());
+    assert_eq!(sii_string(&info.strings, 1), "");
+}
+
+#[test]
+fn test_sii_json_escapes_control_bytes() {
+    let mut general = vec![0u8; 32];
+    general[3] = 1; // name
+    let image = build_image(&[
+        (SII_CATEGORY_GENERAL, general),
+        (SII_CATEGORY_STRINGS, strings_category(&["EL\u{1}1008\u{1f}"])),
+    ]);
+    let json = parse_sii(&image).to_json();
+    assert!(json.contains("\"name\":\"EL\\u00011008\\u001f\""));
+    assert!(!json.chars().any(|c| (c as u32) < 0x20));
+}
+
+#[test]
+fn test_guess_data_type_fallback() {
+    assert_eq!(guess_data_type(1), 0x0001);
+    assert_eq!(guess_data_type(16), 0x0006);
+    assert_eq!(guess_data_type(12), 0x0000);
+}
+
+#[test]
+fn test_sii_lookup_and_discovered_pdos() {
+    let mut general = vec![0u8; 32];
+    general[5] = COE_DETAILS_SDO_INFO | 0x01;
+
+    let mut txpdo = vec![0x00, 0x1A, 2, 3, 0, 1, 0, 0];
+    txpdo.extend_from_slice(&[0x00, 0x60, 1, 2, 0x03, 16, 0, 0]); // INT16 "Value"
+    txpdo.extend_from_slice(&[0x00, 0x60, 2, 0, 0x00, 16, 0, 0]); // no data type
+    // Alternative PDO not assigned to a sync manager
+    txpdo.extend_from_slice(&[0x01, 0x1A, 1, 0xFF, 0, 0, 0, 0]);
+    txpdo.extend_from_slice(&[0x10, 0x60, 1, 0, 0x00, 8, 0, 0]);
+
+    let image = build_image(&[
+        (SII_CATEGORY_STRINGS, strings_category(&["AI Inputs", "Value"])),
+        (SII_CATEGORY_GENERAL, general),
+        (SII_CATEGORY_TXPDO, txpdo),
+    ]);
+    let info = parse_sii(&image);
+    assert!(info.sdo_info_supported());
+    assert_eq!(info.find_pdo(0x1A00).unwrap().name, "AI Inputs");
+    assert_eq!(info.find_pdo_entry(0x6000, 1).unwrap().data_type, 0x03);
+    assert_eq!(info.find_pdo_entry(0x6000, 2).unwrap().data_type, 0);
+    assert!(info.find_pdo_entry(0x6000, 3).is_none());
+
+    let pdos = sii_discovered_pdos(&info);
+    assert_eq!(pdos.len(), 1);
+    assert_eq!(pdos[0].info.index, 0x1A00);
+    assert_eq!(pdos[0].info.sync_manager, 3);
+    assert_eq!(&pdos[0].info.name[..10], b"AI Inputs\0");
+    assert_eq!(pdos[0].entries[0].info.data_type, 0x0003);
+    assert_eq!(&pdos[0].entries[0].info.name[..6], b"Value\0");
+    // Undescribed entries are left for the caller to guess (scan) or keep unknown (process image)
+    assert_eq!(pdos[0].entries[1].info.data_type, 0);
+}
+
+#[test]
+fn test_esc_info_from_registers() {
+    // ET1100: type 0x11, 8 FMMUs, 8 SMs, ports 0/1 EBUS, port 2 MII, port 3 not implemented,
+    // features: DC + 64-bit DC
+    let raw = [0x11, 0x02, 0x00, 0x00, 0x08, 0x08, 0x08, 0b00_11_10_10, 0x0C, 0x00];
+    let esc = EscInfo::from_registers(&raw);
+    assert_eq!(esc.esc_type, 0x11);
+    assert_eq!(esc.fmmu_count, 8);
+    assert_eq!(esc.sm_count, 8);
+    assert_eq!(esc.port_types, [2, 2, 3, 0]);
+    assert_eq!(esc.port_count(), 3);
+    assert!(esc.dc_supported());
+    assert!(esc.dc_64bit());
+
+    let esc = EscInfo::from_registers(&[0u8; 10]);
+    assert_eq!(esc, EscInfo::default());
+    assert!(!esc.dc_supported());
+}
+
+#[test]
+fn test_sii_mailbox_protocols() {
+    let mut info = SiiInfo {
+        mailbox_protocols: 0x04 | 0x08 | 0x20, // CoE, FoE, VoE
+        ..Default::default()
+    };
+    assert_eq!(
+        sii_mailbox_protocols(&info),
+        MAILBOX_PROTO_COE | MAILBOX_PROTO_FOE | MAILBOX_PROTO_VOE
+    );
+
+    info.mailbox_protocols = 0;
+    info.general = Some(SiiGeneral { eoe_details: 1, soe_channels: 1, ..Default::default() });
+    assert_eq!(sii_mailbox_protocols(&info), MAILBOX_PROTO_EOE | MAILBOX_PROTO_SOE);
+}
@@ -0,0 +1,123 @@
+use super::*;
+
+/// Active ports for an EK1100-style coupler or terminal (port 0 in, port 1 out)
+const LINE: [bool; 4] = [true, true, false, false];
+/// Last slave of a line: only the entry port is active
+const END: [bool; 4] = [true, false, false, false];
+
+#[test]
+fn test_dl_status_ports() {
+    // Link on ports 0 and 1, loop open on both, communication on both, port 2/3 loop closed
+    let dl_status = 0x0030 | 0x0200 | 0x0800 | 0x1000 | 0x4000;
+    let (link, loop_closed, communication) = dl_status_ports(dl_status);
+    assert_eq!(link, [true, true, false, false]);
+    assert_eq!(loop_closed, [false, false, true, true]);
+    assert_eq!(communication, [true, true, false, false]);
+}
+
+#[test]
+fn test_split_ports_follows_processing_order() {
+    assert_eq!(split_ports(&[true, true, true, true]), (Some(0), vec![3, 1, 2]));
+    assert_eq!(split_ports(&END), (Some(0), vec![]));
+    assert_eq!(split_ports(&[false; 4]), (None, vec![]));
+}
+
+#[test]
+fn test_compute_parents_line() {
+    let parents = compute_parents(&[LINE, LINE, LINE, END]);
+    assert_eq!(parents, vec![None, Some((0, 1)), Some((1, 1)), Some((2, 1))]);
+}
+
+#[test]
+fn test_compute_parents_ek1122_branch() {
+    // 0: EK1100, 1: EK1122 (ports 0, 1, 3 active), 2-3: line on the X1 branch (port 3),
+    // 4: slave after the EK1122 on the E-bus (port 1)
+    let ek1122 = [true, true, false, true];
+    let parents = compute_parents(&[LINE, ek1122, LINE, END, END]);
+    assert_eq!(
+        parents,
+        vec![None, Some((0, 1)), Some((1, 3)), Some((2, 1)), Some((1, 1))]
+    );
+}
+
+#[test]
+fn test_port_delays() {
+    let times = [1000, 1900, 0, 1300];
+    // Frame enters port 0, leaves through port 3 and returns at 1300, then port 1 at 1900
+    assert_eq!(port_delays(&times, &[true, true, false, true]), [0, 600, 0, 300]);
+    // Receive time counters wrap at 32 bits
+    assert_eq!(port_delays(&[u32::MAX - 9, 10, 0, 0], &LINE), [0, 20, 0, 0]);
+}
+
+#[test]
+fn test_link_monitor_reports_break_once() {
+    // DL status with communication on ports 0 and 1, and on port 0 only
+    const DL_LINE: u16 = 0x0200 | 0x0800;
+    const DL_END: u16 = 0x0200;
+    // 0: EK1100, 1: EK1122 (ports 0, 1, 3), 2: on the X1 branch, 3: after the EK1122
+    let dl_status = [DL_LINE, DL_LINE | 0x8000, DL_END, DL_END];
+    let mut monitor = LinkMonitor::new(vec![0x1000, 0x1001, 0x1002, 0x1003], &dl_status);
+    assert!(monitor.changes(&[true; 4]).is_empty());
+
+    // Slave 2 unplugged from the EK1122's X1 port
+    let changes = monitor.changes(&[true, true, false, true]);
+    assert_eq!(changes, vec![(TopologyLink { parent: Some((1, 3)), child: 2 }, true)]);
+    assert_eq!(link_description(&changes[0].0), "slave 1 port 3 and slave 2");
+    assert!(monitor.changes(&[true, true, false, true]).is_empty());
+
+    // Break behind the coupler: only the first link after the last answering slave counts
+    let changes = monitor.changes(&[true, false, false, false]);
+    assert_eq!(changes, vec![(TopologyLink { parent: Some((0, 1)), child: 1 }, true)]);
+
+    // Everything back
+    let restored: Vec<u16> = monitor.changes(&[true; 4]).iter().map(|(l, lost)| { assert!(!lost); l.child }).collect();
+    assert_eq!(restored, vec![1, 2]);
+    assert!(monitor.lost.iter().all(|&l| !l));
+
+    // Master to first slave
+    let changes = monitor.changes(&[false; 4]);
+    assert_eq!(link_description(&changes[0].0), "the master and slave 0");
+    assert_eq!(changes.len(), 1);
+}
+
+#[test]
+fn test_locate_silent_slaves_probes_up_to_the_break() {
+    const DL_LINE: u16 = 0x0200 | 0x0800;
+    const DL_END: u16 = 0x0200;
+    // Line of five slaves, broken between slave 1 and slave 2
+    let dl_status = [DL_LINE, DL_LINE, DL_LINE, DL_LINE, DL_END];
+    let monitor = LinkMonitor::new(vec![0x1000, 0x1001, 0x1002, 0x1003, 0x1004], &dl_status);
+    let mut probed = Vec::new();
+    let (answering, read) = locate_silent_slaves(&monitor.links, 3, |idx| {
+        probed.push(idx);
+        (idx < 2).then_some(DL_LINE)
+    });
+    // Slaves behind the first silent one are not read
+    assert_eq!(probed, vec![0, 1, 2]);
+    assert_eq!(answering, vec![true, true, false, false, false]);
+    assert_eq!(read[1], Some(DL_LINE));
+
+    // Branch off slave 1 (EK1122 X1) silent: probing stops once the missing slave is found
+    let dl_status = [DL_LINE, DL_LINE | 0x8000, DL_END, DL_LINE, DL_END];
+    let monitor = LinkMonitor::new(vec![0x1000, 0x1001, 0x1002, 0x1003, 0x1004], &dl_status);
+    let mut probed = Vec::new();
+    let (answering, _) = locate_silent_slaves(&monitor.links, 1, |idx| {
+        probed.push(idx);
+        (idx != 2).then_some(DL_LINE)
+    });
+    assert_eq!(probed, vec![0, 1, 2]);
+    assert_eq!(answering, vec![true, true, false, true, true]);
+
+    // Nothing missing: nothing read
+    let (answering, _) = locate_silent_slaves(&monitor.links, 0, |_| unreachable!());
+    assert_eq!(answering, vec![true; 5]);
+}
+
+#[test]
+fn test_find_slave_by_alias() {
+    let aliases = [0x0000, 0x0012, 0x0030, 0x0012, 0x0000];
+    assert_eq!(find_slave_by_alias(&aliases, 0x0030), Ok(2));
+    assert_eq!(find_slave_by_alias(&aliases, 0x0044), Err(vec![]));
+    // Duplicates report every position using the alias
+    assert_eq!(find_slave_by_alias(&aliases, 0x0012), Err(vec![1, 3]));
+}
@@ -0,0 +1,69 @@
+use super::*;
+
+#[test]
+fn test_host_watchdog_trips_once_and_latches() {
+    let start = Instant::now();
+    let mut wd = HostWatchdog::new(Duration::from_millis(100), start);
+
+    assert!(!wd.check(start + Duration::from_millis(100)));
+    wd.kick(start + Duration::from_millis(90));
+    assert!(!wd.check(start + Duration::from_millis(180)));
+
+    // Missed deadline trips exactly once
+    assert!(wd.check(start + Duration::from_millis(191)));
+    assert!(wd.latched);
+    assert!(!wd.check(start + Duration::from_millis(400)));
+    assert_eq!(wd.trip_count, 1);
+
+    // Kicks do not clear the latch, an acknowledge does and restarts the deadline
+    wd.kick(start + Duration::from_millis(410));
+    assert!(wd.latched);
+    wd.acknowledge(start + Duration::from_millis(420));
+    assert!(!wd.latched);
+    assert!(!wd.check(start + Duration::from_millis(500)));
+    assert!(wd.check(start + Duration::from_millis(521)));
+    assert_eq!(wd.trip_count, 2);
+}
+
+#[test]
+fn test_esc_watchdog_settings_default_increment() {
+    // 100 ms with the default 100 µs increment is the ESC reset value
+    assert_eq!(esc_watchdog_settings(100_000, 100_000), (2498, 1000, 1000));
+    assert_eq!(esc_watchdog_time_us(2498, 1000), 100_000);
+
+    // Zero disables a watchdog, short times round to at least one increment
+    assert_eq!(esc_watchdog_settings(0, 50), (2498, 1, 0));
+}
+
+#[test]
+fn test_esc_watchdog_settings_coarse_increment() {
+    // 10 s does not fit 16 bits at 100 µs, the divider grows and both times share it
+    let (divider, pdi_count, sm_count) = esc_watchdog_settings(10_000_000, 100_000);
+    assert!(divider > 2498);
+    let sm_us = esc_watchdog_time_us(divider, sm_count);
+    let pdi_us = esc_watchdog_time_us(divider, pdi_count);
+    assert!((9_999_000..=10_001_000).contains(&sm_us), "sm {}", sm_us);
+    assert!((99_000..=101_000).contains(&pdi_us), "pdi {}", pdi_us);
+
+    // Longest representable time saturates instead of wrapping
+    let (divider, _, sm_count) = esc_watchdog_settings(u32::MAX as u64 * 1000, 0);
+    assert_eq!((divider, sm_count), (u16::MAX, u16::MAX));
+}
+
+#[test]
+fn test_parse_esc_watchdog() {
+    let mut block = [0u8; 0x44];
+    block[0x00..0x02].copy_from_slice(&2498u16.to_le_bytes());
+    block[0x10..0x12].copy_from_slice(&500u16.to_le_bytes());
+    block[0x20..0x22].copy_from_slice(&1000u16.to_le_bytes());
+    block[0x40] = 0x01;
+    block[0x42] = 3;
+    block[0x43] = 1;
+    let watchdog = parse_esc_watchdog(4, &block);
+    assert_eq!(watchdog.slave_index, 4);
+    assert_eq!(watchdog.pdi_time_us, 50_000);
+    assert_eq!(watchdog.sm_time_us, 100_000);
+    assert_eq!(watchdog.status, 0x0001);
+    assert_eq!((watchdog.sm_expired_count, watchdog.pdi_expired_count), (3, 1));
+    assert_eq!((watchdog.valid, watchdog.error), (1, 0));
+}
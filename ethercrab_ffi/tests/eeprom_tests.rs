@@ -103,3 +103,124 @@ fn test_eeprom_read_error_cases() {
     );
     assert_eq!(result, -1);
 }
+
+#[test]
+#[serial]
+fn test_eeprom_write_image_dry_run() {
+    if !should_run_hardware_tests() {
+        return; // Skip if hardware tests not enabled
+    }
+    let _interface = setup_master();
+
+    // Re-submitting the current configuration area must report no differences
+    let mut image = [0u8; 32];
+    let result = ethercrab_eeprom_read(TEST_SLAVE_INDEX, 0, image.as_mut_ptr(), image.len());
+    assert_eq!(result, image.len() as i32);
+
+    let mut diffs = [0u16; 16];
+    let result = ethercrab_eeprom_write_image(
+        TEST_SLAVE_INDEX,
+        image.as_ptr(),
+        image.len(),
+        1,
+        diffs.as_mut_ptr(),
+        diffs.len(),
+    );
+    println!("EEPROM dry run differences: {} {:?}", result, &diffs[..result.max(0) as usize]);
+    assert_eq!(result, 0);
+
+    teardown();
+}
+
+#[test]
+#[serial]
+fn test_eeprom_write_error_cases() {
+    ethercrab_destroy();
+
+    let result = ethercrab_eeprom_write(0, 4, ptr::null(), 2);
+    assert_eq!(result, -4);
+
+    let data = [0x34u8, 0x12, 0x00];
+    let result = ethercrab_eeprom_write(0, 4, data.as_ptr(), 0);
+    assert_eq!(result, -4);
+
+    // Odd length: EEPROM is word addressed
+    let result = ethercrab_eeprom_write(0, 4, data.as_ptr(), 3);
+    assert_eq!(result, -4);
+
+    let result = ethercrab_eeprom_write(0, 4, data.as_ptr(), 2);
+    assert_eq!(result, -1);
+}
+
+#[test]
+#[serial]
+fn test_eeprom_write_image_error_cases() {
+    ethercrab_destroy();
+
+    let result = ethercrab_eeprom_write_image(0, ptr::null(), 16, 1, ptr::null_mut(), 0);
+    assert_eq!(result, -4);
+
+    // Image must at least cover the configuration area (words 0-7)
+    let image = [0u8; 16];
+    let result = ethercrab_eeprom_write_image(0, image.as_ptr(), 14, 1, ptr::null_mut(), 0);
+    assert_eq!(result, -4);
+
+    let result = ethercrab_eeprom_write_image(0, image.as_ptr(), 15, 1, ptr::null_mut(), 0);
+    assert_eq!(result, -4);
+
+    let result = ethercrab_eeprom_write_image(0, image.as_ptr(), image.len(), 1, ptr::null_mut(), 0);
+    assert_eq!(result, -1);
+}
+
+#[test]
+#[serial]
+fn test_sii_read_json() {
+    if !should_run_hardware_tests() {
+        return; // Skip if hardware tests not enabled
+    }
+    let _interface = setup_master();
+
+    let mut buffer = vec![0u8; 65536];
+    let result = ethercrab_sii_read_json(TEST_SLAVE_INDEX, buffer.as_mut_ptr(), buffer.len());
+    assert!(result > 0, "SII read failed: {}", result);
+    let json = std::str::from_utf8(&buffer[..result as usize]).unwrap();
+    println!("SII: {}", json);
+    assert!(json.starts_with("{\"stationAlias\":"));
+
+    teardown();
+}
+
+#[test]
+#[serial]
+fn test_sii_parse_json_without_master() {
+    ethercrab_destroy();
+
+    // Header only (words 0-0x3F) followed by the end marker
+    let mut image = vec![0u8; 0x80];
+    image[0x10] = 0x02; // vendor id
+    image.extend_from_slice(&[0xFF, 0xFF]);
+
+    let mut buffer = [0u8; 1024];
+    let result = ethercrab_sii_parse_json(image.as_ptr(), image.len(), buffer.as_mut_ptr(), buffer.len());
+    assert!(result > 0);
+    let json = std::str::from_utf8(&buffer[..result as usize]).unwrap();
+    assert!(json.contains("\"vendorId\":2"));
+    assert!(json.contains("\"general\":null"));
+
+    // Buffer too small
+    let result = ethercrab_sii_parse_json(image.as_ptr(), image.len(), buffer.as_mut_ptr(), 8);
+    assert_eq!(result, -4);
+}
+
+#[test]
+#[serial]
+fn test_sii_json_error_cases() {
+    ethercrab_destroy();
+
+    let mut buffer = [0u8; 64];
+    assert_eq!(ethercrab_sii_read_json(0, ptr::null_mut(), 64), -4);
+    assert_eq!(ethercrab_sii_read_json(0, buffer.as_mut_ptr(), 0), -4);
+    assert_eq!(ethercrab_sii_read_json(0, buffer.as_mut_ptr(), buffer.len()), -1);
+
+    assert_eq!(ethercrab_sii_parse_json(ptr::null(), 16, buffer.as_mut_ptr(), buffer.len()), -4);
+}
@@ -26,14 +26,75 @@ use ethercrab_ffi::{
     ethercrab_cyclic_tx_rx,
     ethercrab_sdo_read,
     ethercrab_read_process_data_byte,
+    ethercrab_write_process_data_byte,
     ethercrab_register_read_u16,
     ethercrab_register_write_u16,
     ethercrab_scan_new,
+    ethercrab_scan_live,
+    ethercrab_pd_symbol_count,
+    ethercrab_pd_get_symbol,
+    ethercrab_pd_get_u16,
+    ethercrab_pd_set_u16,
+    ethercrab_pd_set_f64,
+    ethercrab_pd_bit,
+    ethercrab_pdi_bit,
+    ethercrab_pd_slave_sizes,
+    ethercrab_pd_layout_count,
+    ethercrab_pd_get_layout,
+    ethercrab_failsafe_configure,
+    ethercrab_failsafe_get_active,
+    ethercrab_failsafe_get_reason,
+    ethercrab_failsafe_reset,
+    ethercrab_watchdog_start,
+    ethercrab_watchdog_stop,
+    ethercrab_watchdog_kick,
+    ethercrab_watchdog_acknowledge,
+    ethercrab_watchdog_get_trip_count,
+    ethercrab_watchdog_get_last_trip,
+    ethercrab_esc_watchdog_configure,
+    ethercrab_esc_watchdog_read,
+    FfiEscWatchdog,
+    ESC_WATCHDOG_KEEP,
+    ethercrab_register_read,
+    ethercrab_register_write,
+    REGISTER_ADDR_CONFIGURED,
+    REGISTER_ADDR_POSITION,
+    REGISTER_ADDR_BROADCAST,
+    ethercrab_raw_datagram,
+    DATAGRAM_FRMW,
+    DATAGRAM_LRD,
+    DATAGRAM_LRW,
+    ethercrab_esc_error_counters_read,
+    ethercrab_esc_error_counters_clear,
+    ethercrab_esc_error_rank,
+    FfiEscErrorReport,
+    FfiEscPortRank,
+    ethercrab_link_monitor_start,
+    ethercrab_link_monitor_check,
+    ethercrab_link_monitor_stop,
+    FfiLinkEvent,
+    ethercrab_find_slave_by_alias,
+    ethercrab_find_slave_by_address,
+    FAILSAFE_MODE_ZERO,
+    ethercrab_pd_read_slave,
+    ethercrab_pd_write_slave,
+    PD_BIT_READ,
+    PD_BIT_SET,
+    ethercrab_scan_new_with_options,
+    ethercrab_scan_get_progress,
+    ethercrab_scan_cancel,
     ethercrab_scan_get_slave_count,
     ethercrab_scan_get_slave,
     ethercrab_scan_get_pdo_count,
+    ethercrab_scan_get_slave_topology,
+    ethercrab_scan_export_eni,
+    ethercrab_scan_get_json,
     ethercrab_scan_free,
     FfiSlaveInfo,
+    FfiSlaveTopology,
+    FfiScanOptions,
+    FfiProcessSymbol,
+    FfiPdiSlaveLayout,
 };
 use std::env;
 use std::ffi::CString;
@@ -213,6 +274,8 @@ fn test_state_transitions() {
         let result = ethercrab_request_state(STATE_SAFEOP);
         assert_eq!(result, 0, "Failed to transition to SafeOp");
         assert_eq!(ethercrab_get_state(), STATE_SAFEOP);
+        // Inputs are exchanged in SafeOp as well
+        assert!(ethercrab_cyclic_tx_rx() >= 0, "SafeOp should allow cyclic communication");
     }
     
     // If in SafeOp or just transitioned, go to Op
@@ -323,6 +386,56 @@ fn test_read_process_data_bytes() {
     teardown_hardware();
 }
 
+#[test]
+#[serial]
+fn test_output_writes_reach_the_wire() {
+    if !should_run_hardware_tests() {
+        println!("Skipping hardware test - ETHERCAT_INTERFACE not set");
+        return;
+    }
+
+    assert!(setup_hardware(), "Failed to initialize hardware");
+    assert_eq!(ethercrab_request_state(STATE_SAFEOP), 0);
+    assert_eq!(ethercrab_request_state(STATE_OP), 0);
+
+    // First slave with outputs and its offset in the PDI buffer
+    let mut target = None;
+    let mut pdi_offset = 0u32;
+    for slave in 0..64u16 {
+        let (mut input_size, mut output_size) = (0u32, 0u32);
+        if ethercrab_pd_slave_sizes(slave, &mut input_size, &mut output_size) != 0 {
+            break;
+        }
+        if output_size > 0 {
+            target = Some(slave);
+            break;
+        }
+        pdi_offset += output_size;
+    }
+    let Some(slave) = target else {
+        println!("No slave with outputs, skipping");
+        teardown_hardware();
+        return;
+    };
+    let pdi = ethercrab_get_pdi_buffer_ptr();
+    assert!(!pdi.is_null());
+
+    // Per-slave byte write: must survive the cycle instead of being overwritten from the PDI buffer
+    assert_eq!(ethercrab_write_process_data_byte(slave, 0, 0xA5), 1);
+    assert_eq!(unsafe { *pdi.add(pdi_offset as usize) }, 0xA5);
+    assert!(ethercrab_cyclic_tx_rx() >= 0);
+    assert_eq!(ethercrab_read_process_data_byte(slave, 0, true), 0xA5);
+
+    // PDI buffer write: sent with the next cycle
+    unsafe { *pdi.add(pdi_offset as usize) = 0x5A; }
+    assert!(ethercrab_cyclic_tx_rx() >= 0);
+    assert_eq!(ethercrab_read_process_data_byte(slave, 0, true), 0x5A);
+
+    unsafe { *pdi.add(pdi_offset as usize) = 0x00; }
+    ethercrab_cyclic_tx_rx();
+    teardown_hardware();
+}
+
 // =============================================================================
 // SDO Communication Tests
 // =============================================================================
@@ -430,6 +543,188 @@ fn test_register_read_watchdog_registers() {
     teardown_hardware();
 }
 
+#[test]
+#[serial]
+fn test_esc_watchdog_configure_all() {
+    if !should_run_hardware_tests() {
+        println!("Skipping hardware test - ETHERCAT_INTERFACE not set");
+        return;
+    }
+
+    assert!(setup_hardware(), "Failed to initialize hardware");
+    assert_eq!(ethercrab_request_state(STATE_PREOP), 0);
+
+    let mut watchdogs = [FfiEscWatchdog::default(); 64];
+    let count = ethercrab_esc_watchdog_configure(100, ESC_WATCHDOG_KEEP, std::ptr::null(), 0, watchdogs.as_mut_ptr(), watchdogs.len());
+    assert!(count > 0, "Watchdog configuration failed: {}", get_last_error());
+    // Failed slaves have valid = 0 and their error set
+    for watchdog in watchdogs.iter().filter(|w| w.valid == 1) {
+        println!(
+            "Slave {}: divider {} SM {} µs PDI {} µs status 0x{:04X}",
+            watchdog.slave_index, watchdog.divider, watchdog.sm_time_us, watchdog.pdi_time_us, watchdog.status
+        );
+        assert_eq!(watchdog.sm_time_us, 100_000);
+    }
+    assert_eq!(watchdogs.iter().filter(|w| w.valid == 1).count(), count as usize);
+
+    // An unknown slave is reported without stopping the others
+    let indices = [u16::MAX, 0];
+    let count = ethercrab_esc_watchdog_configure(100, ESC_WATCHDOG_KEEP, indices.as_ptr(), indices.len(), watchdogs.as_mut_ptr(), 2);
+    assert_eq!(count, 1, "{}", get_last_error());
+    assert_eq!((watchdogs[0].valid, watchdogs[0].error), (0, -2));
+    assert_eq!((watchdogs[1].valid, watchdogs[1].error), (1, 0));
+
+    teardown_hardware();
+}
+
+#[test]
+#[serial]
+fn test_register_access_addressing_modes() {
+    if !should_run_hardware_tests() {
+        println!("Skipping hardware test - ETHERCAT_INTERFACE not set");
+        return;
+    }
+
+    assert!(setup_hardware(), "Failed to initialize hardware");
+
+    // APRD of the configured station address (0x0010) of the first slave in the ring
+    let mut station = [0u8; 2];
+    let mut wkc = 0u16;
+    assert_eq!(ethercrab_register_read(REGISTER_ADDR_POSITION, 0, 0x0010, station.as_mut_ptr(), 2, &mut wkc), 2);
+    assert_eq!(wkc, 1);
+    let address = u16::from_le_bytes(station);
+    println!("Slave at position 0 has station address 0x{:04X}", address);
+
+    // FPRD of the same register through that address must agree
+    let mut readback = [0u8; 2];
+    assert_eq!(ethercrab_register_read(REGISTER_ADDR_CONFIGURED, address, 0x0010, readback.as_mut_ptr(), 2, &mut wkc), 2);
+    assert_eq!(wkc, 1);
+    assert_eq!(readback, station);
+
+    // BRD of the ESC type/revision block is answered by every slave
+    let mut info = [0u8; 10];
+    assert_eq!(ethercrab_register_read(REGISTER_ADDR_BROADCAST, 0, 0x0000, info.as_mut_ptr(), info.len() as u16, &mut wkc), 10);
+    println!("Broadcast read of 0x0000 answered by {} slaves", wkc);
+    assert!(wkc > 0);
+
+    teardown_hardware();
+}
+
+#[test]
+#[serial]
+fn test_raw_datagram_frmw() {
+    if !should_run_hardware_tests() {
+        println!("Skipping hardware test - ETHERCAT_INTERFACE not set");
+        return;
+    }
+
+    assert!(setup_hardware(), "Failed to initialize hardware");
+
+    let mut station = [0u8; 2];
+    let mut wkc = 0u16;
+    assert_eq!(ethercrab_register_read(REGISTER_ADDR_POSITION, 0, 0x0010, station.as_mut_ptr(), 2, &mut wkc), 2);
+    let mut esc_type = [0u8; 1];
+    assert_eq!(ethercrab_register_read(REGISTER_ADDR_BROADCAST, 0, 0x0000, esc_type.as_mut_ptr(), 1, &mut wkc), 1);
+    let slave_count = wkc;
+
+    // FRMW of the DC system time (0x0910) from the first slave, as used for DC drift
+    // compensation: read once, written by every other slave
+    let address = (0x0910u32 << 16) | u16::from_le_bytes(station) as u32;
+    let mut response = [0u8; 8];
+    let count = ethercrab_raw_datagram(DATAGRAM_FRMW, address, std::ptr::null(), 8, slave_count, response.as_mut_ptr(), &mut wkc);
+    assert_eq!(count, 8, "FRMW failed: {}", get_last_error());
+    println!("FRMW 0x{:08X}: WKC {} system time {}", address, wkc, u64::from_le_bytes(response));
+    assert_eq!(wkc, slave_count);
+
+    teardown_hardware();
+}
+
+#[test]
+#[serial]
+fn test_esc_error_counters_clear_and_read() {
+    if !should_run_hardware_tests() {
+        println!("Skipping hardware test - ETHERCAT_INTERFACE not set");
+        return;
+    }
+
+    assert!(setup_hardware(), "Failed to initialize hardware");
+
+    assert_eq!(ethercrab_esc_error_counters_clear(), 0, "Clear failed: {}", get_last_error());
+    std::thread::sleep(std::time::Duration::from_millis(100));
+
+    let mut reports = [FfiEscErrorReport::default(); 64];
+    let count = ethercrab_esc_error_counters_read(reports.as_mut_ptr(), reports.len());
+    assert!(count > 0, "Read failed: {}", get_last_error());
+    for report in &reports[..(count as usize).min(reports.len())] {
+        assert_eq!(report.valid, 1);
+        println!(
+            "Slave {}: RX {:?} invalid {:?} lost link {:?} over {} ms",
+            report.slave_index, report.counters.rx_error, report.counters.invalid_frame, report.counters.lost_link, report.interval_ms
+        );
+    }
+
+    let mut ranks = [FfiEscPortRank::default(); 16];
+    let ranked = ethercrab_esc_error_rank(ranks.as_mut_ptr(), ranks.len());
+    assert!(ranked >= 0);
+    for rank in &ranks[..(ranked as usize).min(ranks.len())] {
+        println!("Slave {} port {}: {} errors ({:.1}/s)", rank.slave_index, rank.port, rank.errors, rank.errors_per_second);
+    }
+
+    teardown_hardware();
+}
+
+#[test]
+#[serial]
+fn test_link_monitor_steady_topology() {
+    if !should_run_hardware_tests() {
+        println!("Skipping hardware test - ETHERCAT_INTERFACE not set");
+        return;
+    }
+
+    assert!(setup_hardware(), "Failed to initialize hardware");
+
+    let count = ethercrab_link_monitor_start();
+    assert!(count > 0, "Monitor start failed: {}", get_last_error());
+
+    // Nothing is unplugged during the test, so no link changes are reported
+    let mut events = [FfiLinkEvent::default(); 8];
+    for _ in 0..5 {
+        assert_eq!(ethercrab_link_monitor_check(events.as_mut_ptr(), events.len()), 0);
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    assert_eq!(ethercrab_link_monitor_stop(), 0);
+    assert_eq!(ethercrab_link_monitor_check(events.as_mut_ptr(), events.len()), -2);
+
+    teardown_hardware();
+}
+
+#[test]
+#[serial]
+fn test_slave_lookup_by_address() {
+    if !should_run_hardware_tests() {
+        println!("Skipping hardware test - ETHERCAT_INTERFACE not set");
+        return;
+    }
+
+    assert!(setup_hardware(), "Failed to initialize hardware");
+
+    // Every slave is found again through its own station address
+    let mut position = 0u16;
+    loop {
+        let mut station = [0u8; 2];
+        let mut wkc = 0u16;
+        if ethercrab_register_read(REGISTER_ADDR_POSITION, position, 0x0010, station.as_mut_ptr(), 2, &mut wkc) < 0 {
+            break;
+        }
+        assert_eq!(ethercrab_find_slave_by_address(u16::from_le_bytes(station)), position as i32);
+        position += 1;
+    }
+    assert!(position > 0);
+    assert_eq!(ethercrab_find_slave_by_alias(0), -4);
+
+    teardown_hardware();
+}
+
 #[test]
 #[serial]
 fn test_register_write_read_roundtrip() {
@@ -545,6 +840,12 @@ fn test_scan_get_slave_info() {
             mailbox_protocols: 0,
             dc_supported: 0,
             _padding2: [0],
+            dc_64bit: 0,
+            fmmu_count: 0,
+            sm_count: 0,
+            esc_type: 0,
+            port_types: [0; 4],
+            esc_features: 0,
         };
         
         let result = ethercrab_scan_get_slave(ctx, i, &mut info);
@@ -562,15 +863,367 @@ fn test_scan_get_slave_info() {
             info.identity.product_code,
             info.configured_address
         );
+        println!(
+            "  Mailbox: 0x{:02X}, DC: {} (64-bit: {}), Ports: {} {:?}, FMMUs: {}, SMs: {}",
+            info.mailbox_protocols,
+            info.dc_supported,
+            info.dc_64bit,
+            info.port_count,
+            info.port_types,
+            info.fmmu_count,
+            info.sm_count
+        );
         
+        let mut topology = FfiSlaveTopology::default();
+        assert_eq!(ethercrab_scan_get_slave_topology(ctx, i, &mut topology), 0);
+        println!(
+            "  Parent: {} port {}, entry port {}, link {:?}, delays {:?} (DC valid: {})",
+            topology.parent_index,
+            topology.parent_port,
+            topology.entry_port,
+            topology.link,
+            topology.port_delay_ns,
+            topology.dc_valid
+        );
+        if i == 0 {
+            assert_eq!(topology.parent_index, -1, "First slave must hang off the master");
+        } else {
+            assert!((topology.parent_index as u32) < i, "Parent must precede slave {}", i);
+        }
+
         // Get PDO count for this slave
         let pdo_count = ethercrab_scan_get_pdo_count(ctx, i);
         println!("  PDO count: {}", pdo_count);
     }
+
+    let mut eni = vec![0u8; 1024 * 1024];
+    let len = ethercrab_scan_export_eni(ctx, eni.as_mut_ptr(), eni.len());
+    assert!(len > 0, "ENI export failed: {}", len);
+    let eni = String::from_utf8_lossy(&eni[..len as usize]);
+    assert_eq!(eni.matches("<Slave>").count(), slave_count as usize);
+    println!("ENI export: {} bytes", len);
+
+    let mut json = vec![0u8; 1024 * 1024];
+    let len = ethercrab_scan_get_json(ctx, json.as_mut_ptr(), json.len());
+    assert!(len > 0, "Scan JSON failed: {}", len);
+    let json = String::from_utf8_lossy(&json[..len as usize]);
+    assert!(json.starts_with("{\"schemaVersion\":1,"));
+    assert_eq!(json.matches("\"position\":").count(), slave_count as usize);
     
     ethercrab_scan_free(ctx);
 }
 
+#[test]
+fn test_scan_get_slave_topology_null_safe() {
+    let mut topology = FfiSlaveTopology::default();
+    assert_eq!(ethercrab_scan_get_slave_topology(std::ptr::null(), 0, &mut topology), -1);
+}
+
+#[test]
+#[serial]
+fn test_scan_export_eni_error_cases() {
+    let mut buffer = [0u8; 16];
+    assert_eq!(ethercrab_scan_export_eni(std::ptr::null(), buffer.as_mut_ptr(), buffer.len()), -1);
+
+    // Buffer too small for even an empty network
+    let ctx = Box::into_raw(Box::new(ethercrab_ffi::ScanContext::new(Vec::new())));
+    assert_eq!(ethercrab_scan_export_eni(ctx, buffer.as_mut_ptr(), buffer.len()), -4);
+    assert_eq!(ethercrab_scan_export_eni(ctx, std::ptr::null_mut(), 0), -4);
+    ethercrab_scan_free(ctx);
+}
+
+#[test]
+#[serial]
+fn test_scan_get_json_error_cases() {
+    let mut buffer = [0u8; 16];
+    assert_eq!(ethercrab_scan_get_json(std::ptr::null(), buffer.as_mut_ptr(), buffer.len()), -1);
+
+    let ctx = Box::into_raw(Box::new(ethercrab_ffi::ScanContext::new(Vec::new())));
+    assert_eq!(ethercrab_scan_get_json(ctx, std::ptr::null_mut(), 0), -4);
+    // {"schemaVersion":1,"scanTime":...,"slaves":[]} does not fit in 16 bytes
+    assert_eq!(ethercrab_scan_get_json(ctx, buffer.as_mut_ptr(), buffer.len()), -4);
+    let mut buffer = [0u8; 256];
+    let len = ethercrab_scan_get_json(ctx, buffer.as_mut_ptr(), buffer.len());
+    assert!(len > 0);
+    assert!(String::from_utf8_lossy(&buffer[..len as usize]).ends_with("\"slaves\":[]}"));
+    ethercrab_scan_free(ctx);
+}
+
+#[test]
+#[serial]
+fn test_scan_live_requires_master() {
+    ethercrab_destroy();
+    assert!(ethercrab_scan_live(std::ptr::null()).is_null());
+}
+
+#[test]
+#[serial]
+fn test_scan_progress_and_cancel_when_idle() {
+    let mut done = u32::MAX;
+    let mut total = u32::MAX;
+    assert_eq!(ethercrab_scan_get_progress(&mut done, &mut total), 0);
+    assert_ne!(done, u32::MAX);
+    assert_ne!(total, u32::MAX);
+    assert_eq!(ethercrab_scan_get_progress(std::ptr::null_mut(), std::ptr::null_mut()), 0);
+    assert_eq!(ethercrab_scan_cancel(), -1);
+}
+
+#[test]
+#[serial]
+fn test_scan_new_with_options_null_interface() {
+    let options = FfiScanOptions { mailbox_timeout_ms: 5000, ..Default::default() };
+    assert!(ethercrab_scan_new_with_options(std::ptr::null(), &options).is_null());
+}
+
+#[test]
+#[serial]
+fn test_pd_typed_access_without_master() {
+    ethercrab_destroy();
+    assert_eq!(ethercrab_pd_symbol_count(), 0);
+    let mut symbol = FfiProcessSymbol::default();
+    assert_eq!(ethercrab_pd_get_symbol(0, &mut symbol), -1);
+    assert_eq!(ethercrab_pd_get_symbol(0, std::ptr::null_mut()), -4);
+
+    let mut value = 0u16;
+    assert_eq!(ethercrab_pd_get_u16(0, 0x6000, 1, &mut value), -1);
+    assert_eq!(ethercrab_pd_get_u16(0, 0x6000, 1, std::ptr::null_mut()), -4);
+    assert_eq!(ethercrab_pd_set_u16(0, 0x7000, 1, 0x1234), -1);
+    assert_eq!(ethercrab_pd_set_f64(0, 0x7000, 1, 1.5), -1);
+}
+
+#[test]
+#[serial]
+fn test_pd_bit_without_master() {
+    ethercrab_destroy();
+    assert_eq!(ethercrab_pd_bit(0, true, 0, PD_BIT_SET), -1);
+    assert_eq!(ethercrab_pd_bit(0, false, 0, PD_BIT_READ), -1);
+    assert_eq!(ethercrab_pdi_bit(0, PD_BIT_READ), -1);
+}
+
+#[test]
+#[serial]
+fn test_pd_slave_slices_without_master() {
+    ethercrab_destroy();
+    let (mut input_size, mut output_size) = (0u32, 0u32);
+    assert_eq!(ethercrab_pd_slave_sizes(0, &mut input_size, &mut output_size), -1);
+    assert_eq!(ethercrab_pd_slave_sizes(0, std::ptr::null_mut(), &mut output_size), -4);
+
+    let mut buf = [0u8; 8];
+    assert_eq!(ethercrab_pd_read_slave(0, false, 0, buf.as_mut_ptr(), buf.len()), -1);
+    assert_eq!(ethercrab_pd_read_slave(0, false, 0, std::ptr::null_mut(), 8), -4);
+    assert_eq!(ethercrab_pd_write_slave(0, 0, buf.as_ptr(), buf.len()), -1);
+    assert_eq!(ethercrab_pd_write_slave(0, 0, std::ptr::null(), 8), -4);
+}
+
+#[test]
+#[serial]
+fn test_pd_layout_without_master() {
+    ethercrab_destroy();
+    assert_eq!(ethercrab_pd_layout_count(), 0);
+    let mut layout = FfiPdiSlaveLayout::default();
+    assert_eq!(ethercrab_pd_get_layout(0, &mut layout), -1);
+    assert_eq!(ethercrab_pd_get_layout(0, std::ptr::null_mut()), -4);
+}
+
+#[test]
+fn test_ffi_pdi_slave_layout_layout() {
+    // Must match FFI_PDI_SLAVE_LAYOUT_SIZE and the offsets read in src/ec_master.ts
+    assert_eq!(std::mem::size_of::<FfiPdiSlaveLayout>(), 24);
+    let layout = FfiPdiSlaveLayout::default();
+    let base = &layout as *const _ as usize;
+    assert_eq!(&layout.input_offset as *const _ as usize - base, 8);
+    assert_eq!(&layout.output_logical as *const _ as usize - base, 16);
+    assert_eq!(&layout.input_logical as *const _ as usize - base, 20);
+}
+
+#[test]
+#[serial]
+fn test_failsafe_without_master() {
+    ethercrab_destroy();
+    assert_eq!(ethercrab_failsafe_configure(0, FAILSAFE_MODE_ZERO, std::ptr::null(), 0), -1);
+    assert_eq!(ethercrab_failsafe_get_active(std::ptr::null_mut(), 0), -1);
+    assert_eq!(ethercrab_failsafe_get_reason(), 0);
+    assert_eq!(ethercrab_failsafe_reset(), -1);
+}
+
+#[test]
+#[serial]
+fn test_host_watchdog_without_master() {
+    ethercrab_destroy();
+    let mut trip = 0u32;
+    let mut message = [0u8; 256];
+    assert_eq!(ethercrab_watchdog_kick(), -1);
+    assert_eq!(ethercrab_watchdog_get_last_trip(&mut trip, message.as_mut_ptr(), message.len()), -1);
+    assert_eq!(ethercrab_watchdog_start(0), -4);
+
+    // The supervisor runs without a master; a miss only latches and records the error
+    assert_eq!(ethercrab_watchdog_start(20), 0);
+    assert_eq!(ethercrab_watchdog_kick(), 0);
+    assert_eq!(ethercrab_watchdog_get_last_trip(&mut trip, message.as_mut_ptr(), message.len()), 0);
+    assert_eq!(trip, 0);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    assert_eq!(ethercrab_watchdog_kick(), 1);
+    assert_eq!(ethercrab_watchdog_get_trip_count(), 1);
+    assert!(get_last_error().contains("heartbeat"));
+
+    // The trip message survives later errors
+    assert_eq!(ethercrab_request_state(3), -1);
+    let n = ethercrab_watchdog_get_last_trip(&mut trip, message.as_mut_ptr(), message.len());
+    assert!(n > 0);
+    assert_eq!(trip, 1);
+    assert!(std::str::from_utf8(&message[..n as usize]).unwrap().contains("heartbeat"));
+
+    assert_eq!(ethercrab_watchdog_acknowledge(), 0);
+    assert_eq!(ethercrab_watchdog_kick(), 0);
+    assert_eq!(ethercrab_watchdog_stop(), 0);
+    assert_eq!(ethercrab_watchdog_stop(), -1);
+    assert_eq!(ethercrab_watchdog_get_trip_count(), 0);
+}
+
+#[test]
+#[serial]
+fn test_esc_watchdog_without_master() {
+    ethercrab_destroy();
+    let mut watchdogs = [FfiEscWatchdog::default(); 4];
+    assert_eq!(ethercrab_esc_watchdog_configure(100, 100, std::ptr::null(), 0, watchdogs.as_mut_ptr(), watchdogs.len()), -1);
+    assert_eq!(ethercrab_esc_watchdog_configure(100, 100, std::ptr::null(), 0, std::ptr::null_mut(), 4), -4);
+    assert_eq!(ethercrab_esc_watchdog_read(0, &mut watchdogs[0]), -1);
+    assert_eq!(ethercrab_esc_watchdog_read(0, std::ptr::null_mut()), -4);
+}
+
+#[test]
+#[serial]
+fn test_register_access_without_master() {
+    ethercrab_destroy();
+    let mut buf = [0u8; 8];
+    let mut wkc = 0u16;
+    assert_eq!(ethercrab_register_read(REGISTER_ADDR_CONFIGURED, 0x1000, 0x0130, buf.as_mut_ptr(), 2, &mut wkc), -1);
+    assert_eq!(ethercrab_register_write(REGISTER_ADDR_BROADCAST, 0, 0x0120, buf.as_ptr(), 2, &mut wkc), -1);
+    assert_eq!(ethercrab_register_read(REGISTER_ADDR_POSITION, 0, 0x0130, std::ptr::null_mut(), 2, &mut wkc), -4);
+    assert_eq!(ethercrab_register_write(REGISTER_ADDR_POSITION, 0, 0x0120, buf.as_ptr(), 2, std::ptr::null_mut()), -4);
+}
+
+#[test]
+#[serial]
+fn test_raw_datagram_without_master() {
+    ethercrab_destroy();
+    let mut response = [0u8; 8];
+    let mut wkc = 0u16;
+    assert_eq!(ethercrab_raw_datagram(DATAGRAM_LRD, 0, std::ptr::null(), 8, 1, response.as_mut_ptr(), &mut wkc), -1);
+    assert_eq!(ethercrab_raw_datagram(DATAGRAM_LRD, 0, std::ptr::null(), 8, 1, std::ptr::null_mut(), &mut wkc), -4);
+    assert_eq!(ethercrab_raw_datagram(DATAGRAM_LRW, 0, std::ptr::null(), 8, 1, response.as_mut_ptr(), std::ptr::null_mut()), -4);
+}
+
+#[test]
+#[serial]
+fn test_esc_error_counters_without_master() {
+    ethercrab_destroy();
+    let mut reports = [FfiEscErrorReport::default(); 4];
+    let mut ranks = [FfiEscPortRank::default(); 4];
+    assert_eq!(ethercrab_esc_error_counters_read(reports.as_mut_ptr(), reports.len()), -1);
+    assert_eq!(ethercrab_esc_error_counters_read(std::ptr::null_mut(), 4), -4);
+    assert_eq!(ethercrab_esc_error_counters_clear(), -1);
+    assert_eq!(ethercrab_esc_error_rank(ranks.as_mut_ptr(), ranks.len()), -1);
+    assert_eq!(ethercrab_esc_error_rank(std::ptr::null_mut(), 4), -4);
+}
+
+#[test]
+fn test_ffi_esc_error_layout() {
+    // Must match FFI_ESC_ERROR_REPORT_SIZE / FFI_ESC_PORT_RANK_SIZE and the offsets read in src/ec_master.ts
+    assert_eq!(std::mem::size_of::<FfiEscErrorReport>(), 44);
+    let report = FfiEscErrorReport::default();
+    let base = &report as *const _ as usize;
+    assert_eq!(&report.counters as *const _ as usize - base, 2);
+    assert_eq!(&report.delta as *const _ as usize - base, 20);
+    assert_eq!(&report.valid as *const _ as usize - base, 38);
+    assert_eq!(&report.interval_ms as *const _ as usize - base, 40);
+
+    assert_eq!(std::mem::size_of::<FfiEscPortRank>(), 12);
+    let rank = FfiEscPortRank::default();
+    let base = &rank as *const _ as usize;
+    assert_eq!(&rank.errors as *const _ as usize - base, 4);
+    assert_eq!(&rank.errors_per_second as *const _ as usize - base, 8);
+}
+
+#[test]
+#[serial]
+fn test_link_monitor_without_master() {
+    ethercrab_destroy();
+    let mut events = [FfiLinkEvent::default(); 4];
+    assert_eq!(ethercrab_link_monitor_start(), -1);
+    assert_eq!(ethercrab_link_monitor_check(events.as_mut_ptr(), events.len()), -1);
+    assert_eq!(ethercrab_link_monitor_check(std::ptr::null_mut(), 4), -4);
+    assert_eq!(ethercrab_link_monitor_stop(), -1);
+}
+
+#[test]
+fn test_ffi_link_event_layout() {
+    // Must match FFI_LINK_EVENT_SIZE and the offsets read in src/ec_master.ts
+    assert_eq!(std::mem::size_of::<FfiLinkEvent>(), 12);
+    let event = FfiLinkEvent::default();
+    let base = &event as *const _ as usize;
+    assert_eq!(&event.lost as *const _ as usize - base, 3);
+    assert_eq!(&event.child_index as *const _ as usize - base, 4);
+    assert_eq!(&event.parent_dl_status as *const _ as usize - base, 10);
+}
+
+#[test]
+#[serial]
+fn test_slave_lookup_without_master() {
+    ethercrab_destroy();
+    assert_eq!(ethercrab_find_slave_by_alias(0x0012), -1);
+    assert_eq!(ethercrab_find_slave_by_address(0x1000), -1);
+}
+
+#[test]
+fn test_ffi_esc_watchdog_layout() {
+    // Must match FFI_ESC_WATCHDOG_SIZE and the offsets read in src/ec_master.ts
+    assert_eq!(std::mem::size_of::<FfiEscWatchdog>(), 24);
+    let watchdog = FfiEscWatchdog::default();
+    let base = &watchdog as *const _ as usize;
+    assert_eq!(&watchdog.pdi_time_us as *const _ as usize - base, 8);
+    assert_eq!(&watchdog.sm_time_us as *const _ as usize - base, 12);
+    assert_eq!(&watchdog.status as *const _ as usize - base, 16);
+    assert_eq!(&watchdog.pdi_expired_count as *const _ as usize - base, 19);
+    assert_eq!(&watchdog.valid as *const _ as usize - base, 20);
+    assert_eq!(&watchdog.error as *const _ as usize - base, 21);
+}
+
+#[test]
+fn test_ffi_process_symbol_layout() {
+    // Must match FFI_PROCESS_SYMBOL_SIZE and the offsets read in src/ec_master.ts
+    assert_eq!(std::mem::size_of::<FfiProcessSymbol>(), 20);
+    let symbol = FfiProcessSymbol::default();
+    let base = &symbol as *const _ as usize;
+    assert_eq!(&symbol.is_output as *const _ as usize - base, 7);
+    assert_eq!(&symbol.bit_offset as *const _ as usize - base, 8);
+    assert_eq!(&symbol.data_type as *const _ as usize - base, 14);
+    assert_eq!(&symbol.pdi_bit_offset as *const _ as usize - base, 16);
+}
+
+#[test]
+fn test_ffi_scan_options_layout() {
+    // Must match FFI_SCAN_OPTIONS_SIZE and encodeScanOptions in src/ec_master.ts
+    assert_eq!(std::mem::size_of::<FfiScanOptions>(), 24);
+    let options = FfiScanOptions::default();
+    let base = &options as *const _ as usize;
+    assert_eq!(&options.pdu_retries as *const _ as usize - base, 12);
+    assert_eq!(&options.flags as *const _ as usize - base, 20);
+}
+
+#[test]
+fn test_ffi_slave_topology_layout() {
+    // Must match FFI_SLAVE_TOPOLOGY_SIZE and the offsets read in src/ec_master.ts
+    assert_eq!(std::mem::size_of::<FfiSlaveTopology>(), 52);
+    let topology = FfiSlaveTopology::default();
+    let base = &topology as *const _ as usize;
+    assert_eq!(&topology.parent_port as *const _ as usize - base, 4);
+    assert_eq!(&topology.link as *const _ as usize - base, 6);
+    assert_eq!(&topology.dc_valid as *const _ as usize - base, 18);
+    assert_eq!(&topology.port_receive_time_ns as *const _ as usize - base, 20);
+    assert_eq!(&topology.port_delay_ns as *const _ as usize - base, 36);
+}
+
 #[test]
 #[serial]
 fn test_scan_free_null_safe() {
@@ -579,3 +1232,30 @@ fn test_scan_free_null_safe() {
     // No crash = success
 }
 
+
+#[test]
+fn test_ffi_slave_info_layout() {
+    // Must match FFI_SLAVE_INFO_SIZE and the offsets read in src/ec_master.ts
+    assert_eq!(std::mem::size_of::<FfiSlaveInfo>(), 100);
+    let info = FfiSlaveInfo {
+        identity: ethercrab_ffi::SlaveIdentity { vendor_id: 0, product_code: 0, revision: 0, serial_number: 0 },
+        name: [0u8; 64],
+        configured_address: 0,
+        alias_address: 0,
+        port_count: 0,
+        _padding: [0],
+        mailbox_protocols: 0,
+        dc_supported: 0,
+        _padding2: [0],
+        dc_64bit: 0,
+        fmmu_count: 0,
+        sm_count: 0,
+        esc_type: 0,
+        port_types: [0; 4],
+        esc_features: 0,
+    };
+    let base = &info as *const _ as usize;
+    assert_eq!(&info.dc_64bit as *const _ as usize - base, 90);
+    assert_eq!(&info.port_types as *const _ as usize - base, 94);
+    assert_eq!(&info.esc_features as *const _ as usize - base, 98);
+}
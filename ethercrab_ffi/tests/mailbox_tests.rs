@@ -217,3 +217,69 @@ fn test_mailbox_resilient_error_cases() {
     // Test with invalid slave index (if master was initialized)
     // This would require setup, so we test the error path only
 }
+
+#[test]
+#[serial]
+fn test_raw_mailbox_exchange() {
+    if !should_run_hardware_tests() {
+        return; // Skip if hardware tests not enabled
+    }
+    let _interface = setup_master();
+
+    // CoE SDO upload request for 0x1000:00 (device type) sent as a raw frame
+    let request = [0x00, 0x20, 0x40, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00];
+    let result = ethercrab_mailbox_send(TEST_SLAVE_INDEX, 0x03, 1, request.as_ptr(), request.len(), 0);
+    if result != 0 {
+        println!("Raw mailbox send returned error: {} (slave may have no mailbox)", result);
+        teardown();
+        return;
+    }
+
+    let mut response = [0u8; 1024];
+    let mut mailbox_type = 0u8;
+    let mut counter = 0u8;
+    let result = ethercrab_mailbox_receive(
+        TEST_SLAVE_INDEX,
+        response.as_mut_ptr(),
+        response.len(),
+        &mut mailbox_type,
+        &mut counter,
+        0,
+    );
+    assert!(result >= 0, "Raw mailbox receive failed: {}", result);
+    println!("Raw mailbox response: type 0x{:X}, counter {}, {:?}", mailbox_type, counter, &response[..result as usize]);
+    assert_eq!(mailbox_type, 0x03);
+
+    teardown();
+}
+
+#[test]
+#[serial]
+fn test_raw_mailbox_send_error_cases() {
+    ethercrab_destroy();
+
+    let data = [0u8; 4];
+    // Type 0x00 is reserved for error replies, type is a 4-bit field
+    assert_eq!(ethercrab_mailbox_send(0, 0x00, 1, data.as_ptr(), data.len(), 0), -4);
+    assert_eq!(ethercrab_mailbox_send(0, 0x10, 1, data.as_ptr(), data.len(), 0), -4);
+    // Counter is a 3-bit field
+    assert_eq!(ethercrab_mailbox_send(0, 0x0F, 8, data.as_ptr(), data.len(), 0), -4);
+    assert_eq!(ethercrab_mailbox_send(0, 0x0F, 1, ptr::null(), 4, 0), -4);
+
+    assert_eq!(ethercrab_mailbox_send(0, 0x0F, 1, data.as_ptr(), data.len(), 0), -1);
+}
+
+#[test]
+#[serial]
+fn test_raw_mailbox_receive_error_cases() {
+    ethercrab_destroy();
+
+    let mut data = [0u8; 4];
+    assert_eq!(ethercrab_mailbox_receive(0, ptr::null_mut(), 4, ptr::null_mut(), ptr::null_mut(), 0), -4);
+    assert_eq!(ethercrab_mailbox_receive(0, data.as_mut_ptr(), 0, ptr::null_mut(), ptr::null_mut(), 0), -4);
+
+    assert_eq!(
+        ethercrab_mailbox_receive(0, data.as_mut_ptr(), data.len(), ptr::null_mut(), ptr::null_mut(), 0),
+        -1
+    );
+}
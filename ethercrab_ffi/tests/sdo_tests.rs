@@ -168,3 +168,74 @@ fn test_sdo_write_error_cases() {
     assert_eq!(result, -4);
 }
 
+
+#[test]
+#[serial]
+fn test_pdo_remap_error_cases() {
+    ethercrab_destroy();
+
+    let pdos = [0x1A00u16];
+    let counts = [1u8];
+    let entries = [0x6000_0110u32];
+
+    // Null arrays with non-zero counts
+    assert_eq!(ethercrab_pdo_remap(0, 0x1C13, ptr::null(), counts.as_ptr(), 1, entries.as_ptr(), 1), -4);
+    assert_eq!(ethercrab_pdo_remap(0, 0x1C13, pdos.as_ptr(), counts.as_ptr(), 1, ptr::null(), 1), -4);
+    // Not an assignment object, RxPDO in the TxPDO assignment, counts not matching the entries
+    assert_eq!(ethercrab_pdo_remap(0, 0x1C10, pdos.as_ptr(), counts.as_ptr(), 1, entries.as_ptr(), 1), -4);
+    let rx = [0x1600u16];
+    assert_eq!(ethercrab_pdo_remap(0, 0x1C13, rx.as_ptr(), counts.as_ptr(), 1, entries.as_ptr(), 1), -4);
+    assert_eq!(ethercrab_pdo_remap(0, 0x1C13, pdos.as_ptr(), counts.as_ptr(), 1, entries.as_ptr(), 0), -4);
+
+    // Valid request without a master
+    assert_eq!(ethercrab_pdo_remap(0, 0x1C13, pdos.as_ptr(), counts.as_ptr(), 1, entries.as_ptr(), 1), -1);
+}
+
+#[test]
+#[serial]
+fn test_pdo_remap_reapplies_current_assignment() {
+    if !should_run_hardware_tests() {
+        return; // Skip if hardware tests not enabled
+    }
+    let _interface = setup_master();
+
+    let read = |index: u16, sub_index: u8| -> Option<u32> {
+        let mut data = [0u8; 4];
+        let result = ethercrab_sdo_read(TEST_SLAVE_INDEX, index, sub_index, data.as_mut_ptr(), 4);
+        (result > 0).then(|| u32::from_le_bytes(data))
+    };
+
+    // Read the current TxPDO assignment and mapping, then write it back unchanged
+    let Some(count) = read(0x1C13, 0).map(|c| c as u8) else {
+        println!("Slave {} has no 0x1C13, skipping", TEST_SLAVE_INDEX);
+        teardown();
+        return;
+    };
+    let mut pdos = Vec::new();
+    let mut counts = Vec::new();
+    let mut entries = Vec::new();
+    for sub_index in 1..=count {
+        let pdo = read(0x1C13, sub_index).unwrap() as u16;
+        let n = read(pdo, 0).unwrap() as u8;
+        pdos.push(pdo);
+        counts.push(n);
+        for entry_sub in 1..=n {
+            entries.push(read(pdo, entry_sub).unwrap());
+        }
+    }
+
+    let result = ethercrab_pdo_remap(
+        TEST_SLAVE_INDEX,
+        0x1C13,
+        pdos.as_ptr(),
+        counts.as_ptr(),
+        pdos.len(),
+        entries.as_ptr(),
+        entries.len(),
+    );
+    println!("PDO remap result: {}", result);
+    // Fixed mappings reject the write with an SDO abort
+    assert!(result == 0 || result == -3);
+
+    teardown();
+}
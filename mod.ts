@@ -2,7 +2,33 @@
 export { AlStatusCode, EcMaster, RegisterAddress, SlaveState } from "./src/ec_master.ts";
 
 // Export types
-export type { EmergencyEvent, EniConfig, StateChangeEvent } from "./src/ec_master.ts";
+export type {
+  DatagramCommand,
+  DatagramResult,
+  EmergencyEvent,
+  EniConfig,
+  EscErrorCounters,
+  EscErrorReport,
+  EscPortRank,
+  EscWatchdogInfo,
+  FailSafeMode,
+  FailSafeReason,
+  FailSafeStatus,
+  LinkEvent,
+  PdDataType,
+  PdiSlaveLayout,
+  PdoAssignment,
+  ProcessSymbol,
+  RawMailboxFrame,
+  RegisterReadResult,
+  RegisterTarget,
+  ScanOptions,
+  ScanResult,
+  SiiInfo,
+  SlaveRef,
+  StateChangeEvent,
+  WatchdogTimeoutEvent,
+} from "./src/ec_master.ts";
 
 // Export error classes
 export {
@@ -22,10 +48,12 @@ export type { ResolvedSlave, SlaveVariable } from "./src/utils/resolve-slaves.ts
 
 // Export additional types that might be useful
 export type {
+  EniPdo,
   EniSlaveConfig,
   ProcessDataEntry,
   ProcessDataMapping,
   ProcessVariable,
+  SlaveTopology,
 } from "./src/types/eni-config.ts";
 
 export type { PdoMapping } from "./src/types/ec_types.ts";
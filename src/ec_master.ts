@@ -1,19 +1,50 @@
 import {
   EMERGENCY_INFO_SIZE,
   ethercrabSymbols,
+  FFI_ESC_ERROR_REPORT_SIZE,
+  FFI_ESC_PORT_RANK_SIZE,
+  FFI_ESC_WATCHDOG_SIZE,
+  FFI_LINK_EVENT_SIZE,
   FFI_PDO_ENTRY_INFO_SIZE,
+  FFI_PDI_SLAVE_LAYOUT_SIZE,
   FFI_PDO_INFO_SIZE,
+  FFI_PROCESS_SYMBOL_SIZE,
+  FFI_SCAN_OPTIONS_SIZE,
   FFI_SLAVE_INFO_SIZE,
+  FFI_SLAVE_TOPOLOGY_SIZE,
   INIT_COMMAND_SIZE,
   SLAVE_IDENTITY_SIZE,
+  SOE_ELEMENT_VALUE,
 } from "./ffi/symbols.ts";
 import {
   AlStatusCode,
+  DatagramCommand,
+  DatagramResult,
   EmergencyEvent,
+  EscErrorCounters,
+  EscErrorReport,
+  EscPortRank,
+  EscWatchdogInfo,
+  FailSafeMode,
+  FailSafeReason,
+  FailSafeStatus,
+  LinkEvent,
+  PdDataType,
+  PdiSlaveLayout,
+  PdoAssignment,
   PdoMapping,
+  ProcessSymbol,
+  RawMailboxFrame,
   RegisterAddress,
+  RegisterReadResult,
+  RegisterTarget,
+  ScanOptions,
+  ScanResult,
+  SiiInfo,
+  SlaveRef,
   SlaveState,
   StateChangeEvent,
+  WatchdogTimeoutEvent,
 } from "./types/ec_types.ts";
 import {
   EniConfig,
@@ -21,6 +52,7 @@ import {
   InitCommand,
   ProcessDataEntry,
   ProcessVariable,
+  SlaveTopology,
 } from "./types/eni-config.ts";
 import {
   EtherCatError,
@@ -33,7 +65,33 @@ import { EventEmitter } from "node:events";
 import { join } from "@std/path";
 
 export { AlStatusCode, RegisterAddress, SlaveState };
-export type { EmergencyEvent, EniConfig, StateChangeEvent };
+export type {
+  DatagramCommand,
+  DatagramResult,
+  EmergencyEvent,
+  EniConfig,
+  EscErrorCounters,
+  EscErrorReport,
+  EscPortRank,
+  EscWatchdogInfo,
+  FailSafeMode,
+  FailSafeReason,
+  FailSafeStatus,
+  LinkEvent,
+  PdDataType,
+  PdiSlaveLayout,
+  PdoAssignment,
+  ProcessSymbol,
+  RawMailboxFrame,
+  RegisterReadResult,
+  RegisterTarget,
+  ScanOptions,
+  ScanResult,
+  SiiInfo,
+  SlaveRef,
+  StateChangeEvent,
+  WatchdogTimeoutEvent,
+};
 
 /**
  * Get the platform and architecture-specific library filename.
@@ -85,6 +143,8 @@ export class EcMaster extends EventEmitter {
   private mailboxToggleBits: Map<number, number> = new Map(); // slaveIndex -> 0 or 1
   private emergencyPollingInterval?: number; // Timer ID
   private lastEmergencySlave: Map<number, EmergencyEvent> = new Map(); // Track per-slave
+  private watchdogTrips = 0;
+  private linkMonitorInterval?: number; // Timer ID
 
   private isClosing = false;
   private isClosed = false;
@@ -186,110 +246,15 @@ export class EcMaster extends EventEmitter {
   /**
    * Discovery Mode: Scan network and generate EniConfig
    */
-  static async discoverNetwork(interfaceName: string, dirPath?: string): Promise<EniConfig> {
-    // Retry configuration constants
-    const MAX_SCAN_RETRIES = 5;
-    const BASE_RETRY_DELAY_MS = 50;
-    const MAX_RETRY_DELAY_MS = 500;
-
+  static async discoverNetwork(
+    interfaceName: string,
+    dirPath?: string,
+    options?: ScanOptions,
+  ): Promise<EniConfig> {
     const dl = EcMaster.openLibrary(dirPath || EcMaster.defaultDirPath);
 
     try {
-      // [Step 1] PROACTIVE CLEANUP: Ensure any previous state is cleared
-      // This reduces the chance of hitting the lock in the first place
-      dl.symbols.ethercrab_destroy();
-
-      const interfaceNameBuffer = new TextEncoder().encode(
-        interfaceName + "\0",
-      );
-
-      // [Step 2] RETRY LOOP with exponential backoff
-      let ctx: Deno.PointerValue | null = null;
-      let lastErrorMsg: string | null = null;
-
-      for (let attempt = 0; attempt <= MAX_SCAN_RETRIES; attempt++) {
-        // Attempt scan
-        // Note: ethercrab_scan_new returns null pointer if STATE is locked
-        ctx = await dl.symbols.ethercrab_scan_new(interfaceNameBuffer);
-
-        // Check if we got a valid pointer (not null and not 0n)
-        if (ctx !== null && ctx !== undefined) {
-          const ctxValue = Deno.UnsafePointer.value(ctx);
-          if (ctxValue !== 0n) {
-            break; // Success, exit loop
-          }
-        }
-
-        // Check for specific errors that should abort retries immediately
-        const errorBuf = new Uint8Array(1024);
-
-        const errorLen = dl.symbols.ethercrab_get_last_error(
-          errorBuf,
-          BigInt(errorBuf.length),
-        );
-        if (errorLen > 0) {
-          const errorMsg = new TextDecoder().decode(errorBuf.slice(0, errorLen)).trim();
-          if (errorMsg.length > 0) {
-            lastErrorMsg = errorMsg;
-          }
-          // Permission errors are fatal and should not be retried
-          if (
-            errorMsg.includes("Permission denied") || errorMsg.includes("Operation not permitted")
-          ) {
-            throw new Error(
-              `Network discovery failed: ${errorMsg}. (Are you running with sudo/admin privileges?)`,
-            );
-          }
-        }
-
-        // If we failed and have retries left, wait and retry
-        if (attempt < MAX_SCAN_RETRIES) {
-          // Exponential backoff with cap
-          const rawDelay = Math.min(
-            BASE_RETRY_DELAY_MS * Math.pow(2, attempt),
-            MAX_RETRY_DELAY_MS,
-          );
-          // Jitter: +/- 20% to avoid synchronized retry storms
-          const jitter = rawDelay * 0.4 * (Math.random() - 0.5);
-          const finalDelay = Math.max(10, Math.floor(rawDelay + jitter));
-
-          // Log warning only if it's not the very first immediate retry
-          if (attempt > 0) {
-            const detail = lastErrorMsg
-              ? `Discovery failed: ${lastErrorMsg}.`
-              : "Discovery lock contention.";
-            console.warn(
-              `[EtherCAT] ${detail} Retrying in ${finalDelay}ms (Attempt ${
-                attempt + 1
-              }/${MAX_SCAN_RETRIES})...`,
-            );
-          }
-
-          await new Promise((r) => setTimeout(r, finalDelay));
-        }
-      }
-
-      // Final check: if we still don't have a valid context, throw error
-      if (ctx === null || ctx === undefined) {
-        const detail = lastErrorMsg
-          ? ` Last error: ${lastErrorMsg}.`
-          : " The EtherCAT master driver is busy or locked.";
-        throw new Error(
-          `Failed to start network scan after ${MAX_SCAN_RETRIES} attempts.${detail}`,
-        );
-      }
-
-      const ctxValue = Deno.UnsafePointer.value(ctx);
-      if (ctxValue === 0n) {
-        const detail = lastErrorMsg
-          ? ` Last error: ${lastErrorMsg}.`
-          : " The EtherCAT master driver is busy or locked.";
-        throw new Error(
-          `Failed to start network scan after ${MAX_SCAN_RETRIES} attempts.${detail}`,
-        );
-      }
-
-      // ctx is now guaranteed to be valid, proceed with scan processing
+      const ctx = await EcMaster.startScan(dl, interfaceName, options);
 
       const slaveCount = dl.symbols.ethercrab_scan_get_slave_count(ctx);
       const slaves: EniSlaveConfig[] = [];
@@ -307,6 +272,7 @@ export class EcMaster extends EventEmitter {
       const slaveInfoBuffer = new Uint8Array(FFI_SLAVE_INFO_SIZE);
       const pdoInfoBuffer = new Uint8Array(FFI_PDO_INFO_SIZE);
       const entryInfoBuffer = new Uint8Array(FFI_PDO_ENTRY_INFO_SIZE);
+      const topologyBuffer = new Uint8Array(FFI_SLAVE_TOPOLOGY_SIZE);
 
       for (let i = 0; i < slaveCount; i++) {
         if (dl.symbols.ethercrab_scan_get_slave(ctx, i, slaveInfoBuffer) < 0) {
@@ -324,6 +290,12 @@ export class EcMaster extends EventEmitter {
         // 86-87: Mailbox Protocols (u16)
         // 88: DC Supported (u8)
         // 89: Padding (u8)
+        // 90: DC 64-bit (u8)
+        // 91: FMMU Count (u8)
+        // 92: SM Count (u8)
+        // 93: ESC Type (u8)
+        // 94-97: Port Types (u8 x4: 0=n/a, 1=not configured, 2=EBUS, 3=MII)
+        // 98-99: ESC Features (u16)
 
         const vendorId = view.getUint32(0, true);
         const productCode = view.getUint32(4, true);
@@ -338,7 +310,9 @@ export class EcMaster extends EventEmitter {
 
         // Parse mailbox protocol flags (CoE is the primary one we use)
         const coe = (mailboxProtocols & 0x01) !== 0;
-        // Note: FoE (0x02), EoE (0x04), and SoE (0x08) flags are available but not yet used
+        const foe = (mailboxProtocols & 0x02) !== 0;
+        const eoe = (mailboxProtocols & 0x04) !== 0;
+        // Note: SoE (0x08), AoE (0x10) and VoE (0x20) flags are available but not yet used
 
         // Track DC support
         if (dcSupported === 1) {
@@ -499,6 +473,21 @@ export class EcMaster extends EventEmitter {
           slaveConfig.pollTime = 20; // Default 20ms poll time (Class B recommendation)
           slaveConfig.supportsCoE = true; // NEW
         }
+        if (foe) slaveConfig.supportsFoE = true;
+        if (eoe) slaveConfig.supportsEoE = true;
+
+        if (dl.symbols.ethercrab_scan_get_slave_topology(ctx, i, topologyBuffer) === 0) {
+          const topology = EcMaster.parseTopology(topologyBuffer);
+          slaveConfig.topology = topology;
+          // Parents always precede their children in propagation order
+          const parent = slaves[topology.parentIndex];
+          if (parent && topology.parentPort !== null && parent.physAddr !== undefined) {
+            slaveConfig.previousPort = {
+              port: "ABCD"[topology.parentPort],
+              physAddr: parent.physAddr,
+            };
+          }
+        }
 
         slaves.push(slaveConfig);
       }
@@ -530,6 +519,262 @@ export class EcMaster extends EventEmitter {
     }
   }
 
+  /**
+   * Discovery Mode: Scan network and return the raw scan as one document
+   * (identities, capabilities, topology, PDOs), without deriving a process image.
+   */
+  static async scanNetwork(
+    interfaceName: string,
+    dirPath?: string,
+    options?: ScanOptions,
+  ): Promise<ScanResult> {
+    const dl = EcMaster.openLibrary(dirPath || EcMaster.defaultDirPath);
+
+    try {
+      const ctx = await EcMaster.startScan(dl, interfaceName, options);
+      try {
+        const buffer = new Uint8Array(1024 * 1024);
+        const result = dl.symbols.ethercrab_scan_get_json(ctx, buffer, BigInt(buffer.length));
+        if (result < 0) {
+          throw new Error(`Scan JSON export failed with code ${result}`);
+        }
+        return JSON.parse(new TextDecoder().decode(buffer.subarray(0, result)));
+      } finally {
+        dl.symbols.ethercrab_scan_free(ctx);
+      }
+    } finally {
+      dl.close();
+    }
+  }
+
+  /**
+   * Discovery Mode: Scan network and export it as an ETG.2100 ENI XML document
+   * (slaves, PDO assignment, mailbox settings, process image and PS init commands).
+   * The result can be saved and loaded again with loadEniFromXml().
+   */
+  static async discoverNetworkEni(
+    interfaceName: string,
+    dirPath?: string,
+    options?: ScanOptions,
+  ): Promise<string> {
+    const dl = EcMaster.openLibrary(dirPath || EcMaster.defaultDirPath);
+
+    try {
+      const ctx = await EcMaster.startScan(dl, interfaceName, options);
+      try {
+        // ~2-10 KB per slave, 1 MB covers the largest networks
+        const buffer = new Uint8Array(1024 * 1024);
+        const result = dl.symbols.ethercrab_scan_export_eni(ctx, buffer, BigInt(buffer.length));
+        if (result < 0) {
+          throw new Error(`ENI export failed with code ${result}`);
+        }
+        return new TextDecoder().decode(buffer.subarray(0, result));
+      } finally {
+        dl.symbols.ethercrab_scan_free(ctx);
+      }
+    } finally {
+      dl.close();
+    }
+  }
+
+  /**
+   * Start a scan on a fresh context, retrying while the driver is locked.
+   * The caller owns the returned context and must release it with ethercrab_scan_free.
+   */
+  private static async startScan(
+    dl: Deno.DynamicLibrary<typeof ethercrabSymbols>,
+    interfaceName: string,
+    options?: ScanOptions,
+  ): Promise<Deno.PointerObject> {
+    // Retry configuration constants
+    const MAX_SCAN_RETRIES = 5;
+    const BASE_RETRY_DELAY_MS = 50;
+    const MAX_RETRY_DELAY_MS = 500;
+
+    // [Step 1] PROACTIVE CLEANUP: Ensure any previous state is cleared
+    // This reduces the chance of hitting the lock in the first place
+    dl.symbols.ethercrab_destroy();
+
+    const interfaceNameBuffer = new TextEncoder().encode(
+      interfaceName + "\0",
+    );
+    const optionsBuffer = EcMaster.encodeScanOptions(options);
+
+    // [Step 2] RETRY LOOP with exponential backoff
+    let ctx: Deno.PointerValue | null = null;
+    let lastErrorMsg: string | null = null;
+
+    for (let attempt = 0; attempt <= MAX_SCAN_RETRIES; attempt++) {
+      // Attempt scan
+      // Note: ethercrab_scan_new returns null pointer if STATE is locked
+      options?.signal?.throwIfAborted();
+      ctx = await EcMaster.trackScan(
+        dl,
+        options,
+        dl.symbols.ethercrab_scan_new_with_options(interfaceNameBuffer, optionsBuffer),
+      );
+
+      // Check if we got a valid pointer (not null and not 0n)
+      if (ctx !== null && ctx !== undefined) {
+        const ctxValue = Deno.UnsafePointer.value(ctx);
+        if (ctxValue !== 0n) {
+          break; // Success, exit loop
+        }
+      }
+
+      // A cancelled scan is not retried
+      options?.signal?.throwIfAborted();
+
+      // Check for specific errors that should abort retries immediately
+      const errorBuf = new Uint8Array(1024);
+
+      const errorLen = dl.symbols.ethercrab_get_last_error(
+        errorBuf,
+        BigInt(errorBuf.length),
+      );
+      if (errorLen > 0) {
+        const errorMsg = new TextDecoder().decode(errorBuf.slice(0, errorLen)).trim();
+        if (errorMsg.length > 0) {
+          lastErrorMsg = errorMsg;
+        }
+        // Permission errors are fatal and should not be retried
+        if (
+          errorMsg.includes("Permission denied") || errorMsg.includes("Operation not permitted")
+        ) {
+          throw new Error(
+            `Network discovery failed: ${errorMsg}. (Are you running with sudo/admin privileges?)`,
+          );
+        }
+      }
+
+      // If we failed and have retries left, wait and retry
+      if (attempt < MAX_SCAN_RETRIES) {
+        // Exponential backoff with cap
+        const rawDelay = Math.min(
+          BASE_RETRY_DELAY_MS * Math.pow(2, attempt),
+          MAX_RETRY_DELAY_MS,
+        );
+        // Jitter: +/- 20% to avoid synchronized retry storms
+        const jitter = rawDelay * 0.4 * (Math.random() - 0.5);
+        const finalDelay = Math.max(10, Math.floor(rawDelay + jitter));
+
+        // Log warning only if it's not the very first immediate retry
+        if (attempt > 0) {
+          const detail = lastErrorMsg
+            ? `Discovery failed: ${lastErrorMsg}.`
+            : "Discovery lock contention.";
+          console.warn(
+            `[EtherCAT] ${detail} Retrying in ${finalDelay}ms (Attempt ${
+              attempt + 1
+            }/${MAX_SCAN_RETRIES})...`,
+          );
+        }
+
+        await new Promise((r) => setTimeout(r, finalDelay));
+      }
+    }
+
+    // Final check: if we still don't have a valid context, throw error
+    if (ctx === null || ctx === undefined) {
+      const detail = lastErrorMsg
+        ? ` Last error: ${lastErrorMsg}.`
+        : " The EtherCAT master driver is busy or locked.";
+      throw new Error(
+        `Failed to start network scan after ${MAX_SCAN_RETRIES} attempts.${detail}`,
+      );
+    }
+
+    const ctxValue = Deno.UnsafePointer.value(ctx);
+    if (ctxValue === 0n) {
+      const detail = lastErrorMsg
+        ? ` Last error: ${lastErrorMsg}.`
+        : " The EtherCAT master driver is busy or locked.";
+      throw new Error(
+        `Failed to start network scan after ${MAX_SCAN_RETRIES} attempts.${detail}`,
+      );
+    }
+
+    return ctx;
+  }
+
+  /** FfiScanOptions (6 x u32, zero = default) for ethercrab_scan_new_with_options/ethercrab_scan_live. */
+  private static encodeScanOptions(options?: ScanOptions): Uint8Array {
+    const buffer = new Uint8Array(FFI_SCAN_OPTIONS_SIZE);
+    const view = new DataView(buffer.buffer);
+    view.setUint32(0, options?.pduTimeoutMs ?? 0, true);
+    view.setUint32(4, options?.mailboxTimeoutMs ?? 0, true);
+    view.setUint32(8, options?.eepromTimeoutMs ?? 0, true);
+    view.setUint32(12, options?.pduRetries ?? 0, true);
+    view.setUint32(16, options?.maxSlaves ?? 0, true);
+    // SCAN_FLAG_SII_ONLY = 0x01, SCAN_FLAG_WALK_OD = 0x02
+    const flags = (options?.siiOnly ? 0x01 : 0) | (options?.walkObjectDictionary ? 0x02 : 0);
+    view.setUint32(20, flags, true);
+    return buffer;
+  }
+
+  /**
+   * Report progress and forward aborts to the library while a nonblocking scan call runs.
+   */
+  private static async trackScan<T>(
+    dl: Deno.DynamicLibrary<typeof ethercrabSymbols>,
+    options: ScanOptions | undefined,
+    scan: Promise<T>,
+  ): Promise<T> {
+    const onAbort = () => dl.symbols.ethercrab_scan_cancel();
+    options?.signal?.addEventListener("abort", onAbort);
+    const onProgress = options?.onProgress;
+    const done = new Uint32Array(1);
+    const total = new Uint32Array(1);
+    const timer = onProgress
+      ? setInterval(() => {
+        if (dl.symbols.ethercrab_scan_get_progress(done, total) === 1) {
+          onProgress(done[0], total[0]);
+        }
+      }, 100)
+      : undefined;
+    try {
+      return await scan;
+    } finally {
+      clearInterval(timer);
+      options?.signal?.removeEventListener("abort", onAbort);
+    }
+  }
+
+  private static parseTopology(buffer: Uint8Array): SlaveTopology {
+    const view = new DataView(buffer.buffer);
+    // FfiSlaveTopology:
+    // 0: DL Status (u16)
+    // 2: Parent Index (i16, -1 = master)
+    // 4: Parent Port (u8, 0xFF = none)
+    // 5: Entry Port (u8)
+    // 6-9: Link (u8 x4)
+    // 10-13: Loop Closed (u8 x4)
+    // 14-17: Communication (u8 x4)
+    // 18: DC Valid (u8)
+    // 19: Padding (u8)
+    // 20-35: Port Receive Time ns (u32 x4)
+    // 36-51: Port Delay ns (u32 x4)
+    const parentPort = view.getUint8(4);
+    const dcValid = view.getUint8(18) === 1;
+    return {
+      dlStatus: view.getUint16(0, true),
+      parentIndex: view.getInt16(2, true),
+      parentPort: parentPort === 0xFF ? null : parentPort,
+      entryPort: view.getUint8(5),
+      ports: [0, 1, 2, 3].map((p) => ({
+        link: view.getUint8(6 + p) !== 0,
+        loopClosed: view.getUint8(10 + p) !== 0,
+        communication: view.getUint8(14 + p) !== 0,
+        ...(dcValid
+          ? {
+            receiveTimeNs: view.getUint32(20 + p * 4, true),
+            delayNs: view.getUint32(36 + p * 4, true),
+          }
+          : {}),
+      })),
+    };
+  }
+
   private static readName(buffer: Uint8Array): string {
     let end = 0;
     while (end < buffer.length && buffer[end] !== 0) end++;
@@ -616,26 +861,178 @@ export class EcMaster extends EventEmitter {
       return;
     }
 
-    // Convert ms to watchdog timeout value
-    // With default divider (~2498 * 40ns = ~100µs per count):
-    // timeout_value = timeoutMs * 1000 / 100 = timeoutMs * 10
-    // For safety, we use a simpler approximation: timeoutMs * 10
-    const timeoutValue = Math.round(timeoutMs * 10);
-
-    const slaveCount = this.eniConfig.slaves.length;
-    for (let i = 0; i < slaveCount; i++) {
-      try {
-        await this.setSmWatchdogTimeout(i, timeoutValue);
-      } catch (error) {
-        // Some slaves (like EK1100 coupler) may not support watchdog configuration
-        // Log warning but continue
+    try {
+      const failed = (await this.configureWatchdogs(timeoutMs)).filter((w) => !w.valid);
+      if (failed.length > 0) {
         console.warn(
-          `Warning: Could not configure watchdog for slave ${i}: ${
-            error instanceof Error ? error.message : error
-          }`,
+          `Warning: Could not configure watchdogs of slaves ${failed.map((w) => w.slaveIndex).join(", ")}`,
         );
       }
+    } catch (error) {
+      // Log warning but continue, slaves keep their default watchdogs
+      console.warn(
+        `Warning: Could not configure watchdogs: ${error instanceof Error ? error.message : error}`,
+      );
+    }
+  }
+
+  /**
+   * Set the SM (process data) watchdog, and optionally the PDI watchdog, of every slave or
+   * a selection from milliseconds. Divider and counts are computed natively, written in
+   * PRE-OP and read back. A failing slave does not stop the others; check `valid`/`error`.
+   * @param smTimeoutMs - SM watchdog time, 0 disables it
+   * @param options.pdiTimeoutMs - PDI watchdog time, defaults to keeping the current one
+   * @param options.slaves - Slaves by index, alias or address, defaults to all
+   * @returns Effective settings, or the error, per requested slave
+   */
+  async configureWatchdogs(
+    smTimeoutMs: number,
+    options: { pdiTimeoutMs?: number; slaves?: SlaveRef[] } = {},
+  ): Promise<EscWatchdogInfo[]> {
+    const slaves = options.slaves
+      ? new Uint16Array(options.slaves.map((slave) => this.resolveSlave(slave)))
+      : null;
+    const capacity = slaves ? slaves.length : 128; // MAX_SUBDEVICES
+    const out = new Uint8Array(capacity * FFI_ESC_WATCHDOG_SIZE);
+    const result = await this.dl.symbols.ethercrab_esc_watchdog_configure(
+      smTimeoutMs,
+      options.pdiTimeoutMs ?? 0xFFFFFFFF,
+      slaves,
+      BigInt(slaves ? slaves.length : 0),
+      out,
+      BigInt(capacity),
+    );
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`Watchdog configuration failed: ${message}`, result, context);
+    }
+    // One entry per requested slave; unused entries stay zeroed (not valid, no error)
+    const infos: EscWatchdogInfo[] = [];
+    for (let i = 0; i < capacity; i++) {
+      const info = EcMaster.decodeEscWatchdog(out.subarray(i * FFI_ESC_WATCHDOG_SIZE));
+      if (info.valid || info.error !== 0) infos.push(info);
+    }
+    return infos;
+  }
+
+  /**
+   * Position of a slave given by index, station alias or configured station address.
+   * Throws when no slave matches or when the alias is used by more than one slave.
+   */
+  resolveSlave(slave: SlaveRef): number {
+    if (typeof slave === "number") return slave;
+    const result = "alias" in slave
+      ? this.dl.symbols.ethercrab_find_slave_by_alias(slave.alias)
+      : this.dl.symbols.ethercrab_find_slave_by_address(slave.address);
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`Slave lookup failed: ${message}`, result, context);
+    }
+    return result;
+  }
+
+  /**
+   * Read a slave's watchdog divider, effective SM/PDI watchdog times and status (0x0440).
+   * @param slave - Zero-based index of the slave, or `{ alias }` / `{ address }`
+   */
+  async readWatchdog(slave: SlaveRef): Promise<EscWatchdogInfo> {
+    const slaveIndex = this.resolveSlave(slave);
+    const out = new Uint8Array(FFI_ESC_WATCHDOG_SIZE);
+    const result = await this.dl.symbols.ethercrab_esc_watchdog_read(slaveIndex, out);
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`Watchdog read failed: ${message}`, result, context);
+    }
+    return EcMaster.decodeEscWatchdog(out);
+  }
+
+  private static decodeEscWatchdog(bytes: Uint8Array): EscWatchdogInfo {
+    const view = new DataView(bytes.buffer, bytes.byteOffset, FFI_ESC_WATCHDOG_SIZE);
+    return {
+      slaveIndex: view.getUint16(0, true),
+      divider: view.getUint16(2, true),
+      pdiCount: view.getUint16(4, true),
+      smCount: view.getUint16(6, true),
+      pdiTimeUs: view.getUint32(8, true),
+      smTimeUs: view.getUint32(12, true),
+      status: view.getUint16(16, true),
+      smExpiredCount: view.getUint8(18),
+      pdiExpiredCount: view.getUint8(19),
+      valid: view.getUint8(20) === 1,
+      error: view.getInt8(21),
+    };
+  }
+
+  /**
+   * Read the ESC error counters of every slave and port, with the increase since the
+   * previous call (or since init / clearErrorCounters()). Counters stop at 255.
+   */
+  async readErrorCounters(): Promise<EscErrorReport[]> {
+    const capacity = 128; // MAX_SUBDEVICES
+    const out = new Uint8Array(capacity * FFI_ESC_ERROR_REPORT_SIZE);
+    const result = await this.dl.symbols.ethercrab_esc_error_counters_read(out, BigInt(capacity));
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`Error counter read failed: ${message}`, result, context);
     }
+    const reports: EscErrorReport[] = [];
+    for (let i = 0; i < Math.min(result, capacity); i++) {
+      const bytes = out.subarray(i * FFI_ESC_ERROR_REPORT_SIZE, (i + 1) * FFI_ESC_ERROR_REPORT_SIZE);
+      const view = new DataView(bytes.buffer, bytes.byteOffset, FFI_ESC_ERROR_REPORT_SIZE);
+      reports.push({
+        slaveIndex: view.getUint16(0, true),
+        valid: bytes[38] !== 0,
+        counters: EcMaster.decodeEscErrorCounters(bytes.subarray(2, 20)),
+        delta: EcMaster.decodeEscErrorCounters(bytes.subarray(20, 38)),
+        intervalMs: view.getUint32(40, true),
+      });
+    }
+    return reports;
+  }
+
+  private static decodeEscErrorCounters(bytes: Uint8Array): EscErrorCounters {
+    return {
+      invalidFrame: Array.from(bytes.subarray(0, 4)),
+      rxError: Array.from(bytes.subarray(4, 8)),
+      forwardedRxError: Array.from(bytes.subarray(8, 12)),
+      lostLink: Array.from(bytes.subarray(12, 16)),
+      processingUnit: bytes[16],
+      pdi: bytes[17],
+    };
+  }
+
+  /** Clear the error and lost link counters of all slaves and restart the deltas. */
+  async clearErrorCounters(): Promise<void> {
+    const result = await this.dl.symbols.ethercrab_esc_error_counters_clear();
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`Error counter clear failed: ${message}`, result, context);
+    }
+  }
+
+  /**
+   * Ports with errors over the interval of the last readErrorCounters(), highest error
+   * rate first.
+   */
+  rankPortErrors(): EscPortRank[] {
+    const capacity = 128 * 4;
+    const out = new Uint8Array(capacity * FFI_ESC_PORT_RANK_SIZE);
+    const result = this.dl.symbols.ethercrab_esc_error_rank(out, BigInt(capacity));
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`Port error ranking failed: ${message}`, result, context);
+    }
+    const ranks: EscPortRank[] = [];
+    for (let i = 0; i < Math.min(result, capacity); i++) {
+      const view = new DataView(out.buffer, i * FFI_ESC_PORT_RANK_SIZE, FFI_ESC_PORT_RANK_SIZE);
+      ranks.push({
+        slaveIndex: view.getUint16(0, true),
+        port: view.getUint8(2),
+        errors: view.getUint32(4, true),
+        errorsPerSecond: view.getFloat32(8, true),
+      });
+    }
+    return ranks;
   }
 
   getState(): SlaveState {
@@ -692,7 +1089,7 @@ export class EcMaster extends EventEmitter {
       if (slave.initCommands) {
         slave.initCommands.forEach((cmd) => {
           // Only push supported commands
-          if (cmd.type === "sdo" || cmd.type === "register") {
+          if (cmd.type === "sdo" || cmd.type === "register" || cmd.type === "soe") {
             allInitCmds.push({ cmd, slaveIndex });
           }
         });
@@ -716,32 +1113,38 @@ export class EcMaster extends EventEmitter {
 
         // slave_index
         view.setUint16(offset + 0, item.slaveIndex, true);
-        // command_type (0=SDO, 1=Register)
-        const type = item.cmd.type === "register" ? 1 : 0;
+        // command_type (0=SDO, 1=Register, 2=SoE)
+        const type = item.cmd.type === "register" ? 1 : item.cmd.type === "soe" ? 2 : 0;
         view.setUint8(offset + 2, type);
 
         // index (u16) at offset 4 (aligned)
-        // For register, use 'ado' or 'register'
-        const indexVal = item.cmd.type === "sdo" ? (item.cmd.index || 0) : (item.cmd.ado || 0);
+        // For register, use 'ado' or 'register'; for SoE, the IDN
+        const indexVal = item.cmd.type === "sdo"
+          ? (item.cmd.index || 0)
+          : item.cmd.type === "soe"
+          ? (item.cmd.idn || 0)
+          : (item.cmd.ado || 0);
 
         view.setUint16(offset + 4, indexVal, true);
 
-        // sub_index (u8) at offset 6
-        view.setUint8(offset + 6, item.cmd.subIndex || 0);
+        // sub_index (u8) at offset 6 (drive number for SoE)
+        const subIndexVal = item.cmd.type === "soe" ? (item.cmd.driveNo || 0) : (item.cmd.subIndex || 0);
+        view.setUint8(offset + 6, subIndexVal);
 
         // value (u8[4]) at offset 7
-        // item.cmd.value can be number or string (hex)
+        // item.cmd.value can be number or string (hex); SoE commands only carry raw data
+        const rawValue = item.cmd.type === "soe" ? item.cmd.data : item.cmd.value;
         const valBytes = new Uint8Array(4);
         let val = BigInt(0);
 
-        if (typeof item.cmd.value === "string") {
+        if (typeof rawValue === "string") {
           // Parse hex string
-          const cleanHex = item.cmd.value.trim().replace(/^0x/i, "");
+          const cleanHex = rawValue.trim().replace(/^0x/i, "");
           if (cleanHex) {
             val = BigInt("0x" + cleanHex);
           }
-        } else if (typeof item.cmd.value === "number") {
-          val = BigInt(item.cmd.value);
+        } else if (typeof rawValue === "number") {
+          val = BigInt(rawValue);
         }
 
         const valView = new DataView(valBytes.buffer);
@@ -937,14 +1340,15 @@ export class EcMaster extends EventEmitter {
    * Write a single byte to a slave's output process data.
    *
    * **Usage Pattern:**
-   * - **For writing outputs**: Use `writePdoByte()` to write directly to slave outputs.
-   *   This writes immediately to the slave's output buffer and will be sent on the next
-   *   `runCycle()` call. This is the recommended method for writing outputs.
+   * - **For writing outputs**: Use `writePdoByte()` to write a slave's output byte.
+   *   The byte is stored in the shared process data buffer at the slave's output offset,
+   *   the same image `getProcessDataBuffer()` exposes, and is sent on the next `runCycle()`.
+   *   If the same byte is written through both paths, the last write before the cycle wins.
    *
    * - **For reading inputs**: Use `getProcessDataBuffer()` for bulk reading, or
    *   `readPdoByte()` for per-slave byte access.
    *
-   * @param slaveIndex - Zero-based index of the slave (matches discovered slave order)
+   * @param slave - Zero-based index of the slave (matches discovered slave order), or `{ alias }` / `{ address }`
    * @param byteOffset - Byte offset within the slave's output buffer (0-based)
    * @param value - The byte value to write (0-255)
    * @returns `true` if the write succeeded, `false` otherwise
@@ -955,7 +1359,8 @@ export class EcMaster extends EventEmitter {
    * const success = master.writePdoByte(1, 0, 0xFF);
    * ```
    */
-  writePdoByte(slaveIndex: number, byteOffset: number, value: number): boolean {
+  writePdoByte(slave: SlaveRef, byteOffset: number, value: number): boolean {
+    const slaveIndex = this.resolveSlave(slave);
     const result = this.dl.symbols.ethercrab_write_process_data_byte(
       slaveIndex,
       byteOffset,
@@ -967,11 +1372,11 @@ export class EcMaster extends EventEmitter {
   /**
    * Read a single byte from a slave's input or output process data.
    *
-   * This method reads directly from ethercrab's in-memory buffers, making it fast
-   * and synchronous. For bulk input reading, consider using `getProcessDataBuffer()`
-   * instead.
+   * This method reads from the master's process data image, the same buffer the cyclic
+   * exchange fills, making it fast and synchronous. For bulk input reading, consider using
+   * `getProcessDataBuffer()` instead.
    *
-   * @param slaveIndex - Zero-based index of the slave (matches discovered slave order)
+   * @param slave - Zero-based index of the slave (matches discovered slave order), or `{ alias }` / `{ address }`
    * @param byteOffset - Byte offset within the slave's buffer (0-based)
    * @param isOutput - If `true`, read from output buffer; if `false`, read from input buffer (default)
    * @returns The byte value (0-255), or 0 if the read failed
@@ -981,11 +1386,12 @@ export class EcMaster extends EventEmitter {
    * // Read first input byte from slave at index 1
    * const inputValue = master.readPdoByte(1, 0, false);
    *
-   * // Read first output byte from slave at index 1 (to verify a write)
+   * // Read first output byte from slave at index 1 as it will be sent with the next cycle
    * const outputValue = master.readPdoByte(1, 0, true);
    * ```
    */
-  readPdoByte(slaveIndex: number, byteOffset: number, isOutput: boolean = false): number {
+  readPdoByte(slave: SlaveRef, byteOffset: number, isOutput: boolean = false): number {
+    const slaveIndex = this.resolveSlave(slave);
     return this.dl.symbols.ethercrab_read_process_data_byte(
       slaveIndex,
       byteOffset,
@@ -993,63 +1399,729 @@ export class EcMaster extends EventEmitter {
     );
   }
 
-  // Feature 501: SDO Operations
-  async sdoRead(
-    slaveIndex: number,
-    index: number,
-    subIndex: number,
-  ): Promise<Uint8Array> {
-    const buffer = new Uint8Array(256); // Max reasonable size? expedited is 4.
-
-    const bytesRead = await this.dl.symbols.ethercrab_sdo_read(
-      slaveIndex,
-      index,
-      subIndex,
-      buffer,
-      BigInt(buffer.length),
-    );
-
-    if (bytesRead < 0) {
-      const { message, context } = this.getLastErrorStructured();
-      throw new FfiError(`SDO read failed: ${message}`, bytesRead, context);
+  /**
+   * Symbol table of the active process image: every mapped PDO entry with its bit offset,
+   * bit length and data type (from SDO Information or the SII, 0 when neither describes it).
+   * Built on PreOp→SafeOp from 0x1C12/0x1C13 (or the SII), empty before.
+   */
+  getProcessSymbols(): ProcessSymbol[] {
+    const count = this.dl.symbols.ethercrab_pd_symbol_count();
+    const buffer = new Uint8Array(FFI_PROCESS_SYMBOL_SIZE);
+    const view = new DataView(buffer.buffer);
+    const symbols: ProcessSymbol[] = [];
+    for (let i = 0; i < count; i++) {
+      if (this.dl.symbols.ethercrab_pd_get_symbol(i, buffer) !== 0) break;
+      symbols.push({
+        slaveIndex: view.getUint16(0, true),
+        pdoIndex: view.getUint16(2, true),
+        index: view.getUint16(4, true),
+        subIndex: view.getUint8(6),
+        isOutput: view.getUint8(7) !== 0,
+        bitOffset: view.getUint32(8, true),
+        bitLen: view.getUint16(12, true),
+        dataType: view.getUint16(14, true),
+        pdiBitOffset: view.getUint32(16, true),
+      });
     }
-    return buffer.slice(0, bytesRead);
+    return symbols;
   }
 
-  async sdoWrite(
-    slaveIndex: number,
-    index: number,
-    subIndex: number,
-    data: Uint8Array,
-  ): Promise<void> {
-    const result = await this.dl.symbols.ethercrab_sdo_write(
-      slaveIndex,
-      index,
-      subIndex,
-      data as unknown as BufferSource, // Safe because Uint8Array is BufferSource
-      BigInt(data.length),
-    );
+  /**
+   * Per-slave layout of the process image: output and input offset/length inside
+   * getProcessDataBuffer() (`[Outputs | Inputs]`) and the logical addresses in the frame.
+   * Slaves without process data are included with zero lengths. Empty before SafeOp.
+   */
+  getProcessImageLayout(): PdiSlaveLayout[] {
+    const count = this.dl.symbols.ethercrab_pd_layout_count();
+    const buffer = new Uint8Array(FFI_PDI_SLAVE_LAYOUT_SIZE);
+    const view = new DataView(buffer.buffer);
+    const logical = (offset: number) => {
+      const address = view.getUint32(offset, true);
+      return address === 0xFFFFFFFF ? null : address;
+    };
+    const layouts: PdiSlaveLayout[] = [];
+    for (let i = 0; i < count; i++) {
+      if (this.dl.symbols.ethercrab_pd_get_layout(i, buffer) !== 0) break;
+      layouts.push({
+        slaveIndex: i,
+        outputOffset: view.getUint32(0, true),
+        outputLength: view.getUint32(4, true),
+        inputOffset: view.getUint32(8, true),
+        inputLength: view.getUint32(12, true),
+        outputLogicalAddress: logical(16),
+        inputLogicalAddress: logical(20),
+      });
+    }
+    return layouts;
+  }
 
+  /**
+   * Read a mapped variable by object index from the process image (inputs or outputs).
+   * INT64/UINT64 are returned as bigint, BOOL as boolean.
+   * @param slave - Slave index, `{ alias }` or `{ address }`
+   * @param index - Mapped object index (e.g. 0x6000)
+   * @param subIndex - Mapped object sub index
+   * @param type - Access type; its width must match the mapped bit length, and its type the
+   *   entry's data type when that is known (bit strings such as WORD accept either sign)
+   */
+  readVariable(slave: SlaveRef, index: number, subIndex: number, type: PdDataType): number | bigint | boolean {
+    const slaveIndex = this.resolveSlave(slave);
+    const s = this.dl.symbols;
+    let result: number;
+    let value: number | bigint | boolean;
+    switch (type) {
+      case "BOOL": {
+        const out = new Uint8Array(1);
+        result = s.ethercrab_pd_get_bool(slaveIndex, index, subIndex, out);
+        value = out[0] !== 0;
+        break;
+      }
+      case "INT8": {
+        const out = new Int8Array(1);
+        result = s.ethercrab_pd_get_i8(slaveIndex, index, subIndex, out);
+        value = out[0];
+        break;
+      }
+      case "INT16": {
+        const out = new Int16Array(1);
+        result = s.ethercrab_pd_get_i16(slaveIndex, index, subIndex, out);
+        value = out[0];
+        break;
+      }
+      case "INT32": {
+        const out = new Int32Array(1);
+        result = s.ethercrab_pd_get_i32(slaveIndex, index, subIndex, out);
+        value = out[0];
+        break;
+      }
+      case "INT64": {
+        const out = new BigInt64Array(1);
+        result = s.ethercrab_pd_get_i64(slaveIndex, index, subIndex, out);
+        value = out[0];
+        break;
+      }
+      case "UINT8": {
+        const out = new Uint8Array(1);
+        result = s.ethercrab_pd_get_u8(slaveIndex, index, subIndex, out);
+        value = out[0];
+        break;
+      }
+      case "UINT16": {
+        const out = new Uint16Array(1);
+        result = s.ethercrab_pd_get_u16(slaveIndex, index, subIndex, out);
+        value = out[0];
+        break;
+      }
+      case "UINT32": {
+        const out = new Uint32Array(1);
+        result = s.ethercrab_pd_get_u32(slaveIndex, index, subIndex, out);
+        value = out[0];
+        break;
+      }
+      case "UINT64": {
+        const out = new BigUint64Array(1);
+        result = s.ethercrab_pd_get_u64(slaveIndex, index, subIndex, out);
+        value = out[0];
+        break;
+      }
+      case "REAL32": {
+        const out = new Float32Array(1);
+        result = s.ethercrab_pd_get_f32(slaveIndex, index, subIndex, out);
+        value = out[0];
+        break;
+      }
+      case "REAL64": {
+        const out = new Float64Array(1);
+        result = s.ethercrab_pd_get_f64(slaveIndex, index, subIndex, out);
+        value = out[0];
+        break;
+      }
+      default:
+        throw new Error(`Unsupported process data type: ${type}`);
+    }
     if (result < 0) {
       const { message, context } = this.getLastErrorStructured();
-      throw new FfiError(`SDO write failed: ${message}`, result, context);
+      throw new FfiError(`Process data read failed: ${message}`, result, context);
     }
+    return value;
   }
 
-  // Feature 305: EEPROM Access
-  async readEEPROM(
-    slaveIndex: number,
-    address: number,
-    len: number = 2,
-  ): Promise<Uint8Array> {
-    const buffer = new Uint8Array(len);
-    const result = await this.dl.symbols.ethercrab_eeprom_read(
-      slaveIndex,
-      address,
-      buffer,
-      BigInt(buffer.length),
-    );
-
+  /**
+   * Write a mapped output variable by object index. Sent with the next runCycle().
+   * @param slave - Slave index, `{ alias }` or `{ address }`
+   * @param index - Mapped object index (e.g. 0x7000)
+   * @param subIndex - Mapped object sub index
+   * @param type - Access type; its width must match the mapped bit length, and its type the
+   *   entry's data type when that is known (bit strings such as WORD accept either sign)
+   * @param value - New value
+   */
+  writeVariable(
+    slave: SlaveRef,
+    index: number,
+    subIndex: number,
+    type: PdDataType,
+    value: number | bigint | boolean,
+  ): void {
+    const slaveIndex = this.resolveSlave(slave);
+    const s = this.dl.symbols;
+    let result: number;
+    switch (type) {
+      case "BOOL":
+        result = s.ethercrab_pd_set_bool(slaveIndex, index, subIndex, value ? 1 : 0);
+        break;
+      case "INT8":
+        result = s.ethercrab_pd_set_i8(slaveIndex, index, subIndex, Number(value));
+        break;
+      case "INT16":
+        result = s.ethercrab_pd_set_i16(slaveIndex, index, subIndex, Number(value));
+        break;
+      case "INT32":
+        result = s.ethercrab_pd_set_i32(slaveIndex, index, subIndex, Number(value));
+        break;
+      case "INT64":
+        result = s.ethercrab_pd_set_i64(slaveIndex, index, subIndex, BigInt(value));
+        break;
+      case "UINT8":
+        result = s.ethercrab_pd_set_u8(slaveIndex, index, subIndex, Number(value));
+        break;
+      case "UINT16":
+        result = s.ethercrab_pd_set_u16(slaveIndex, index, subIndex, Number(value));
+        break;
+      case "UINT32":
+        result = s.ethercrab_pd_set_u32(slaveIndex, index, subIndex, Number(value));
+        break;
+      case "UINT64":
+        result = s.ethercrab_pd_set_u64(slaveIndex, index, subIndex, BigInt(value));
+        break;
+      case "REAL32":
+        result = s.ethercrab_pd_set_f32(slaveIndex, index, subIndex, Number(value));
+        break;
+      case "REAL64":
+        result = s.ethercrab_pd_set_f64(slaveIndex, index, subIndex, Number(value));
+        break;
+      default:
+        throw new Error(`Unsupported process data type: ${type}`);
+    }
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`Process data write failed: ${message}`, result, context);
+    }
+  }
+
+  /**
+   * Read one bit of a slave's input (default) or output image from the process image.
+   * @param slave - Slave index, `{ alias }` or `{ address }`
+   * @param bitOffset - Bit offset inside the slave's input or output image
+   * @param isOutput - Read the output image instead of the inputs
+   */
+  readBit(slave: SlaveRef, bitOffset: number, isOutput: boolean = false): boolean {
+    const slaveIndex = this.resolveSlave(slave);
+    return this.pdBitResult(this.dl.symbols.ethercrab_pd_bit(slaveIndex, isOutput, bitOffset, 0));
+  }
+
+  /**
+   * Set or clear one output bit of a slave. The read-modify-write holds the PDI lock,
+   * so it cannot race with the output copy in runCycle().
+   * @param slave - Slave index, `{ alias }` or `{ address }`
+   * @param bitOffset - Bit offset inside the slave's output image
+   * @param value - New bit value
+   */
+  writeBit(slave: SlaveRef, bitOffset: number, value: boolean): void {
+    const slaveIndex = this.resolveSlave(slave);
+    this.pdBitResult(this.dl.symbols.ethercrab_pd_bit(slaveIndex, true, bitOffset, value ? 1 : 2));
+  }
+
+  /**
+   * Toggle one output bit of a slave and return its new value.
+   * @param slave - Slave index, `{ alias }` or `{ address }`
+   * @param bitOffset - Bit offset inside the slave's output image
+   */
+  toggleBit(slave: SlaveRef, bitOffset: number): boolean {
+    const slaveIndex = this.resolveSlave(slave);
+    return this.pdBitResult(this.dl.symbols.ethercrab_pd_bit(slaveIndex, true, bitOffset, 3));
+  }
+
+  /**
+   * Read, set, clear or toggle a bit addressed by its offset in the whole PDI buffer
+   * (`[Outputs | Inputs]`). Only output bits can be changed. Returns the bit after the operation.
+   * @param pdiBitOffset - Bit offset from the start of the PDI buffer
+   * @param op - Bit operation, defaults to "read"
+   */
+  pdiBit(pdiBitOffset: number, op: "read" | "set" | "clear" | "toggle" = "read"): boolean {
+    const code = { read: 0, set: 1, clear: 2, toggle: 3 }[op];
+    return this.pdBitResult(this.dl.symbols.ethercrab_pdi_bit(pdiBitOffset, code));
+  }
+
+  /**
+   * Input and output image sizes of a slave in bytes (available from SafeOp).
+   * @param slave - Slave index, `{ alias }` or `{ address }`
+   */
+  getSlaveProcessDataSizes(slave: SlaveRef): { inputSize: number; outputSize: number } {
+    const slaveIndex = this.resolveSlave(slave);
+    const inputSize = new Uint32Array(1);
+    const outputSize = new Uint32Array(1);
+    const result = this.dl.symbols.ethercrab_pd_slave_sizes(slaveIndex, inputSize, outputSize);
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`Process data size query failed: ${message}`, result, context);
+    }
+    return { inputSize: inputSize[0], outputSize: outputSize[0] };
+  }
+
+  /**
+   * Copy a slave's input image (or a range of it) out of the process image in one call.
+   * Available in SafeOp and Op: runCycle() refreshes the inputs in both (outputs are only sent in Op).
+   * @param slave - Slave index, `{ alias }` or `{ address }`
+   * @param offset - Byte offset inside the slave's inputs
+   * @param length - Bytes to copy, defaults to the rest of the image
+   */
+  readSlaveInputs(slave: SlaveRef, offset: number = 0, length?: number): Uint8Array {
+    const slaveIndex = this.resolveSlave(slave);
+    return this.readSlaveImage(slaveIndex, false, offset, length);
+  }
+
+  /**
+   * Copy a slave's output image (or a range of it) out of the process image in one call.
+   * @param slave - Slave index, `{ alias }` or `{ address }`
+   * @param offset - Byte offset inside the slave's outputs
+   * @param length - Bytes to copy, defaults to the rest of the image
+   */
+  readSlaveOutputs(slave: SlaveRef, offset: number = 0, length?: number): Uint8Array {
+    const slaveIndex = this.resolveSlave(slave);
+    return this.readSlaveImage(slaveIndex, true, offset, length);
+  }
+
+  /**
+   * Write bytes into a slave's output image in one call. Sent with the next runCycle().
+   * @param slave - Slave index, `{ alias }` or `{ address }`
+   * @param data - Bytes to write, must fit in the slave's outputs from `offset`
+   * @param offset - Byte offset inside the slave's outputs
+   */
+  writeSlaveOutputs(slave: SlaveRef, data: Uint8Array, offset: number = 0): void {
+    const slaveIndex = this.resolveSlave(slave);
+    const result = this.dl.symbols.ethercrab_pd_write_slave(
+      slaveIndex,
+      offset,
+      data as unknown as BufferSource,
+      BigInt(data.length),
+    );
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`Process data write failed: ${message}`, result, context);
+    }
+  }
+
+  private readSlaveImage(slaveIndex: number, isOutput: boolean, offset: number, length?: number): Uint8Array {
+    if (length === undefined) {
+      const sizes = this.getSlaveProcessDataSizes(slaveIndex);
+      length = Math.max(0, (isOutput ? sizes.outputSize : sizes.inputSize) - offset);
+    }
+    const buffer = new Uint8Array(length);
+    const result = this.dl.symbols.ethercrab_pd_read_slave(slaveIndex, isOutput, offset, buffer, BigInt(length));
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`Process data read failed: ${message}`, result, context);
+    }
+    return buffer.slice(0, result);
+  }
+
+  /**
+   * Configure the outputs a slave falls back to on WKC loss, host heartbeat timeout,
+   * Op→SafeOp and destroy. Slaves stay in fail-safe until resetFailSafe() or the next
+   * requestState(SlaveState.OP).
+   * @param slave - Slave index, `{ alias }` or `{ address }`
+   * @param mode - Fail-safe mode
+   * @param data - Output image for "user" (zero-padded to the slave's outputs)
+   */
+  setFailSafeOutputs(slave: SlaveRef, mode: FailSafeMode, data?: Uint8Array): void {
+    const slaveIndex = this.resolveSlave(slave);
+    const code = { none: 0, zero: 1, hold: 2, user: 3 }[mode];
+    const image = data ?? new Uint8Array(0);
+    const result = this.dl.symbols.ethercrab_failsafe_configure(
+      slaveIndex,
+      code,
+      mode === "user" ? image as unknown as BufferSource : null,
+      BigInt(image.length),
+    );
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`Fail-safe configuration failed: ${message}`, result, context);
+    }
+  }
+
+  /** Slaves currently sending their fail-safe outputs and why. */
+  getFailSafeStatus(): FailSafeStatus {
+    const reasons: FailSafeReason[] = ["none", "wkcLoss", "heartbeat", "opToSafeOp", "destroy"];
+    const count = this.dl.symbols.ethercrab_failsafe_get_active(null, 0n);
+    if (count <= 0) return { reason: "none", slaves: [] };
+    const out = new Uint16Array(count);
+    const total = this.dl.symbols.ethercrab_failsafe_get_active(out, BigInt(count));
+    return {
+      reason: reasons[this.dl.symbols.ethercrab_failsafe_get_reason()] ?? "none",
+      slaves: Array.from(out.subarray(0, Math.min(total, count))),
+    };
+  }
+
+  /** Leave fail-safe: host outputs are sent again from the next runCycle(). */
+  resetFailSafe(): void {
+    this.dl.symbols.ethercrab_failsafe_reset();
+  }
+
+  /**
+   * Start the library-side liveness watchdog. Call kickWatchdog() at least every
+   * `timeoutMs`; if the event loop stalls, a native thread sends the fail-safe outputs,
+   * requests SafeOp and latches until acknowledgeWatchdog().
+   * @param timeoutMs - Heartbeat deadline in milliseconds
+   */
+  startWatchdog(timeoutMs: number): void {
+    const result = this.dl.symbols.ethercrab_watchdog_start(timeoutMs);
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`Watchdog start failed: ${message}`, result, context);
+    }
+    this.watchdogTrips = 0;
+  }
+
+  stopWatchdog(): void {
+    this.dl.symbols.ethercrab_watchdog_stop();
+  }
+
+  /**
+   * Heartbeat. Returns false while latched after a missed deadline; emits "watchdogTimeout"
+   * once per miss.
+   */
+  kickWatchdog(): boolean {
+    const result = this.dl.symbols.ethercrab_watchdog_kick();
+    // The trip is reported once the native side finished handling it, with its own message
+    const tripCount = new Uint32Array(1);
+    const message = new Uint8Array(512);
+    const len = this.dl.symbols.ethercrab_watchdog_get_last_trip(tripCount, message, BigInt(message.length));
+    const trips = tripCount[0]!;
+    if (len >= 0 && trips > this.watchdogTrips) {
+      this.watchdogTrips = trips;
+      const event: WatchdogTimeoutEvent = {
+        tripCount: trips,
+        error: new TextDecoder().decode(message.subarray(0, len)),
+      };
+      this.emit("watchdogTimeout", event);
+    }
+    return result === 0;
+  }
+
+  /**
+   * Clear the watchdog latch. requestState(SlaveState.OP) is refused until then; outputs stay
+   * fail-safe until that request.
+   */
+  acknowledgeWatchdog(): void {
+    this.dl.symbols.ethercrab_watchdog_acknowledge();
+  }
+
+  /**
+   * Watch the topology captured now. Every `intervalMs` a broadcast read of DL status counts
+   * the slaves; when fewer answer, the monitor finds the exact link that went down and emits
+   * "linkLost" (also recorded in the error ring), then "linkRestored" once it is back.
+   * @param intervalMs - Poll interval in milliseconds (default: 100)
+   */
+  async startLinkMonitor(intervalMs: number = 100): Promise<void> {
+    this.stopLinkMonitor();
+    const result = await this.dl.symbols.ethercrab_link_monitor_start();
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`Link monitor start failed: ${message}`, result, context);
+    }
+
+    const capacity = 16;
+    const out = new Uint8Array(capacity * FFI_LINK_EVENT_SIZE);
+    let checking = false;
+    this.linkMonitorInterval = setInterval(async () => {
+      if (this.isClosing || checking) return;
+      checking = true;
+      try {
+        const count = await this.dl.symbols.ethercrab_link_monitor_check(out, BigInt(capacity));
+        if (this.isClosing || count <= 0) return;
+        for (let i = 0; i < Math.min(count, capacity); i++) {
+          const view = new DataView(out.buffer, i * FFI_LINK_EVENT_SIZE, FFI_LINK_EVENT_SIZE);
+          const parentIndex = view.getInt16(0, true);
+          const lost = view.getUint8(3) !== 0;
+          const childIndex = view.getUint16(4, true);
+          const between = parentIndex < 0
+            ? `the master and slave ${childIndex}`
+            : `slave ${parentIndex} port ${view.getUint8(2)} and slave ${childIndex}`;
+          const event: LinkEvent = {
+            parentIndex: parentIndex < 0 ? null : parentIndex,
+            parentPort: parentIndex < 0 ? null : view.getUint8(2),
+            childIndex,
+            slaveCount: view.getUint16(6, true),
+            expectedCount: view.getUint16(8, true),
+            parentDlStatus: view.getUint16(10, true),
+            message: `Link ${lost ? "lost" : "restored"} between ${between}`,
+          };
+          this.emit(lost ? "linkLost" : "linkRestored", event);
+        }
+      } finally {
+        checking = false;
+      }
+    }, intervalMs);
+  }
+
+  stopLinkMonitor(): void {
+    if (this.linkMonitorInterval !== undefined) {
+      clearInterval(this.linkMonitorInterval);
+      this.linkMonitorInterval = undefined;
+      this.dl.symbols.ethercrab_link_monitor_stop();
+    }
+  }
+
+  private pdBitResult(result: number): boolean {
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`Process data bit access failed: ${message}`, result, context);
+    }
+    return result === 1;
+  }
+
+  // Feature 501: SDO Operations
+  /**
+   * Read an object entry (expedited, normal or segmented upload).
+   *
+   * Starts with a 256-byte buffer. When the object is larger the library rejects the read
+   * with -4 and reports the object size as `required_len`, and the read is repeated once
+   * with a buffer of that size.
+   */
+  async sdoRead(
+    slave: SlaveRef,
+    index: number,
+    subIndex: number,
+  ): Promise<Uint8Array> {
+    const slaveIndex = this.resolveSlave(slave);
+    let buffer = new Uint8Array(256); // Normal and segmented uploads can exceed 4 bytes
+
+    let bytesRead = await this.dl.symbols.ethercrab_sdo_read(
+      slaveIndex,
+      index,
+      subIndex,
+      buffer,
+      BigInt(buffer.length),
+    );
+
+    if (bytesRead === -4) {
+      const requiredLen = Number(this.getLastErrorStructured().context?.required_len);
+      if (Number.isInteger(requiredLen) && requiredLen > buffer.length) {
+        buffer = new Uint8Array(requiredLen);
+        bytesRead = await this.dl.symbols.ethercrab_sdo_read(
+          slaveIndex,
+          index,
+          subIndex,
+          buffer,
+          BigInt(buffer.length),
+        );
+      }
+    }
+
+    if (bytesRead < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`SDO read failed: ${message}`, bytesRead, context);
+    }
+    return buffer.slice(0, bytesRead);
+  }
+
+  async sdoWrite(
+    slave: SlaveRef,
+    index: number,
+    subIndex: number,
+    data: Uint8Array,
+  ): Promise<void> {
+    const slaveIndex = this.resolveSlave(slave);
+    const result = await this.dl.symbols.ethercrab_sdo_write(
+      slaveIndex,
+      index,
+      subIndex,
+      data as unknown as BufferSource, // Safe because Uint8Array is BufferSource
+      BigInt(data.length),
+    );
+
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`SDO write failed: ${message}`, result, context);
+    }
+  }
+
+  /**
+   * Replace the RxPDO (0x1C12) or TxPDO (0x1C13) assignment of a slave and the contents of the
+   * assigned mapping objects. PreOp only. Entries are checked against the object dictionary
+   * (SDO Information; skipped, with a note in the error ring, only when the SII reports no SDO
+   * Information), written in the disable-write-enable sequence and read back. On failure the
+   * previous assignment is written back.
+   * @param slave - Slave index, `{ alias }` or `{ address }`
+   * @param assignIndex - 0x1C12 (outputs) or 0x1C13 (inputs)
+   * @param pdos - Mapping objects in assignment order (at most 254, each with at most 254
+   *   entries), empty to clear the assignment
+   */
+  async remapPdos(slave: SlaveRef, assignIndex: 0x1C12 | 0x1C13, pdos: PdoAssignment[]): Promise<void> {
+    const slaveIndex = this.resolveSlave(slave);
+    const indices = new Uint16Array(pdos.map((p) => p.index));
+    const counts = new Uint8Array(pdos.map((p) => p.entries.length));
+    const entries = new Uint32Array(
+      pdos.flatMap((p) => p.entries.map((e) => ((e.index << 16) | (e.subIndex << 8) | e.bitLen) >>> 0)),
+    );
+
+    const result = await this.dl.symbols.ethercrab_pdo_remap(
+      slaveIndex,
+      assignIndex,
+      indices,
+      counts,
+      BigInt(pdos.length),
+      entries,
+      BigInt(entries.length),
+    );
+
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`PDO remap failed: ${message}`, result, context);
+    }
+  }
+
+  /**
+   * Read elements of an SoE IDN from a drive (Servo profile over EtherCAT).
+   *
+   * @param slave - Zero-based index of the slave, or `{ alias }` / `{ address }`
+   * @param driveNo - Drive number within the slave (0-7)
+   * @param idn - IDN, e.g. 0x0020 for S-0-0032 or 0x8000 | n for P-0-n
+   * @param elements - Element selector, defaults to `SOE_ELEMENT_VALUE`
+   * @returns The raw element data
+   */
+  async soeRead(
+    slave: SlaveRef,
+    driveNo: number,
+    idn: number,
+    elements: number = SOE_ELEMENT_VALUE,
+  ): Promise<Uint8Array> {
+    const slaveIndex = this.resolveSlave(slave);
+    const buffer = new Uint8Array(1024);
+    const bytesRead = await this.dl.symbols.ethercrab_soe_read(
+      slaveIndex,
+      driveNo,
+      idn,
+      elements,
+      buffer,
+      BigInt(buffer.length),
+    );
+
+    if (bytesRead < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`SoE read failed: ${message}`, bytesRead, context);
+    }
+    return buffer.slice(0, bytesRead);
+  }
+
+  /**
+   * Write an element of an SoE IDN on a drive.
+   *
+   * @param slave - Zero-based index of the slave, or `{ alias }` / `{ address }`
+   * @param driveNo - Drive number within the slave (0-7)
+   * @param idn - IDN to write
+   * @param data - Raw element data (little endian)
+   * @param elements - Element selector, defaults to `SOE_ELEMENT_VALUE`
+   */
+  async soeWrite(
+    slave: SlaveRef,
+    driveNo: number,
+    idn: number,
+    data: Uint8Array,
+    elements: number = SOE_ELEMENT_VALUE,
+  ): Promise<void> {
+    const slaveIndex = this.resolveSlave(slave);
+    const result = await this.dl.symbols.ethercrab_soe_write(
+      slaveIndex,
+      driveNo,
+      idn,
+      elements,
+      data as unknown as BufferSource,
+      BigInt(data.length),
+    );
+
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`SoE write failed: ${message}`, result, context);
+    }
+  }
+
+  /**
+   * Send a raw mailbox frame to a slave's SM0 (for protocols such as VoE or AoE).
+   * @param slave - Slave index, `{ alias }` or `{ address }`
+   * @param type - Mailbox type (0x1-0xF)
+   * @param counter - Mailbox counter (1-7, 0 disables repeat detection on the slave)
+   * @param data - Mailbox payload without the 6-byte header
+   * @param timeoutMs - Timeout, 0 uses the mailbox response timeout
+   */
+  async mailboxSend(
+    slave: SlaveRef,
+    type: number,
+    counter: number,
+    data: Uint8Array,
+    timeoutMs: number = 0,
+  ): Promise<void> {
+    const slaveIndex = this.resolveSlave(slave);
+    const result = await this.dl.symbols.ethercrab_mailbox_send(
+      slaveIndex,
+      type,
+      counter,
+      data as unknown as BufferSource,
+      BigInt(data.length),
+      timeoutMs,
+    );
+
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`Mailbox send failed: ${message}`, result, context);
+    }
+  }
+
+  /**
+   * Receive the next raw mailbox frame from a slave's SM1.
+   * Mailbox error replies are returned with `type` 0.
+   * @param slave - Slave index, `{ alias }` or `{ address }`
+   * @param timeoutMs - Timeout, 0 uses the mailbox response timeout
+   */
+  async mailboxReceive(
+    slave: SlaveRef,
+    timeoutMs: number = 0,
+  ): Promise<RawMailboxFrame> {
+    const slaveIndex = this.resolveSlave(slave);
+    const buffer = new Uint8Array(1024);
+    const typeOut = new Uint8Array(1);
+    const counterOut = new Uint8Array(1);
+    const result = await this.dl.symbols.ethercrab_mailbox_receive(
+      slaveIndex,
+      buffer,
+      BigInt(buffer.length),
+      typeOut,
+      counterOut,
+      timeoutMs,
+    );
+
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`Mailbox receive failed: ${message}`, result, context);
+    }
+    return { type: typeOut[0], counter: counterOut[0], data: buffer.slice(0, result) };
+  }
+
+  // Feature 305: EEPROM Access
+  async readEEPROM(
+    slave: SlaveRef,
+    address: number,
+    len: number = 2,
+  ): Promise<Uint8Array> {
+    const slaveIndex = this.resolveSlave(slave);
+    const buffer = new Uint8Array(len);
+    const result = await this.dl.symbols.ethercrab_eeprom_read(
+      slaveIndex,
+      address,
+      buffer,
+      BigInt(buffer.length),
+    );
+
     if (result < 0) {
       const { message, context } = this.getLastErrorStructured();
       throw new FfiError(`EEPROM read failed: ${message}`, result, context);
@@ -1057,6 +2129,135 @@ export class EcMaster extends EventEmitter {
     return buffer.slice(0, result); // Result is bytes read
   }
 
+  /**
+   * Write words to the SII EEPROM. Only changed words are written and each is verified.
+   * Writes touching words 0-7 also update the configuration area CRC.
+   * @param slave - Slave index, `{ alias }` or `{ address }`
+   * @param wordAddress - First EEPROM word address
+   * @param data - Data to write (even length, little endian words)
+   * @returns Number of words actually written
+   */
+  async writeEEPROM(
+    slave: SlaveRef,
+    wordAddress: number,
+    data: Uint8Array,
+  ): Promise<number> {
+    const slaveIndex = this.resolveSlave(slave);
+    const result = await this.dl.symbols.ethercrab_eeprom_write(
+      slaveIndex,
+      wordAddress,
+      data as unknown as BufferSource,
+      BigInt(data.length),
+    );
+
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`EEPROM write failed: ${message}`, result, context);
+    }
+    return result;
+  }
+
+  /**
+   * Write and verify a complete SII image (starting at word 0). The configuration
+   * area CRC is recalculated from the image.
+   * @param slave - Slave index, `{ alias }` or `{ address }`
+   * @param image - Full EEPROM image
+   * @param dryRun - Only report differing words, write nothing
+   * @returns Word addresses that differed (and were written unless `dryRun`)
+   */
+  async writeEEPROMImage(
+    slave: SlaveRef,
+    image: Uint8Array,
+    dryRun: boolean = false,
+  ): Promise<number[]> {
+    const slaveIndex = this.resolveSlave(slave);
+    const diffs = new Uint16Array(image.length / 2);
+    const result = await this.dl.symbols.ethercrab_eeprom_write_image(
+      slaveIndex,
+      image as unknown as BufferSource,
+      BigInt(image.length),
+      dryRun ? 1 : 0,
+      diffs,
+      BigInt(diffs.length),
+    );
+
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`EEPROM image write failed: ${message}`, result, context);
+    }
+    return Array.from(diffs.slice(0, result));
+  }
+
+  /**
+   * Scan the network through the running master (PreOp, SafeOp or Op).
+   * Unlike scanNetwork() the interface is not reopened and the cyclic exchange keeps running;
+   * only acyclic register, SII and SDO reads are issued. The DC receive times are not
+   * re-latched, so port delays are not reported. Destroying the master cancels a running scan.
+   */
+  async scanLive(options?: ScanOptions): Promise<ScanResult> {
+    options?.signal?.throwIfAborted();
+    const ctx = await EcMaster.trackScan(
+      this.dl,
+      options,
+      this.dl.symbols.ethercrab_scan_live(EcMaster.encodeScanOptions(options)),
+    );
+    if (ctx === null) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`Live scan failed: ${message}`, -1, context);
+    }
+    try {
+      const buffer = new Uint8Array(1024 * 1024);
+      const result = this.dl.symbols.ethercrab_scan_get_json(ctx, buffer, BigInt(buffer.length));
+      if (result < 0) {
+        const { message, context } = this.getLastErrorStructured();
+        throw new FfiError(`Scan JSON export failed: ${message}`, result, context);
+      }
+      return JSON.parse(new TextDecoder().decode(buffer.subarray(0, result)));
+    } finally {
+      this.dl.symbols.ethercrab_scan_free(ctx);
+    }
+  }
+
+  /**
+   * Read and parse a slave's SII EEPROM (general, strings, FMMU, SM, PDO and DC categories).
+   * @param slave - Slave index, `{ alias }` or `{ address }`
+   */
+  async readSII(slave: SlaveRef): Promise<SiiInfo> {
+    const slaveIndex = this.resolveSlave(slave);
+    const buffer = new Uint8Array(65536);
+    const result = await this.dl.symbols.ethercrab_sii_read_json(
+      slaveIndex,
+      buffer,
+      BigInt(buffer.length),
+    );
+
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`SII read failed: ${message}`, result, context);
+    }
+    return JSON.parse(new TextDecoder().decode(buffer.subarray(0, result)));
+  }
+
+  /**
+   * Parse a raw SII image (e.g. from a file or `readEEPROM`) without accessing the network.
+   * @param image - SII image starting at word 0
+   */
+  parseSII(image: Uint8Array): SiiInfo {
+    const buffer = new Uint8Array(65536);
+    const result = this.dl.symbols.ethercrab_sii_parse_json(
+      image as unknown as BufferSource,
+      BigInt(image.length),
+      buffer,
+      BigInt(buffer.length),
+    );
+
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`SII parse failed: ${message}`, result, context);
+    }
+    return JSON.parse(new TextDecoder().decode(buffer.subarray(0, result)));
+  }
+
   // Feature 404: Mailbox Polling
   configureMailboxPolling(intervalMs: number): void {
     const result = this.dl.symbols.ethercrab_configure_mailbox_polling(
@@ -1065,7 +2266,8 @@ export class EcMaster extends EventEmitter {
     if (result < 0) throw new FfiError("Mailbox polling config failed", result);
   }
 
-  async checkMailbox(slaveIndex: number, statusAddr: number): Promise<boolean> {
+  async checkMailbox(slave: SlaveRef, statusAddr: number): Promise<boolean> {
+    const slaveIndex = this.resolveSlave(slave);
     const result = await this.dl.symbols.ethercrab_check_mailbox(
       slaveIndex,
       statusAddr,
@@ -1218,11 +2420,12 @@ export class EcMaster extends EventEmitter {
    * - 0x0420: SM Watchdog timeout (default ~1000 with divider = ~100ms)
    * - 0x0440: SM Watchdog status
    *
-   * @param slaveIndex - Zero-based index of the slave
+   * @param slave - Zero-based index of the slave, or `{ alias }` / `{ address }`
    * @param registerAddress - Register address (e.g., 0x0420 for SM watchdog)
    * @returns The 16-bit register value
    */
-  async registerRead(slaveIndex: number, registerAddress: number): Promise<number> {
+  async registerRead(slave: SlaveRef, registerAddress: number): Promise<number> {
+    const slaveIndex = this.resolveSlave(slave);
     const result = await this.dl.symbols.ethercrab_register_read_u16(
       slaveIndex,
       registerAddress,
@@ -1242,11 +2445,12 @@ export class EcMaster extends EventEmitter {
    * - 0x0410: PDI Watchdog timeout
    * - 0x0420: SM Watchdog timeout
    *
-   * @param slaveIndex - Zero-based index of the slave
+   * @param slave - Zero-based index of the slave, or `{ alias }` / `{ address }`
    * @param registerAddress - Register address
    * @param value - The 16-bit value to write
    */
-  async registerWrite(slaveIndex: number, registerAddress: number, value: number): Promise<void> {
+  async registerWrite(slave: SlaveRef, registerAddress: number, value: number): Promise<void> {
+    const slaveIndex = this.resolveSlave(slave);
     const result = await this.dl.symbols.ethercrab_register_write_u16(
       slaveIndex,
       registerAddress,
@@ -1258,16 +2462,119 @@ export class EcMaster extends EventEmitter {
     }
   }
 
+  /**
+   * Read 1..1024 bytes starting at an ESC register. The target selects FPRD (configured
+   * station address), APRD (ring position) or BRD (all slaves, data OR-ed together).
+   * Rejects when no slave answered. A partial broadcast answer resolves with the lower WKC
+   * and empty data, since the native layer cannot recover the payload of a short response.
+   * @param target - `{ configuredAddress }`, `{ position }` or `"broadcast"`
+   * @param registerAddress - First register address
+   * @param length - Number of bytes to read
+   */
+  async readRegisters(
+    target: RegisterTarget,
+    registerAddress: number,
+    length: number,
+  ): Promise<RegisterReadResult> {
+    const [mode, address] = EcMaster.registerTargetArgs(target);
+    const data = new Uint8Array(length);
+    const wkc = new Uint16Array(1);
+    const result = await this.dl.symbols.ethercrab_register_read(
+      mode,
+      address,
+      registerAddress,
+      data,
+      length,
+      wkc,
+    );
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`Register read failed: ${message}`, result, context);
+    }
+    return { data: data.subarray(0, result), wkc: wkc[0] };
+  }
+
+  /**
+   * Write 1..1024 bytes starting at an ESC register with FPWR, APWR or BWR.
+   * @param target - `{ configuredAddress }`, `{ position }` or `"broadcast"`
+   * @param registerAddress - First register address
+   * @param data - Bytes to write
+   * @returns Working counter (slaves that accepted the write)
+   */
+  async writeRegisters(
+    target: RegisterTarget,
+    registerAddress: number,
+    data: Uint8Array,
+  ): Promise<number> {
+    const [mode, address] = EcMaster.registerTargetArgs(target);
+    const wkc = new Uint16Array(1);
+    const result = await this.dl.symbols.ethercrab_register_write(
+      mode,
+      address,
+      registerAddress,
+      data,
+      data.length,
+      wkc,
+    );
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`Register write failed: ${message}`, result, context);
+    }
+    return wkc[0];
+  }
+
+  /**
+   * Send exactly one LRD, LWR, LRW or FRMW datagram through the PDU loop, without going
+   * through the slave group. Intended for support consoles and diagnostics.
+   * @param command - Datagram type
+   * @param address - Logical address, or for FRMW `(register << 16) | stationAddress`
+   * @param options.data - Payload for LWR/LRW
+   * @param options.length - Response length for LRD/FRMW (defaults to the payload length)
+   * @param options.expectedWkc - Working counter the datagram should reach (default 1)
+   * @returns Response data (empty when the WKC missed `expectedWkc`) and the working counter
+   */
+  async sendDatagram(
+    command: DatagramCommand,
+    address: number,
+    options: { data?: Uint8Array; length?: number; expectedWkc?: number } = {},
+  ): Promise<DatagramResult> {
+    const codes: Record<DatagramCommand, number> = { LRD: 10, LWR: 11, LRW: 12, FRMW: 14 };
+    const length = options.length ?? options.data?.length ?? 0;
+    const response = new Uint8Array(length);
+    const wkc = new Uint16Array(1);
+    const result = await this.dl.symbols.ethercrab_raw_datagram(
+      codes[command],
+      address,
+      options.data ?? null,
+      length,
+      options.expectedWkc ?? 1,
+      response,
+      wkc,
+    );
+    if (result < 0) {
+      const { message, context } = this.getLastErrorStructured();
+      throw new FfiError(`${command} datagram failed: ${message}`, result, context);
+    }
+    return { data: response.subarray(0, result), wkc: wkc[0] };
+  }
+
+  private static registerTargetArgs(target: RegisterTarget): [number, number] {
+    if (target === "broadcast") return [2, 0];
+    if ("position" in target) return [1, target.position];
+    return [0, target.configuredAddress];
+  }
+
   /**
    * Read the SM watchdog timeout for a slave.
    *
    * The SM watchdog triggers if no valid process data is received within the timeout,
    * resetting outputs to a safe state (typically 0). Default is typically ~100ms.
    *
-   * @param slaveIndex - Zero-based index of the slave
+   * @param slave - Zero-based index of the slave, or `{ alias }` / `{ address }`
    * @returns The watchdog timeout value in watchdog divider units
    */
-  async getSmWatchdogTimeout(slaveIndex: number): Promise<number> {
+  async getSmWatchdogTimeout(slave: SlaveRef): Promise<number> {
+    const slaveIndex = this.resolveSlave(slave);
     return await this.registerRead(slaveIndex, RegisterAddress.SM_WATCHDOG);
   }
 
@@ -1285,10 +2592,11 @@ export class EcMaster extends EventEmitter {
    * - 10000 = ~1000ms (1 second)
    * - 0 = watchdog disabled (NOT RECOMMENDED for safety)
    *
-   * @param slaveIndex - Zero-based index of the slave
+   * @param slave - Zero-based index of the slave, or `{ alias }` / `{ address }`
    * @param timeoutValue - Watchdog timeout value (0 to disable, >0 to set)
    */
-  async setSmWatchdogTimeout(slaveIndex: number, timeoutValue: number): Promise<void> {
+  async setSmWatchdogTimeout(slave: SlaveRef, timeoutValue: number): Promise<void> {
+    const slaveIndex = this.resolveSlave(slave);
     await this.registerWrite(slaveIndex, RegisterAddress.SM_WATCHDOG, timeoutValue);
   }
 
@@ -1307,7 +2615,8 @@ export class EcMaster extends EventEmitter {
   }
 
   // Error Handling
-  async getLastAlStatusCode(slaveIndex: number): Promise<AlStatusCode> {
+  async getLastAlStatusCode(slave: SlaveRef): Promise<AlStatusCode> {
+    const slaveIndex = this.resolveSlave(slave);
     return (await this.dl.symbols.ethercrab_get_al_status_code(
       slaveIndex,
     )) as AlStatusCode;
@@ -1343,6 +2652,7 @@ export class EcMaster extends EventEmitter {
 
     this.stopMailboxPolling();
     this.stopEmergencyPolling();
+    this.stopLinkMonitor();
 
     try {
       // Await the native destroy - it joins the TX/RX thread (up to ~50-100ms)
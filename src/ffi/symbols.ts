@@ -128,6 +128,19 @@ export const ethercrabSymbols = {
     result: "i32",
     nonblocking: true, // Async: Network I/O (SDO)
   },
+  ethercrab_pdo_remap: {
+    parameters: [
+      "u16", // slave_index
+      "u16", // assign_index (0x1C12 RxPDO, 0x1C13 TxPDO)
+      "buffer", // pdo_indices (u16 per PDO)
+      "buffer", // entry_counts (u8 per PDO)
+      "usize", // pdo_count
+      "buffer", // entries (u32 per entry: index << 16 | sub_index << 8 | bit_len)
+      "usize", // entry_count
+    ],
+    result: "i32",
+    nonblocking: true, // Async: Network I/O (SDO)
+  },
   ethercrab_eeprom_read: {
     parameters: [
       "u16", // slave_index
@@ -138,6 +151,94 @@ export const ethercrabSymbols = {
     result: "i32", // bytes read or error
     nonblocking: true, // Async: Network I/O (EEPROM)
   },
+  ethercrab_eeprom_write: {
+    parameters: [
+      "u16", // slave_index
+      "u16", // word_address
+      "buffer", // data (even length)
+      "usize", // len
+    ],
+    result: "i32", // words written or error
+    nonblocking: true, // Async: Network I/O (EEPROM)
+  },
+  ethercrab_eeprom_write_image: {
+    parameters: [
+      "u16", // slave_index
+      "buffer", // image (starting at word 0)
+      "usize", // len
+      "u8", // dry_run (1 = only report differences)
+      "buffer", // diff_out: *mut u16 (may be null)
+      "usize", // max_diffs
+    ],
+    result: "i32", // number of differing words or error
+    nonblocking: true, // Async: Network I/O (EEPROM)
+  },
+  ethercrab_sii_read_json: {
+    parameters: [
+      "u16", // slave_index
+      "buffer", // buffer (JSON output)
+      "usize", // len
+    ],
+    result: "i32", // bytes written or error
+    nonblocking: true, // Async: Network I/O (EEPROM)
+  },
+  ethercrab_sii_parse_json: {
+    parameters: [
+      "buffer", // image
+      "usize", // image_len
+      "buffer", // buffer (JSON output)
+      "usize", // len
+    ],
+    result: "i32", // bytes written or error
+  },
+  ethercrab_soe_read: {
+    parameters: [
+      "u16", // slave_index
+      "u8", // drive_no
+      "u16", // idn
+      "u8", // elements (SOE_ELEMENT_* flags)
+      "buffer", // data_out
+      "usize", // max_len
+    ],
+    result: "i32", // bytes read or error
+    nonblocking: true, // Async: Network I/O (SoE mailbox)
+  },
+  ethercrab_soe_write: {
+    parameters: [
+      "u16", // slave_index
+      "u8", // drive_no
+      "u16", // idn
+      "u8", // elements (SOE_ELEMENT_* flags)
+      "buffer", // data
+      "usize", // len
+    ],
+    result: "i32",
+    nonblocking: true, // Async: Network I/O (SoE mailbox)
+  },
+  ethercrab_mailbox_send: {
+    parameters: [
+      "u16", // slave_index
+      "u8", // mailbox_type (0x1-0xF, e.g. 0x1 AoE, 0xF VoE)
+      "u8", // counter (0-7)
+      "buffer", // data
+      "usize", // len
+      "u32", // timeout_ms (0 = mailbox response timeout)
+    ],
+    result: "i32",
+    nonblocking: true, // Async: Network I/O (SM0 write)
+  },
+  ethercrab_mailbox_receive: {
+    parameters: [
+      "u16", // slave_index
+      "buffer", // data_out
+      "usize", // max_len
+      "buffer", // type_out: *mut u8
+      "buffer", // counter_out: *mut u8
+      "u32", // timeout_ms (0 = mailbox response timeout)
+    ],
+    result: "i32", // payload bytes
+    nonblocking: true, // Async: Network I/O (SM1 read)
+  },
   ethercrab_get_last_emergency: {
     parameters: [
       "buffer", // out: *mut EmergencyInfo
@@ -156,6 +257,173 @@ export const ethercrabSymbols = {
     // nonblocking: false, // Sync: Memory read (but uses block_on internally)
   },
 
+  // Typed process data access by (slave, index, sub_index), see ethercrab_pd_symbol_count
+  ethercrab_pd_symbol_count: {
+    parameters: [],
+    result: "u32",
+  },
+  ethercrab_pd_get_symbol: {
+    parameters: ["u32", "buffer"], // idx, out: *mut FfiProcessSymbol
+    result: "i32",
+  },
+  ethercrab_pd_get_bool: {
+    parameters: ["u16", "u16", "u8", "buffer"], // slave_index, index, sub_index, out: *mut u8
+    result: "i32",
+  },
+  ethercrab_pd_set_bool: {
+    parameters: ["u16", "u16", "u8", "u8"], // slave_index, index, sub_index, value
+    result: "i32",
+  },
+  ethercrab_pd_get_i8: {
+    parameters: ["u16", "u16", "u8", "buffer"], // slave_index, index, sub_index, out: *mut i8
+    result: "i32",
+  },
+  ethercrab_pd_set_i8: {
+    parameters: ["u16", "u16", "u8", "i8"], // slave_index, index, sub_index, value
+    result: "i32",
+  },
+  ethercrab_pd_get_i16: {
+    parameters: ["u16", "u16", "u8", "buffer"], // slave_index, index, sub_index, out: *mut i16
+    result: "i32",
+  },
+  ethercrab_pd_set_i16: {
+    parameters: ["u16", "u16", "u8", "i16"], // slave_index, index, sub_index, value
+    result: "i32",
+  },
+  ethercrab_pd_get_i32: {
+    parameters: ["u16", "u16", "u8", "buffer"], // slave_index, index, sub_index, out: *mut i32
+    result: "i32",
+  },
+  ethercrab_pd_set_i32: {
+    parameters: ["u16", "u16", "u8", "i32"], // slave_index, index, sub_index, value
+    result: "i32",
+  },
+  ethercrab_pd_get_i64: {
+    parameters: ["u16", "u16", "u8", "buffer"], // slave_index, index, sub_index, out: *mut i64
+    result: "i32",
+  },
+  ethercrab_pd_set_i64: {
+    parameters: ["u16", "u16", "u8", "i64"], // slave_index, index, sub_index, value
+    result: "i32",
+  },
+  ethercrab_pd_get_u8: {
+    parameters: ["u16", "u16", "u8", "buffer"], // slave_index, index, sub_index, out: *mut u8
+    result: "i32",
+  },
+  ethercrab_pd_set_u8: {
+    parameters: ["u16", "u16", "u8", "u8"], // slave_index, index, sub_index, value
+    result: "i32",
+  },
+  ethercrab_pd_get_u16: {
+    parameters: ["u16", "u16", "u8", "buffer"], // slave_index, index, sub_index, out: *mut u16
+    result: "i32",
+  },
+  ethercrab_pd_set_u16: {
+    parameters: ["u16", "u16", "u8", "u16"], // slave_index, index, sub_index, value
+    result: "i32",
+  },
+  ethercrab_pd_get_u32: {
+    parameters: ["u16", "u16", "u8", "buffer"], // slave_index, index, sub_index, out: *mut u32
+    result: "i32",
+  },
+  ethercrab_pd_set_u32: {
+    parameters: ["u16", "u16", "u8", "u32"], // slave_index, index, sub_index, value
+    result: "i32",
+  },
+  ethercrab_pd_get_u64: {
+    parameters: ["u16", "u16", "u8", "buffer"], // slave_index, index, sub_index, out: *mut u64
+    result: "i32",
+  },
+  ethercrab_pd_set_u64: {
+    parameters: ["u16", "u16", "u8", "u64"], // slave_index, index, sub_index, value
+    result: "i32",
+  },
+  ethercrab_pd_get_f32: {
+    parameters: ["u16", "u16", "u8", "buffer"], // slave_index, index, sub_index, out: *mut f32
+    result: "i32",
+  },
+  ethercrab_pd_set_f32: {
+    parameters: ["u16", "u16", "u8", "f32"], // slave_index, index, sub_index, value
+    result: "i32",
+  },
+  ethercrab_pd_get_f64: {
+    parameters: ["u16", "u16", "u8", "buffer"], // slave_index, index, sub_index, out: *mut f64
+    result: "i32",
+  },
+  ethercrab_pd_set_f64: {
+    parameters: ["u16", "u16", "u8", "f64"], // slave_index, index, sub_index, value
+    result: "i32",
+  },
+  ethercrab_pd_bit: {
+    parameters: ["u16", "bool", "u32", "u8"], // slave_index, is_output, bit_offset, op (0 read, 1 set, 2 clear, 3 toggle)
+    result: "i32",
+  },
+  ethercrab_pdi_bit: {
+    parameters: ["u32", "u8"], // pdi_bit_offset, op
+    result: "i32",
+  },
+  ethercrab_pd_layout_count: {
+    parameters: [],
+    result: "u32",
+  },
+  ethercrab_pd_get_layout: {
+    parameters: ["u16", "buffer"], // slave_index, out: *mut FfiPdiSlaveLayout
+    result: "i32",
+  },
+  ethercrab_failsafe_configure: {
+    parameters: ["u16", "u8", "buffer", "usize"], // slave_index, mode (0 none, 1 zero, 2 hold, 3 user), data, len
+    result: "i32",
+  },
+  ethercrab_failsafe_get_active: {
+    parameters: ["buffer", "usize"], // out: *mut u16, max
+    result: "i32",
+  },
+  ethercrab_failsafe_get_reason: {
+    parameters: [],
+    result: "u8",
+  },
+  ethercrab_failsafe_reset: {
+    parameters: [],
+    result: "i32",
+  },
+  // Host liveness watchdog, see ethercrab_watchdog_start
+  ethercrab_watchdog_start: {
+    parameters: ["u32"], // timeout_ms
+    result: "i32",
+  },
+  ethercrab_watchdog_stop: {
+    parameters: [],
+    result: "i32",
+  },
+  ethercrab_watchdog_kick: {
+    parameters: [],
+    result: "i32", // 0 ok, 1 latched after a miss, -1 not running
+  },
+  ethercrab_watchdog_acknowledge: {
+    parameters: [],
+    result: "i32",
+  },
+  ethercrab_watchdog_get_trip_count: {
+    parameters: [],
+    result: "u32",
+  },
+  ethercrab_watchdog_get_last_trip: {
+    parameters: ["buffer", "buffer", "usize"], // trip_count: *mut u32, message, len
+    result: "i32", // message bytes, 0 if none yet, -1 not running
+  },
+  ethercrab_pd_slave_sizes: {
+    parameters: ["u16", "buffer", "buffer"], // slave_index, input_size: *mut u32, output_size: *mut u32
+    result: "i32",
+  },
+  ethercrab_pd_read_slave: {
+    parameters: ["u16", "bool", "u32", "buffer", "usize"], // slave_index, is_output, offset, buf, len
+    result: "i32",
+  },
+  ethercrab_pd_write_slave: {
+    parameters: ["u16", "u32", "buffer", "usize"], // slave_index, offset, data, len
+    result: "i32",
+  },
+
   // Register Read/Write (for watchdog configuration etc.)
   ethercrab_register_read_u16: {
     parameters: ["u16", "u16"], // slave_index, register_address
@@ -167,6 +435,74 @@ export const ethercrabSymbols = {
     result: "i32", // 0 = success, negative = error
     nonblocking: true, // Async: Network I/O (register write)
   },
+  ethercrab_find_slave_by_alias: {
+    parameters: ["u16"], // alias (0x0012 / SII word 4)
+    result: "i32", // slave index, -2 = not found, -3 = alias not unique
+  },
+  ethercrab_find_slave_by_address: {
+    parameters: ["u16"], // configured station address
+    result: "i32", // slave index, -2 = not found
+  },
+  ethercrab_register_read: {
+    // mode (0 = configured address, 1 = position, 2 = broadcast), address, register,
+    // buf: *mut u8, len, wkc_out: *mut u16
+    parameters: ["u8", "u16", "u16", "buffer", "u16", "buffer"],
+    result: "i32", // bytes read, negative = error
+    nonblocking: true, // Async: Network I/O (FPRD/APRD/BRD)
+  },
+  ethercrab_register_write: {
+    // mode, address, register, data: *const u8, len, wkc_out: *mut u16
+    parameters: ["u8", "u16", "u16", "buffer", "u16", "buffer"],
+    result: "i32", // 0 = success, negative = error
+    nonblocking: true, // Async: Network I/O (FPWR/APWR/BWR)
+  },
+  ethercrab_raw_datagram: {
+    // command (10 = LRD, 11 = LWR, 12 = LRW, 14 = FRMW), address, data: *const u8 (null for
+    // reads), len, expected_wkc, response: *mut u8, wkc_out: *mut u16
+    parameters: ["u8", "u32", "buffer", "u16", "u16", "buffer", "buffer"],
+    result: "i32", // response bytes, negative = error
+    nonblocking: true, // Async: Network I/O (single datagram)
+  },
+  ethercrab_esc_watchdog_configure: {
+    // sm_timeout_ms, pdi_timeout_ms (0xFFFFFFFF = keep), slave_indices (null = all), slave_count,
+    // out: *mut FfiEscWatchdog, out_len
+    parameters: ["u32", "u32", "buffer", "usize", "buffer", "usize"],
+    result: "i32", // slaves configured (failures are reported per slave in out), negative = error
+    nonblocking: true, // Async: Network I/O (register write + read-back)
+  },
+  ethercrab_esc_watchdog_read: {
+    parameters: ["u16", "buffer"], // slave_index, out: *mut FfiEscWatchdog
+    result: "i32",
+    nonblocking: true, // Async: Network I/O (register read)
+  },
+  ethercrab_esc_error_counters_read: {
+    parameters: ["buffer", "usize"], // out: *mut FfiEscErrorReport, max
+    result: "i32", // slave count, negative = error
+    nonblocking: true, // Async: Network I/O (FPRD per slave)
+  },
+  ethercrab_esc_error_counters_clear: {
+    parameters: [],
+    result: "i32",
+    nonblocking: true, // Async: Network I/O (BWR)
+  },
+  ethercrab_link_monitor_start: {
+    parameters: [],
+    result: "i32", // slave count, negative = error
+    nonblocking: true, // Async: Network I/O (DL status of every slave)
+  },
+  ethercrab_link_monitor_check: {
+    parameters: ["buffer", "usize"], // out: *mut FfiLinkEvent, max
+    result: "i32", // event count, negative = error
+    nonblocking: true, // Async: Network I/O (BRD, FPRD on topology change)
+  },
+  ethercrab_link_monitor_stop: {
+    parameters: [],
+    result: "i32",
+  },
+  ethercrab_esc_error_rank: {
+    parameters: ["buffer", "usize"], // out: *mut FfiEscPortRank, max
+    result: "i32", // ranked port count, negative = error
+  },
 
   // Discovery
   ethercrab_scan_new: {
@@ -174,6 +510,24 @@ export const ethercrabSymbols = {
     result: "pointer", // *mut ScanContext
     nonblocking: true,
   },
+  ethercrab_scan_new_with_options: {
+    parameters: ["buffer", "buffer"], // interface: *const c_char, options: *const FfiScanOptions (nullable)
+    result: "pointer", // *mut ScanContext
+    nonblocking: true,
+  },
+  ethercrab_scan_live: {
+    parameters: ["buffer"], // options: *const FfiScanOptions (nullable)
+    result: "pointer", // *mut ScanContext (scan through the running master)
+    nonblocking: true,
+  },
+  ethercrab_scan_get_progress: {
+    parameters: ["buffer", "buffer"], // done: *mut u32, total: *mut u32
+    result: "i32", // 1 = scan running, 0 = idle
+  },
+  ethercrab_scan_cancel: {
+    parameters: [],
+    result: "i32", // 0 or -1 (no scan running)
+  },
   ethercrab_scan_get_slave_count: {
     parameters: ["pointer"],
     result: "u32",
@@ -182,6 +536,10 @@ export const ethercrabSymbols = {
     parameters: ["pointer", "u32", "buffer"], // ctx, idx, out_info
     result: "i32",
   },
+  ethercrab_scan_get_slave_topology: {
+    parameters: ["pointer", "u32", "buffer"], // ctx, idx, out_topology
+    result: "i32",
+  },
   ethercrab_scan_get_pdo_count: {
     parameters: ["pointer", "u32"], // ctx, slave_idx
     result: "u32",
@@ -198,6 +556,14 @@ export const ethercrabSymbols = {
     parameters: ["pointer", "u32", "u32", "u32", "buffer"], // ctx, slave_idx, pdo_pos, entry_pos, out_info
     result: "i32",
   },
+  ethercrab_scan_get_json: {
+    parameters: ["pointer", "buffer", "usize"], // ctx, buffer (JSON output), len
+    result: "i32", // bytes written or error
+  },
+  ethercrab_scan_export_eni: {
+    parameters: ["pointer", "buffer", "usize"], // ctx, buffer (XML output), len
+    result: "i32", // bytes written or error
+  },
   ethercrab_scan_free: {
     parameters: ["pointer"],
     result: "void",
@@ -228,7 +594,7 @@ export const SLAVE_IDENTITY_SIZE = 16;
 
 // FfiInitCommand Layout (packed with padding for alignment):
 // 0: slave_index (u16)
-// 2: command_type (u8)
+// 2: command_type (u8) -> 0=SDO, 1=Register, 2=SoE (index=IDN, sub_index=drive number)
 // 3: padding (1 byte) -> aligns next u16 to offset 4
 // 4: index (u16)
 // 6: sub_index (u8)
@@ -237,6 +603,14 @@ export const SLAVE_IDENTITY_SIZE = 16;
 // Total: 12 bytes
 export const INIT_COMMAND_SIZE = 12;
 
+// SoE IDN element selectors (SoE header "elements" byte)
+export const SOE_ELEMENT_NAME = 0x02;
+export const SOE_ELEMENT_ATTRIBUTE = 0x04;
+export const SOE_ELEMENT_UNIT = 0x08;
+export const SOE_ELEMENT_MIN = 0x10;
+export const SOE_ELEMENT_MAX = 0x20;
+export const SOE_ELEMENT_VALUE = 0x40;
+
 // EmergencyInfo
 // slave_index: u16 (2)
 // error_code: u16 (2)
@@ -246,7 +620,15 @@ export const INIT_COMMAND_SIZE = 12;
 export const EMERGENCY_INFO_SIZE = 6;
 
 // Discovery Struct Sizes (aligned)
-export const FFI_SLAVE_INFO_SIZE = 92; // 16 + 64 + 2 + 2 + 1 + 1 + 2 + 1 + 1 = 90 -> padded to 92
+export const FFI_SLAVE_INFO_SIZE = 100; // 16 + 64 + 2 + 2 + 1 + 1 + 2 + 1 + 1 + 1 + 1 + 1 + 1 + 4 + 2 = 100
+export const FFI_SLAVE_TOPOLOGY_SIZE = 52; // 2 + 2 + 1 + 1 + 4 + 4 + 4 + 1 + 1 + 16 + 16 = 52
+export const FFI_SCAN_OPTIONS_SIZE = 24; // 6 x u32
+export const FFI_PROCESS_SYMBOL_SIZE = 20; // 2 + 2 + 2 + 1 + 1 + 4 + 2 + 2 + 4 = 20
+export const FFI_PDI_SLAVE_LAYOUT_SIZE = 24; // 6 x u32
+export const FFI_ESC_WATCHDOG_SIZE = 24; // 2 + 2 + 2 + 2 + 4 + 4 + 2 + 1 + 1 + 1 + 1 + 2 (pad) = 24
+export const FFI_ESC_ERROR_REPORT_SIZE = 44; // 2 + 18 + 18 + 1 + 1 + 4 = 44
+export const FFI_ESC_PORT_RANK_SIZE = 12; // 2 + 1 + 1 + 4 + 4 = 12
+export const FFI_LINK_EVENT_SIZE = 12; // 2 + 1 + 1 + 2 + 2 + 2 + 2 = 12
 export const FFI_PDO_INFO_SIZE = 68; // 2 + 1 + 1 + 64 = 68
 export const FFI_PDO_ENTRY_INFO_SIZE = 70; // 2 + 1 + 1 + 2 + 64 = 70
 export const NAME_BUFFER_SIZE = 64;
@@ -42,6 +42,7 @@ interface MockOverrides {
   ethercrab_get_last_emergency?: (buf: Uint8Array) => number;
   ethercrab_register_read_u16?: (idx: number, addr: number) => Promise<number>;
   ethercrab_register_write_u16?: (idx: number, addr: number, val: number) => Promise<number>;
+  ethercrab_esc_watchdog_configure?: (...args: any[]) => Promise<number>;
   [key: string]: unknown;
 }
 
@@ -90,6 +91,8 @@ function setupMocks(overrides: MockOverrides = {}) {
           ((_idx: any, _addr: any) => Promise.resolve(0)),
         ethercrab_register_write_u16: overrides.ethercrab_register_write_u16 ??
           ((_idx: any, _addr: any, _val: any) => Promise.resolve(0)),
+        ethercrab_esc_watchdog_configure: overrides.ethercrab_esc_watchdog_configure ??
+          ((..._args: any[]) => Promise.resolve(0)),
         ethercrab_version: (buf: Uint8Array, _len: any) => {
           const encoded = new TextEncoder().encode(EcMaster.REQUIRED_FFI_VERSION);
           buf.set(encoded);
@@ -475,6 +478,42 @@ Deno.test({
   },
 });
 
+Deno.test({
+  name: "SDO read retries with the size reported in required_len",
+  fn: async () => {
+    const sdoData = new Uint8Array(300).map((_, i) => i & 0xFF);
+    const bufferSizes: number[] = [];
+    setupMocks({
+      ethercrab_get_state: () => SlaveState.PRE_OP,
+      ethercrab_sdo_read: async (_idx: any, _i: any, _si: any, buf: Uint8Array, len: bigint) => {
+        bufferSizes.push(Number(len));
+        if (Number(len) < sdoData.length) return -4;
+        buf.set(sdoData);
+        return sdoData.length;
+      },
+      ethercrab_get_last_error: (buf: Uint8Array, _len: bigint) => {
+        const msg = 'SDO read buffer too small||{"op":"sdo_read","required_len":"300"}';
+        const encoded = new TextEncoder().encode(msg);
+        buf.set(encoded);
+        return encoded.length;
+      },
+    });
+
+    let master: EcMaster | null = null;
+    try {
+      master = new EcMaster(mixedSlaveConfig);
+      await master.initialize();
+
+      const result = await master.sdoRead(2, 0x1008, 0);
+      assertEquals(bufferSizes, [256, 300], "SDO read should retry once with the required size");
+      assertEquals(result, sdoData);
+    } finally {
+      if (master) await master.close();
+      teardownMocks();
+    }
+  },
+});
+
 Deno.test({
   name: "SDO write succeeds after init with CoE slave",
   fn: async () => {
@@ -689,7 +728,7 @@ Deno.test({
   name: "Watchdog configured before PreOp→SafeOp when watchdogTimeoutMs is set",
   fn: async () => {
     let currentState = SlaveState.PRE_OP;
-    const registerWrites: { idx: number; addr: number; val: number }[] = [];
+    const watchdogCalls: { smMs: number; pdiMs: number; state: number }[] = [];
 
     setupMocks({
       ethercrab_get_state: () => currentState,
@@ -697,9 +736,9 @@ Deno.test({
         currentState = state;
         return 0;
       },
-      ethercrab_register_write_u16: async (idx: number, addr: number, val: number) => {
-        registerWrites.push({ idx, addr, val });
-        return 0;
+      ethercrab_esc_watchdog_configure: async (smMs: number, pdiMs: number) => {
+        watchdogCalls.push({ smMs, pdiMs, state: currentState });
+        return 1;
       },
     });
 
@@ -710,14 +749,11 @@ Deno.test({
 
       await master.requestState(SlaveState.SAFE_OP);
 
-      // Watchdog register (0x0420) should have been written before SafeOp
-      const smWatchdogWrite = registerWrites.find((w) => w.addr === 0x0420);
-      assert(smWatchdogWrite !== undefined, "SM watchdog register should be written");
-      assertEquals(
-        smWatchdogWrite!.val,
-        2000, // 200ms * 10
-        "Watchdog timeout value should be correctly computed",
-      );
+      // Watchdogs should have been configured in PreOp, divider/counts are computed natively
+      assertEquals(watchdogCalls.length, 1, "Watchdogs should be configured once");
+      assertEquals(watchdogCalls[0].smMs, 200, "SM watchdog time should be passed in ms");
+      assertEquals(watchdogCalls[0].pdiMs, 0xFFFFFFFF, "PDI watchdog should be kept");
+      assertEquals(watchdogCalls[0].state, SlaveState.PRE_OP);
     } finally {
       if (master) await master.close();
       teardownMocks();
@@ -735,8 +771,16 @@ Deno.test({
         currentState = state;
         return 0;
       },
-      ethercrab_register_write_u16: async (_idx: number, _addr: number, _val: number) => {
-        return -1; // Watchdog write fails (e.g., coupler doesn't support it)
+      ethercrab_esc_watchdog_configure: async (
+        _smMs: number,
+        _pdiMs: number,
+        _slaves: any,
+        _count: any,
+        out: Uint8Array,
+      ) => {
+        // Slave 0 reported as failed (valid = 0, error = -3, e.g. read-back mismatch)
+        out[21] = 0xFD;
+        return 0;
       },
     });
 
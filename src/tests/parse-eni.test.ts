@@ -272,3 +272,44 @@ Deno.test("parseEniXml - Test Case 1.4: No Protocol Support", async () => {
   assertEquals(slave.supportsEoE, undefined, "slave.supportsEoE should be undefined");
   assertEquals(slave.supportsFoE, undefined, "slave.supportsFoE should be undefined");
 });
+
+// discovered.eni.xml is the exact output of ScanContext::to_eni_xml() (pinned by
+// test_eni_export_matches_fixture in ethercrab_ffi/src/scan_tests.rs)
+Deno.test("parseEniXml - loads an ENI exported by the scanner", async () => {
+  const config = await parseEniXml("./src/tests/fixtures/discovered.eni.xml");
+  assertEquals(config.slaves.length, 2);
+  assertEquals(config.processImage?.inputs.byteSize, 5);
+
+  const slave = config.slaves[1]!;
+  assertEquals(slave.name, "EL3102");
+  assertEquals(slave.physAddr, 1002);
+  assertEquals(slave.supportsCoE, true);
+
+  const pdos = slave.processData?.pdos;
+  assertExists(pdos);
+  assertEquals(pdos.map((p) => p.index), [0x1A00, 0x1A01]);
+  assertEquals(pdos.map((p) => p.isInput), [true, true]);
+  assertEquals(pdos[0]!.sm, 3);
+  assertEquals(
+    pdos[0]!.entries.map((e) => [e.index, e.subIndex, e.bitLen]),
+    [[0x6000, 1, 1], [0x0000, 0, 7], [0x6000, 17, 16]],
+  );
+  assertEquals(pdos[1]!.entries.map((e) => [e.index, e.subIndex, e.bitLen]), [[0x6010, 17, 16]]);
+
+  // PS PDO assignment: clear 0x1C12/0x1C13, assign 0x1A00/0x1A01, set the count
+  assertEquals(
+    slave.initCommands?.map((c) => [c.type, c.index, c.subIndex, c.value, c.dataLength]),
+    [
+      ["sdo", 0x1C12, 0, 0, 1],
+      ["sdo", 0x1C13, 0, 0, 1],
+      ["sdo", 0x1C13, 1, 0x1A00, 2],
+      ["sdo", 0x1C13, 2, 0x1A01, 2],
+      ["sdo", 0x1C13, 0, 2, 1],
+    ],
+  );
+  assertEquals(slave.initCommands?.[0]!.transition, ["PS"]);
+
+  const coupler = config.slaves[0]!;
+  assertEquals(coupler.initCommands, []);
+  assertEquals(coupler.processData?.pdos, undefined);
+});
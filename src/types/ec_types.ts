@@ -56,3 +56,368 @@ export interface PdoMapping extends ProcessDataMapping {
   currentValue?: number | boolean;
   newValue?: number | boolean;
 }
+
+/** Types for EcMaster.readVariable()/writeVariable(); the width must match the mapped entry. */
+export type PdDataType =
+  | "BOOL"
+  | "INT8"
+  | "INT16"
+  | "INT32"
+  | "INT64"
+  | "UINT8"
+  | "UINT16"
+  | "UINT32"
+  | "UINT64"
+  | "REAL32"
+  | "REAL64";
+
+/** One mapped PDO entry of the active process image (built on PreOp→SafeOp). */
+export interface ProcessSymbol {
+  slaveIndex: number;
+  /** 0x16xx (outputs) or 0x1Axx (inputs). */
+  pdoIndex: number;
+  index: number;
+  subIndex: number;
+  isOutput: boolean;
+  /** Within the slave's input or output image. */
+  bitOffset: number;
+  bitLen: number;
+  /** CoE data type from SDO Information or the SII, 0 when neither describes the entry. */
+  dataType: number;
+  /** Within the buffer returned by getProcessDataBuffer(). */
+  pdiBitOffset: number;
+}
+
+/** Where one slave's process data sits, from EcMaster.getProcessImageLayout(). */
+export interface PdiSlaveLayout {
+  slaveIndex: number;
+  /** Byte offset and length in the buffer returned by getProcessDataBuffer(). */
+  outputOffset: number;
+  outputLength: number;
+  inputOffset: number;
+  inputLength: number;
+  /** Logical start address in the cyclic frame (from the slave's FMMUs), null if not mapped. */
+  outputLogicalAddress: number | null;
+  inputLogicalAddress: number | null;
+}
+
+/**
+ * Outputs a slave falls back to on communication loss, host heartbeat timeout, Op→SafeOp
+ * and destroy: "none" (keep host outputs), all zero, hold the last value, or user bytes.
+ */
+export type FailSafeMode = "none" | "zero" | "hold" | "user";
+
+/** What switched the configured slaves to their fail-safe outputs. */
+export type FailSafeReason = "none" | "wkcLoss" | "heartbeat" | "opToSafeOp" | "destroy";
+
+/** Slaves currently sending their fail-safe image, from EcMaster.getFailSafeStatus(). */
+export interface FailSafeStatus {
+  reason: FailSafeReason;
+  slaves: number[];
+}
+
+/** Emitted as "watchdogTimeout" when the host missed its heartbeat (see EcMaster.startWatchdog()). */
+export interface WatchdogTimeoutEvent {
+  /** Missed heartbeats since startWatchdog(). */
+  tripCount: number;
+  error: string;
+}
+
+/** Watchdog settings of one slave as read back from the ESC (0x0400-0x0443). */
+export interface EscWatchdogInfo {
+  slaveIndex: number;
+  /** Divider register; one watchdog increment is (divider + 2) * 40 ns. */
+  divider: number;
+  pdiCount: number;
+  smCount: number;
+  /** Effective PDI watchdog time, 0 = disabled. */
+  pdiTimeUs: number;
+  /** Effective SM (process data) watchdog time, 0 = disabled. */
+  smTimeUs: number;
+  /** Register 0x0440; bit 0 clear means the SM watchdog has expired. */
+  status: number;
+  smExpiredCount: number;
+  pdiExpiredCount: number;
+  /** False when configuring this slave failed; the settings above are then not populated. */
+  valid: boolean;
+  /** Per-slave result of configureWatchdogs(): 0, -2 (not found) or -3 (write or read-back failed). */
+  error: number;
+}
+
+/**
+ * Addressing for EcMaster.readRegisters()/writeRegisters(): configured station address
+ * (FPRD/FPWR), ring position (APRD/APWR) or every slave (BRD/BWR).
+ */
+export type RegisterTarget =
+  | { configuredAddress: number }
+  | { position: number }
+  | "broadcast";
+
+/** Data and working counter of EcMaster.readRegisters(). */
+export interface RegisterReadResult {
+  data: Uint8Array;
+  /** Slaves that processed the datagram; for a broadcast this is the number of slaves. */
+  wkc: number;
+}
+
+/** Datagram types accepted by EcMaster.sendDatagram(). */
+export type DatagramCommand = "LRD" | "LWR" | "LRW" | "FRMW";
+
+/** Response of EcMaster.sendDatagram(). */
+export interface DatagramResult {
+  /** Returned payload; empty when a writing datagram missed the expected WKC. */
+  data: Uint8Array;
+  wkc: number;
+}
+
+/** ESC error counters (0x0300-0x0313); per-port arrays are indexed by port 0-3. */
+export interface EscErrorCounters {
+  invalidFrame: number[];
+  rxError: number[];
+  forwardedRxError: number[];
+  lostLink: number[];
+  processingUnit: number;
+  pdi: number;
+}
+
+/** Error counters of one slave from EcMaster.readErrorCounters(). */
+export interface EscErrorReport {
+  slaveIndex: number;
+  /** False when the slave did not answer; counters and delta are zero. */
+  valid: boolean;
+  counters: EscErrorCounters;
+  /** Increase since the previous read, or since init / clearErrorCounters(). */
+  delta: EscErrorCounters;
+  intervalMs: number;
+}
+
+/** A port with errors in the last read interval, from EcMaster.rankPortErrors(). */
+export interface EscPortRank {
+  slaveIndex: number;
+  port: number;
+  /** Invalid frames + RX errors + lost links; forwarded errors are not counted. */
+  errors: number;
+  errorsPerSecond: number;
+}
+
+/** Emitted as "linkLost" / "linkRestored" by the link monitor (EcMaster.startLinkMonitor()). */
+export interface LinkEvent {
+  /** Slave on the master side of the link, null when the link is the master's own. */
+  parentIndex: number | null;
+  parentPort: number | null;
+  /** First slave behind the link. */
+  childIndex: number;
+  /** Slaves answering the broadcast read when the change was seen. */
+  slaveCount: number;
+  /** Slaves when the monitor was started. */
+  expectedCount: number;
+  /** DL status (0x0110) of the parent slave, 0 for the master. */
+  parentDlStatus: number;
+  /** e.g. "Link lost between slave 7 port 1 and slave 8" */
+  message: string;
+}
+
+/**
+ * Slave selector accepted by every slave-level EcMaster call: the position in the ring,
+ * the station alias (0x0012, loaded from SII word 4) or the configured station address.
+ */
+export type SlaveRef = number | { alias: number } | { address: number };
+
+/** PDO for EcMaster.remapPdos(): mapping object (0x16xx/0x1Axx) and its entries in order. */
+export interface PdoAssignment {
+  index: number;
+  /** Entry index 0 maps a gap (padding) of bitLen bits. */
+  entries: { index: number; subIndex: number; bitLen: number }[];
+}
+
+/** Raw mailbox frame received from a slave's SM1 (payload without the 6-byte header). */
+export interface RawMailboxFrame {
+  /** Mailbox type (0x0 = error reply, 0x1 AoE, 0x3 CoE, 0x5 SoE, 0xF VoE). */
+  type: number;
+  /** Mailbox counter (0-7). */
+  counter: number;
+  data: Uint8Array;
+}
+
+/** PDO entry from the SII TxPDO/RxPDO categories (ETG2010 Table 15). */
+export interface SiiPdoEntry {
+  index: number;
+  subIndex: number;
+  name: string;
+  /** CoE data type index (e.g. 0x0001 BOOL, 0x0007 UINT32). */
+  dataType: number;
+  bitLen: number;
+  flags: number;
+}
+
+/** PDO from the SII TxPDO/RxPDO categories (ETG2010 Table 14). */
+export interface SiiPdo {
+  index: number;
+  syncManager: number;
+  dcSync: number;
+  name: string;
+  flags: number;
+  entries: SiiPdoEntry[];
+}
+
+/** Parsed SII (slave information interface) EEPROM contents, see ETG2010. */
+export interface SiiInfo {
+  stationAlias: number;
+  vendorId: number;
+  productCode: number;
+  revision: number;
+  serialNumber: number;
+  mailbox: {
+    rxOffset: number;
+    rxSize: number;
+    txOffset: number;
+    txSize: number;
+    /** Supported mailbox protocols bitmask (0x01 AoE, 0x02 EoE, 0x04 CoE, 0x08 FoE, 0x10 SoE, 0x20 VoE). */
+    protocols: number;
+  };
+  sizeBytes: number;
+  version: number;
+  strings: string[];
+  general: {
+    group: string;
+    image: string;
+    order: string;
+    name: string;
+    coeDetails: number;
+    foeDetails: number;
+    eoeDetails: number;
+    soeChannels: number;
+    ds402Channels: number;
+    sysmanClass: number;
+    flags: number;
+    /** Current consumption on E-Bus in mA (negative = feeding). */
+    currentOnEbusMa: number;
+    physicalPort: number;
+    physicalMemoryAddress: number;
+  } | null;
+  /** FMMU usage: 0 unused, 1 outputs, 2 inputs, 3 SM status. */
+  fmmus: number[];
+  syncManagers: {
+    startAddress: number;
+    length: number;
+    control: number;
+    status: number;
+    enable: number;
+    /** 0 unused, 1 mailbox out, 2 mailbox in, 3 process data out, 4 process data in. */
+    type: number;
+  }[];
+  txPdos: SiiPdo[];
+  rxPdos: SiiPdo[];
+  dc: {
+    cycleTime0: number;
+    shiftTime0: number;
+    shiftTime1: number;
+    sync1CycleFactor: number;
+    assignActivate: number;
+    sync0CycleFactor: number;
+    name: string;
+    description: string;
+  }[];
+}
+
+/** PDO with its mapped entries, as assigned at scan time. */
+export interface ScanPdo {
+  index: number;
+  name: string;
+  /** Canonical sync manager: 2 = outputs (RxPDO), 3 = inputs (TxPDO). */
+  syncManager: number;
+  entries: {
+    index: number; // 0 = padding
+    subIndex: number;
+    bitLen: number;
+    dataType: number; // CoE data type code (ETG1000.6)
+    name: string;
+  }[];
+}
+
+/** One slave of a network scan, in frame propagation order. */
+export interface ScanSlave {
+  position: number;
+  name: string;
+  identity: {
+    vendorId: number;
+    productCode: number;
+    revision: number;
+    serialNo: number;
+  };
+  config: {
+    configuredAddress: number;
+    aliasAddress: number;
+    /** Per port: "EBUS" | "MII" | "unconfigured" | "none". */
+    portPhysics: string[];
+  };
+  capabilities: {
+    mailbox: {
+      coe: boolean;
+      foe: boolean;
+      eoe: boolean;
+      soe: boolean;
+      aoe: boolean;
+      voe: boolean;
+    };
+    dc: boolean;
+    dc64Bit: boolean;
+    portCount: number;
+    fmmuCount: number;
+    smCount: number;
+    escType: number;
+    escFeatures: number;
+  };
+  topology: {
+    dlStatus: number;
+    parentIndex: number | null; // null = connected to the master
+    parentPort: number | null;
+    entryPort: number;
+    ports: {
+      link: boolean;
+      loopClosed: boolean;
+      communication: boolean;
+      receiveTimeNs: number | null; // null without DC
+      delayNs: number | null;
+    }[];
+  };
+  pdos: {
+    inputs: ScanPdo[];
+    outputs: ScanPdo[];
+  };
+  /** Object dictionary, only filled with ScanOptions.walkObjectDictionary. */
+  objects: {
+    index: number;
+    name: string;
+  }[];
+}
+
+/** Options for EcMaster.scanNetwork(), discoverNetwork(), discoverNetworkEni() and scanLive(). */
+export interface ScanOptions {
+  /** PDU timeout (default: ethercrab default). Not applied by scanLive(). */
+  pduTimeoutMs?: number;
+  /** Timeout per SDO Information request (default 1000 ms). */
+  mailboxTimeoutMs?: number;
+  /** Timeout per SII image read (default 100 ms). */
+  eepromTimeoutMs?: number;
+  /** PDU retries on timeout (default 0). Not applied by scanLive(). */
+  pduRetries?: number;
+  /** Scan only the first N slaves (default: all). */
+  maxSlaves?: number;
+  /** Read PDOs from the SII categories only, no CoE. */
+  siiOnly?: boolean;
+  /** Walk the whole object dictionary via SDO Information (ScanSlave.objects). */
+  walkObjectDictionary?: boolean;
+  /** Called while the scan runs with the number of slaves scanned so far. */
+  onProgress?: (done: number, total: number) => void;
+  /** Aborting cancels the scan before the next slave. */
+  signal?: AbortSignal;
+}
+
+/** Result of EcMaster.scanNetwork(), schema version 1. */
+export interface ScanResult {
+  schemaVersion: number;
+  /** Milliseconds since the Unix epoch. */
+  scanTime: number;
+  slaves: ScanSlave[];
+}
@@ -110,6 +110,7 @@ export interface EniSlaveConfig {
     outputOffset?: number; // Byte offset in PDI
     outputBitLength?: number; // Explicit bit length from ENI (from <Send><BitLength>)
     entries?: ProcessDataEntry[];
+    pdos?: EniPdo[]; // From <RxPdo>/<TxPdo>
   };
 
   // From <Slave><Mailbox><Recv>
@@ -123,6 +124,39 @@ export interface EniSlaveConfig {
 
   // From <Slave><InitCmds>
   initCommands?: InitCommand[];
+
+  // Only set by EcMaster.discoverNetwork()
+  topology?: SlaveTopology;
+}
+
+/** Physical port states and position in the tree, as seen during a network scan. */
+export interface SlaveTopology {
+  dlStatus: number; // Raw DL status register (0x0110)
+  parentIndex: number; // Position of the parent slave, -1 = connected to the master
+  parentPort: number | null; // Port (0-3) on the parent this slave is connected to
+  entryPort: number; // Port facing the master (normally 0)
+  ports: {
+    link: boolean;
+    loopClosed: boolean;
+    communication: boolean;
+    receiveTimeNs?: number; // DC receive time latched on this port
+    delayNs?: number; // Round trip through the segment behind this port
+  }[];
+}
+
+/** PDO description from <Slave><ProcessData><RxPdo>/<TxPdo>. */
+export interface EniPdo {
+  index: number; // Mapping object (0x16xx RxPDO, 0x1Axx TxPDO)
+  name: string;
+  isInput: boolean; // TxPdo
+  sm?: number; // Sync manager the PDO is assigned to, if any
+  entries: {
+    index: number; // 0 = padding
+    subIndex: number;
+    bitLen: number;
+    name?: string;
+    dataType?: string;
+  }[];
 }
 
 export interface ProcessDataEntry {
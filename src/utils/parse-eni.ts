@@ -4,6 +4,7 @@ import type {
   EniCyclicCmd,
   EniCyclicConfig,
   EniCyclicFrame,
+  EniPdo,
   EniSlaveConfig,
   InitCommand,
   ProcessDataEntry,
@@ -100,7 +101,12 @@ interface EniSlave {
       PollTime?: string;
     };
     Protocol?: string | string[];
-    CoE?: { Profile?: unknown };
+    CoE?: {
+      Profile?: unknown;
+      InitCmds?: {
+        InitCmd?: EniCoeInitCmd[] | EniCoeInitCmd;
+      };
+    };
     EoE?: unknown;
     FoE?: unknown;
   };
@@ -136,6 +142,8 @@ interface EniSlave {
         PdoOffset?: string;
       }>;
     };
+    RxPdo?: EniXmlPdo[] | EniXmlPdo;
+    TxPdo?: EniXmlPdo[] | EniXmlPdo;
   };
   InitCmds?: {
     InitCmd?: EniInitCmd[] | EniInitCmd;
@@ -174,6 +182,36 @@ interface EniInitCmd {
   Value?: string;
 }
 
+// <Slave><Mailbox><CoE><InitCmds><InitCmd> (ETG.2100 MailboxCmdType)
+interface EniCoeInitCmd {
+  Transition?: string | string[];
+  Comment?: string;
+  Timeout?: string;
+  Ccs?: string; // 1 = download, 2 = upload
+  Index?: string;
+  SubIndex?: string;
+  Data?: string; // hexBinary, bytes in transfer order
+}
+
+interface EniXmlPdo {
+  Sm?: string;
+  Index?: string;
+  Name?: string;
+  Entry?: Array<{
+    Index?: string;
+    SubIndex?: string;
+    BitLen?: string;
+    Name?: string;
+    DataType?: string;
+  }> | {
+    Index?: string;
+    SubIndex?: string;
+    BitLen?: string;
+    Name?: string;
+    DataType?: string;
+  };
+}
+
 interface EniVariable {
   Name: string;
   DataType?: string;
@@ -210,6 +248,9 @@ export async function parseEniXml(xmlPath: string): Promise<EniConfig> {
       let result: number;
       if (trimmed.startsWith("0x") || trimmed.startsWith("0X")) {
         result = parseInt(trimmed, 16);
+      } else if (trimmed.startsWith("#x")) {
+        // ETG.2100 hex notation, e.g. <Index>#x1a00</Index>
+        result = parseInt(trimmed.slice(2), 16);
       } else if (/^-?\d+$/.test(trimmed)) {
         result = parseInt(trimmed, 10);
       } else {
@@ -276,6 +317,58 @@ export async function parseEniXml(xmlPath: string): Promise<EniConfig> {
     });
   };
 
+  // CoE init commands carry hexBinary data in transfer order (little-endian); values
+  // up to 4 bytes become numbers, longer downloads do not fit the FFI init command
+  const parseCoeInitCmds = (
+    cmds: EniCoeInitCmd[] | EniCoeInitCmd | undefined,
+    slaveIndex: number,
+  ): InitCommand[] => {
+    if (!cmds) return [];
+    const cmdArray = Array.isArray(cmds) ? cmds : [cmds];
+    return cmdArray.map((cmd) => {
+      const data = cmd.Data?.trim() ?? "";
+      const bytes = (data.match(/[0-9a-fA-F]{2}/g) ?? []).map((b) => parseInt(b, 16));
+      const c: InitCommand = {
+        type: parseHex(cmd.Ccs) === 1 && bytes.length > 0 && bytes.length <= 4 ? "sdo" : "unknown",
+        slaveIndex,
+        transition: Array.isArray(cmd.Transition)
+          ? cmd.Transition
+          : (cmd.Transition ? [cmd.Transition] : undefined),
+        comment: cmd.Comment,
+        timeout: parseHex(cmd.Timeout),
+        index: parseHex(cmd.Index),
+        subIndex: parseHex(cmd.SubIndex),
+        data: cmd.Data,
+        dataLength: bytes.length,
+      };
+      if (c.type === "sdo") {
+        c.value = bytes.reduceRight((acc, b) => acc * 256 + b, 0);
+      }
+      return c;
+    });
+  };
+
+  const parsePdos = (pdos: EniXmlPdo[] | EniXmlPdo | undefined, isInput: boolean): EniPdo[] => {
+    if (!pdos) return [];
+    const pdoArray = Array.isArray(pdos) ? pdos : [pdos];
+    return pdoArray.map((pdo) => {
+      const entries = pdo.Entry ? (Array.isArray(pdo.Entry) ? pdo.Entry : [pdo.Entry]) : [];
+      return {
+        index: parseHex(pdo.Index) ?? 0,
+        name: pdo.Name ?? "",
+        isInput,
+        sm: parseHex(pdo.Sm),
+        entries: entries.map((e) => ({
+          index: parseHex(e.Index) ?? 0,
+          subIndex: parseHex(e.SubIndex) ?? 0,
+          bitLen: parseHex(e.BitLen) ?? 0,
+          name: e.Name,
+          dataType: e.DataType,
+        })),
+      };
+    });
+  };
+
   const masterXml = config.Master;
   const cyclicXml = config.Cyclic
     ? (Array.isArray(config.Cyclic) ? config.Cyclic[0] : config.Cyclic)
@@ -325,7 +418,10 @@ export async function parseEniXml(xmlPath: string): Promise<EniConfig> {
       serialNumber: parseHex(slaveXml.Info?.SerialNo),
       physAddr: parseHex(slaveXml.Info?.PhysAddr),
       autoIncAddr: parseHex(slaveXml.Info?.AutoIncAddr),
-      initCommands: parseInitCmds(slaveXml.InitCmds?.InitCmd, slaveIndex),
+      initCommands: [
+        ...parseInitCmds(slaveXml.InitCmds?.InitCmd, slaveIndex),
+        ...parseCoeInitCmds(slaveXml.Mailbox?.CoE?.InitCmds?.InitCmd, slaveIndex),
+      ],
     };
 
     if (slaveXml.PreviousPort) {
@@ -504,12 +600,18 @@ export async function parseEniXml(xmlPath: string): Promise<EniConfig> {
         }
       }
 
+      const pdos = [
+        ...parsePdos(slaveXml.ProcessData.RxPdo, false),
+        ...parsePdos(slaveXml.ProcessData.TxPdo, true),
+      ];
+
       slave.processData = {
         inputOffset: Math.floor(inputOffset / 8),
         inputBitLength,
         outputOffset: Math.floor(outputOffset / 8),
         outputBitLength,
         entries,
+        ...(pdos.length > 0 ? { pdos } : {}),
       };
     }
 